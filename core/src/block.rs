@@ -15,6 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::invoice::Invoice;
+use super::receipt::Receipt;
 use crate::client::{EngineInfo, TermInfo};
 use crate::consensus::CodeChainEngine;
 use crate::error::{BlockError, Error};
@@ -23,7 +24,7 @@ use crate::BlockId;
 use ccrypto::BLAKE_NULL_RLP;
 use ckey::Address;
 use cmerkle::skewed_merkle_root;
-use cstate::{FindActionHandler, StateDB, StateError, StateWithCache, TopLevelState};
+use cstate::{FindActionHandler, StateDB, StateError, StateWithCache, TopLevelState, TopStateView};
 use ctypes::errors::HistoryError;
 use ctypes::header::{Header, Seal};
 use ctypes::util::unexpected::Mismatch;
@@ -89,6 +90,7 @@ pub struct ExecutedBlock {
     state: TopLevelState,
     transactions: Vec<SignedTransaction>,
     invoices: Vec<Invoice>,
+    receipts: Vec<Receipt>,
     transactions_set: HashSet<TxHash>,
 }
 
@@ -99,6 +101,7 @@ impl ExecutedBlock {
             state,
             transactions: Default::default(),
             invoices: Default::default(),
+            receipts: Default::default(),
             transactions_set: Default::default(),
         }
     }
@@ -115,6 +118,18 @@ impl ExecutedBlock {
     pub fn header(&self) -> &Header {
         &self.header
     }
+
+    #[cfg(test)]
+    pub fn new_for_test(header: Header, state: TopLevelState) -> Self {
+        ExecutedBlock {
+            header,
+            state,
+            transactions: Default::default(),
+            invoices: Default::default(),
+            receipts: Default::default(),
+            transactions_set: Default::default(),
+        }
+    }
 }
 
 /// Block that is ready for transactions to be added.
@@ -132,7 +147,13 @@ impl<'x> OpenBlock<'x> {
         author: Address,
         extra_data: Bytes,
     ) -> Result<Self, Error> {
-        let state = TopLevelState::from_existing(db, *parent.state_root()).map_err(StateError::from)?;
+        let mut state = TopLevelState::from_existing(db, *parent.state_root()).map_err(StateError::from)?;
+        let params = state
+            .metadata()
+            .map_err(StateError::from)?
+            .and_then(|metadata| metadata.params().cloned())
+            .unwrap_or_else(|| *engine.machine().genesis_common_params());
+        state.set_script_params(&params);
         let mut r = OpenBlock {
             block: ExecutedBlock::new(state, parent),
             engine,
@@ -173,11 +194,34 @@ impl<'x> OpenBlock<'x> {
             self.block.header.timestamp(),
         ) {
             Ok(()) => {
+                self.block.receipts.push(Receipt {
+                    hash,
+                    // The hash is known after the block is sealed, so it is set on the insertion.
+                    block_hash: Default::default(),
+                    block_number: self.block.header.number(),
+                    error: None,
+                    fee: tx.fee,
+                    created_assets: tx.shard_transaction().map(|tx| tx.created_assets()).unwrap_or_default(),
+                    touched_action_data: self.block.state.touched_action_data().to_vec(),
+                });
                 self.block.transactions_set.insert(h.unwrap_or(hash));
                 self.block.transactions.push(tx);
                 None
             }
-            Err(err) => Some(err),
+            Err(err) => {
+                if let StateError::Runtime(runtime_error) = &err {
+                    self.block.receipts.push(Receipt {
+                        hash,
+                        block_hash: Default::default(),
+                        block_number: self.block.header.number(),
+                        error: Some(runtime_error.clone()),
+                        fee: 0,
+                        created_assets: Vec::new(),
+                        touched_action_data: Vec::new(),
+                    });
+                }
+                Some(err)
+            }
         };
         self.block.invoices.push(Invoice {
             hash,
@@ -408,6 +452,14 @@ impl SealedBlock {
         block_rlp.append_list(&self.block.transactions);
         block_rlp.out()
     }
+
+    /// Create a sealed block without transactions, which skips the execution.
+    #[cfg(test)]
+    pub fn new_empty_for_test(header: Header, state: TopLevelState) -> Self {
+        SealedBlock {
+            block: ExecutedBlock::new_for_test(header, state),
+        }
+    }
 }
 
 /// Trait for a object that is a `ExecutedBlock`.
@@ -438,6 +490,11 @@ pub trait IsBlock {
         &self.block().invoices
     }
 
+    /// Get the execution results of the transactions pushed into this block.
+    fn receipts(&self) -> &[Receipt] {
+        &self.block().receipts
+    }
+
     /// Get the final state associated with this object's block.
     fn state(&self) -> &TopLevelState {
         &self.block().state
@@ -0,0 +1,625 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::block_info::BestBlockChanged;
+use super::body_db::BodyProvider;
+use super::extras::TransactionAddress;
+use super::headerchain::HeaderProvider;
+use crate::consensus::stake::{Action as StakeAction, CUSTOM_ACTION_HANDLER_ID as STAKE_ACTION_HANDLER_ID};
+use crate::db::{self, Key, Readable, Writable};
+use crate::UnverifiedTransaction;
+use ckey::{public_to_address, Address};
+use ctypes::transaction::{Action, ShardTransaction};
+use ctypes::{BlockHash, BlockNumber, TxHash};
+use kvdb::{DBTransaction, KeyValueDB};
+use primitives::{Bytes, H160, H256};
+use std::collections::HashMap;
+use std::ops::Deref;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// The lock script hashes of P2PKH and P2PKHBurn. Their first parameter is the account id of the owner.
+const P2PKH_LOCK_SCRIPT_HASH: [u8; 20] = [
+    0x5f, 0x59, 0x60, 0xa7, 0xbc, 0xa6, 0xce, 0xee, 0xb0, 0xc9, 0x7b, 0xc7, 0x17, 0x56, 0x29, 0x14, 0xe7, 0xa1, 0xde,
+    0x04,
+];
+const P2PKH_BURN_LOCK_SCRIPT_HASH: [u8; 20] = [
+    0x37, 0x57, 0x2b, 0xdc, 0xc2, 0x2d, 0x39, 0xa5, 0x9c, 0x0d, 0x12, 0xd3, 0x01, 0xf6, 0x27, 0x1b, 0xa3, 0xfd, 0xd4,
+    0x51,
+];
+
+/// Secondary index from an address to the canonical transactions related to it.
+///
+/// The transactions of an address are numbered in the order they are included in the canonical chain.
+/// Since the retracted blocks are always the tail of the canonical chain, a reorganization only
+/// truncates the history of each address before the enacted transactions are appended.
+/// A transaction is indexed at most once per address, because the same block can be reported again,
+/// e.g. when it is committed and when the proposal on top of it is imported.
+pub struct AddressIndexDB {
+    db: Arc<dyn KeyValueDB>,
+    /// The new best blocks are indexed by `catch_up` instead of `update_best_block` until it becomes true.
+    caught_up: AtomicBool,
+}
+
+/// The number of blocks written in a batch while catching up with the canonical chain.
+const CATCH_UP_BATCH_SIZE: BlockNumber = 1000;
+
+type BlockTransactions = (BlockHash, Vec<UnverifiedTransaction>);
+
+impl AddressIndexDB {
+    pub fn new(db: Arc<dyn KeyValueDB>, genesis_hash: BlockHash, best_block_hash: BlockHash) -> Self {
+        let index = Self {
+            db,
+            caught_up: AtomicBool::new(false),
+        };
+        let indexed_block_hash = index.best_indexed_block_hash().unwrap_or(genesis_hash);
+        index.caught_up.store(indexed_block_hash == best_block_hash, Ordering::SeqCst);
+        index
+    }
+
+    pub fn update_best_block(
+        &self,
+        batch: &mut DBTransaction,
+        best_block_changed: &BestBlockChanged,
+        bodies: &dyn BodyProvider,
+    ) {
+        if !self.caught_up.load(Ordering::SeqCst) {
+            return
+        }
+        let block = match best_block_changed.best_block() {
+            Some(block) => block,
+            None => return,
+        };
+        let block_hash = block.hash();
+
+        let mut retracted = Vec::new();
+        let mut enacted = Vec::new();
+        match best_block_changed {
+            BestBlockChanged::CanonChainAppended {
+                ..
+            } => {}
+            BestBlockChanged::BranchBecomingCanonChain {
+                tree_route,
+                ..
+            } => {
+                for hash in &tree_route.retracted {
+                    let body = bodies.block_body(hash).expect("Retracted block must be in database.");
+                    retracted.push((*hash, body.transactions()));
+                }
+                for hash in &tree_route.enacted {
+                    let body = bodies.block_body(hash).expect("Enacted block must be in database.");
+                    enacted.push((*hash, body.transactions()));
+                }
+            }
+            BestBlockChanged::None => return,
+        }
+        enacted.push((block_hash, block.transactions()));
+
+        self.update(batch, retracted, enacted);
+        batch.put(db::COL_ADDRESS_INDEX, BEST_INDEXED_BLOCK_KEY, &block_hash);
+    }
+
+    /// Indexes a chunk of the canonical blocks that are imported while the index is disabled,
+    /// including the ones imported before the index existed.
+    /// It returns true when the index has caught up with the best block.
+    ///
+    /// It should not run concurrently with `update_best_block`.
+    pub fn catch_up(
+        &self,
+        headers: &dyn HeaderProvider,
+        bodies: &dyn BodyProvider,
+        best_block_hash: BlockHash,
+    ) -> bool {
+        if self.caught_up.load(Ordering::SeqCst) {
+            return true
+        }
+        let indexed_block_hash = self.best_indexed_block_hash().unwrap_or_else(|| headers.genesis_hash());
+        if indexed_block_hash == best_block_hash {
+            cinfo!(BLOCKCHAIN, "The address index has caught up with the best block");
+            self.caught_up.store(true, Ordering::SeqCst);
+            return true
+        }
+        // The bodies of the blocks before the snapshot don't exist.
+        let transactions =
+            |hash: &BlockHash| -> Option<BlockTransactions> { Some((*hash, bodies.block_body(hash)?.transactions())) };
+
+        // The indexed blocks that are retracted while the index is disabled are removed first.
+        let is_canonical = |hash: &BlockHash| {
+            headers.block_number(hash).and_then(|number| headers.block_hash(number)).as_ref() == Some(hash)
+        };
+        if !is_canonical(&indexed_block_hash) {
+            let mut retracted = Vec::new();
+            let mut hash = indexed_block_hash;
+            while !is_canonical(&hash) {
+                retracted.push(hash);
+                hash = match headers.block_header(&hash) {
+                    Some(header) => *header.parent_hash(),
+                    None => return self.give_up_catching_up(indexed_block_hash),
+                };
+            }
+            let mut batch = DBTransaction::new();
+            self.update(&mut batch, retracted.iter().filter_map(transactions).collect(), Vec::new());
+            batch.put(db::COL_ADDRESS_INDEX, BEST_INDEXED_BLOCK_KEY, &hash);
+            self.db.write(batch).expect("Low level database error. Some issue with disk?");
+            return false
+        }
+
+        let indexed_block_number = headers.block_number(&indexed_block_hash).expect("Canonical blocks have numbers");
+        let best_block_number = match headers.block_number(&best_block_hash) {
+            Some(number) => number,
+            None => return self.give_up_catching_up(indexed_block_hash),
+        };
+        let last_block_number = best_block_number.min(indexed_block_number + CATCH_UP_BATCH_SIZE);
+        let last_block_hash = match headers.block_hash(last_block_number) {
+            Some(hash) => hash,
+            None => return self.give_up_catching_up(indexed_block_hash),
+        };
+        let hashes: Vec<_> =
+            ((indexed_block_number + 1)..=last_block_number).filter_map(|number| headers.block_hash(number)).collect();
+
+        let mut batch = DBTransaction::new();
+        self.update(&mut batch, Vec::new(), hashes.iter().filter_map(transactions).collect());
+        batch.put(db::COL_ADDRESS_INDEX, BEST_INDEXED_BLOCK_KEY, &last_block_hash);
+        self.db.write(batch).expect("Low level database error. Some issue with disk?");
+
+        let remaining = best_block_number - last_block_number;
+        cinfo!(
+            BLOCKCHAIN,
+            "Indexed the transactions by the addresses up to block #{}, {} blocks remain",
+            last_block_number,
+            remaining
+        );
+        if remaining == 0 {
+            self.caught_up.store(true, Ordering::SeqCst);
+        }
+        remaining == 0
+    }
+
+    /// The blocks that are not connected to the best block are left unindexed.
+    fn give_up_catching_up(&self, indexed_block_hash: BlockHash) -> bool {
+        cwarn!(BLOCKCHAIN, "Cannot find the route from the indexed block {} to the best block", indexed_block_hash);
+        self.caught_up.store(true, Ordering::SeqCst);
+        true
+    }
+
+    fn update(&self, batch: &mut DBTransaction, retracted: Vec<BlockTransactions>, enacted: Vec<BlockTransactions>) {
+        let mut counts: HashMap<Address, u64> = HashMap::new();
+        // The positions written or deleted in this batch, which are not visible in the database yet.
+        let mut positions: HashMap<(Address, TxHash), Option<u64>> = HashMap::new();
+
+        for (block_hash, transactions) in retracted {
+            for (address, tx_hash, _) in address_entries(block_hash, transactions) {
+                let index = match self.position(&address, &tx_hash) {
+                    Some(index) => index,
+                    None => continue,
+                };
+                let count = counts.entry(address).or_insert_with(|| self.transaction_count(&address));
+                *count = (*count).min(index);
+                batch.delete(db::COL_ADDRESS_INDEX, &AddressTransaction {
+                    address,
+                    tx_hash,
+                });
+                positions.insert((address, tx_hash), None);
+            }
+        }
+        for (address, new_count) in &counts {
+            for index in *new_count..self.transaction_count(address) {
+                batch.delete(db::COL_ADDRESS_INDEX, &AddressHistoryIndex {
+                    address: *address,
+                    index,
+                });
+            }
+        }
+
+        for (block_hash, transactions) in enacted {
+            for (address, tx_hash, transaction_address) in address_entries(block_hash, transactions) {
+                let indexed = match positions.get(&(address, tx_hash)) {
+                    Some(position) => position.is_some(),
+                    None => self.position(&address, &tx_hash).is_some(),
+                };
+                if indexed {
+                    continue
+                }
+                let count = *counts.entry(address).or_insert_with(|| self.transaction_count(&address));
+                batch.write(
+                    db::COL_ADDRESS_INDEX,
+                    &AddressHistoryIndex {
+                        address,
+                        index: count,
+                    },
+                    &transaction_address,
+                );
+                batch.write(
+                    db::COL_ADDRESS_INDEX,
+                    &AddressTransaction {
+                        address,
+                        tx_hash,
+                    },
+                    &count,
+                );
+                positions.insert((address, tx_hash), Some(count));
+                counts.insert(address, count + 1);
+            }
+        }
+        for (address, count) in counts {
+            batch.write(db::COL_ADDRESS_INDEX, &address, &count);
+        }
+    }
+
+    /// Returns the hash of the block up to which the canonical transactions are indexed.
+    fn best_indexed_block_hash(&self) -> Option<BlockHash> {
+        let hash = self
+            .db
+            .get(db::COL_ADDRESS_INDEX, BEST_INDEXED_BLOCK_KEY)
+            .expect("Low level database error. Some issue with disk?")?;
+        Some(H256::from_slice(&hash).into())
+    }
+
+    /// Returns the position of the transaction in the history of the address if it is indexed.
+    fn position(&self, address: &Address, tx_hash: &TxHash) -> Option<u64> {
+        self.db.read(db::COL_ADDRESS_INDEX, &AddressTransaction {
+            address: *address,
+            tx_hash: *tx_hash,
+        })
+    }
+
+    /// Returns the number of the canonical transactions related to the given address.
+    pub fn transaction_count(&self, address: &Address) -> u64 {
+        self.db.read::<u64, _>(db::COL_ADDRESS_INDEX, address).unwrap_or(0)
+    }
+
+    /// Returns at most `limit` transactions of the given address, skipping the first `offset` ones.
+    pub fn transaction_addresses(&self, address: &Address, offset: u64, limit: u64) -> Vec<TransactionAddress> {
+        let count = self.transaction_count(address);
+        (offset..count.min(offset.saturating_add(limit)))
+            .map(|index| {
+                self.db
+                    .read(db::COL_ADDRESS_INDEX, &AddressHistoryIndex {
+                        address: *address,
+                        index,
+                    })
+                    .expect("Indexed transaction must be in database")
+            })
+            .collect()
+    }
+}
+
+fn address_entries(
+    block_hash: BlockHash,
+    transactions: Vec<UnverifiedTransaction>,
+) -> impl Iterator<Item = (Address, TxHash, TransactionAddress)> {
+    transactions.into_iter().enumerate().flat_map(move |(index, tx)| {
+        let tx_hash = tx.hash();
+        let transaction_address = TransactionAddress {
+            block_hash,
+            index,
+        };
+        related_addresses(&tx).into_iter().map(move |address| (address, tx_hash, transaction_address))
+    })
+}
+
+/// Returns the signer, the `Pay` receiver, the stake delegatees and the owners of the created assets.
+fn related_addresses(tx: &UnverifiedTransaction) -> Vec<Address> {
+    let mut addresses = Vec::new();
+    if let Ok(public) = tx.recover_public() {
+        addresses.push(public_to_address(&public));
+    }
+    match &tx.action {
+        Action::Pay {
+            receiver,
+            ..
+        } => addresses.push(*receiver),
+        Action::Custom {
+            handler_id,
+            bytes,
+        } if *handler_id == STAKE_ACTION_HANDLER_ID => match rlp::decode(bytes) {
+            Ok(StakeAction::DelegateCCS {
+                address,
+                ..
+            })
+            | Ok(StakeAction::Revoke {
+                address,
+                ..
+            }) => addresses.push(address),
+            Ok(StakeAction::Redelegate {
+                prev_delegatee,
+                next_delegatee,
+                ..
+            }) => {
+                addresses.push(prev_delegatee);
+                addresses.push(next_delegatee);
+            }
+            _ => {}
+        },
+        _ => {}
+    }
+    if let Some(shard_tx) = tx.shard_transaction() {
+        let outputs: Vec<(&H160, &[Bytes])> = match &shard_tx {
+            ShardTransaction::MintAsset {
+                output,
+                ..
+            }
+            | ShardTransaction::IncreaseAssetSupply {
+                output,
+                ..
+            } => vec![(&output.lock_script_hash, &output.parameters[..])],
+            ShardTransaction::TransferAsset {
+                outputs,
+                ..
+            } => outputs.iter().map(|output| (&output.lock_script_hash, &output.parameters[..])).collect(),
+            ShardTransaction::WrapCCC {
+                output,
+                ..
+            } => vec![(&output.lock_script_hash, &output.parameters[..])],
+            ShardTransaction::ClawbackAsset {
+                lock_script_hash,
+                parameters,
+                ..
+            } => vec![(lock_script_hash, &parameters[..])],
+            _ => vec![],
+        };
+        addresses.extend(
+            outputs.into_iter().filter_map(|(lock_script_hash, parameters)| owner(lock_script_hash, parameters)),
+        );
+    }
+    addresses.sort_unstable();
+    addresses.dedup();
+    addresses
+}
+
+fn owner(lock_script_hash: &H160, parameters: &[Bytes]) -> Option<Address> {
+    if lock_script_hash[..] != P2PKH_LOCK_SCRIPT_HASH && lock_script_hash[..] != P2PKH_BURN_LOCK_SCRIPT_HASH {
+        return None
+    }
+    match parameters.first() {
+        Some(account_id) if account_id.len() == 20 => Some(H160::from_slice(account_id).into()),
+        _ => None,
+    }
+}
+
+enum AddressIndex {
+    TransactionCount = 0,
+    Transaction = 1,
+    Position = 2,
+    BestIndexedBlock = 3,
+}
+
+const BEST_INDEXED_BLOCK_KEY: &[u8] = &[AddressIndex::BestIndexedBlock as u8];
+
+pub struct AddressIndexKey([u8; 29]);
+
+impl Deref for AddressIndexKey {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// Finds the position of a transaction in the history of an address
+struct AddressTransaction {
+    address: Address,
+    tx_hash: TxHash,
+}
+
+pub struct AddressTransactionKey([u8; 53]);
+
+impl Deref for AddressTransactionKey {
+    type Target = [u8];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+/// The position of a transaction in the history of an address
+struct AddressHistoryIndex {
+    address: Address,
+    index: u64,
+}
+
+impl Key<u64> for Address {
+    type Target = AddressIndexKey;
+
+    fn key(&self) -> AddressIndexKey {
+        let mut result = [0u8; 29];
+        result[0] = AddressIndex::TransactionCount as u8;
+        result[1..21].copy_from_slice(&***self);
+        AddressIndexKey(result)
+    }
+}
+
+impl Key<TransactionAddress> for AddressHistoryIndex {
+    type Target = AddressIndexKey;
+
+    fn key(&self) -> AddressIndexKey {
+        let mut result = [0u8; 29];
+        result[0] = AddressIndex::Transaction as u8;
+        result[1..21].copy_from_slice(&**self.address);
+        result[21..].copy_from_slice(&self.index.to_be_bytes());
+        AddressIndexKey(result)
+    }
+}
+
+impl Key<u64> for AddressTransaction {
+    type Target = AddressTransactionKey;
+
+    fn key(&self) -> AddressTransactionKey {
+        let mut result = [0u8; 53];
+        result[0] = AddressIndex::Position as u8;
+        result[1..21].copy_from_slice(&**self.address);
+        result[21..].copy_from_slice(&**self.tx_hash);
+        AddressTransactionKey(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::body_db::BodyDB;
+    use super::super::route::TreeRoute;
+    use super::*;
+    use crate::block::Block;
+    use crate::encoded;
+    use crate::views::BlockView;
+    use ctypes::header::Seal;
+    use ctypes::transaction::Transaction;
+    use ctypes::{Header, Tracker};
+
+    #[derive(Default)]
+    struct TestBodies {
+        blocks: HashMap<BlockHash, Bytes>,
+    }
+
+    impl TestBodies {
+        fn insert(&mut self, parent: Option<&Header>, receivers: &[Address]) -> Header {
+            let mut header = Header::default();
+            if let Some(parent) = parent {
+                header.set_parent_hash(parent.hash());
+                header.set_number(parent.number() + 1);
+            }
+            let transactions = receivers
+                .iter()
+                .enumerate()
+                .map(|(seq, receiver)| {
+                    let tx = Transaction {
+                        seq: seq as u64,
+                        fee: header.number(),
+                        network_id: Default::default(),
+                        action: Action::Pay {
+                            receiver: *receiver,
+                            quantity: 1,
+                        },
+                    };
+                    UnverifiedTransaction::new(tx, Default::default())
+                })
+                .collect();
+            let block = Block {
+                header: header.clone(),
+                transactions,
+            };
+            self.blocks.insert(header.hash(), block.rlp_bytes(&Seal::With));
+            header
+        }
+
+        fn best_block_changed(&self, header: &Header, tree_route: Option<TreeRoute>) -> BestBlockChanged {
+            let best_block = self.blocks[&header.hash()].clone();
+            match tree_route {
+                Some(tree_route) => BestBlockChanged::BranchBecomingCanonChain {
+                    best_block,
+                    tree_route,
+                },
+                None => BestBlockChanged::CanonChainAppended {
+                    best_block,
+                },
+            }
+        }
+    }
+
+    impl BodyProvider for TestBodies {
+        fn is_known_body(&self, hash: &BlockHash) -> bool {
+            self.blocks.contains_key(hash)
+        }
+
+        fn transaction_address(&self, _hash: &TxHash) -> Option<TransactionAddress> {
+            None
+        }
+
+        fn transaction_address_by_tracker(&self, _tracker: &Tracker) -> Option<TransactionAddress> {
+            None
+        }
+
+        fn block_body(&self, hash: &BlockHash) -> Option<encoded::Body> {
+            let block = self.blocks.get(hash)?;
+            Some(encoded::Body::new(BodyDB::block_to_body(&BlockView::new(block))))
+        }
+    }
+
+    fn new_index(genesis: &Header, best: &Header) -> AddressIndexDB {
+        AddressIndexDB::new(Arc::new(kvdb_memorydb::create(db::NUM_COLUMNS.unwrap())), genesis.hash(), best.hash())
+    }
+
+    fn apply(index: &AddressIndexDB, best_block_changed: &BestBlockChanged, bodies: &dyn BodyProvider) {
+        let mut batch = DBTransaction::new();
+        index.update_best_block(&mut batch, best_block_changed, bodies);
+        index.db.write(batch).unwrap();
+    }
+
+    fn block_hashes(index: &AddressIndexDB, address: &Address) -> Vec<BlockHash> {
+        index.transaction_addresses(address, 0, 100).into_iter().map(|address| address.block_hash).collect()
+    }
+
+    #[test]
+    fn re_reported_block_is_indexed_once() {
+        let mut bodies = TestBodies::default();
+        let alice = Address::random();
+        let genesis = bodies.insert(None, &[]);
+        let index = new_index(&genesis, &genesis);
+        let block1 = bodies.insert(Some(&genesis), &[alice, alice]);
+
+        let changed = bodies.best_block_changed(&block1, None);
+        apply(&index, &changed, &bodies);
+        apply(&index, &changed, &bodies);
+
+        assert_eq!(2, index.transaction_count(&alice));
+        assert_eq!(vec![block1.hash(), block1.hash()], block_hashes(&index, &alice));
+    }
+
+    #[test]
+    fn reorg_replaces_retracted_transactions() {
+        let mut bodies = TestBodies::default();
+        let alice = Address::random();
+        let bob = Address::random();
+        let genesis = bodies.insert(None, &[]);
+        let index = new_index(&genesis, &genesis);
+        let block1 = bodies.insert(Some(&genesis), &[alice]);
+        let block2 = bodies.insert(Some(&block1), &[alice, bob]);
+        let fork2 = bodies.insert(Some(&block1), &[bob]);
+        let fork3 = bodies.insert(Some(&fork2), &[alice]);
+
+        apply(&index, &bodies.best_block_changed(&block1, None), &bodies);
+        apply(&index, &bodies.best_block_changed(&block2, None), &bodies);
+        assert_eq!(vec![block1.hash(), block2.hash()], block_hashes(&index, &alice));
+        assert_eq!(vec![block2.hash()], block_hashes(&index, &bob));
+
+        let tree_route = TreeRoute {
+            ancestor: block1.hash(),
+            enacted: vec![fork2.hash(), fork3.hash()],
+            retracted: vec![block2.hash()],
+        };
+        apply(&index, &bodies.best_block_changed(&fork3, Some(tree_route)), &bodies);
+
+        assert_eq!(2, index.transaction_count(&alice));
+        assert_eq!(vec![block1.hash(), fork3.hash()], block_hashes(&index, &alice));
+        assert_eq!(1, index.transaction_count(&bob));
+        assert_eq!(vec![fork2.hash()], block_hashes(&index, &bob));
+        assert_eq!(Some(fork3.hash()), index.best_indexed_block_hash());
+    }
+
+    #[test]
+    fn new_best_block_is_not_indexed_before_catching_up() {
+        let mut bodies = TestBodies::default();
+        let alice = Address::random();
+        let genesis = bodies.insert(None, &[]);
+        let block1 = bodies.insert(Some(&genesis), &[alice]);
+        let block2 = bodies.insert(Some(&block1), &[alice]);
+        let index = new_index(&genesis, &block1);
+
+        apply(&index, &bodies.best_block_changed(&block2, None), &bodies);
+
+        assert_eq!(0, index.transaction_count(&alice));
+        assert_eq!(None, index.best_indexed_block_hash());
+    }
+}
@@ -14,19 +14,23 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use super::address_index_db::AddressIndexDB;
 use super::block_info::BestBlockChanged;
 use super::body_db::{BodyDB, BodyProvider};
 use super::extras::{BlockDetails, TransactionAddress};
 use super::headerchain::{HeaderChain, HeaderProvider};
 use super::invoice_db::{InvoiceDB, InvoiceProvider};
+use super::receipt_db::{ReceiptDB, ReceiptProvider};
 use super::route::{tree_route, ImportRoute};
 use crate::blockchain_info::BlockChainInfo;
 use crate::consensus::CodeChainEngine;
 use crate::db;
 use crate::encoded;
 use crate::invoice::Invoice;
+use crate::receipt::Receipt;
 use crate::transaction::LocalizedTransaction;
 use crate::views::{BlockView, HeaderView};
+use ckey::Address;
 use ctypes::{BlockHash, BlockNumber, Tracker, TxHash};
 use kvdb::{DBTransaction, KeyValueDB};
 use parking_lot::RwLock;
@@ -49,6 +53,8 @@ pub struct BlockChain {
     headerchain: HeaderChain,
     body_db: BodyDB,
     invoice_db: InvoiceDB,
+    receipt_db: ReceiptDB,
+    address_index_db: Option<AddressIndexDB>,
 
     pending_best_block_hash: RwLock<Option<BlockHash>>,
     pending_best_proposal_block_hash: RwLock<Option<BlockHash>>,
@@ -56,7 +62,8 @@ pub struct BlockChain {
 
 impl BlockChain {
     /// Create new instance of blockchain from given Genesis.
-    pub fn new(genesis: &[u8], db: Arc<dyn KeyValueDB>) -> Self {
+    /// The transactions are indexed by the related addresses only if `address_index` is true.
+    pub fn new(genesis: &[u8], db: Arc<dyn KeyValueDB>, address_index: bool) -> Self {
         let genesis_block = BlockView::new(genesis);
 
         // load best block
@@ -83,19 +90,41 @@ impl BlockChain {
             }
         };
 
+        let headerchain = HeaderChain::new(&genesis_block.header_view(), db.clone());
+        let body_db = BodyDB::new(&genesis_block, db.clone());
+        // The address index catches up with the chain in the background. See `catch_up_address_index`.
+        let address_index_db = if address_index {
+            Some(AddressIndexDB::new(db.clone(), genesis_block.hash(), best_block_hash))
+        } else {
+            None
+        };
+
         Self {
             best_block_hash: RwLock::new(best_block_hash),
             best_proposal_block_hash: RwLock::new(best_proposal_block_hash),
 
-            headerchain: HeaderChain::new(&genesis_block.header_view(), db.clone()),
-            body_db: BodyDB::new(&genesis_block, db.clone()),
+            headerchain,
+            body_db,
             invoice_db: InvoiceDB::new(db.clone()),
+            receipt_db: ReceiptDB::new(db.clone()),
+            address_index_db,
 
             pending_best_block_hash: RwLock::new(None),
             pending_best_proposal_block_hash: RwLock::new(None),
         }
     }
 
+    /// Indexes a chunk of the canonical blocks that are not indexed by the addresses yet.
+    /// It returns true if there is nothing left to index.
+    pub fn catch_up_address_index(&self) -> bool {
+        match &self.address_index_db {
+            Some(address_index_db) => {
+                address_index_db.catch_up(&self.headerchain, &self.body_db, self.best_block_hash())
+            }
+            None => true,
+        }
+    }
+
     pub fn insert_bootstrap_header(&self, batch: &mut DBTransaction, header: &HeaderView) {
         self.headerchain.insert_bootstrap_header(batch, header);
 
@@ -108,6 +137,11 @@ impl BlockChain {
         batch.put(db::COL_EXTRA, BEST_PROPOSAL_BLOCK_KEY, &hash);
     }
 
+    /// Inserts the body of a bootstrap block whose header is already inserted.
+    pub fn insert_bootstrap_body(&self, batch: &mut DBTransaction, block: &BlockView) {
+        self.body_db.insert_body(batch, block);
+    }
+
     pub fn insert_header(
         &self,
         batch: &mut DBTransaction,
@@ -128,6 +162,7 @@ impl BlockChain {
         batch: &mut DBTransaction,
         bytes: &[u8],
         invoices: Vec<Invoice>,
+        receipts: Vec<Receipt>,
         engine: &dyn CodeChainEngine,
     ) -> ImportRoute {
         // create views onto rlp
@@ -150,9 +185,17 @@ impl BlockChain {
         self.headerchain.insert_header(batch, &new_header, engine);
         self.body_db.insert_body(batch, &new_block);
         self.body_db.update_best_block(batch, &best_block_changed);
+        if let Some(address_index_db) = &self.address_index_db {
+            address_index_db.update_best_block(batch, &best_block_changed, &self.body_db);
+        }
         for invoice in invoices {
             self.invoice_db.insert_invoice(batch, invoice.hash, invoice.tracker, invoice.error);
         }
+        let is_best_block = best_block_changed.new_best_hash().is_some();
+        for mut receipt in receipts {
+            receipt.block_hash = new_block_hash;
+            self.receipt_db.insert_receipt(batch, receipt, is_best_block);
+        }
 
         if let Some(best_block_hash) = best_block_changed.new_best_hash() {
             let mut pending_best_block_hash = self.pending_best_block_hash.write();
@@ -172,7 +215,7 @@ impl BlockChain {
         ctrace!(BLOCKCHAIN, "Committing.");
         self.headerchain.commit();
         self.body_db.commit();
-        // NOTE: There are no commit for InvoiceDB
+        // NOTE: There are no commit for InvoiceDB and ReceiptDB
 
         let mut best_block_hash = self.best_block_hash.write();
         let mut pending_best_block_hash = self.pending_best_block_hash.write();
@@ -285,6 +328,9 @@ impl BlockChain {
 
         self.headerchain.update_best_as_committed(batch, block_hash);
         self.body_db.update_best_block(batch, &best_block_changed);
+        if let Some(address_index_db) = &self.address_index_db {
+            address_index_db.update_best_block(batch, &best_block_changed, &self.body_db);
+        }
 
         let mut pending_best_block_hash = self.pending_best_block_hash.write();
         batch.put(db::COL_EXTRA, BEST_BLOCK_KEY, &block_hash);
@@ -353,10 +399,23 @@ impl BlockChain {
     pub fn best_proposal_header(&self) -> encoded::Header {
         self.headerchain.best_proposal_header()
     }
+
+    /// Get at most `limit` addresses of the canonical transactions related to the given address,
+    /// skipping the first `offset` ones.
+    /// Returns None if the address index is disabled.
+    pub fn transaction_addresses_by_address(
+        &self,
+        address: &Address,
+        offset: u64,
+        limit: u64,
+    ) -> Option<Vec<TransactionAddress>> {
+        let address_index_db = self.address_index_db.as_ref()?;
+        Some(address_index_db.transaction_addresses(address, offset, limit))
+    }
 }
 
 /// Interface for querying blocks by hash and by number.
-pub trait BlockProvider: HeaderProvider + BodyProvider + InvoiceProvider {
+pub trait BlockProvider: HeaderProvider + BodyProvider + InvoiceProvider + ReceiptProvider {
     /// Returns true if the given block is known
     /// (though not necessarily a part of the canon chain).
     fn is_known(&self, hash: &BlockHash) -> bool {
@@ -449,4 +508,10 @@ impl InvoiceProvider for BlockChain {
     }
 }
 
+impl ReceiptProvider for BlockChain {
+    fn receipt(&self, hash: &TxHash) -> Option<Receipt> {
+        self.receipt_db.receipt(hash)
+    }
+}
+
 impl BlockProvider for BlockChain {}
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod address_index_db;
 mod block_info;
 #[cfg_attr(feature = "cargo-clippy", allow(clippy::module_inception))]
 mod blockchain;
@@ -21,6 +22,7 @@ mod body_db;
 mod extras;
 mod headerchain;
 mod invoice_db;
+mod receipt_db;
 mod route;
 
 pub use self::blockchain::{BlockChain, BlockProvider};
@@ -28,4 +30,5 @@ pub use self::body_db::BodyProvider;
 pub use self::extras::{BlockDetails, TransactionAddress, TransactionAddresses};
 pub use self::headerchain::HeaderProvider;
 pub use self::invoice_db::InvoiceProvider;
+pub use self::receipt_db::ReceiptProvider;
 pub use self::route::ImportRoute;
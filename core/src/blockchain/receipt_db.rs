@@ -0,0 +1,112 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::db::{self, CacheUpdatePolicy, Key, Readable, Writable};
+use crate::receipt::Receipt;
+use ctypes::TxHash;
+use kvdb::{DBTransaction, KeyValueDB};
+use parking_lot::RwLock;
+use primitives::H256;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Structure providing fast access to the execution results of transactions.
+///
+/// **Does not do input data verification.**
+pub struct ReceiptDB {
+    // transaction hash -> receipt
+    receipt_cache: RwLock<HashMap<TxHash, Receipt>>,
+
+    db: Arc<dyn KeyValueDB>,
+}
+
+impl ReceiptDB {
+    pub fn new(db: Arc<dyn KeyValueDB>) -> Self {
+        Self {
+            receipt_cache: Default::default(),
+
+            db,
+        }
+    }
+
+    /// Inserts the receipt into backing cache database.
+    /// The receipt of a transaction that failed before is overwritten.
+    /// The receipt of a block that doesn't become the best block doesn't overwrite the existing one,
+    /// not to hide the receipt of the canonical chain.
+    pub fn insert_receipt(&self, batch: &mut DBTransaction, receipt: Receipt, is_best_block: bool) {
+        if !is_best_block && self.receipt(&receipt.hash).is_some() {
+            return
+        }
+        let mut receipt_cache = self.receipt_cache.write();
+        batch.write_with_cache(db::COL_RECEIPT, &mut *receipt_cache, receipt.hash, receipt, CacheUpdatePolicy::Remove);
+    }
+}
+
+/// Interface for querying receipts.
+pub trait ReceiptProvider {
+    /// Get the receipt of the transaction with the given hash.
+    fn receipt(&self, hash: &TxHash) -> Option<Receipt>;
+}
+
+impl ReceiptProvider for ReceiptDB {
+    fn receipt(&self, hash: &TxHash) -> Option<Receipt> {
+        self.db.read_with_cache(db::COL_RECEIPT, &mut *self.receipt_cache.write(), hash)
+    }
+}
+
+impl Key<Receipt> for TxHash {
+    type Target = H256;
+
+    fn key(&self) -> H256 {
+        **self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ctypes::BlockHash;
+
+    fn receipt(hash: TxHash, block_hash: BlockHash) -> Receipt {
+        Receipt {
+            hash,
+            block_hash,
+            block_number: 1,
+            error: None,
+            fee: 10,
+            created_assets: Vec::new(),
+            touched_action_data: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn receipt_of_a_side_block_does_not_overwrite_the_existing_one() {
+        let db = Arc::new(kvdb_memorydb::create(db::NUM_COLUMNS.unwrap()));
+        let receipt_db = ReceiptDB::new(db.clone());
+        let tx_hash = TxHash::from(H256::random());
+        let best_block_hash = BlockHash::from(H256::random());
+
+        let mut batch = DBTransaction::new();
+        receipt_db.insert_receipt(&mut batch, receipt(tx_hash, best_block_hash), true);
+        db.write(batch).unwrap();
+
+        let mut batch = DBTransaction::new();
+        receipt_db.insert_receipt(&mut batch, receipt(tx_hash, BlockHash::from(H256::random())), false);
+        db.write(batch).unwrap();
+
+        assert_eq!(Some(best_block_hash), receipt_db.receipt(&tx_hash).map(|receipt| receipt.block_hash));
+    }
+}
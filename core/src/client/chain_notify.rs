@@ -48,4 +48,9 @@ pub trait ChainNotify: Send + Sync {
     fn transactions_received(&self, _hashes: Vec<TxHash>, _peer_id: NodeId) {
         // does nothing by default
     }
+
+    /// fires when transactions are added to the mem pool
+    fn new_pending_transactions(&self, _hashes: Vec<TxHash>) {
+        // does nothing by default
+    }
 }
@@ -18,15 +18,18 @@ use super::importer::Importer;
 use super::{
     AccountData, AssetClient, BlockChainClient, BlockChainInfo, BlockChainTrait, BlockProducer, ChainNotify,
     ClientConfig, DatabaseClient, EngineClient, EngineInfo, ExecuteClient, ImportBlock, ImportResult,
-    MiningBlockChainClient, Shard, StateInfo, StateOrBlock, TextClient,
+    MiningBlockChainClient, PruningMode, Shard, StateInfo, StateOrBlock, StateProofClient, TextClient,
+};
+use crate::block::{Block, ClosedBlock, IsBlock, OpenBlock, SealedBlock};
+use crate::blockchain::{
+    BlockChain, BlockProvider, BodyProvider, HeaderProvider, InvoiceProvider, ReceiptProvider, TransactionAddress,
 };
-use crate::block::{ClosedBlock, IsBlock, OpenBlock, SealedBlock};
-use crate::blockchain::{BlockChain, BlockProvider, BodyProvider, HeaderProvider, InvoiceProvider, TransactionAddress};
 use crate::client::{ConsensusClient, SnapshotClient, TermInfo};
 use crate::consensus::{CodeChainEngine, EngineError};
 use crate::encoded;
 use crate::error::{BlockImportError, Error, ImportError, SchemeError};
 use crate::miner::{Miner, MinerService};
+use crate::receipt::Receipt;
 use crate::scheme::Scheme;
 use crate::service::ClientIoMessage;
 use crate::transaction::{LocalizedTransaction, PendingSignedTransactions, SignedTransaction, UnverifiedTransaction};
@@ -37,12 +40,15 @@ use ckey::{Address, NetworkId, PlatformAddress, Public};
 use cmerkle::Result as TrieResult;
 use cnetwork::NodeId;
 use cstate::{
-    ActionHandler, AssetScheme, FindActionHandler, OwnedAsset, StateDB, StateResult, Text, TopLevelState, TopStateView,
+    ActionHandler, AssetScheme, FindActionHandler, OwnedAsset, StateDB, StateProof, StatePruner, StateResult, Text,
+    TopLevelState, TopStateView,
 };
 use ctimer::{TimeoutHandler, TimerApi, TimerScheduleError, TimerToken};
 use ctypes::transaction::{AssetTransferInput, PartialHashing, ShardTransaction};
 use ctypes::{BlockHash, BlockNumber, CommonParams, Header, ShardId, Tracker, TxHash};
-use cvm::{decode, execute, ChainTimeInfo, ScriptResult, VMConfig};
+use cvm::{
+    decode_at_era, execute_with_cost, execute_with_trace, ChainTimeInfo, RuntimeError, ScriptResult, Step, VMConfig,
+};
 use kvdb::{DBTransaction, KeyValueDB};
 use parking_lot::{Mutex, RwLock, RwLockReadGuard};
 use primitives::{Bytes, H160, H256, U256};
@@ -89,20 +95,30 @@ impl Client {
         reseal_timer: TimerApi,
     ) -> Result<Arc<Client>, Error> {
         let journal_db = new_journaldb(Arc::clone(&db), Algorithm::Archive, crate::db::COL_STATE);
-        let mut state_db = StateDB::new(journal_db);
+        let mut state_db = match config.pruning {
+            PruningMode::Archive => StateDB::new(journal_db),
+            PruningMode::Fast => {
+                let pruner = StatePruner::new(Arc::clone(&db), crate::db::COL_STATE, config.pruning_history);
+                StateDB::new_with_pruner(journal_db, pruner)
+            }
+        };
         if !scheme.check_genesis_root(state_db.as_hashdb()) {
             return Err(SchemeError::InvalidState.into())
         }
+        let mut batch = DBTransaction::new();
         if state_db.is_empty() {
             // Sets the correct state root.
             state_db = scheme.ensure_genesis_state(state_db)?;
-            let mut batch = DBTransaction::new();
+            state_db.pin_root(&mut batch, *scheme.genesis_header().state_root());
             state_db.journal_under(&mut batch, 0, *scheme.genesis_header().hash())?;
-            db.write(batch)?;
+        } else {
+            // The genesis state is never pruned even if the pruning is enabled later.
+            state_db.pin_root(&mut batch, *scheme.genesis_header().state_root());
         }
+        db.write(batch)?;
 
         let gb = scheme.genesis_block();
-        let chain = BlockChain::new(&gb, db.clone());
+        let chain = BlockChain::new(&gb, db.clone(), config.address_index);
         scheme.check_genesis_common_params(&chain)?;
 
         let engine = scheme.engine.clone();
@@ -133,6 +149,12 @@ impl Client {
         &*self.engine
     }
 
+    /// Verifies the commit of a bootstrap block, which is sealed in its child header.
+    /// The state of the bootstrap block must be restored before the verification.
+    pub fn verify_bootstrap_commit(&self, child: &Header) -> Result<(), Error> {
+        self.engine().verify_block_external(child)
+    }
+
     /// Adds an actor to be notified on certain events
     pub fn add_notify(&self, target: Weak<dyn ChainNotify>) {
         self.notify.write().push(target);
@@ -144,6 +166,12 @@ impl Client {
         });
     }
 
+    pub fn new_pending_transactions(&self, hashes: &[TxHash]) {
+        self.notify(|notify| {
+            notify.new_pending_transactions(hashes.to_vec());
+        });
+    }
+
     pub fn new_blocks(
         &self,
         imported: &[BlockHash],
@@ -244,9 +272,19 @@ impl Client {
         let hashes: Vec<_> = transactions.iter().map(UnverifiedTransaction::hash).collect();
         self.transactions_received(&hashes, peer_id);
         let results = self.importer.miner.import_external_transactions(self, transactions);
+        let imported: Vec<_> =
+            hashes.into_iter().zip(results.iter()).filter(|(_, result)| result.is_ok()).map(|(hash, _)| hash).collect();
+        self.new_pending_transactions(&imported);
         results.len()
     }
 
+    /// Indexes a chunk of the blocks that are not indexed by the addresses yet.
+    /// It returns true if there is nothing left to index.
+    pub fn catch_up_address_index(&self) -> bool {
+        let _import_lock = self.importer.import_lock.lock();
+        self.block_chain().catch_up_address_index()
+    }
+
     /// This is triggered by a message coming from the Tendermint engine when a block is committed.
     /// See EngineClient::update_best_as_committed() for details.
     pub fn update_best_as_committed(&self, block_hash: BlockHash) {
@@ -265,6 +303,14 @@ impl Client {
             let mut state_db = self.state_db().write();
             state_db.clear_cache();
 
+            // The committed block becomes the best block here instead of when it is imported.
+            if !route.is_none() {
+                let number = chain.block_number(&block_hash).expect("The committed block must be imported");
+                let mut batch = DBTransaction::new();
+                state_db.prune(&mut batch, number);
+                self.db().write_buffered(batch);
+            }
+
             route
         };
 
@@ -411,9 +457,51 @@ impl TextClient for Client {
     }
 }
 
+impl StateProofClient for Client {
+    fn account_proof(&self, address: &Address, id: BlockId) -> TrieResult<Option<StateProof>> {
+        if let Some(state) = Client::state_at(&self, id) {
+            Ok(Some(state.account_proof(address)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn shard_proof(&self, shard_id: ShardId, id: BlockId) -> TrieResult<Option<StateProof>> {
+        if let Some(state) = Client::state_at(&self, id) {
+            Ok(Some(state.shard_proof(shard_id)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn action_data_proof(&self, key: &H256, id: BlockId) -> TrieResult<Option<StateProof>> {
+        if let Some(state) = Client::state_at(&self, id) {
+            Ok(Some(state.action_data_proof(key)?))
+        } else {
+            Ok(None)
+        }
+    }
+
+    fn asset_proof(
+        &self,
+        tracker: Tracker,
+        index: usize,
+        shard_id: ShardId,
+        id: BlockId,
+    ) -> TrieResult<Option<(StateProof, Option<StateProof>)>> {
+        if let Some(state) = Client::state_at(&self, id) {
+            Ok(Some(state.asset_proof(shard_id, tracker, index)?))
+        } else {
+            Ok(None)
+        }
+    }
+}
+
 impl ExecuteClient for Client {
     fn execute_transaction(&self, transaction: &ShardTransaction, sender: &Address) -> StateResult<()> {
         let mut state = Client::state_at(&self, BlockId::Latest).expect("Latest state MUST exist");
+        let params = self.common_params(BlockId::Latest).expect("Latest state MUST exist");
+        state.set_script_params(&params);
         state.apply_shard_transaction(
             transaction,
             sender,
@@ -430,38 +518,62 @@ impl ExecuteClient for Client {
         inputs: &[AssetTransferInput],
         params: &[Vec<Bytes>],
         indices: &[usize],
-    ) -> Result<Vec<String>, DatabaseError> {
+        trace: bool,
+    ) -> Result<Vec<(String, u64, Option<Vec<Step>>)>, DatabaseError> {
+        let common_params = self.common_params(BlockId::Latest).expect("Latest state MUST exist");
         let mut results = Vec::with_capacity(indices.len());
         for (i, index) in indices.iter().enumerate() {
             let input = inputs.get(*index);
             let param = params.get(i);
             let result = match (input, param) {
                 (Some(input), Some(param)) => {
-                    let lock_script = decode(&input.lock_script);
-                    let unlock_script = decode(&input.unlock_script);
+                    let lock_script = decode_at_era(&input.lock_script, common_params.era());
+                    let unlock_script = decode_at_era(&input.unlock_script, common_params.era());
                     match (lock_script, unlock_script) {
                         (Ok(lock_script), Ok(unlock_script)) => {
-                            match execute(
-                                &unlock_script,
-                                &param,
-                                &lock_script,
-                                tx,
-                                VMConfig::default(),
-                                &input,
-                                false,
-                                self,
-                                self.best_block_header().number(),
-                                self.best_block_header().timestamp(),
-                            ) {
-                                Ok(ScriptResult::Burnt) => "burnt".to_string(),
-                                Ok(ScriptResult::Unlocked) => "unlocked".to_string(),
-                                _ => "failed".to_string(),
-                            }
+                            let config = VMConfig::with_max_cost(common_params.max_script_cost());
+                            let best_block_header = self.best_block_header();
+                            let (result, cost, steps) = if trace {
+                                let (result, cost, steps) = execute_with_trace(
+                                    &unlock_script,
+                                    &param,
+                                    &lock_script,
+                                    tx,
+                                    config,
+                                    &input,
+                                    false,
+                                    self,
+                                    best_block_header.number(),
+                                    best_block_header.timestamp(),
+                                );
+                                (result, cost, Some(steps))
+                            } else {
+                                let (result, cost) = execute_with_cost(
+                                    &unlock_script,
+                                    &param,
+                                    &lock_script,
+                                    tx,
+                                    config,
+                                    &input,
+                                    false,
+                                    self,
+                                    best_block_header.number(),
+                                    best_block_header.timestamp(),
+                                );
+                                (result, cost, None)
+                            };
+                            let result = match result {
+                                Ok(ScriptResult::Burnt) => "burnt",
+                                Ok(ScriptResult::Unlocked) => "unlocked",
+                                Err(RuntimeError::OutOfGas) => "outOfGas",
+                                _ => "failed",
+                            };
+                            (result.to_string(), cost, steps)
                         }
-                        _ => "invalid".to_string(),
+                        _ => ("invalid".to_string(), 0, None),
                     }
                 }
-                _ => "invalid".to_string(),
+                _ => ("invalid".to_string(), 0, None),
             };
             results.push(result);
         }
@@ -476,6 +588,10 @@ impl StateInfo for Client {
             TopLevelState::from_existing(self.state_db.read().clone(&root), root).ok()
         })
     }
+
+    fn is_state_pruned(&self, id: BlockId) -> bool {
+        self.block_header(&id).map_or(false, |header| !self.state_db.read().as_hashdb().contains(&header.state_root()))
+    }
 }
 
 impl EngineInfo for Client {
@@ -644,6 +760,16 @@ impl ImportBlock for Client {
         Ok(header.hash())
     }
 
+    fn import_bootstrap_block(&self, block: &Block) -> Result<BlockHash, BlockImportError> {
+        let hash = block.header.hash();
+        if self.block_chain().block_body(&hash).is_some() {
+            return Err(BlockImportError::Import(ImportError::AlreadyInChain))
+        }
+        let import_lock = self.importer.import_lock.lock();
+        self.importer.import_bootstrap_block(block, self, &import_lock);
+        Ok(hash)
+    }
+
     fn import_sealed_block(&self, block: &SealedBlock) -> ImportResult {
         let h = block.header().hash();
         let route = {
@@ -689,7 +815,9 @@ impl BlockChainClient for Client {
 
     /// Import own transaction
     fn queue_own_transaction(&self, transaction: SignedTransaction) -> Result<(), Error> {
+        let hash = transaction.hash();
         self.importer.miner.import_own_transaction(self, transaction)?;
+        self.new_pending_transactions(&[hash]);
         Ok(())
     }
 
@@ -777,6 +905,28 @@ impl BlockChainClient for Client {
         let chain = self.block_chain();
         chain.error_hints_by_tracker(tracker)
     }
+
+    fn transaction_receipt(&self, hash: &TxHash) -> Option<Receipt> {
+        let chain = self.block_chain();
+        // The receipts of the retracted blocks are not served.
+        chain.receipt(hash).filter(|receipt| chain.block_hash(receipt.block_number) == Some(receipt.block_hash))
+    }
+
+    fn block_receipts(&self, id: &BlockId) -> Option<Vec<Receipt>> {
+        let chain = self.block_chain();
+        let block_hash = Self::block_hash(&chain, id)?;
+        let body = chain.block_body(&block_hash)?;
+        body.transaction_hashes()
+            .iter()
+            .map(|hash| chain.receipt(hash).filter(|receipt| receipt.block_hash == block_hash))
+            .collect()
+    }
+
+    fn transactions_by_address(&self, address: &Address, offset: u64, limit: u64) -> Option<Vec<LocalizedTransaction>> {
+        let chain = self.block_chain();
+        let addresses = chain.transaction_addresses_by_address(address, offset, limit)?;
+        Some(addresses.iter().filter_map(|transaction_address| chain.transaction(transaction_address)).collect())
+    }
 }
 
 impl TermInfo for Client {
@@ -60,6 +60,33 @@ impl FromStr for DatabaseCompactionProfile {
     }
 }
 
+/// The way to keep the states of the old blocks
+#[derive(Debug, PartialEq, Clone, Copy)]
+pub enum PruningMode {
+    /// Keep all the states
+    Archive,
+    /// Keep only the states of the recent blocks
+    Fast,
+}
+
+impl Default for PruningMode {
+    fn default() -> Self {
+        PruningMode::Archive
+    }
+}
+
+impl FromStr for PruningMode {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "archive" => Ok(PruningMode::Archive),
+            "fast" => Ok(PruningMode::Fast),
+            _ => Err("Invalid pruning mode given. Expected archive/fast.".into()),
+        }
+    }
+}
+
 /// Client configuration. Includes configs for all sub-systems.
 #[derive(Debug, PartialEq)]
 pub struct ClientConfig {
@@ -73,18 +100,28 @@ pub struct ClientConfig {
     pub state_cache_size: usize,
     /// Type of block verifier used by client.
     pub verifier_type: VerifierType,
+    /// Index the transactions by the related addresses.
+    pub address_index: bool,
+    /// State pruning mode.
+    pub pruning: PruningMode,
+    /// The number of the recent blocks whose states are kept in the fast pruning mode.
+    pub pruning_history: u64,
 }
 
 impl Default for ClientConfig {
     fn default() -> Self {
         let mb = 1024 * 1024;
         const DEFAULT_STATE_CACHE_SIZE: u32 = 25;
+        const DEFAULT_PRUNING_HISTORY: u64 = 1024;
         Self {
             queue: Default::default(),
             db_cache_size: Default::default(),
             db_compaction: Default::default(),
             state_cache_size: DEFAULT_STATE_CACHE_SIZE as usize * mb,
             verifier_type: Default::default(),
+            address_index: false,
+            pruning: Default::default(),
+            pruning_history: DEFAULT_PRUNING_HISTORY,
         }
     }
 }
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::{BlockChainTrait, Client, ClientConfig};
-use crate::block::{enact, IsBlock, LockedBlock};
+use crate::block::{enact, Block, IsBlock, LockedBlock};
 use crate::blockchain::{BodyProvider, HeaderProvider, ImportRoute};
 use crate::client::EngineInfo;
 use crate::consensus::CodeChainEngine;
@@ -27,7 +27,7 @@ use crate::verification::queue::{BlockQueue, HeaderQueue};
 use crate::verification::{self, PreverifiedBlock, Verifier};
 use crate::views::{BlockView, HeaderView};
 use cio::IoChannel;
-use ctypes::header::Header;
+use ctypes::header::{Header, Seal};
 use ctypes::BlockHash;
 use kvdb::DBTransaction;
 use parking_lot::{Mutex, MutexGuard};
@@ -184,22 +184,28 @@ impl Importer {
 
         // Commit results
         let invoices = block.invoices().to_owned();
+        let receipts = block.receipts().to_owned();
 
         assert_eq!(hash, BlockView::new(block_data).header_view().hash());
 
         let mut batch = DBTransaction::new();
 
         block.state().journal_under(&mut batch, number).expect("DB commit failed");
-        let route = chain.insert_block(&mut batch, block_data, invoices, self.engine.borrow());
+        let route = chain.insert_block(&mut batch, block_data, invoices, receipts, self.engine.borrow());
 
         // Final commit to the DB
         client.db().write_buffered(batch);
         chain.commit();
 
+        // The engines committing the blocks later prune the states in `Client::update_best_as_committed`.
         if hash == chain.best_block_hash() {
             let mut state_db = client.state_db().write();
             let state = block.state();
             state_db.override_state(&state);
+
+            let mut batch = DBTransaction::new();
+            state_db.prune(&mut batch, number);
+            client.db().write_buffered(batch);
         }
 
         route
@@ -376,6 +382,21 @@ impl Importer {
         client.db().flush().expect("DB flush failed.");
     }
 
+    pub fn import_bootstrap_block<'a>(&'a self, block: &'a Block, client: &Client, _importer_lock: &MutexGuard<()>) {
+        let hash = block.header.hash();
+        ctrace!(CLIENT, "Importing bootstrap block body {}-{:?}", block.header.number(), hash);
+
+        {
+            let chain = client.block_chain();
+            let mut batch = DBTransaction::new();
+            chain.insert_bootstrap_body(&mut batch, &BlockView::new(&block.rlp_bytes(&Seal::With)));
+            client.db().write_buffered(batch);
+            chain.commit();
+        }
+
+        client.db().flush().expect("DB flush failed.");
+    }
+
     fn check_header(&self, header: &Header, parent: &Header) -> bool {
         // FIXME: self.verifier.verify_block_family
         if let Err(e) = self.engine.verify_block_family(&header, &parent) {
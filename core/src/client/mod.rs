@@ -25,24 +25,25 @@ mod test_client;
 pub use self::chain_notify::ChainNotify;
 
 pub use self::client::Client;
-pub use self::config::ClientConfig;
+pub use self::config::{ClientConfig, PruningMode};
 pub use self::test_client::TestBlockChainClient;
 
-use crate::block::{ClosedBlock, OpenBlock, SealedBlock};
+use crate::block::{Block, ClosedBlock, OpenBlock, SealedBlock};
 use crate::blockchain_info::BlockChainInfo;
 use crate::consensus::EngineError;
 use crate::encoded;
 use crate::error::{BlockImportError, Error as GenericError};
+use crate::receipt::Receipt;
 use crate::transaction::{LocalizedTransaction, PendingSignedTransactions, SignedTransaction};
 use crate::types::{BlockId, BlockStatus, TransactionId, VerificationQueueInfo as BlockQueueInfo};
 use cdb::DatabaseError;
 use ckey::{Address, NetworkId, PlatformAddress, Public};
 use cmerkle::Result as TrieResult;
 use cnetwork::NodeId;
-use cstate::{AssetScheme, FindActionHandler, OwnedAsset, StateResult, Text, TopLevelState, TopStateView};
+use cstate::{AssetScheme, FindActionHandler, OwnedAsset, StateProof, StateResult, Text, TopLevelState, TopStateView};
 use ctypes::transaction::{AssetTransferInput, PartialHashing, ShardTransaction};
 use ctypes::{BlockHash, BlockNumber, CommonParams, Header, ShardId, Tracker, TxHash};
-use cvm::ChainTimeInfo;
+use cvm::{ChainTimeInfo, Step};
 use kvdb::KeyValueDB;
 use primitives::{Bytes, H160, H256, U256};
 use std::ops::Range;
@@ -197,6 +198,10 @@ pub trait ImportBlock {
     /// Bootstrap headers don't execute any verifications
     fn import_bootstrap_header(&self, bytes: &Header) -> Result<BlockHash, BlockImportError>;
 
+    /// Import the transactions of a trusted bootstrap block whose header is already imported
+    /// The transactions are not executed
+    fn import_bootstrap_block(&self, block: &Block) -> Result<BlockHash, BlockImportError>;
+
     /// Import sealed block. Skips all verifications.
     fn import_sealed_block(&self, block: &SealedBlock) -> ImportResult;
 
@@ -253,6 +258,17 @@ pub trait BlockChainClient: Sync + Send + AccountData + BlockChainTrait + Import
     fn transaction_by_tracker(&self, tracker: &Tracker) -> Option<LocalizedTransaction>;
 
     fn error_hints_by_tracker(&self, tracker: &Tracker) -> Vec<(TxHash, Option<String>)>;
+
+    /// Get the receipt of the transaction with given hash.
+    fn transaction_receipt(&self, hash: &TxHash) -> Option<Receipt>;
+
+    /// Get the receipts of all transactions in the given block.
+    /// Returns None if the block or any of its receipts is not known.
+    fn block_receipts(&self, id: &BlockId) -> Option<Vec<Receipt>>;
+
+    /// Get at most `limit` canonical transactions related to the given address, skipping the first `offset` ones.
+    /// Returns None if the address index is disabled.
+    fn transactions_by_address(&self, address: &Address, offset: u64, limit: u64) -> Option<Vec<LocalizedTransaction>>;
 }
 
 /// Result of import block operation.
@@ -316,16 +332,39 @@ pub trait TextClient {
     fn get_text(&self, tx_hash: TxHash, id: BlockId) -> TrieResult<Option<Text>>;
 }
 
+/// Provides Merkle proofs of the state, which can be verified against the state root of the block.
+/// They return `None` if the state of the block is not available.
+pub trait StateProofClient {
+    fn account_proof(&self, address: &Address, id: BlockId) -> TrieResult<Option<StateProof>>;
+
+    fn shard_proof(&self, shard_id: ShardId, id: BlockId) -> TrieResult<Option<StateProof>>;
+
+    fn action_data_proof(&self, key: &H256, id: BlockId) -> TrieResult<Option<StateProof>>;
+
+    /// Returns the proof of the shard in the top-level trie and, if the shard exists,
+    /// the proof of the asset in the shard-level trie.
+    fn asset_proof(
+        &self,
+        tracker: Tracker,
+        index: usize,
+        shard_id: ShardId,
+        id: BlockId,
+    ) -> TrieResult<Option<(StateProof, Option<StateProof>)>>;
+}
+
 pub trait ExecuteClient: ChainTimeInfo {
     fn execute_transaction(&self, transaction: &ShardTransaction, sender: &Address) -> StateResult<()>;
 
+    /// Returns the result of each input with the cost its scripts consumed.
+    /// The steps of the scripts are returned too if `trace` is true.
     fn execute_vm(
         &self,
         tx: &dyn PartialHashing,
         inputs: &[AssetTransferInput],
         params: &[Vec<Bytes>],
         indices: &[usize],
-    ) -> Result<Vec<String>, DatabaseError>;
+        trace: bool,
+    ) -> Result<Vec<(String, u64, Option<Vec<Step>>)>, DatabaseError>;
 }
 
 pub trait StateInfo {
@@ -335,6 +374,11 @@ pub trait StateInfo {
     /// Otherwise, this can fail (but may not) if the DB prunes state or the block
     /// is unknown.
     fn state_at(&self, id: BlockId) -> Option<TopLevelState>;
+
+    /// Check whether the state of the block is pruned.
+    ///
+    /// It returns false if the block is unknown.
+    fn is_state_pruned(&self, id: BlockId) -> bool;
 }
 
 pub trait SnapshotClient {
@@ -30,7 +30,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use crate::block::{ClosedBlock, OpenBlock, SealedBlock};
+use crate::block::{Block, ClosedBlock, OpenBlock, SealedBlock};
 use crate::blockchain_info::BlockChainInfo;
 use crate::client::{
     AccountData, BlockChainClient, BlockChainTrait, BlockProducer, BlockStatus, ConsensusClient, EngineInfo,
@@ -42,6 +42,7 @@ use crate::db::{COL_STATE, NUM_COLUMNS};
 use crate::encoded;
 use crate::error::{BlockImportError, Error as GenericError};
 use crate::miner::{Miner, MinerService, TransactionImportResult};
+use crate::receipt::Receipt;
 use crate::scheme::Scheme;
 use crate::transaction::{LocalizedTransaction, PendingSignedTransactions, SignedTransaction};
 use crate::types::{BlockId, TransactionId, VerificationQueueInfo as QueueInfo};
@@ -507,6 +508,10 @@ impl ImportBlock for TestBlockChainClient {
         unimplemented!()
     }
 
+    fn import_bootstrap_block(&self, _block: &Block) -> Result<BlockHash, BlockImportError> {
+        unimplemented!()
+    }
+
     fn import_sealed_block(&self, _block: &SealedBlock) -> ImportResult {
         Ok(H256::default().into())
     }
@@ -600,6 +605,18 @@ impl BlockChainClient for TestBlockChainClient {
     fn error_hints_by_tracker(&self, _: &Tracker) -> Vec<(TxHash, Option<String>)> {
         unimplemented!();
     }
+
+    fn transaction_receipt(&self, _hash: &TxHash) -> Option<Receipt> {
+        unimplemented!();
+    }
+
+    fn block_receipts(&self, _id: &BlockId) -> Option<Vec<Receipt>> {
+        unimplemented!();
+    }
+
+    fn transactions_by_address(&self, _: &Address, _: u64, _: u64) -> Option<Vec<LocalizedTransaction>> {
+        unimplemented!();
+    }
 }
 
 impl TimeoutHandler for TestBlockChainClient {
@@ -685,4 +702,8 @@ impl StateInfo for TestBlockChainClient {
 
         Some(top_state)
     }
+
+    fn is_state_pruned(&self, _id: BlockId) -> bool {
+        false
+    }
 }
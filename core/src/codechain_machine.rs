@@ -178,9 +178,21 @@ impl CodeChainMachine {
             } => params.min_asset_mint_cost(),
             Action::TransferAsset {
                 ..
+            }
+            | Action::ClawbackAsset {
+                ..
             } => params.min_asset_transfer_cost(),
             Action::ChangeAssetScheme {
                 ..
+            }
+            | Action::SetAssetFrozen {
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                ..
             } => params.min_asset_scheme_change_cost(),
             Action::IncreaseAssetSupply {
                 ..
@@ -26,11 +26,12 @@ mod validator_set;
 pub use self::null_engine::NullEngine;
 pub use self::simple_poa::SimplePoA;
 pub use self::solo::Solo;
+pub use self::tendermint::light_client;
 pub use self::tendermint::{
     ConsensusMessage, Height, Step, Tendermint, TendermintParams, TimeGapParams, View, VoteOn, VoteStep,
 };
 pub use self::validator_set::validator_list::RoundRobinValidator;
-pub use self::validator_set::{DynamicValidator, ValidatorSet};
+pub use self::validator_set::{simulate_proposers, DynamicValidator, ProposerSelection, ValidatorSet};
 
 use self::bit_set::BitSet;
 use crate::account_provider::AccountProvider;
@@ -128,6 +129,15 @@ impl EngineType {
     }
 }
 
+/// The round of a consensus engine and the step timeouts it has fired.
+#[derive(Debug, Clone)]
+pub struct ConsensusStatus {
+    pub height: u64,
+    pub view: u64,
+    pub step: &'static str,
+    pub timeouts: Vec<(&'static str, u64)>,
+}
+
 /// A consensus mechanism for the chain.
 pub trait ConsensusEngine: Sync + Send {
     /// The name of this engine.
@@ -241,6 +251,11 @@ pub trait ConsensusEngine: Sync + Send {
 
     fn send_snapshot_notify(&self, _block_hash: BlockHash) {}
 
+    /// Returns the current round of the engine if it runs rounds.
+    fn consensus_status(&self) -> Option<ConsensusStatus> {
+        None
+    }
+
     fn get_best_block_from_best_proposal_header(&self, header: &HeaderView<'_>) -> BlockHash {
         header.hash()
     }
@@ -139,10 +139,7 @@ impl ConsensusEngine for Solo {
             }
             header.number()
         };
-        let rewards = stake::drain_calculated_rewards(&mut block.state_mut())?;
-        for (address, reward) in rewards {
-            self.machine.add_balance(block, &address, reward)?;
-        }
+        stake::pay_calculated_rewards(block.state_mut())?;
 
         stake::on_term_close(block.state_mut(), last_term_finished_block_num, &[])?;
         Ok(())
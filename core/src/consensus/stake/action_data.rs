@@ -14,16 +14,17 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::CUSTOM_ACTION_HANDLER_ID;
+use super::{CUSTOM_ACTION_HANDLER_ID, MAX_COMMISSION_RATE};
 use ckey::{public_to_address, Address, Public};
 use cstate::{ActionData, ActionDataKeyBuilder, StateResult, TopLevelState, TopState, TopStateView};
 use ctypes::errors::RuntimeError;
+use ctypes::CommonParams;
 use primitives::{Bytes, H256};
-use rlp::{decode_list, encode_list, Decodable, Encodable, Rlp, RlpStream};
+use rlp::{decode_list, encode_list, Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use std::cmp::Ordering;
 use std::collections::btree_map::{BTreeMap, Entry};
 use std::collections::btree_set::{self, BTreeSet};
-use std::collections::{btree_map, HashMap, HashSet};
+use std::collections::{btree_map, HashMap, HashSet, VecDeque};
 use std::mem;
 use std::ops::Deref;
 use std::vec;
@@ -44,12 +45,32 @@ lazy_static! {
         ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"Validators").into_key();
     pub static ref CURRENT_VALIDATORS_KEY: H256 =
         ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"CurrentValidators").into_key();
+    pub static ref UNBONDINGS_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"Unbondings").into_key();
+    pub static ref SLASHES_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"Slashes").into_key();
+    pub static ref MISSED_PRECOMMITS_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"MissedPrecommits").into_key();
+    pub static ref COMMISSIONS_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"Commissions").into_key();
+    pub static ref AUTO_COMPOUNDERS_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"AutoCompounders").into_key();
+    pub static ref PROPOSALS_KEY: H256 =
+        ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"Proposals").into_key();
 }
 
 pub fn get_delegation_key(address: &Address) -> H256 {
     ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 2).append(&"Delegation").append(address).into_key()
 }
 
+pub fn get_delegators_key(address: &Address) -> H256 {
+    ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 2).append(&"Delegators").append(address).into_key()
+}
+
+pub fn get_rewards_key(address: &Address) -> H256 {
+    ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 2).append(&"Rewards").append(address).into_key()
+}
+
 pub fn get_intermediate_rewards_key() -> H256 {
     ActionDataKeyBuilder::new(CUSTOM_ACTION_HANDLER_ID, 1).append(&"IntermediateRewards").into_key()
 }
@@ -149,6 +170,12 @@ impl Stakeholders {
         }
     }
 
+    pub fn update_by_increased_delegation(&mut self, delegation: &Delegation<'_>) {
+        if delegation.sum() > 0 {
+            self.0.insert(*delegation.delegator);
+        }
+    }
+
     pub fn update_by_decreased_balance(&mut self, account: &StakeAccount<'_>, delegation: &Delegation<'_>) {
         assert!(account.address == delegation.delegator);
         if account.balance == 0 && delegation.sum() == 0 {
@@ -180,6 +207,18 @@ impl<'a> Delegation<'a> {
 
     pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
         let key = get_delegation_key(self.delegator);
+        let prev_delegatees: BTreeMap<Address, StakeQuantity> = decode_map(state.action_data(&key)?.as_ref());
+        for delegatee in prev_delegatees.keys().filter(|delegatee| !self.delegatees.contains_key(delegatee)) {
+            let mut delegators = Delegators::load_from_state(state, delegatee)?;
+            delegators.remove(self.delegator);
+            delegators.save_to_state(state)?;
+        }
+        for delegatee in self.delegatees.keys().filter(|delegatee| !prev_delegatees.contains_key(delegatee)) {
+            let mut delegators = Delegators::load_from_state(state, delegatee)?;
+            delegators.insert(*self.delegator);
+            delegators.save_to_state(state)?;
+        }
+
         if !self.delegatees.is_empty() {
             let encoded = encode_map(&self.delegatees);
             state.update_action_data(&key, encoded)?;
@@ -233,6 +272,45 @@ impl<'a> Delegation<'a> {
     }
 }
 
+/// The delegators who delegate to the delegatee. It is updated by `Delegation::save_to_state`.
+pub struct Delegators<'a> {
+    pub delegatee: &'a Address,
+    delegators: BTreeSet<Address>,
+}
+
+impl<'a> Delegators<'a> {
+    pub fn load_from_state(state: &TopLevelState, delegatee: &'a Address) -> StateResult<Delegators<'a>> {
+        let key = get_delegators_key(delegatee);
+        let action_data = state.action_data(&key)?;
+        Ok(Delegators {
+            delegatee,
+            delegators: decode_set(action_data.as_ref()),
+        })
+    }
+
+    fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = get_delegators_key(self.delegatee);
+        if !self.delegators.is_empty() {
+            state.update_action_data(&key, encode_set(&self.delegators))?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    fn insert(&mut self, delegator: Address) {
+        self.delegators.insert(delegator);
+    }
+
+    fn remove(&mut self, delegator: &Address) {
+        self.delegators.remove(delegator);
+    }
+
+    pub fn iter(&self) -> btree_set::Iter<'_, Address> {
+        self.delegators.iter()
+    }
+}
+
 #[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd, RlpDecodable, RlpEncodable)]
 pub struct Validator {
     weight: StakeQuantity,
@@ -251,7 +329,7 @@ impl Validator {
         }
     }
 
-    fn new(delegation: StakeQuantity, deposit: Deposit, pubkey: Public) -> Self {
+    pub(crate) fn new(delegation: StakeQuantity, deposit: Deposit, pubkey: Public) -> Self {
         Self {
             weight: delegation,
             delegation,
@@ -271,6 +349,14 @@ impl Validator {
     pub fn delegation(&self) -> StakeQuantity {
         self.delegation
     }
+
+    pub fn deposit(&self) -> Deposit {
+        self.deposit
+    }
+
+    pub fn weight(&self) -> StakeQuantity {
+        self.weight
+    }
 }
 
 #[derive(Debug)]
@@ -397,6 +483,12 @@ impl From<NextValidators> for Vec<Validator> {
     }
 }
 
+impl From<Vec<Validator>> for NextValidators {
+    fn from(validators: Vec<Validator>) -> Self {
+        Self(validators)
+    }
+}
+
 impl IntoIterator for NextValidators {
     type Item = Validator;
     type IntoIter = vec::IntoIter<Self::Item>;
@@ -539,6 +631,16 @@ impl IntermediateRewards {
         mem::swap(&mut new, &mut self.calculated);
         new
     }
+
+    /// The rewards accumulated during the current term.
+    pub fn current(&self) -> &BTreeMap<Address, u64> {
+        &self.current
+    }
+
+    /// The rewards of the previous term, which are paid at the end of the current term.
+    pub fn calculated(&self) -> &BTreeMap<Address, u64> {
+        &self.calculated
+    }
 }
 
 pub struct Candidates(Vec<Candidate>);
@@ -652,6 +754,13 @@ impl Candidates {
         expired
     }
 
+    pub fn subtract_deposit(&mut self, address: &Address, quantity: Deposit) {
+        if let Some(candidate) = self.0.iter_mut().find(|c| public_to_address(&c.pubkey) == *address) {
+            assert!(candidate.deposit >= quantity, "Cannot subtract more than the deposit");
+            candidate.deposit -= quantity;
+        }
+    }
+
     pub fn remove(&mut self, address: &Address) -> Option<Candidate> {
         if let Some(index) = self.0.iter().position(|c| public_to_address(&c.pubkey) == *address) {
             Some(self.0.remove(index))
@@ -672,6 +781,12 @@ impl Candidates {
     }
 }
 
+impl From<Candidates> for Vec<Candidate> {
+    fn from(val: Candidates) -> Self {
+        val.0
+    }
+}
+
 pub struct Jail(BTreeMap<Address, Prisoner>);
 #[derive(Clone, Debug, Eq, PartialEq, RlpEncodable, RlpDecodable)]
 pub struct Prisoner {
@@ -744,6 +859,10 @@ impl Jail {
         }
     }
 
+    pub fn prisoners(&self) -> btree_map::Values<'_, Address, Prisoner> {
+        self.0.values()
+    }
+
     pub fn drain_released_prisoners(&mut self, term_index: u64) -> Vec<Prisoner> {
         let (released, retained): (Vec<_>, Vec<_>) =
             self.0.values().cloned().partition(|c| c.released_at <= term_index);
@@ -778,6 +897,557 @@ impl Banned {
     pub fn is_banned(&self, address: &Address) -> bool {
         self.0.contains(address)
     }
+
+    pub fn iter(&self) -> btree_set::Iter<'_, Address> {
+        self.0.iter()
+    }
+}
+
+/// The commission rates of the validators in basis points.
+/// A validator who didn't set its commission rate takes the whole reward.
+/// A new commission rate is pending until the current term closes.
+pub struct Commissions {
+    current: BTreeMap<Address, u64>,
+    pending: BTreeMap<Address, u64>,
+}
+
+impl Commissions {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<Commissions> {
+        let key = *COMMISSIONS_KEY;
+        let action_data = state.action_data(&key)?;
+        let (current, pending) = decode_map_tuple(action_data.as_ref());
+
+        Ok(Commissions {
+            current,
+            pending,
+        })
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *COMMISSIONS_KEY;
+        if self.current.is_empty() && self.pending.is_empty() {
+            state.remove_action_data(&key);
+        } else {
+            state.update_action_data(&key, encode_map_tuple(&self.current, &self.pending))?;
+        }
+        Ok(())
+    }
+
+    pub fn set(&mut self, validator: Address, commission_rate: u64) {
+        self.pending.insert(validator, commission_rate);
+    }
+
+    pub fn apply_pending(&mut self) {
+        let pending = mem::replace(&mut self.pending, BTreeMap::new());
+        self.current.extend(pending);
+    }
+
+    pub fn rate_of(&self, validator: &Address) -> u64 {
+        self.current.get(validator).cloned().unwrap_or(MAX_COMMISSION_RATE)
+    }
+}
+
+/// The delegators who receive their rewards at the end of each term instead of claiming them.
+pub struct AutoCompounders(BTreeSet<Address>);
+impl AutoCompounders {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<AutoCompounders> {
+        let key = *AUTO_COMPOUNDERS_KEY;
+        let action_data = state.action_data(&key)?;
+        Ok(AutoCompounders(decode_set(action_data.as_ref())))
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *AUTO_COMPOUNDERS_KEY;
+        if !self.0.is_empty() {
+            state.update_action_data(&key, encode_set(&self.0))?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    pub fn set(&mut self, delegator: Address, enabled: bool) {
+        if enabled {
+            self.0.insert(delegator);
+        } else {
+            self.0.remove(&delegator);
+        }
+    }
+
+    pub fn contains(&self, delegator: &Address) -> bool {
+        self.0.contains(delegator)
+    }
+}
+
+/// The rewards of a delegator which are not claimed yet, by the delegatee who earned them.
+pub struct Rewards<'a> {
+    pub delegator: &'a Address,
+    rewards: BTreeMap<Address, u64>,
+}
+
+impl<'a> Rewards<'a> {
+    pub fn load_from_state(state: &TopLevelState, delegator: &'a Address) -> StateResult<Rewards<'a>> {
+        let key = get_rewards_key(delegator);
+        let action_data = state.action_data(&key)?;
+        Ok(Rewards {
+            delegator,
+            rewards: decode_map(action_data.as_ref()),
+        })
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = get_rewards_key(self.delegator);
+        if !self.rewards.is_empty() {
+            state.update_action_data(&key, encode_map(&self.rewards))?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    pub fn add_quantity(&mut self, delegatee: Address, quantity: u64) {
+        if quantity == 0 {
+            return
+        }
+        *self.rewards.entry(delegatee).or_insert(0) += quantity;
+    }
+
+    pub fn drain(&mut self) -> u64 {
+        mem::replace(&mut self.rewards, BTreeMap::new()).values().sum()
+    }
+
+    pub fn iter(&self) -> btree_map::Iter<'_, Address, u64> {
+        self.rewards.iter()
+    }
+}
+
+/// The stake which left the delegatee. It is released at the end of the term `released_at`,
+/// and delegated to the redelegatee or returned to the delegator.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unbonding {
+    pub delegator: Address,
+    pub delegatee: Address,
+    pub quantity: StakeQuantity,
+    pub released_at: u64,
+    pub redelegatee: Option<Address>,
+}
+
+impl Encodable for Unbonding {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        let item_count = if self.redelegatee.is_some() {
+            5
+        } else {
+            4
+        };
+        s.begin_list(item_count)
+            .append(&self.delegator)
+            .append(&self.delegatee)
+            .append(&self.quantity)
+            .append(&self.released_at);
+        if let Some(redelegatee) = &self.redelegatee {
+            s.append(redelegatee);
+        }
+    }
+}
+
+impl Decodable for Unbonding {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != 4 && item_count != 5 {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected: 4,
+                got: item_count,
+            })
+        }
+        Ok(Self {
+            delegator: rlp.val_at(0)?,
+            delegatee: rlp.val_at(1)?,
+            quantity: rlp.val_at(2)?,
+            released_at: rlp.val_at(3)?,
+            redelegatee: if item_count == 5 {
+                Some(rlp.val_at(4)?)
+            } else {
+                None
+            },
+        })
+    }
+}
+
+pub struct Unbondings(Vec<Unbonding>);
+impl Unbondings {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<Unbondings> {
+        let key = *UNBONDINGS_KEY;
+        let unbondings = state.action_data(&key)?.map(|data| decode_list::<Unbonding>(&data)).unwrap_or_default();
+        Ok(Unbondings(unbondings))
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *UNBONDINGS_KEY;
+        if !self.0.is_empty() {
+            let encoded = encode_iter(self.0.iter());
+            state.update_action_data(&key, encoded)?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, unbonding: Unbonding) {
+        self.0.push(unbonding);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Unbonding> {
+        self.0.iter()
+    }
+
+    pub fn drain_released(&mut self, term_index: u64) -> Vec<Unbonding> {
+        let (released, retained): (Vec<_>, Vec<_>) = self.0.drain(..).partition(|u| u.released_at <= term_index);
+        self.0 = retained;
+        released
+    }
+
+    /// Subtracts the slashed quantity from the stakes which left the delegatee,
+    /// and returns the slashed quantities.
+    pub fn slash<F>(&mut self, delegatee: &Address, slashed_quantity: F) -> Vec<SlashedDelegation>
+    where
+        F: Fn(StakeQuantity) -> StakeQuantity, {
+        let mut slashed = Vec::new();
+        for unbonding in self.0.iter_mut().filter(|u| u.delegatee == *delegatee) {
+            let quantity = slashed_quantity(unbonding.quantity);
+            if quantity > 0 {
+                unbonding.quantity -= quantity;
+                slashed.push(SlashedDelegation {
+                    delegator: unbonding.delegator,
+                    quantity,
+                });
+            }
+        }
+        self.0.retain(|u| u.quantity > 0);
+        slashed
+    }
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum SlashReason {
+    DoubleVote,
+    Downtime,
+}
+
+impl Encodable for SlashReason {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        let tag: u8 = match self {
+            SlashReason::DoubleVote => 1,
+            SlashReason::Downtime => 2,
+        };
+        s.append(&tag);
+    }
+}
+
+impl Decodable for SlashReason {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        match rlp.as_val::<u8>()? {
+            1 => Ok(SlashReason::DoubleVote),
+            2 => Ok(SlashReason::Downtime),
+            _ => Err(DecoderError::Custom("Unexpected slash reason")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Eq, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct SlashedDelegation {
+    pub delegator: Address,
+    pub quantity: StakeQuantity,
+}
+
+/// The record of the stakes slashed for a misbehavior of the offender.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Slash {
+    pub offender: Address,
+    pub reason: SlashReason,
+    pub term: u64,
+    pub deposit: Deposit,
+    pub delegations: Vec<SlashedDelegation>,
+}
+
+impl Slash {
+    pub fn total(&self) -> u64 {
+        self.deposit + self.delegations.iter().map(|d| d.quantity).sum::<u64>()
+    }
+}
+
+impl Encodable for Slash {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(5)
+            .append(&self.offender)
+            .append(&self.reason)
+            .append(&self.term)
+            .append(&self.deposit)
+            .append_list(&self.delegations);
+    }
+}
+
+impl Decodable for Slash {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != 5 {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected: 5,
+                got: item_count,
+            })
+        }
+        Ok(Self {
+            offender: rlp.val_at(0)?,
+            reason: rlp.val_at(1)?,
+            term: rlp.val_at(2)?,
+            deposit: rlp.val_at(3)?,
+            delegations: rlp.list_at(4)?,
+        })
+    }
+}
+
+pub struct Slashes(Vec<Slash>);
+impl Slashes {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<Slashes> {
+        let key = *SLASHES_KEY;
+        let slashes = state.action_data(&key)?.map(|data| decode_list::<Slash>(&data)).unwrap_or_default();
+        Ok(Slashes(slashes))
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *SLASHES_KEY;
+        if !self.0.is_empty() {
+            let encoded = encode_iter(self.0.iter());
+            state.update_action_data(&key, encoded)?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    pub fn add(&mut self, slash: Slash) {
+        self.0.push(slash);
+    }
+
+    pub fn remove_before(&mut self, term: u64) {
+        self.0.retain(|slash| slash.term >= term);
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Slash> {
+        self.0.iter()
+    }
+}
+
+/// The validators who missed the precommits of the recent blocks, from the oldest block.
+pub struct MissedPrecommits(VecDeque<Vec<Address>>);
+impl MissedPrecommits {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<MissedPrecommits> {
+        let key = *MISSED_PRECOMMITS_KEY;
+        let blocks = match state.action_data(&key)? {
+            Some(data) => Rlp::new(&data).iter().map(|rlp| rlp.as_list().unwrap()).collect(),
+            None => VecDeque::new(),
+        };
+        Ok(MissedPrecommits(blocks))
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *MISSED_PRECOMMITS_KEY;
+        if !self.0.is_empty() {
+            let mut rlp = RlpStream::new_list(self.0.len());
+            for missed in &self.0 {
+                rlp.append_list(missed);
+            }
+            state.update_action_data(&key, rlp.drain())?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    /// Adds the validators who missed the precommits of a new block,
+    /// and forgets the blocks that are out of the window.
+    pub fn push(&mut self, missed: Vec<Address>, window: usize) {
+        self.0.push_back(missed);
+        while self.0.len() > window {
+            self.0.pop_front();
+        }
+    }
+
+    pub fn is_full(&self, window: usize) -> bool {
+        self.0.len() == window
+    }
+
+    pub fn count(&self) -> BTreeMap<Address, u64> {
+        let mut result = BTreeMap::new();
+        for address in self.0.iter().flatten() {
+            *result.entry(*address).or_default() += 1;
+        }
+        result
+    }
+
+    pub fn forget(&mut self, address: &Address) {
+        for missed in self.0.iter_mut() {
+            missed.retain(|a| a != address);
+        }
+    }
+}
+
+/// What a governance proposal asks for when it passes.
+#[derive(Clone, Debug, PartialEq)]
+pub enum ProposalContent {
+    ChangeParams(Box<CommonParams>),
+    Text(Bytes),
+}
+
+impl Encodable for ProposalContent {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        match self {
+            ProposalContent::ChangeParams(params) => {
+                s.begin_list(2).append(&1u8).append(&**params);
+            }
+            ProposalContent::Text(text) => {
+                s.begin_list(2).append(&2u8).append(text);
+            }
+        }
+    }
+}
+
+impl Decodable for ProposalContent {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != 2 {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected: 2,
+                got: item_count,
+            })
+        }
+        match rlp.val_at::<u8>(0)? {
+            1 => Ok(ProposalContent::ChangeParams(Box::new(rlp.val_at(1)?))),
+            2 => Ok(ProposalContent::Text(rlp.val_at(1)?)),
+            _ => Err(DecoderError::Custom("Unexpected proposal content")),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: Address,
+    pub deposit: Deposit,
+    pub content: ProposalContent,
+    /// The last term in which the proposal can be voted on.
+    pub voting_ends_at: u64,
+    /// Whether each voter approves the proposal.
+    pub votes: BTreeMap<Address, bool>,
+}
+
+impl Encodable for Proposal {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(6)
+            .append(&self.id)
+            .append(&self.proposer)
+            .append(&self.deposit)
+            .append(&self.content)
+            .append(&self.voting_ends_at);
+        encode_map_impl(s, &self.votes);
+    }
+}
+
+impl Decodable for Proposal {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != 6 {
+            return Err(DecoderError::RlpIncorrectListLen {
+                expected: 6,
+                got: item_count,
+            })
+        }
+        Ok(Self {
+            id: rlp.val_at(0)?,
+            proposer: rlp.val_at(1)?,
+            deposit: rlp.val_at(2)?,
+            content: rlp.val_at(3)?,
+            voting_ends_at: rlp.val_at(4)?,
+            votes: decode_map_impl(rlp.at(5)?),
+        })
+    }
+}
+
+/// The governance proposals which are being voted on.
+#[derive(Debug, Default)]
+pub struct Proposals {
+    next_id: u64,
+    proposals: Vec<Proposal>,
+}
+
+impl Proposals {
+    pub fn load_from_state(state: &TopLevelState) -> StateResult<Proposals> {
+        let key = *PROPOSALS_KEY;
+        let proposals = match state.action_data(&key)? {
+            Some(data) => {
+                let rlp = Rlp::new(&data);
+                Proposals {
+                    next_id: rlp.val_at(0).unwrap(),
+                    proposals: rlp.list_at(1).unwrap(),
+                }
+            }
+            None => Default::default(),
+        };
+        Ok(proposals)
+    }
+
+    pub fn save_to_state(&self, state: &mut TopLevelState) -> StateResult<()> {
+        let key = *PROPOSALS_KEY;
+        if self.next_id != 0 {
+            let mut rlp = RlpStream::new_list(2);
+            rlp.append(&self.next_id).append_list(&self.proposals);
+            state.update_action_data(&key, rlp.drain())?;
+        } else {
+            state.remove_action_data(&key);
+        }
+        Ok(())
+    }
+
+    /// Adds a new proposal and returns its id.
+    pub fn submit(
+        &mut self,
+        proposer: Address,
+        deposit: Deposit,
+        content: ProposalContent,
+        voting_ends_at: u64,
+    ) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.proposals.push(Proposal {
+            id,
+            proposer,
+            deposit,
+            content,
+            voting_ends_at,
+            votes: Default::default(),
+        });
+        id
+    }
+
+    pub fn vote(&mut self, id: u64, voter: Address, approve: bool) -> Result<(), RuntimeError> {
+        let proposal = self
+            .proposals
+            .iter_mut()
+            .find(|proposal| proposal.id == id)
+            .ok_or_else(|| RuntimeError::FailedToHandleCustomAction(format!("There is no proposal {}", id)))?;
+        proposal.votes.insert(voter, approve);
+        Ok(())
+    }
+
+    /// Removes the proposals whose voting period is over.
+    pub fn drain_closed(&mut self, current_term: u64) -> Vec<Proposal> {
+        let (closed, open) = self.proposals.drain(..).partition(|proposal| proposal.voting_ends_at <= current_term);
+        self.proposals = open;
+        closed
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Proposal> {
+        self.proposals.iter()
+    }
 }
 
 fn decode_set<V>(data: Option<&ActionData>) -> BTreeSet<V>
@@ -895,6 +1565,7 @@ mod tests {
     use cstate::tests::helpers;
     use rand::{Rng, SeedableRng};
     use rand_xorshift::XorShiftRng;
+    use rlp::rlp_encode_and_decode_test;
 
     fn rng() -> XorShiftRng {
         let seed: [u8; 16] = [0, 1, 2, 3, 4, 5, 6, 7, 0, 1, 2, 3, 4, 5, 6, 7];
@@ -1213,6 +1884,42 @@ mod tests {
         assert_eq!(result, None);
     }
 
+    #[test]
+    fn delegators_follow_the_delegations() {
+        let mut state = helpers::get_temp_state();
+
+        // Prepare
+        let delegator1 = Address::random();
+        let delegator2 = Address::random();
+        let delegatee = Address::random();
+
+        let mut delegation = Delegation::load_from_state(&state, &delegator1).unwrap();
+        delegation.add_quantity(delegatee, 100).unwrap();
+        delegation.save_to_state(&mut state).unwrap();
+        let mut delegation = Delegation::load_from_state(&state, &delegator2).unwrap();
+        delegation.add_quantity(delegatee, 100).unwrap();
+        delegation.save_to_state(&mut state).unwrap();
+
+        let delegators = Delegators::load_from_state(&state, &delegatee).unwrap();
+        assert_eq!(delegators.iter().collect::<BTreeSet<_>>(), [delegator1, delegator2].iter().collect());
+
+        // Revoke the whole delegation of the first delegator
+        let mut delegation = Delegation::load_from_state(&state, &delegator1).unwrap();
+        delegation.subtract_quantity(delegatee, 100).unwrap();
+        delegation.save_to_state(&mut state).unwrap();
+
+        let delegators = Delegators::load_from_state(&state, &delegatee).unwrap();
+        assert_eq!(delegators.iter().collect::<Vec<_>>(), vec![&delegator2]);
+
+        // Revoke the rest
+        let mut delegation = Delegation::load_from_state(&state, &delegator2).unwrap();
+        delegation.subtract_quantity(delegatee, 100).unwrap();
+        delegation.save_to_state(&mut state).unwrap();
+
+        let result = state.action_data(&get_delegators_key(&delegatee)).unwrap();
+        assert_eq!(result, None);
+    }
+
     #[test]
     fn load_and_save_intermediate_rewards() {
         let mut state = helpers::get_temp_state();
@@ -1852,4 +2559,65 @@ mod tests {
             pubkeys.iter().map(|pubkey| candidates.get_index(&public_to_address(pubkey)).unwrap()).collect();
         assert_eq!(indexes, vec![5, 6, 7, 8, 9, 0, 1, 2, 3, 4]);
     }
+
+    #[test]
+    fn encode_and_decode_slash() {
+        rlp_encode_and_decode_test!(Slash {
+            offender: Address::random(),
+            reason: SlashReason::Downtime,
+            term: 3,
+            deposit: 10,
+            delegations: vec![SlashedDelegation {
+                delegator: Address::random(),
+                quantity: 5,
+            }],
+        });
+    }
+
+    #[test]
+    fn encode_and_decode_proposal() {
+        let mut votes = BTreeMap::new();
+        votes.insert(Address::random(), true);
+        votes.insert(Address::random(), false);
+        rlp_encode_and_decode_test!(Proposal {
+            id: 1,
+            proposer: Address::random(),
+            deposit: 100,
+            content: ProposalContent::ChangeParams(Box::new(CommonParams::default_for_test())),
+            voting_ends_at: 3,
+            votes,
+        });
+        rlp_encode_and_decode_test!(Proposal {
+            id: 2,
+            proposer: Address::random(),
+            deposit: 100,
+            content: ProposalContent::Text(b"text".to_vec()),
+            voting_ends_at: 3,
+            votes: BTreeMap::new(),
+        });
+    }
+
+    #[test]
+    fn closed_proposals_are_drained() {
+        let mut state = helpers::get_temp_state();
+        let proposer = Address::random();
+
+        let mut proposals = Proposals::load_from_state(&state).unwrap();
+        assert_eq!(0, proposals.submit(proposer, 100, ProposalContent::Text(vec![]), 1));
+        assert_eq!(1, proposals.submit(proposer, 100, ProposalContent::Text(vec![]), 2));
+        proposals.vote(1, proposer, true).unwrap();
+        assert!(proposals.vote(2, proposer, true).is_err());
+        proposals.save_to_state(&mut state).unwrap();
+
+        let mut proposals = Proposals::load_from_state(&state).unwrap();
+        let closed = proposals.drain_closed(1);
+        assert_eq!(vec![0], closed.iter().map(|p| p.id).collect::<Vec<_>>());
+        proposals.save_to_state(&mut state).unwrap();
+
+        let mut proposals = Proposals::load_from_state(&state).unwrap();
+        let open: Vec<_> = proposals.iter().collect();
+        assert_eq!(1, open.len());
+        assert_eq!(Some(&true), open[0].votes.get(&proposer));
+        assert_eq!(2, proposals.submit(proposer, 100, ProposalContent::Text(vec![]), 3));
+    }
 }
@@ -14,6 +14,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+use super::{ProposalContent, MAX_COMMISSION_RATE};
 use crate::client::ConsensusClient;
 use crate::consensus::{ConsensusMessage, ValidatorSet};
 use ccrypto::Blake;
@@ -33,6 +34,10 @@ enum ActionTag {
     SelfNominate = 4,
     ReportDoubleVote = 5,
     Redelegate = 6,
+    ClaimRewards = 7,
+    SetAutoCompound = 8,
+    SubmitProposal = 9,
+    Vote = 10,
     ChangeParams = 0xFF,
 }
 
@@ -52,6 +57,10 @@ impl Decodable for ActionTag {
             4 => Ok(ActionTag::SelfNominate),
             5 => Ok(ActionTag::ReportDoubleVote),
             6 => Ok(ActionTag::Redelegate),
+            7 => Ok(ActionTag::ClaimRewards),
+            8 => Ok(ActionTag::SetAutoCompound),
+            9 => Ok(ActionTag::SubmitProposal),
+            10 => Ok(ActionTag::Vote),
             0xFF => Ok(ActionTag::ChangeParams),
             _ => Err(DecoderError::Custom("Unexpected ActionTag Value")),
         }
@@ -80,6 +89,20 @@ pub enum Action {
     SelfNominate {
         deposit: u64,
         metadata: Bytes,
+        /// The share of the rewards the validator takes before they are split to the delegators,
+        /// in basis points. The previous rate is kept if it's None.
+        commission_rate: Option<u64>,
+    },
+    ClaimRewards,
+    SetAutoCompound {
+        enabled: bool,
+    },
+    SubmitProposal {
+        content: ProposalContent,
+    },
+    Vote {
+        proposal_id: u64,
+        approve: bool,
     },
     ChangeParams {
         metadata_seq: u64,
@@ -115,6 +138,7 @@ impl Action {
             } => {}
             Action::SelfNominate {
                 metadata,
+                commission_rate,
                 ..
             } => {
                 if metadata.len() > current_params.max_candidate_metadata_size() {
@@ -123,7 +147,42 @@ impl Action {
                         current_params.max_candidate_metadata_size()
                     )))
                 }
+                if let Some(commission_rate) = commission_rate {
+                    if *commission_rate > MAX_COMMISSION_RATE {
+                        return Err(SyntaxError::InvalidCustomAction(format!(
+                            "Too high commission rate: the limit is {}",
+                            MAX_COMMISSION_RATE
+                        )))
+                    }
+                }
             }
+            Action::ClaimRewards => {}
+            Action::SetAutoCompound {
+                ..
+            } => {}
+            Action::SubmitProposal {
+                content,
+            } => {
+                if current_params.voting_period() == 0 {
+                    return Err(SyntaxError::InvalidCustomAction(String::from("The governance is not enabled")))
+                }
+                match content {
+                    ProposalContent::ChangeParams(params) => {
+                        params.verify_change(current_params).map_err(SyntaxError::InvalidCustomAction)?
+                    }
+                    ProposalContent::Text(text) => {
+                        if text.len() > current_params.max_text_content_size() {
+                            return Err(SyntaxError::InvalidCustomAction(format!(
+                                "Too long proposal text: the size limit is {}",
+                                current_params.max_text_content_size()
+                            )))
+                        }
+                    }
+                }
+            }
+            Action::Vote {
+                ..
+            } => {}
             Action::ChangeParams {
                 metadata_seq,
                 params,
@@ -235,8 +294,36 @@ impl Encodable for Action {
             Action::SelfNominate {
                 deposit,
                 metadata,
+                commission_rate,
+            } => {
+                if let Some(commission_rate) = commission_rate {
+                    s.begin_list(4)
+                        .append(&ActionTag::SelfNominate)
+                        .append(deposit)
+                        .append(metadata)
+                        .append(commission_rate);
+                } else {
+                    s.begin_list(3).append(&ActionTag::SelfNominate).append(deposit).append(metadata);
+                }
+            }
+            Action::ClaimRewards => {
+                s.begin_list(1).append(&ActionTag::ClaimRewards);
+            }
+            Action::SetAutoCompound {
+                enabled,
             } => {
-                s.begin_list(3).append(&ActionTag::SelfNominate).append(deposit).append(metadata);
+                s.begin_list(2).append(&ActionTag::SetAutoCompound).append(enabled);
+            }
+            Action::SubmitProposal {
+                content,
+            } => {
+                s.begin_list(2).append(&ActionTag::SubmitProposal).append(content);
+            }
+            Action::Vote {
+                proposal_id,
+                approve,
+            } => {
+                s.begin_list(3).append(&ActionTag::Vote).append(proposal_id).append(approve);
             }
             Action::ChangeParams {
                 metadata_seq,
@@ -323,15 +410,71 @@ impl Decodable for Action {
             }
             ActionTag::SelfNominate => {
                 let item_count = rlp.item_count()?;
-                if item_count != 3 {
+                if item_count != 3 && item_count != 4 {
                     return Err(DecoderError::RlpInvalidLength {
-                        expected: 3,
+                        expected: if item_count < 3 {
+                            3
+                        } else {
+                            4
+                        },
                         got: item_count,
                     })
                 }
                 Ok(Action::SelfNominate {
                     deposit: rlp.val_at(1)?,
                     metadata: rlp.val_at(2)?,
+                    commission_rate: if item_count == 4 {
+                        Some(rlp.val_at(3)?)
+                    } else {
+                        None
+                    },
+                })
+            }
+            ActionTag::ClaimRewards => {
+                let item_count = rlp.item_count()?;
+                if item_count != 1 {
+                    return Err(DecoderError::RlpInvalidLength {
+                        expected: 1,
+                        got: item_count,
+                    })
+                }
+                Ok(Action::ClaimRewards)
+            }
+            ActionTag::SetAutoCompound => {
+                let item_count = rlp.item_count()?;
+                if item_count != 2 {
+                    return Err(DecoderError::RlpInvalidLength {
+                        expected: 2,
+                        got: item_count,
+                    })
+                }
+                Ok(Action::SetAutoCompound {
+                    enabled: rlp.val_at(1)?,
+                })
+            }
+            ActionTag::SubmitProposal => {
+                let item_count = rlp.item_count()?;
+                if item_count != 2 {
+                    return Err(DecoderError::RlpInvalidLength {
+                        expected: 2,
+                        got: item_count,
+                    })
+                }
+                Ok(Action::SubmitProposal {
+                    content: rlp.val_at(1)?,
+                })
+            }
+            ActionTag::Vote => {
+                let item_count = rlp.item_count()?;
+                if item_count != 3 {
+                    return Err(DecoderError::RlpInvalidLength {
+                        expected: 3,
+                        got: item_count,
+                    })
+                }
+                Ok(Action::Vote {
+                    proposal_id: rlp.val_at(1)?,
+                    approve: rlp.val_at(2)?,
                 })
             }
             ActionTag::ChangeParams => {
@@ -395,6 +538,76 @@ mod tests {
         );
     }
 
+    #[test]
+    fn rlp_of_self_nominate() {
+        rlp_encode_and_decode_test!(Action::SelfNominate {
+            deposit: 100,
+            metadata: b"metadata".to_vec(),
+            commission_rate: None,
+        });
+        rlp_encode_and_decode_test!(Action::SelfNominate {
+            deposit: 100,
+            metadata: b"metadata".to_vec(),
+            commission_rate: Some(1000),
+        });
+    }
+
+    #[test]
+    fn decode_fail_if_self_nominate_has_too_many_items() {
+        let mut s = RlpStream::new_list(5);
+        s.append(&ActionTag::SelfNominate).append(&100u64).append(&Vec::<u8>::new()).append(&1000u64).append(&0u64);
+        assert_eq!(
+            Err(DecoderError::RlpInvalidLength {
+                expected: 4,
+                got: 5,
+            }),
+            Rlp::new(&s.out()).as_val::<Action>()
+        );
+    }
+
+    #[test]
+    fn rlp_of_reward_actions() {
+        rlp_encode_and_decode_test!(Action::ClaimRewards);
+        rlp_encode_and_decode_test!(Action::SetAutoCompound {
+            enabled: true,
+        });
+    }
+
+    #[test]
+    fn verify_fails_if_commission_rate_is_too_high() {
+        let action = Action::SelfNominate {
+            deposit: 100,
+            metadata: vec![],
+            commission_rate: Some(MAX_COMMISSION_RATE + 1),
+        };
+        assert!(action.verify(&CommonParams::default_for_test(), None, None).is_err());
+    }
+
+    #[test]
+    fn rlp_of_governance_actions() {
+        rlp_encode_and_decode_test!(Action::SubmitProposal {
+            content: ProposalContent::ChangeParams(CommonParams::default_for_test().into()),
+        });
+        rlp_encode_and_decode_test!(Action::SubmitProposal {
+            content: ProposalContent::Text(b"text".to_vec()),
+        });
+        rlp_encode_and_decode_test!(Action::Vote {
+            proposal_id: 3,
+            approve: false,
+        });
+    }
+
+    #[test]
+    fn verify_fails_if_governance_is_not_enabled() {
+        let action = Action::SubmitProposal {
+            content: ProposalContent::Text(vec![]),
+        };
+        let mut params = CommonParams::default_for_test();
+        assert!(action.verify(&params, None, None).is_err());
+        params.set_governance_params_for_test(100, 2);
+        assert_eq!(Ok(()), action.verify(&params, None, None));
+    }
+
     #[test]
     fn rlp_of_change_params() {
         rlp_encode_and_decode_test!(Action::ChangeParams {
@@ -24,7 +24,7 @@ use ckey::{public_to_address, recover, Address, Public, Signature};
 use cstate::{ActionHandler, StateResult, TopLevelState, TopState, TopStateView};
 use ctypes::errors::{RuntimeError, SyntaxError};
 use ctypes::util::unexpected::Mismatch;
-use ctypes::{CommonParams, Header};
+use ctypes::{CommonParams, Header, MAX_LIVENESS_THRESHOLD, MAX_SLASH_RATE};
 use parking_lot::RwLock;
 use primitives::{Bytes, H256};
 use rlp::{Decodable, Rlp};
@@ -32,13 +32,20 @@ use std::collections::btree_map::BTreeMap;
 use std::collections::HashMap;
 use std::sync::{Arc, Weak};
 
-pub use self::action_data::{Banned, CurrentValidators, NextValidators, PreviousValidators, Validator};
-use self::action_data::{Candidates, Delegation, IntermediateRewards, Jail, ReleaseResult, StakeAccount, Stakeholders};
+pub use self::action_data::{
+    AutoCompounders, Banned, Candidate, Candidates, Commissions, CurrentValidators, Delegation, Delegators,
+    IntermediateRewards, Jail, MissedPrecommits, NextValidators, PreviousValidators, Prisoner, Proposal,
+    ProposalContent, Proposals, Rewards, Slash, SlashReason, SlashedDelegation, Slashes, Unbonding, Unbondings,
+    Validator, NEXT_VALIDATORS_KEY,
+};
+use self::action_data::{ReleaseResult, StakeAccount, Stakeholders};
 pub use self::actions::Action;
 pub use self::distribute::fee_distribute;
 use super::ValidatorSet;
 
 pub const CUSTOM_ACTION_HANDLER_ID: u64 = 2;
+/// The commission rates are in basis points.
+pub const MAX_COMMISSION_RATE: u64 = 10_000;
 
 pub struct Stake {
     genesis_stakes: HashMap<Address, u64>,
@@ -112,6 +119,7 @@ impl ActionHandler for Stake {
             Action::SelfNominate {
                 deposit,
                 metadata,
+                commission_rate,
             } => {
                 let (current_term, nomination_ends_at) = {
                     let metadata = state.metadata()?.expect("Metadata must exist");
@@ -124,8 +132,23 @@ impl ActionHandler for Stake {
                     let nomination_ends_at = current_term + expiration;
                     (current_term, nomination_ends_at)
                 };
-                self_nominate(state, fee_payer, sender_public, deposit, current_term, nomination_ends_at, metadata)
+                self_nominate(state, fee_payer, sender_public, deposit, current_term, nomination_ends_at, metadata)?;
+                if let Some(commission_rate) = commission_rate {
+                    set_commission_rate(state, fee_payer, commission_rate)?;
+                }
+                Ok(())
             }
+            Action::ClaimRewards => claim_rewards(state, fee_payer),
+            Action::SetAutoCompound {
+                enabled,
+            } => set_auto_compound(state, fee_payer, enabled),
+            Action::SubmitProposal {
+                content,
+            } => submit_proposal(state, fee_payer, content),
+            Action::Vote {
+                proposal_id,
+                approve,
+            } => vote(state, fee_payer, proposal_id, approve),
             Action::ChangeParams {
                 metadata_seq,
                 params,
@@ -206,11 +229,24 @@ fn delegate_ccs(state: &mut TopLevelState, fee_payer: &Address, delegatee: &Addr
 }
 
 fn revoke(state: &mut TopLevelState, fee_payer: &Address, delegatee: &Address, quantity: u64) -> StateResult<()> {
+    let unbonding_ends_at = unbonding_ends_at(state)?;
     let mut delegator = StakeAccount::load_from_state(state, fee_payer)?;
     let mut delegation = Delegation::load_from_state(state, &fee_payer)?;
 
-    delegator.add_balance(quantity)?;
     delegation.subtract_quantity(*delegatee, quantity)?;
+    if let Some(released_at) = unbonding_ends_at {
+        let mut unbondings = Unbondings::load_from_state(state)?;
+        unbondings.add(Unbonding {
+            delegator: *fee_payer,
+            delegatee: *delegatee,
+            quantity,
+            released_at,
+            redelegatee: None,
+        });
+        unbondings.save_to_state(state)?;
+    } else {
+        delegator.add_balance(quantity)?;
+    }
     // delegation does not touch stakeholders
 
     delegation.save_to_state(state)?;
@@ -237,11 +273,24 @@ fn redelegate(
     assert!(!banned.is_banned(&next_delegatee), "A candidate must not be banned");
     assert_eq!(None, jailed.get_prisoner(next_delegatee), "A candidate must not be jailed");
 
+    let unbonding_ends_at = unbonding_ends_at(state)?;
     let delegator = StakeAccount::load_from_state(state, fee_payer)?;
     let mut delegation = Delegation::load_from_state(state, &fee_payer)?;
 
     delegation.subtract_quantity(*prev_delegatee, quantity)?;
-    delegation.add_quantity(*next_delegatee, quantity)?;
+    if let Some(released_at) = unbonding_ends_at {
+        let mut unbondings = Unbondings::load_from_state(state)?;
+        unbondings.add(Unbonding {
+            delegator: *fee_payer,
+            delegatee: *prev_delegatee,
+            quantity,
+            released_at,
+            redelegatee: Some(*next_delegatee),
+        });
+        unbondings.save_to_state(state)?;
+    } else {
+        delegation.add_quantity(*next_delegatee, quantity)?;
+    }
 
     delegation.save_to_state(state)?;
     delegator.save_to_state(state)?;
@@ -257,6 +306,141 @@ fn redelegate(
     Ok(())
 }
 
+fn set_commission_rate(state: &mut TopLevelState, validator: &Address, commission_rate: u64) -> StateResult<()> {
+    let mut commissions = Commissions::load_from_state(state)?;
+    commissions.set(*validator, commission_rate);
+    commissions.save_to_state(state)?;
+
+    ctrace!(
+        ENGINE,
+        "Set the commission rate of the next term. validator: {}, commission_rate: {}",
+        validator,
+        commission_rate
+    );
+    Ok(())
+}
+
+fn claim_rewards(state: &mut TopLevelState, fee_payer: &Address) -> StateResult<()> {
+    let mut rewards = Rewards::load_from_state(state, fee_payer)?;
+    let quantity = rewards.drain();
+    if quantity == 0 {
+        return Err(RuntimeError::FailedToHandleCustomAction("There is no reward to claim".into()).into())
+    }
+    rewards.save_to_state(state)?;
+    state.add_balance(fee_payer, quantity)?;
+
+    ctrace!(ENGINE, "Claimed rewards. delegator: {}, quantity: {}", fee_payer, quantity);
+    Ok(())
+}
+
+fn set_auto_compound(state: &mut TopLevelState, fee_payer: &Address, enabled: bool) -> StateResult<()> {
+    let mut auto_compounders = AutoCompounders::load_from_state(state)?;
+    auto_compounders.set(*fee_payer, enabled);
+    auto_compounders.save_to_state(state)?;
+
+    ctrace!(ENGINE, "Set auto-compound. delegator: {}, enabled: {}", fee_payer, enabled);
+    Ok(())
+}
+
+fn submit_proposal(state: &mut TopLevelState, fee_payer: &Address, content: ProposalContent) -> StateResult<()> {
+    let (deposit, voting_period, current_term) = {
+        let metadata = state.metadata()?.expect("Metadata must exist");
+        // The governance is enabled only by the CommonParams, which are stored after the first ChangeParams.
+        let (deposit, voting_period) =
+            metadata.params().map_or((0, 0), |params| (params.proposal_deposit(), params.voting_period()));
+        (deposit, voting_period, metadata.current_term_id())
+    };
+    if voting_period == 0 {
+        return Err(RuntimeError::FailedToHandleCustomAction("The governance is not enabled".into()).into())
+    }
+    let voting_ends_at = current_term + voting_period - 1;
+    state.sub_balance(fee_payer, deposit)?;
+
+    let mut proposals = Proposals::load_from_state(state)?;
+    let id = proposals.submit(*fee_payer, deposit, content, voting_ends_at);
+    proposals.save_to_state(state)?;
+
+    ctrace!(ENGINE, "Submitted a proposal. id: {}, proposer: {}, voting_ends_at: {}", id, fee_payer, voting_ends_at);
+    Ok(())
+}
+
+fn vote(state: &mut TopLevelState, fee_payer: &Address, proposal_id: u64, approve: bool) -> StateResult<()> {
+    let account = StakeAccount::load_from_state(state, fee_payer)?;
+    let delegation = Delegation::load_from_state(state, fee_payer)?;
+    if account.balance + delegation.sum() == 0 {
+        return Err(RuntimeError::FailedToHandleCustomAction("Only the stakeholders can vote".into()).into())
+    }
+
+    let mut proposals = Proposals::load_from_state(state)?;
+    proposals.vote(proposal_id, *fee_payer, approve)?;
+    proposals.save_to_state(state)?;
+
+    ctrace!(ENGINE, "Voted. proposal: {}, voter: {}, approve: {}", proposal_id, fee_payer, approve);
+    Ok(())
+}
+
+/// Tallies the proposals whose voting period is over.
+/// A proposal passes if the voters who approve it have more than half of the stakes.
+/// The deposit is returned to the proposer if the proposal passes, and burned otherwise.
+fn close_proposals(state: &mut TopLevelState, current_term: u64) -> StateResult<()> {
+    let mut proposals = Proposals::load_from_state(state)?;
+    let closed = proposals.drain_closed(current_term);
+    if closed.is_empty() {
+        return Ok(())
+    }
+    proposals.save_to_state(state)?;
+
+    let stakes = get_stakes(state)?;
+    let total_stakes: u64 = stakes.values().sum();
+    for proposal in closed {
+        let approved: u64 =
+            proposal.votes.iter().filter(|(_, approve)| **approve).filter_map(|(voter, _)| stakes.get(voter)).sum();
+        if total_stakes / 2 >= approved {
+            cinfo!(ENGINE, "Proposal {} is rejected. approved: {}, total: {}", proposal.id, approved, total_stakes);
+            continue
+        }
+        state.add_balance(&proposal.proposer, proposal.deposit)?;
+
+        match proposal.content {
+            ProposalContent::ChangeParams(params) => {
+                let (seq, current_params) = {
+                    let metadata = state.metadata()?.expect("Metadata must exist");
+                    (metadata.seq(), metadata.params().cloned())
+                };
+                let current_params = match current_params {
+                    Some(current_params) => current_params,
+                    None => {
+                        cwarn!(ENGINE, "Proposal {} passed but the governance is not enabled", proposal.id);
+                        continue
+                    }
+                };
+                // The params could be changed after the proposal is submitted.
+                if let Err(err) = params.verify_change(&current_params) {
+                    cwarn!(ENGINE, "Proposal {} passed but cannot be applied: {}", proposal.id, err);
+                    continue
+                }
+                state.update_params(seq, *params)?;
+            }
+            ProposalContent::Text(_) => {}
+        }
+        cinfo!(ENGINE, "Proposal {} passed. approved: {}, total: {}", proposal.id, approved, total_stakes);
+    }
+    Ok(())
+}
+
+/// The term at the end of which the stake revoked now is released, or None if it's released immediately.
+fn unbonding_ends_at(state: &TopLevelState) -> StateResult<Option<u64>> {
+    let metadata = match state.metadata()? {
+        Some(metadata) => metadata,
+        None => return Ok(None),
+    };
+    let unbonding_period = metadata.params().map(CommonParams::unbonding_period).unwrap_or(0);
+    if unbonding_period == 0 {
+        return Ok(None)
+    }
+    Ok(Some(metadata.current_term_id() + unbonding_period))
+}
+
 fn self_nominate(
     state: &mut TopLevelState,
     fee_payer: &Address,
@@ -343,6 +527,67 @@ pub fn drain_calculated_rewards(state: &mut TopLevelState) -> StateResult<BTreeM
     Ok(drained)
 }
 
+/// Pays the rewards of the validators. A validator takes its commission, and the rest is split
+/// to its delegators in proportion to their delegations. The shares of the delegators are kept
+/// until they claim them. If a delegator enabled auto-compound, its share is re-delegated to the
+/// same validator instead: the CCC of the share is burned and the same quantity of CCS is delegated.
+pub fn pay_calculated_rewards(state: &mut TopLevelState) -> StateResult<()> {
+    let validator_rewards = drain_calculated_rewards(state)?;
+    if validator_rewards.is_empty() {
+        return Ok(())
+    }
+
+    let commissions = Commissions::load_from_state(state)?;
+    let auto_compounders = AutoCompounders::load_from_state(state)?;
+    let mut stakeholders = Stakeholders::load_from_state(state)?;
+
+    let mut delegations: HashMap<Address, HashMap<Address, u64>> = HashMap::new();
+    for delegator in stakeholders.iter() {
+        let delegation = Delegation::load_from_state(state, delegator)?;
+        for (delegatee, quantity) in delegation.iter() {
+            if validator_rewards.contains_key(delegatee) {
+                delegations.entry(*delegatee).or_default().insert(*delegator, *quantity);
+            }
+        }
+    }
+
+    for (validator, reward) in validator_rewards {
+        let commission =
+            (u128::from(reward) * u128::from(commissions.rate_of(&validator)) / u128::from(MAX_COMMISSION_RATE)) as u64;
+        let delegators_reward = reward - commission;
+        let mut validator_reward = commission;
+
+        match delegations.get(&validator) {
+            Some(delegations) if delegators_reward > 0 => {
+                let mut shares = fee_distribute(delegators_reward, delegations);
+                for (delegator, share) in &mut shares {
+                    if auto_compounders.contains(delegator) {
+                        let mut delegation = Delegation::load_from_state(state, delegator)?;
+                        delegation.add_quantity(validator, share)?;
+                        stakeholders.update_by_increased_delegation(&delegation);
+                        delegation.save_to_state(state)?;
+                    } else {
+                        let mut rewards = Rewards::load_from_state(state, delegator)?;
+                        rewards.add_quantity(validator, share);
+                        rewards.save_to_state(state)?;
+                    }
+                }
+                validator_reward += shares.remaining_fee();
+            }
+            _ => validator_reward += delegators_reward,
+        }
+        state.add_balance(&validator, validator_reward)?;
+        ctrace!(
+            ENGINE,
+            "Paid the rewards. validator: {}, reward: {}, validator_reward: {}",
+            validator,
+            reward,
+            validator_reward
+        );
+    }
+    stakeholders.save_to_state(state)
+}
+
 pub fn update_validator_weights(state: &mut TopLevelState, block_author: &Address) -> StateResult<()> {
     let mut validators = NextValidators::load_from_state(state)?;
     validators.update_weight(block_author);
@@ -414,8 +659,15 @@ pub fn on_term_close(
     let reverted: Vec<_> = expired.into_iter().chain(released).collect();
     revert_delegations(state, &reverted)?;
 
+    prune_slashes(state, current_term)?;
+    slash_inactive_validators(state, inactive_validators)?;
     jail(state, inactive_validators, custody_until, kick_at)?;
 
+    release_unbondings(state, current_term)?;
+    apply_pending_commissions(state)?;
+
+    close_proposals(state, current_term)?;
+
     let validators = NextValidators::elect(state)?;
     validators.save_to_state(state)?;
 
@@ -423,6 +675,12 @@ pub fn on_term_close(
     Ok(())
 }
 
+fn apply_pending_commissions(state: &mut TopLevelState) -> StateResult<()> {
+    let mut commissions = Commissions::load_from_state(state)?;
+    commissions.apply_pending();
+    commissions.save_to_state(state)
+}
+
 fn update_candidates(
     state: &mut TopLevelState,
     current_term: u64,
@@ -458,6 +716,83 @@ fn release_jailed_prisoners(state: &mut TopLevelState, current_term: u64) -> Sta
     Ok(released.into_iter().map(|p| p.address).collect())
 }
 
+fn release_unbondings(state: &mut TopLevelState, current_term: u64) -> StateResult<()> {
+    let mut unbondings = Unbondings::load_from_state(state)?;
+    let released = unbondings.drain_released(current_term);
+    if released.is_empty() {
+        return Ok(())
+    }
+
+    // The banned and the jailed are not candidates.
+    let candidates = Candidates::load_from_state(state)?;
+    let mut stakeholders = Stakeholders::load_from_state(state)?;
+    for unbonding in &released {
+        match unbonding.redelegatee {
+            Some(redelegatee) if candidates.get_candidate(&redelegatee).is_some() => {
+                let mut delegation = Delegation::load_from_state(state, &unbonding.delegator)?;
+                delegation.add_quantity(redelegatee, unbonding.quantity)?;
+                stakeholders.update_by_increased_delegation(&delegation);
+                delegation.save_to_state(state)?;
+            }
+            _ => {
+                let mut delegator = StakeAccount::load_from_state(state, &unbonding.delegator)?;
+                delegator.add_balance(unbonding.quantity)?;
+                stakeholders.update_by_increased_balance(&delegator);
+                delegator.save_to_state(state)?;
+            }
+        }
+        ctrace!(
+            ENGINE,
+            "on_term_close::unbonded. delegator: {}, delegatee: {}, redelegatee: {:?}, quantity: {}",
+            unbonding.delegator,
+            unbonding.delegatee,
+            unbonding.redelegatee,
+            unbonding.quantity
+        );
+    }
+    stakeholders.save_to_state(state)?;
+    unbondings.save_to_state(state)
+}
+
+/// Records the validators who missed the precommits of a block, and jails the candidates
+/// who missed more than the liveness threshold of the window. Returns the jailed addresses.
+pub fn update_liveness(state: &mut TopLevelState, missed_validators: Vec<Address>) -> StateResult<Vec<Address>> {
+    let metadata = state.metadata()?.expect("The metadata must exist");
+    let params = match metadata.params() {
+        Some(params) if params.liveness_window() != 0 => params,
+        _ => return Ok(Vec::new()),
+    };
+    let window = params.liveness_window();
+
+    let mut missed_precommits = MissedPrecommits::load_from_state(state)?;
+    missed_precommits.push(missed_validators, window as usize);
+
+    let mut offline = Vec::new();
+    if missed_precommits.is_full(window as usize) {
+        let max_missed =
+            (u128::from(window) * u128::from(params.liveness_threshold()) / u128::from(MAX_LIVENESS_THRESHOLD)) as u64;
+        let candidates = Candidates::load_from_state(state)?;
+        for (address, missed) in missed_precommits.count() {
+            if missed > max_missed && candidates.get_candidate(&address).is_some() {
+                offline.push(address);
+            }
+        }
+    }
+    for address in &offline {
+        missed_precommits.forget(address);
+    }
+    missed_precommits.save_to_state(state)?;
+
+    if !offline.is_empty() {
+        let current_term = metadata.current_term_id();
+        let custody_until = current_term + params.custody_period();
+        let kick_at = current_term + params.release_period();
+        slash_inactive_validators(state, &offline)?;
+        jail(state, &offline, custody_until, kick_at)?;
+    }
+    Ok(offline)
+}
+
 pub fn jail(state: &mut TopLevelState, addresses: &[Address], custody_until: u64, kick_at: u64) -> StateResult<()> {
     if addresses.is_empty() {
         return Ok(())
@@ -492,8 +827,18 @@ pub fn ban(state: &mut TopLevelState, informant: &Public, criminal: Address) ->
         (_, Some(jailed)) => jailed.deposit,
         _ => 0,
     };
-    // confiscate criminal's deposit and give the same deposit amount to the informant.
-    state.add_balance(&public_to_address(informant), deposit)?;
+
+    let slash_rate = slash_rate(state, SlashReason::DoubleVote)?;
+    if slash_rate == 0 {
+        // confiscate criminal's deposit and give the same deposit amount to the informant.
+        state.add_balance(&public_to_address(informant), deposit)?;
+    } else {
+        // give the slashed part of the criminal's deposit to the informant and return the rest.
+        let slashed_deposit = slashed_quantity(deposit, slash_rate);
+        state.add_balance(&public_to_address(informant), slashed_deposit)?;
+        state.add_balance(&criminal, deposit - slashed_deposit)?;
+        slash(state, criminal, SlashReason::DoubleVote, slash_rate, slashed_deposit)?;
+    }
 
     jailed.remove(&criminal);
     banned.add(criminal);
@@ -510,6 +855,97 @@ pub fn ban(state: &mut TopLevelState, informant: &Public, criminal: Address) ->
     Ok(())
 }
 
+fn slash_rate(state: &TopLevelState, reason: SlashReason) -> StateResult<u64> {
+    let params = match state.metadata()? {
+        Some(metadata) => metadata.params().cloned(),
+        None => None,
+    };
+    Ok(params.map_or(0, |params| match reason {
+        SlashReason::DoubleVote => params.double_vote_slash_rate(),
+        SlashReason::Downtime => params.downtime_slash_rate(),
+    }))
+}
+
+fn slashed_quantity(quantity: u64, slash_rate: u64) -> u64 {
+    (u128::from(quantity) * u128::from(slash_rate) / u128::from(MAX_SLASH_RATE)) as u64
+}
+
+fn slash_inactive_validators(state: &mut TopLevelState, inactive_validators: &[Address]) -> StateResult<()> {
+    let slash_rate = slash_rate(state, SlashReason::Downtime)?;
+    if slash_rate == 0 || inactive_validators.is_empty() {
+        return Ok(())
+    }
+
+    let mut candidates = Candidates::load_from_state(state)?;
+    let mut slashed_deposits = Vec::with_capacity(inactive_validators.len());
+    for address in inactive_validators {
+        let candidate = candidates.get_candidate(address).expect("There should be a candidate to jail");
+        let slashed_deposit = slashed_quantity(candidate.deposit, slash_rate);
+        candidates.subtract_deposit(address, slashed_deposit);
+        slashed_deposits.push(slashed_deposit);
+    }
+    candidates.save_to_state(state)?;
+
+    for (address, slashed_deposit) in inactive_validators.iter().zip(slashed_deposits) {
+        slash(state, *address, SlashReason::Downtime, slash_rate, slashed_deposit)?;
+    }
+    Ok(())
+}
+
+/// Burns the given rate of the stakes delegated to the offender, including the unbonding ones,
+/// and records the slash with the slashed deposit.
+fn slash(
+    state: &mut TopLevelState,
+    offender: Address,
+    reason: SlashReason,
+    slash_rate: u64,
+    slashed_deposit: u64,
+) -> StateResult<()> {
+    let mut slashed_delegations = Vec::new();
+
+    let mut stakeholders = Stakeholders::load_from_state(state)?;
+    let delegators: Vec<_> = Delegators::load_from_state(state, &offender)?.iter().cloned().collect();
+    for address in &delegators {
+        let delegator = StakeAccount::load_from_state(state, address)?;
+        let mut delegation = Delegation::load_from_state(state, address)?;
+        let quantity = slashed_quantity(delegation.get_quantity(&offender), slash_rate);
+        if quantity > 0 {
+            delegation.subtract_quantity(offender, quantity)?;
+            stakeholders.update_by_decreased_balance(&delegator, &delegation);
+            delegation.save_to_state(state)?;
+            slashed_delegations.push(SlashedDelegation {
+                delegator: *address,
+                quantity,
+            });
+        }
+    }
+    stakeholders.save_to_state(state)?;
+
+    let mut unbondings = Unbondings::load_from_state(state)?;
+    slashed_delegations.extend(unbondings.slash(&offender, |quantity| slashed_quantity(quantity, slash_rate)));
+    unbondings.save_to_state(state)?;
+
+    let term = state.metadata()?.map_or(0, |metadata| metadata.current_term_id());
+    let slash = Slash {
+        offender,
+        reason,
+        term,
+        deposit: slashed_deposit,
+        delegations: slashed_delegations,
+    };
+    ctrace!(ENGINE, "slash. offender: {}, reason: {:?}, quantity: {}", offender, reason, slash.total());
+    let mut slashes = Slashes::load_from_state(state)?;
+    slashes.add(slash);
+    slashes.save_to_state(state)
+}
+
+/// Keeps only the slashes of the closing term. The older ones remain in the states of the past blocks.
+fn prune_slashes(state: &mut TopLevelState, current_term: u64) -> StateResult<()> {
+    let mut slashes = Slashes::load_from_state(state)?;
+    slashes.remove_before(current_term);
+    slashes.save_to_state(state)
+}
+
 fn revert_delegations(state: &mut TopLevelState, reverted_delegatees: &[Address]) -> StateResult<()> {
     // Stakeholders list isn't changed while reverting.
 
@@ -543,7 +979,7 @@ mod tests {
     use super::action_data::get_account_key;
     use super::*;
 
-    use crate::consensus::stake::action_data::{get_delegation_key, Candidate, Prisoner};
+    use crate::consensus::stake::action_data::{get_delegation_key, Candidate, Prisoner, UNBONDINGS_KEY};
     use cstate::tests::helpers;
     use cstate::TopStateView;
     use rlp::Encodable;
@@ -1760,4 +2196,592 @@ mod tests {
     fn pseudo_term_to_block_num_calculator(term_id: u64) -> u64 {
         term_id * 10 + 1
     }
+
+    fn metadata_with_unbonding_period(unbonding_period: u64) -> TopLevelState {
+        let mut state = helpers::get_temp_state_with_metadata();
+        state.metadata().unwrap().unwrap().set_params(CommonParams::default_for_test());
+        let mut params = CommonParams::default_for_test();
+        params.set_dynamic_validator_params_for_test(30, 10, 3, 20, 30, 4, 1000, 10000, 100);
+        params.set_unbonding_period_for_test(unbonding_period);
+        assert_eq!(Ok(()), state.update_params(0, params));
+        state
+    }
+
+    #[test]
+    fn revoked_stake_is_released_after_unbonding_period() {
+        let delegatee_pubkey = Public::random();
+        let delegatee = public_to_address(&delegatee_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = metadata_with_unbonding_period(2);
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &delegatee, &delegatee_pubkey, 0, 0, 30, b"".to_vec()).unwrap();
+
+        let action = Action::DelegateCCS {
+            address: delegatee,
+            quantity: 50,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+        let action = Action::Revoke {
+            address: delegatee,
+            quantity: 20,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&delegatee), 30);
+        let unbondings = Unbondings::load_from_state(&state).unwrap();
+        assert_eq!(unbondings.iter().collect::<Vec<_>>(), vec![&Unbonding {
+            delegator,
+            delegatee,
+            quantity: 20,
+            released_at: 2,
+            redelegatee: None,
+        }]);
+
+        for term in 0..2 {
+            on_term_close(&mut state, pseudo_term_to_block_num_calculator(term), &[]).unwrap();
+            let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+            assert_eq!(account.balance, 50, "Should keep the revoked stake during the unbonding period");
+        }
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(2), &[]).unwrap();
+        let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(account.balance, 70);
+        assert_eq!(state.action_data(&*UNBONDINGS_KEY).unwrap(), None);
+    }
+
+    #[test]
+    fn redelegated_stake_is_delegated_after_unbonding_period() {
+        let prev_delegatee_pubkey = Public::random();
+        let prev_delegatee = public_to_address(&prev_delegatee_pubkey);
+        let next_delegatee_pubkey = Public::random();
+        let next_delegatee = public_to_address(&next_delegatee_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = metadata_with_unbonding_period(1);
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &prev_delegatee, &prev_delegatee_pubkey, 0, 0, 30, b"".to_vec()).unwrap();
+        self_nominate(&mut state, &next_delegatee, &next_delegatee_pubkey, 0, 0, 30, b"".to_vec()).unwrap();
+
+        let action = Action::DelegateCCS {
+            address: prev_delegatee,
+            quantity: 50,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+        let action = Action::Redelegate {
+            prev_delegatee,
+            next_delegatee,
+            quantity: 20,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[]).unwrap();
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&prev_delegatee), 30);
+        assert_eq!(delegation.get_quantity(&next_delegatee), 0, "Should not delegate during the unbonding period");
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(1), &[]).unwrap();
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&prev_delegatee), 30);
+        assert_eq!(delegation.get_quantity(&next_delegatee), 20);
+        let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(account.balance, 50);
+    }
+
+    #[test]
+    fn redelegated_stake_is_returned_if_the_redelegatee_is_jailed() {
+        let prev_delegatee_pubkey = Public::random();
+        let prev_delegatee = public_to_address(&prev_delegatee_pubkey);
+        let next_delegatee_pubkey = Public::random();
+        let next_delegatee = public_to_address(&next_delegatee_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = metadata_with_unbonding_period(1);
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &prev_delegatee, &prev_delegatee_pubkey, 0, 0, 30, b"".to_vec()).unwrap();
+        self_nominate(&mut state, &next_delegatee, &next_delegatee_pubkey, 0, 0, 30, b"".to_vec()).unwrap();
+
+        let action = Action::DelegateCCS {
+            address: prev_delegatee,
+            quantity: 50,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+        let action = Action::Redelegate {
+            prev_delegatee,
+            next_delegatee,
+            quantity: 20,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[next_delegatee]).unwrap();
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(1), &[]).unwrap();
+
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&next_delegatee), 0);
+        let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(account.balance, 70);
+    }
+
+    fn metadata_with_slash_rates(double_vote_slash_rate: u64, downtime_slash_rate: u64) -> TopLevelState {
+        let mut state = helpers::get_temp_state_with_metadata();
+        state.metadata().unwrap().unwrap().set_params(CommonParams::default_for_test());
+        let mut params = CommonParams::default_for_test();
+        params.set_dynamic_validator_params_for_test(30, 10, 3, 20, 30, 4, 1000, 10000, 100);
+        params.set_slash_rates_for_test(double_vote_slash_rate, downtime_slash_rate);
+        assert_eq!(Ok(()), state.update_params(0, params));
+        state
+    }
+
+    #[test]
+    fn double_vote_slashes_deposit_and_delegations() {
+        let informant_pubkey = Public::random();
+        let informant = public_to_address(&informant_pubkey);
+        let criminal_pubkey = Public::random();
+        let criminal = public_to_address(&criminal_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        // 10%
+        let mut state = metadata_with_slash_rates(1000, 0);
+        state.add_balance(&criminal, 1000).unwrap();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &criminal, &criminal_pubkey, 100, 0, 10, b"".to_vec()).unwrap();
+        let action = Action::DelegateCCS {
+            address: criminal,
+            quantity: 40,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        assert_eq!(Ok(()), ban(&mut state, &informant_pubkey, criminal));
+
+        assert!(Banned::load_from_state(&state).unwrap().is_banned(&criminal));
+        assert_eq!(state.balance(&criminal).unwrap(), 990, "Should lose 10% of the deposit");
+        assert_eq!(state.balance(&informant).unwrap(), 10, "The informant should take the slashed deposit");
+
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&criminal), 0, "Delegation should be reverted");
+        let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(account.balance, 96, "Should lose 10% of the delegation");
+
+        let slashes = Slashes::load_from_state(&state).unwrap();
+        assert_eq!(slashes.iter().collect::<Vec<_>>(), vec![&Slash {
+            offender: criminal,
+            reason: SlashReason::DoubleVote,
+            term: 0,
+            deposit: 10,
+            delegations: vec![SlashedDelegation {
+                delegator,
+                quantity: 4,
+            }],
+        }]);
+    }
+
+    #[test]
+    fn slashes_are_kept_until_the_next_term_closes() {
+        let informant_pubkey = Public::random();
+        let criminal_pubkey = Public::random();
+        let criminal = public_to_address(&criminal_pubkey);
+
+        let mut state = metadata_with_slash_rates(1000, 0);
+        state.add_balance(&criminal, 1000).unwrap();
+        let stake = Stake::new(HashMap::new());
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &criminal, &criminal_pubkey, 100, 0, 10, b"".to_vec()).unwrap();
+        assert_eq!(Ok(()), ban(&mut state, &informant_pubkey, criminal));
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[]).unwrap();
+        assert_eq!(
+            1,
+            Slashes::load_from_state(&state).unwrap().iter().count(),
+            "The slash of the closing term is kept"
+        );
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(1), &[]).unwrap();
+        assert_eq!(0, Slashes::load_from_state(&state).unwrap().iter().count(), "The slash of the last term is pruned");
+    }
+
+    #[test]
+    fn downtime_slashes_deposit_and_delegations() {
+        let validator_pubkey = Public::random();
+        let validator = public_to_address(&validator_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        // 1%
+        let mut state = metadata_with_slash_rates(0, 100);
+        let mut params = state.metadata().unwrap().unwrap().params().unwrap().clone();
+        params.set_unbonding_period_for_test(2);
+        assert_eq!(Ok(()), state.update_params(1, params));
+        state.add_balance(&validator, 2000).unwrap();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 1000);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &validator, &validator_pubkey, 1000, 0, 30, b"".to_vec()).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 600,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+        let action = Action::Revoke {
+            address: validator,
+            quantity: 100,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[validator]).unwrap();
+
+        let jail = Jail::load_from_state(&state).unwrap();
+        assert_eq!(jail.get_prisoner(&validator).unwrap().deposit, 990, "Should lose 1% of the deposit");
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&validator), 495, "Should lose 1% of the delegation");
+        let unbondings = Unbondings::load_from_state(&state).unwrap();
+        assert_eq!(
+            unbondings.iter().map(|u| u.quantity).collect::<Vec<_>>(),
+            vec![99],
+            "Unbonding stakes are slashable"
+        );
+
+        let slashes = Slashes::load_from_state(&state).unwrap();
+        assert_eq!(slashes.iter().collect::<Vec<_>>(), vec![&Slash {
+            offender: validator,
+            reason: SlashReason::Downtime,
+            term: 0,
+            deposit: 10,
+            delegations: vec![
+                SlashedDelegation {
+                    delegator,
+                    quantity: 5,
+                },
+                SlashedDelegation {
+                    delegator,
+                    quantity: 1,
+                }
+            ],
+        }]);
+    }
+
+    #[test]
+    fn validator_takes_the_whole_reward_without_commission_rate() {
+        let validator_pubkey = Public::random();
+        let validator = public_to_address(&validator_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = helpers::get_temp_state();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+        self_nominate(&mut state, &validator, &validator_pubkey, 0, 0, 10, b"".to_vec()).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 30,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        let mut rewards = HashMap::new();
+        rewards.insert(validator, 1000);
+        update_calculated_rewards(&mut state, rewards).unwrap();
+        pay_calculated_rewards(&mut state).unwrap();
+
+        assert_eq!(state.balance(&validator).unwrap(), 1000);
+        assert_eq!(Rewards::load_from_state(&state, &delegator).unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn rewards_are_split_by_commission_rate() {
+        let validator_pubkey = Public::random();
+        let validator = public_to_address(&validator_pubkey);
+        let delegator1_pubkey = Public::random();
+        let delegator1 = public_to_address(&delegator1_pubkey);
+        let delegator2_pubkey = Public::random();
+        let delegator2 = public_to_address(&delegator2_pubkey);
+
+        let mut state = helpers::get_temp_state_with_metadata();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator1, 100);
+            genesis_stakes.insert(delegator2, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+
+        // 20%
+        let action = Action::SelfNominate {
+            deposit: 0,
+            metadata: b"".to_vec(),
+            commission_rate: Some(2000),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &validator, &validator_pubkey).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 30,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator1, &delegator1_pubkey).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 10,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator2, &delegator2_pubkey).unwrap();
+        apply_pending_commissions(&mut state).unwrap();
+
+        let mut rewards = HashMap::new();
+        rewards.insert(validator, 1000);
+        update_calculated_rewards(&mut state, rewards).unwrap();
+        pay_calculated_rewards(&mut state).unwrap();
+
+        assert_eq!(state.balance(&validator).unwrap(), 200);
+        let rewards = Rewards::load_from_state(&state, &delegator1).unwrap();
+        assert_eq!(rewards.iter().collect::<Vec<_>>(), vec![(&validator, &600)]);
+        let rewards = Rewards::load_from_state(&state, &delegator2).unwrap();
+        assert_eq!(rewards.iter().collect::<Vec<_>>(), vec![(&validator, &200)]);
+
+        let action = Action::ClaimRewards;
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator1, &delegator1_pubkey).unwrap();
+        assert_eq!(state.balance(&delegator1).unwrap(), 600);
+        assert_eq!(Rewards::load_from_state(&state, &delegator1).unwrap().iter().count(), 0);
+
+        let result = stake.execute(&action.rlp_bytes(), &mut state, &delegator1, &delegator1_pubkey);
+        assert!(result.is_err(), "There is nothing to claim");
+    }
+
+    #[test]
+    fn commission_rate_takes_effect_at_the_next_term() {
+        let validator_pubkey = Public::random();
+        let validator = public_to_address(&validator_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = helpers::get_temp_state_with_metadata();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+
+        // 20%
+        let action = Action::SelfNominate {
+            deposit: 0,
+            metadata: b"".to_vec(),
+            commission_rate: Some(2000),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &validator, &validator_pubkey).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 40,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        let mut rewards = HashMap::new();
+        rewards.insert(validator, 1000);
+        update_calculated_rewards(&mut state, rewards.clone()).unwrap();
+        pay_calculated_rewards(&mut state).unwrap();
+        assert_eq!(state.balance(&validator).unwrap(), 1000);
+        assert_eq!(Rewards::load_from_state(&state, &delegator).unwrap().iter().count(), 0);
+
+        apply_pending_commissions(&mut state).unwrap();
+        update_calculated_rewards(&mut state, rewards).unwrap();
+        pay_calculated_rewards(&mut state).unwrap();
+        assert_eq!(state.balance(&validator).unwrap(), 1000 + 200);
+        let rewards = Rewards::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(rewards.iter().collect::<Vec<_>>(), vec![(&validator, &800)]);
+    }
+
+    #[test]
+    fn auto_compound_redelegates_rewards_to_the_validator() {
+        let validator_pubkey = Public::random();
+        let validator = public_to_address(&validator_pubkey);
+        let delegator_pubkey = Public::random();
+        let delegator = public_to_address(&delegator_pubkey);
+
+        let mut state = helpers::get_temp_state_with_metadata();
+        let stake = {
+            let mut genesis_stakes = HashMap::new();
+            genesis_stakes.insert(delegator, 100);
+            Stake::new(genesis_stakes)
+        };
+        stake.init(&mut state).unwrap();
+
+        // 10%
+        let action = Action::SelfNominate {
+            deposit: 0,
+            metadata: b"".to_vec(),
+            commission_rate: Some(1000),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &validator, &validator_pubkey).unwrap();
+        apply_pending_commissions(&mut state).unwrap();
+        let action = Action::DelegateCCS {
+            address: validator,
+            quantity: 40,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+        let action = Action::SetAutoCompound {
+            enabled: true,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &delegator, &delegator_pubkey).unwrap();
+
+        let mut rewards = HashMap::new();
+        rewards.insert(validator, 100);
+        update_calculated_rewards(&mut state, rewards).unwrap();
+        pay_calculated_rewards(&mut state).unwrap();
+
+        assert_eq!(state.balance(&validator).unwrap(), 10);
+        assert_eq!(state.balance(&delegator).unwrap(), 0);
+        let account = StakeAccount::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(account.balance, 60);
+        let delegation = Delegation::load_from_state(&state, &delegator).unwrap();
+        assert_eq!(delegation.get_quantity(&validator), 40 + 90);
+        assert_eq!(Rewards::load_from_state(&state, &delegator).unwrap().iter().count(), 0);
+    }
+
+    fn metadata_with_governance(proposal_deposit: u64, voting_period: u64) -> TopLevelState {
+        let mut state = helpers::get_temp_state_with_metadata();
+        state.metadata().unwrap().unwrap().set_params(CommonParams::default_for_test());
+        let mut params = CommonParams::default_for_test();
+        params.set_dynamic_validator_params_for_test(30, 10, 3, 20, 30, 4, 1000, 10000, 100);
+        params.set_governance_params_for_test(proposal_deposit, voting_period);
+        assert_eq!(Ok(()), state.update_params(0, params));
+        state
+    }
+
+    fn stake_with(stakes: &[(Address, u64)]) -> Stake {
+        Stake::new(stakes.iter().cloned().collect())
+    }
+
+    #[test]
+    fn passed_proposal_changes_params_at_term_close() {
+        let (pubkey1, pubkey2) = (Public::random(), Public::random());
+        let (address1, address2) = (public_to_address(&pubkey1), public_to_address(&pubkey2));
+
+        let mut state = metadata_with_governance(100, 2);
+        state.add_balance(&address1, 1000).unwrap();
+        let stake = stake_with(&[(address1, 60), (address2, 40)]);
+        stake.init(&mut state).unwrap();
+
+        let mut new_params = *state.metadata().unwrap().unwrap().params().unwrap();
+        new_params.set_governance_params_for_test(100, 3);
+        let action = Action::SubmitProposal {
+            content: ProposalContent::ChangeParams(Box::new(new_params)),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &address1, &pubkey1).unwrap();
+        assert_eq!(state.balance(&address1).unwrap(), 900);
+
+        let action = Action::Vote {
+            proposal_id: 0,
+            approve: true,
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &address1, &pubkey1).unwrap();
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[]).unwrap();
+        assert_eq!(state.metadata().unwrap().unwrap().params().unwrap().voting_period(), 2, "Still voting");
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(1), &[]).unwrap();
+        assert_eq!(state.metadata().unwrap().unwrap().params().unwrap().voting_period(), 3);
+        assert_eq!(state.balance(&address1).unwrap(), 1000);
+        assert_eq!(Proposals::load_from_state(&state).unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn rejected_proposal_burns_the_deposit() {
+        let (pubkey1, pubkey2) = (Public::random(), Public::random());
+        let (address1, address2) = (public_to_address(&pubkey1), public_to_address(&pubkey2));
+
+        let mut state = metadata_with_governance(100, 1);
+        state.add_balance(&address1, 1000).unwrap();
+        let stake = stake_with(&[(address1, 40), (address2, 60)]);
+        stake.init(&mut state).unwrap();
+
+        let action = Action::SubmitProposal {
+            content: ProposalContent::Text(b"text".to_vec()),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &address1, &pubkey1).unwrap();
+        let approve = Action::Vote {
+            proposal_id: 0,
+            approve: true,
+        };
+        stake.execute(&approve.rlp_bytes(), &mut state, &address1, &pubkey1).unwrap();
+        let reject = Action::Vote {
+            proposal_id: 0,
+            approve: false,
+        };
+        stake.execute(&reject.rlp_bytes(), &mut state, &address2, &pubkey2).unwrap();
+
+        on_term_close(&mut state, pseudo_term_to_block_num_calculator(0), &[]).unwrap();
+        assert_eq!(state.balance(&address1).unwrap(), 900);
+        assert_eq!(Proposals::load_from_state(&state).unwrap().iter().count(), 0);
+    }
+
+    #[test]
+    fn only_stakeholders_can_vote_on_open_proposals() {
+        let (pubkey1, pubkey2) = (Public::random(), Public::random());
+        let (address1, address2) = (public_to_address(&pubkey1), public_to_address(&pubkey2));
+
+        let mut state = metadata_with_governance(100, 1);
+        state.add_balance(&address1, 1000).unwrap();
+        let stake = stake_with(&[(address1, 100)]);
+        stake.init(&mut state).unwrap();
+
+        let action = Action::SubmitProposal {
+            content: ProposalContent::Text(vec![]),
+        };
+        stake.execute(&action.rlp_bytes(), &mut state, &address1, &pubkey1).unwrap();
+
+        let action = Action::Vote {
+            proposal_id: 0,
+            approve: true,
+        };
+        assert!(stake.execute(&action.rlp_bytes(), &mut state, &address2, &pubkey2).is_err());
+        let action = Action::Vote {
+            proposal_id: 1,
+            approve: true,
+        };
+        assert!(stake.execute(&action.rlp_bytes(), &mut state, &address1, &pubkey1).is_err());
+    }
+
+    #[test]
+    fn proposal_is_rejected_before_the_params_are_changed() {
+        let pubkey = Public::random();
+        let address = public_to_address(&pubkey);
+
+        let mut state = helpers::get_temp_state_with_metadata();
+        state.add_balance(&address, 1000).unwrap();
+        let stake = stake_with(&[(address, 100)]);
+        stake.init(&mut state).unwrap();
+
+        let action = Action::SubmitProposal {
+            content: ProposalContent::Text(vec![]),
+        };
+        assert!(stake.execute(&action.rlp_bytes(), &mut state, &address, &pubkey).is_err());
+        assert_eq!(state.balance(&address).unwrap(), 1000);
+    }
 }
@@ -16,6 +16,7 @@
 
 use super::super::stake;
 use super::super::{ConsensusEngine, EngineError, Seal};
+use super::liveness;
 use super::network::TendermintExtension;
 pub use super::params::{TendermintParams, TimeoutParams};
 use super::types::TendermintSealView;
@@ -27,7 +28,7 @@ use crate::client::snapshot_notify::NotifySender as SnapshotNotifySender;
 use crate::client::{Client, ConsensusClient};
 use crate::codechain_machine::CodeChainMachine;
 use crate::consensus::tendermint::params::TimeGapParams;
-use crate::consensus::{EngineType, ValidatorSet};
+use crate::consensus::{ConsensusStatus, EngineType, ValidatorSet};
 use crate::encoded;
 use crate::error::Error;
 use crate::views::HeaderView;
@@ -230,6 +231,11 @@ impl ConsensusEngine for Tendermint {
             _ => {
                 stake::update_validator_weights(block.state_mut(), &author)?;
                 stake::add_intermediate_rewards(block.state_mut(), author, block_author_reward)?;
+
+                // The seal of the parent has the precommits on the grandparent.
+                let precommits = TendermintSealView::new(parent.seal()).bitset()?;
+                let validators = self.validators.current_addresses(parent.parent_hash());
+                liveness::track_precommits(block.state_mut(), &validators, &precommits)?;
             }
         }
 
@@ -240,16 +246,18 @@ impl ConsensusEngine for Tendermint {
         let inactive_validators = match term {
             0 => Vec::new(),
             _ => {
-                for (address, reward) in stake::drain_calculated_rewards(block.state_mut())? {
-                    self.machine.add_balance(block, &address, reward)?;
-                }
+                stake::pay_calculated_rewards(block.state_mut())?;
 
                 let start_of_the_current_term = metadata.last_term_finished_block_num() + 1;
                 let validators = stake::NextValidators::load_from_state(block.state())?
                     .into_iter()
                     .map(|val| public_to_address(val.pubkey()))
                     .collect();
-                inactive_validators(&*client, start_of_the_current_term, block.header(), validators)
+                let mut inactive = inactive_validators(&*client, start_of_the_current_term, block.header(), validators);
+                // The validators jailed in the middle of the term are not candidates anymore.
+                let candidates = stake::Candidates::load_from_state(block.state())?;
+                inactive.retain(|address| candidates.get_candidate(address).is_some());
+                inactive
             }
         };
 
@@ -276,6 +284,12 @@ impl ConsensusEngine for Tendermint {
         receiver.recv().unwrap()
     }
 
+    fn consensus_status(&self) -> Option<ConsensusStatus> {
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner.send(worker::Event::GetStatus(result)).unwrap();
+        Some(receiver.recv().unwrap())
+    }
+
     fn set_signer(&self, ap: Arc<AccountProvider>, address: Address) {
         self.has_signer.store(true, AtomicOrdering::SeqCst);
         self.inner
@@ -525,7 +539,16 @@ fn give_additional_rewards<F: FnMut(&Address, u64) -> Result<(), Error>>(
 mod tests {
     use std::iter::FromIterator;
 
+    use ckey::Public;
+    use cstate::tests::helpers;
+    use rlp::RlpStream;
+
+    use super::super::super::BitSet;
+    use super::super::TendermintParams;
     use super::*;
+    use crate::client::{BlockChainTrait, ImportBlock, TestBlockChainClient};
+    use crate::consensus::DynamicValidator;
+    use crate::scheme::Scheme;
 
     #[test]
     fn test_final_rewards() {
@@ -705,4 +728,59 @@ mod tests {
             )
         );
     }
+
+    #[test]
+    fn validator_missing_the_parent_precommit_is_recorded() {
+        let pubkeys: Vec<_> = (0..4).map(|_| Public::random()).collect();
+        let validators: Vec<_> = pubkeys.iter().map(public_to_address).collect();
+        let validator_set = Arc::new(DynamicValidator::new(pubkeys));
+        let engine = Tendermint::new(
+            TendermintParams {
+                validators: Arc::clone(&validator_set),
+                timeouts: Default::default(),
+                block_reward: 0,
+                genesis_stakes: HashMap::new(),
+            },
+            CodeChainMachine::new(CommonParams::default_for_test()),
+        );
+        let client = Arc::new(TestBlockChainClient::new_with_scheme(Scheme::new_test_tendermint()));
+        let consensus_client = Arc::clone(&client) as Arc<dyn ConsensusClient>;
+        engine.register_client(Arc::downgrade(&consensus_client));
+        validator_set.register_client(Arc::downgrade(&consensus_client));
+
+        // The validator 2 missed the precommit on the genesis block.
+        let genesis = client.block_header(&BlockId::Number(0)).unwrap().decode();
+        let mut parent = genesis.generate_child();
+        parent.set_timestamp(genesis.timestamp());
+        parent.set_seal(
+            Seal::Tendermint {
+                prev_view: 0,
+                cur_view: 0,
+                precommits: Vec::new(),
+                precommit_bitset: BitSet::new_with_indices(&[0, 1, 3]),
+            }
+            .seal_fields()
+            .unwrap(),
+        );
+        let mut rlp = RlpStream::new_list(2);
+        rlp.append(&parent);
+        rlp.begin_list(0);
+        client.import_block(rlp.out()).unwrap();
+
+        let mut params = CommonParams::default_for_test();
+        params.set_dynamic_validator_params_for_test(30, 10, 3, 20, 30, 4, 1000, 10000, 100);
+        params.set_liveness_params_for_test(4, 5000);
+        let mut state = helpers::get_temp_state_with_metadata();
+        assert_eq!(Ok(()), state.update_params(0, params));
+        state.increase_term_id(0).unwrap();
+
+        let mut header = parent.generate_child();
+        header.set_timestamp(parent.timestamp());
+        header.set_author(validators[0]);
+        let mut block = ExecutedBlock::new_for_test(header, state);
+        engine.on_close_block(&mut block, Some(&params)).unwrap();
+
+        let missed = stake::MissedPrecommits::load_from_state(block.state()).unwrap();
+        assert_eq!(BTreeMap::from_iter(vec![(validators[2], 1)]), missed.count());
+    }
 }
@@ -0,0 +1,266 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::message::VoteStep;
+use super::stake::{Action, CUSTOM_ACTION_HANDLER_ID};
+use super::vote_collector::DoubleVote;
+use crate::db;
+use crate::transaction::UnverifiedTransaction;
+use ctypes::transaction::Action as TransactionAction;
+use ctypes::BlockNumber;
+use kvdb::{DBTransaction, KeyValueDB};
+use rlp::Rlp;
+use std::collections::{BTreeMap, BTreeSet};
+
+const EVIDENCE_KEY: &[u8] = b"tendermint-evidence";
+/// The evidences on the blocks older than this many blocks from the best block are dropped.
+pub const EVIDENCE_LIFETIME: BlockNumber = 1000;
+/// The evidences in the pool are gossiped again at most once in this many blocks for the nodes that have missed them.
+pub const EVIDENCE_GOSSIP_INTERVAL: BlockNumber = 10;
+
+/// The double votes that are not reported in a block yet.
+///
+/// A validator can be punished once for a round, so the evidences are identified by the round and the signer.
+#[derive(Default)]
+pub struct EvidencePool {
+    evidences: BTreeMap<(VoteStep, usize), DoubleVote>,
+    /// The evidences this node has reported. They are not reported again while they are in the pool.
+    reported: BTreeSet<(VoteStep, usize)>,
+    /// The best block number when the pool was gossiped last time.
+    last_gossiped_at: Option<BlockNumber>,
+}
+
+impl EvidencePool {
+    pub fn load(db: &dyn KeyValueDB) -> Self {
+        let evidences = db
+            .get(db::COL_EXTRA, EVIDENCE_KEY)
+            .expect("Low level database error. Some issue with disk?")
+            .map(|bytes| rlp::decode_list::<DoubleVote>(&bytes))
+            .unwrap_or_default();
+        Self {
+            evidences: evidences.into_iter().map(|evidence| (evidence.key(), evidence)).collect(),
+            reported: Default::default(),
+            last_gossiped_at: None,
+        }
+    }
+
+    fn save(&self, db: &dyn KeyValueDB) {
+        let evidences: Vec<_> = self.evidences.values().cloned().collect();
+        let mut batch = DBTransaction::new();
+        batch.put(db::COL_EXTRA, EVIDENCE_KEY, &rlp::encode_list(&evidences));
+        db.write(batch).expect("Low level database error. Some issue with disk?");
+    }
+
+    pub fn contains(&self, evidence: &DoubleVote) -> bool {
+        self.evidences.contains_key(&evidence.key())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.evidences.is_empty()
+    }
+
+    #[cfg(test)]
+    pub fn evidences(&self) -> Vec<DoubleVote> {
+        self.evidences.values().cloned().collect()
+    }
+
+    pub fn is_expired(evidence: &DoubleVote, best_block_number: BlockNumber) -> bool {
+        evidence.key().0.height + EVIDENCE_LIFETIME < best_block_number
+    }
+
+    /// Returns the evidences this node has not reported yet, and marks them reported.
+    pub fn take_unreported(&mut self) -> Vec<DoubleVote> {
+        let unreported: Vec<_> = self
+            .evidences
+            .iter()
+            .filter(|(key, _)| !self.reported.contains(key))
+            .map(|(_, evidence)| evidence.clone())
+            .collect();
+        self.reported.extend(unreported.iter().map(DoubleVote::key));
+        unreported
+    }
+
+    /// Returns all the evidences to gossip them again if `EVIDENCE_GOSSIP_INTERVAL` blocks have passed since the last time.
+    pub fn take_to_gossip(&mut self, best_block_number: BlockNumber) -> Vec<DoubleVote> {
+        if self.evidences.is_empty() {
+            return Vec::new()
+        }
+        if let Some(last_gossiped_at) = self.last_gossiped_at {
+            if best_block_number < last_gossiped_at + EVIDENCE_GOSSIP_INTERVAL {
+                return Vec::new()
+            }
+        }
+        self.last_gossiped_at = Some(best_block_number);
+        self.evidences.values().cloned().collect()
+    }
+
+    /// Drops the evidences which are too old to be reported.
+    pub fn expire(&mut self, db: &dyn KeyValueDB, best_block_number: BlockNumber) {
+        let expired: Vec<_> = self
+            .evidences
+            .values()
+            .filter(|evidence| Self::is_expired(evidence, best_block_number))
+            .map(DoubleVote::key)
+            .collect();
+        if expired.is_empty() {
+            return
+        }
+        for key in &expired {
+            self.evidences.remove(key);
+            self.reported.remove(key);
+        }
+        self.save(db);
+    }
+
+    /// Returns false if the pool already has an evidence on the same round and signer.
+    pub fn insert(&mut self, db: &dyn KeyValueDB, evidence: DoubleVote) -> bool {
+        if self.contains(&evidence) {
+            return false
+        }
+        self.evidences.insert(evidence.key(), evidence);
+        self.save(db);
+        true
+    }
+
+    /// Removes the evidences reported by the given transactions.
+    pub fn remove_reported(&mut self, db: &dyn KeyValueDB, transactions: &[UnverifiedTransaction]) {
+        let mut removed = false;
+        for tx in transactions {
+            let bytes = match &tx.action {
+                TransactionAction::Custom {
+                    handler_id,
+                    bytes,
+                } if *handler_id == CUSTOM_ACTION_HANDLER_ID => bytes,
+                _ => continue,
+            };
+            if let Ok(Action::ReportDoubleVote {
+                message1,
+                ..
+            }) = Rlp::new(bytes).as_val()
+            {
+                let key = (*message1.round(), message1.signer_index());
+                self.reported.remove(&key);
+                removed |= self.evidences.remove(&key).is_some();
+            }
+        }
+        if removed {
+            self.save(db);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::message::{ConsensusMessage, VoteOn};
+    use super::super::types::Step;
+    use super::*;
+    use ckey::SchnorrSignature;
+    use ctypes::transaction::Transaction;
+    use rlp::Encodable;
+
+    fn double_vote(height: u64, signer_index: usize) -> DoubleVote {
+        let vote = |block_hash| ConsensusMessage {
+            signature: SchnorrSignature::random(),
+            signer_index,
+            on: VoteOn {
+                step: VoteStep::new(height, 0, Step::Precommit),
+                block_hash,
+            },
+        };
+        DoubleVote::new(vote(None), vote(Some(Default::default())))
+    }
+
+    fn report(evidence: &DoubleVote) -> UnverifiedTransaction {
+        let tx = Transaction {
+            seq: 0,
+            fee: 0,
+            network_id: Default::default(),
+            action: TransactionAction::Custom {
+                handler_id: CUSTOM_ACTION_HANDLER_ID,
+                bytes: evidence.to_action().rlp_bytes(),
+            },
+        };
+        UnverifiedTransaction::new(tx, Default::default())
+    }
+
+    #[test]
+    fn evidences_are_deduplicated_by_round_and_signer() {
+        let db = kvdb_memorydb::create(db::NUM_COLUMNS.unwrap());
+        let mut pool = EvidencePool::default();
+        assert!(pool.insert(&db, double_vote(3, 1)));
+        assert!(!pool.insert(&db, double_vote(3, 1)));
+        assert!(pool.insert(&db, double_vote(3, 2)));
+        assert_eq!(2, pool.evidences().len());
+    }
+
+    #[test]
+    fn evidences_are_kept_until_reported() {
+        let db = kvdb_memorydb::create(db::NUM_COLUMNS.unwrap());
+        let mut pool = EvidencePool::default();
+        pool.insert(&db, double_vote(3, 1));
+        pool.insert(&db, double_vote(4, 1));
+        assert_eq!(2, EvidencePool::load(&db).evidences().len());
+
+        pool.remove_reported(&db, &[report(&double_vote(3, 1))]);
+        assert_eq!(vec![double_vote(4, 1).key()], pool.evidences().iter().map(DoubleVote::key).collect::<Vec<_>>());
+        assert_eq!(1, EvidencePool::load(&db).evidences().len());
+    }
+
+    #[test]
+    fn evidences_are_reported_once() {
+        let db = kvdb_memorydb::create(db::NUM_COLUMNS.unwrap());
+        let mut pool = EvidencePool::default();
+        pool.insert(&db, double_vote(3, 1));
+        assert_eq!(
+            vec![double_vote(3, 1).key()],
+            pool.take_unreported().iter().map(DoubleVote::key).collect::<Vec<_>>()
+        );
+        assert!(pool.take_unreported().is_empty());
+
+        pool.insert(&db, double_vote(4, 1));
+        assert_eq!(
+            vec![double_vote(4, 1).key()],
+            pool.take_unreported().iter().map(DoubleVote::key).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn evidences_are_gossiped_again_at_intervals() {
+        let db = kvdb_memorydb::create(db::NUM_COLUMNS.unwrap());
+        let mut pool = EvidencePool::default();
+        assert!(pool.take_to_gossip(10).is_empty(), "Nothing to gossip");
+
+        pool.insert(&db, double_vote(3, 1));
+        assert_eq!(1, pool.take_to_gossip(10).len());
+        assert!(pool.take_to_gossip(10).is_empty());
+        assert!(pool.take_to_gossip(10 + EVIDENCE_GOSSIP_INTERVAL - 1).is_empty());
+        assert_eq!(1, pool.take_to_gossip(10 + EVIDENCE_GOSSIP_INTERVAL).len());
+    }
+
+    #[test]
+    fn old_evidences_expire() {
+        let db = kvdb_memorydb::create(db::NUM_COLUMNS.unwrap());
+        let mut pool = EvidencePool::default();
+        pool.insert(&db, double_vote(3, 1));
+        pool.insert(&db, double_vote(4, 1));
+
+        pool.expire(&db, 3 + EVIDENCE_LIFETIME);
+        assert_eq!(2, pool.evidences().len());
+        pool.expire(&db, 4 + EVIDENCE_LIFETIME);
+        assert_eq!(vec![double_vote(4, 1).key()], pool.evidences().iter().map(DoubleVote::key).collect::<Vec<_>>());
+        assert_eq!(1, EvidencePool::load(&db).evidences().len());
+    }
+}
@@ -0,0 +1,410 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Follows a Tendermint chain from a trusted checkpoint without the state and the blocks.
+//!
+//! The seal of a block carries the precommits on its parent, so a header is committed
+//! when its child is verified. The validators who sign the precommits are read from
+//! the `NextValidators` in the state of the grandparent, which is proven against the state root.
+
+use super::super::stake::{self, Validator};
+use super::engine::is_term_changed;
+use super::types::TendermintSealView;
+use super::{Step, VoteOn, VoteStep};
+use ckey::{verify_schnorr, Public};
+use cstate::{ActionData, StateProof};
+use ctypes::util::unexpected::{Mismatch, OutOfBounds};
+use ctypes::{BlockHash, Header};
+use primitives::H256;
+use rlp::{decode_list, DecoderError};
+use std::fmt;
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// The header is not the child of the last verified header.
+    NotChild(Mismatch<BlockHash>),
+    /// The seal cannot be decoded.
+    InvalidSeal(DecoderError),
+    /// A precommit is not signed by any of the validators.
+    UnknownSigner(usize),
+    /// Two precommits are signed by the same validator.
+    DuplicatedSigner(Public),
+    /// The signers don't have more than two thirds of the delegations.
+    NotEnoughVotes(OutOfBounds<u64>),
+    /// The validators changed at the header but their proof is not given.
+    ValidatorsProofRequired(u64),
+    /// The proof of the validators is not valid against the state root.
+    InvalidValidatorsProof(H256),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::NotChild(mismatch) => {
+                write!(f, "The parent hash is {} but expected {}", mismatch.found, mismatch.expected)
+            }
+            Error::InvalidSeal(err) => write!(f, "Invalid seal: {}", err),
+            Error::UnknownSigner(index) => write!(f, "The precommit {} is not signed by a validator", index),
+            Error::DuplicatedSigner(public) => write!(f, "{} signed the precommits more than once", public),
+            Error::NotEnoughVotes(votes) => write!(f, "Not enough votes: {}", votes),
+            Error::ValidatorsProofRequired(number) => {
+                write!(f, "The validators changed at #{}, but their proof is not given", number)
+            }
+            Error::InvalidValidatorsProof(root) => {
+                write!(f, "The validators are not proven against the state root {}", root)
+            }
+        }
+    }
+}
+
+impl From<DecoderError> for Error {
+    fn from(err: DecoderError) -> Self {
+        Error::InvalidSeal(err)
+    }
+}
+
+/// The public keys of the validators and their delegations, which are the weights of their votes.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Validators(Vec<(Public, u64)>);
+
+impl Validators {
+    /// The validators before the first term close, who have the same weight.
+    pub fn with_equal_weights(pubkeys: Vec<Public>) -> Self {
+        Validators(pubkeys.into_iter().map(|pubkey| (pubkey, 1)).collect())
+    }
+
+    /// Reads the `NextValidators` from a proof of the action data.
+    /// It returns `None` if the proof is valid but there is no validator,
+    /// which means that the validators are not elected yet.
+    pub fn from_proof(proof: &StateProof, state_root: &H256) -> Result<Option<Self>, Error> {
+        if proof.root != *state_root || proof.key.as_slice() != stake::NEXT_VALIDATORS_KEY.as_ref() || !proof.verify() {
+            return Err(Error::InvalidValidatorsProof(*state_root))
+        }
+        let data = match &proof.value {
+            Some(value) => rlp::decode::<ActionData>(value).map_err(|_| Error::InvalidValidatorsProof(*state_root))?,
+            None => return Ok(None),
+        };
+        let validators: Vec<Validator> = decode_list(&data);
+        if validators.is_empty() {
+            return Ok(None)
+        }
+        Ok(Some(Validators(validators.iter().map(|validator| (*validator.pubkey(), validator.delegation())).collect())))
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Checks that the signers of the precommits on the parent of the header have more than two thirds of the delegations.
+    /// The order of the validators changes every block with their weights,
+    /// so the signatures are matched to the validators instead of using the indices in the bitset.
+    fn verify_precommits(&self, header: &Header, parent: &Header) -> Result<(), Error> {
+        let seal_view = TendermintSealView::new(header.seal());
+        let bitset_count = seal_view.bitset()?.count();
+        let precommits_count = seal_view.precommits().item_count()?;
+        if bitset_count != precommits_count {
+            return Err(Error::InvalidSeal(DecoderError::RlpIncorrectListLen {
+                expected: bitset_count,
+                got: precommits_count,
+            }))
+        }
+
+        let precommit_vote_on = VoteOn {
+            step: VoteStep::new(parent.number(), seal_view.parent_block_finalized_view()?, Step::Precommit),
+            block_hash: Some(parent.hash()),
+        };
+        let message = precommit_vote_on.hash();
+
+        let mut signed = vec![false; self.0.len()];
+        let mut voted_delegation = 0u64;
+        for (bitset_index, signature) in seal_view.signatures()? {
+            let position = self
+                .0
+                .iter()
+                .position(|(public, _)| verify_schnorr(public, &signature, &message).unwrap_or(false))
+                .ok_or(Error::UnknownSigner(bitset_index))?;
+            if signed[position] {
+                return Err(Error::DuplicatedSigner(self.0[position].0))
+            }
+            signed[position] = true;
+            voted_delegation += self.0[position].1;
+        }
+
+        let total_delegation: u64 = self.0.iter().map(|(_, delegation)| delegation).sum();
+        if voted_delegation * 3 <= total_delegation * 2 {
+            return Err(Error::NotEnoughVotes(OutOfBounds {
+                min: Some(total_delegation * 2 / 3),
+                max: Some(total_delegation),
+                found: voted_delegation,
+            }))
+        }
+        Ok(())
+    }
+}
+
+/// Verifies the headers one by one from a trusted checkpoint.
+///
+/// A verified header is only a candidate until its child commits it. The seal of a candidate carries the precommits
+/// on its parent, which can be copied to another header, so a candidate is replaced by any other verified child of
+/// the committed header.
+pub struct LightClient {
+    /// The last committed header. The checkpoint is committed from the beginning since it's trusted.
+    committed: Header,
+    /// The validators of the committed header, who sign the precommits on it.
+    validators: Validators,
+    /// The validators of the child of the committed header.
+    next_validators: Validators,
+    /// The verified child of the committed header and the validators of its child.
+    candidate: Option<(Header, Validators)>,
+    term_seconds: u64,
+}
+
+impl LightClient {
+    /// `validators` are the validators of the checkpoint, and `next_validators` are those of its child.
+    /// The light client should be restarted from a new checkpoint if `term_seconds` changes.
+    pub fn new(checkpoint: Header, validators: Validators, next_validators: Validators, term_seconds: u64) -> Self {
+        Self {
+            committed: checkpoint,
+            validators,
+            next_validators,
+            candidate: None,
+            term_seconds,
+        }
+    }
+
+    /// The last verified header, which is the candidate if there is one.
+    pub fn head(&self) -> &Header {
+        self.candidate.as_ref().map_or(&self.committed, |(candidate, _)| candidate)
+    }
+
+    pub fn committed(&self) -> &Header {
+        &self.committed
+    }
+
+    pub fn validators(&self) -> &Validators {
+        &self.validators
+    }
+
+    /// Drops the candidate, so the next header is verified as a child of the committed header.
+    pub fn rollback(&mut self) {
+        self.candidate = None;
+    }
+
+    /// Verifies the child of the head and makes it the new candidate. The head is committed by it if it's a candidate.
+    /// A child of the committed header replaces the candidate.
+    ///
+    /// The validators are elected at the end of a term, so the proof of the `NextValidators` in the state of the header
+    /// is required if the header closes a term. It can be given for any header to follow the validators
+    /// removed in the middle of a term.
+    pub fn verify(&mut self, header: Header, next_validators_proof: Option<&StateProof>) -> Result<(), Error> {
+        let commits_candidate = match &self.candidate {
+            Some((candidate, _)) => header.parent_hash() == &candidate.hash(),
+            None => false,
+        };
+        let (parent, validators, next_validators) = match &self.candidate {
+            Some((candidate, candidate_next_validators)) if commits_candidate => {
+                (candidate, &self.next_validators, candidate_next_validators)
+            }
+            _ if header.parent_hash() == &self.committed.hash() => {
+                (&self.committed, &self.validators, &self.next_validators)
+            }
+            _ => {
+                return Err(Error::NotChild(Mismatch {
+                    expected: self.head().hash(),
+                    found: *header.parent_hash(),
+                }))
+            }
+        };
+        validators.verify_precommits(&header, parent)?;
+
+        // The state root of the header is used only after the header is committed by its child.
+        let header_next_validators = match next_validators_proof {
+            Some(proof) => Validators::from_proof(proof, header.state_root())?,
+            None if is_term_changed(&header, parent, self.term_seconds) => {
+                return Err(Error::ValidatorsProofRequired(header.number()))
+            }
+            None => None,
+        };
+        let header_next_validators = header_next_validators.unwrap_or_else(|| next_validators.clone());
+
+        ctrace!(ENGINE, "Light client verified #{}({})", header.number(), header.hash());
+        if commits_candidate {
+            let (candidate, candidate_next_validators) = self.candidate.take().expect("The candidate is committed");
+            self.committed = candidate;
+            self.validators = std::mem::replace(&mut self.next_validators, candidate_next_validators);
+        }
+        self.candidate = Some((header, header_next_validators));
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::super::BitSet;
+    use super::*;
+    use crate::consensus::stake::NextValidators;
+    use crate::consensus::Seal;
+    use ckey::{sign_schnorr, Generator, KeyPair, Random};
+    use cstate::tests::helpers;
+    use cstate::{StateWithCache, TopStateView};
+
+    fn header(parent: &Header, timestamp: u64, signers: &[&KeyPair]) -> Header {
+        let vote_on = VoteOn {
+            step: VoteStep::new(parent.number(), 0, Step::Precommit),
+            block_hash: Some(parent.hash()),
+        };
+        let mut bitset = BitSet::new();
+        let mut precommits = Vec::new();
+        for (index, signer) in signers.iter().enumerate() {
+            bitset.set(index);
+            precommits.push(sign_schnorr(signer.private(), &vote_on.hash()).unwrap());
+        }
+        let mut header = Header::new();
+        header.set_parent_hash(parent.hash());
+        header.set_number(parent.number() + 1);
+        header.set_timestamp(timestamp);
+        header.set_seal(
+            Seal::Tendermint {
+                prev_view: 0,
+                cur_view: 0,
+                precommits,
+                precommit_bitset: bitset,
+            }
+            .seal_fields()
+            .unwrap(),
+        );
+        header
+    }
+
+    fn light_client(keys: &[KeyPair]) -> LightClient {
+        let validators = Validators::with_equal_weights(keys.iter().map(|key| *key.public()).collect());
+        let mut checkpoint = Header::new();
+        checkpoint.set_number(10);
+        checkpoint.set_timestamp(10);
+        LightClient::new(checkpoint, validators.clone(), validators, 100)
+    }
+
+    #[test]
+    fn verify_the_child_of_the_head() {
+        let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+        let mut client = light_client(&keys);
+        let checkpoint = client.head().clone();
+
+        let child = header(&checkpoint, 11, &[&keys[3], &keys[0], &keys[2]]);
+        assert_eq!(Ok(()), client.verify(child.clone(), None));
+        assert_eq!(client.committed(), &checkpoint);
+        assert_eq!(client.head(), &child);
+
+        let grandchild = header(&child, 12, &[&keys[1], &keys[2], &keys[3]]);
+        assert_eq!(Ok(()), client.verify(grandchild.clone(), None));
+        assert_eq!(client.committed(), &child);
+        assert_eq!(client.head(), &grandchild);
+    }
+
+    #[test]
+    fn candidate_is_replaced_by_another_child_of_the_committed_header() {
+        let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+        let mut client = light_client(&keys);
+        let checkpoint = client.head().clone();
+
+        // The precommits on the checkpoint are copied to a forged header.
+        let child = header(&checkpoint, 11, &[&keys[0], &keys[1], &keys[2]]);
+        let mut forged = child.clone();
+        forged.set_state_root(H256::random());
+        assert_eq!(Ok(()), client.verify(forged.clone(), None));
+        assert_eq!(client.head(), &forged);
+
+        let grandchild = header(&child, 12, &[&keys[0], &keys[1], &keys[2]]);
+        match client.verify(grandchild.clone(), None) {
+            Err(Error::NotChild(_)) => {}
+            result => panic!("The child of another header is accepted: {:?}", result),
+        }
+
+        assert_eq!(Ok(()), client.verify(child.clone(), None));
+        assert_eq!(client.committed(), &checkpoint);
+        assert_eq!(Ok(()), client.verify(grandchild, None));
+        assert_eq!(client.committed(), &child);
+    }
+
+    #[test]
+    fn rollback_to_the_committed_header() {
+        let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+        let mut client = light_client(&keys);
+        let checkpoint = client.head().clone();
+
+        let child = header(&checkpoint, 11, &[&keys[0], &keys[1], &keys[2]]);
+        assert_eq!(Ok(()), client.verify(child, None));
+        client.rollback();
+        assert_eq!(client.head(), &checkpoint);
+        assert_eq!(client.committed(), &checkpoint);
+    }
+
+    #[test]
+    fn reject_headers_without_enough_votes() {
+        let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+        let mut client = light_client(&keys);
+        let checkpoint = client.head().clone();
+
+        let child = header(&checkpoint, 11, &[&keys[0], &keys[1]]);
+        match client.verify(child, None) {
+            Err(Error::NotEnoughVotes(_)) => {}
+            result => panic!("Unexpected result {:?}", result),
+        }
+
+        let stranger = Random.generate().unwrap();
+        let child = header(&checkpoint, 11, &[&keys[0], &keys[1], &stranger]);
+        assert_eq!(Err(Error::UnknownSigner(2)), client.verify(child, None));
+
+        let child = header(&checkpoint, 11, &[&keys[0], &keys[1], &keys[1]]);
+        assert_eq!(Err(Error::DuplicatedSigner(*keys[1].public())), client.verify(child, None));
+        assert_eq!(client.head(), &checkpoint);
+    }
+
+    #[test]
+    fn require_the_validators_at_the_term_close() {
+        let keys: Vec<_> = (0..4).map(|_| Random.generate().unwrap()).collect();
+        let mut client = light_client(&keys);
+        let checkpoint = client.head().clone();
+
+        let child = header(&checkpoint, 100, &[&keys[0], &keys[1], &keys[2]]);
+        assert_eq!(Err(Error::ValidatorsProofRequired(11)), client.verify(child, None));
+
+        let other = header(&checkpoint, 11, &[&keys[0], &keys[1], &keys[2]]);
+        let mut child = header(&other, 12, &[&keys[0], &keys[1], &keys[2]]);
+        child.set_parent_hash(checkpoint.hash());
+        match client.verify(child, None) {
+            Err(Error::UnknownSigner(_)) => {}
+            result => panic!("The precommits on another block are accepted: {:?}", result),
+        }
+    }
+
+    #[test]
+    fn read_the_validators_from_the_proof() {
+        let mut state = helpers::get_temp_state();
+        let pubkey = Public::random();
+        let validators = NextValidators::from_vector_to_test(vec![Validator::new_for_test(10, 20, pubkey)]);
+        validators.save_to_state(&mut state).unwrap();
+        let root = state.commit().unwrap();
+
+        let proof = state.action_data_proof(&*stake::NEXT_VALIDATORS_KEY).unwrap();
+        assert_eq!(Ok(Some(Validators(vec![(pubkey, 10)]))), Validators::from_proof(&proof, &root));
+        assert_eq!(Err(Error::InvalidValidatorsProof(H256::zero())), Validators::from_proof(&proof, &H256::zero()));
+    }
+}
@@ -0,0 +1,127 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! Tracks the precommits carried in the seals, so that the validators who stay offline are jailed.
+//! The missed precommits are kept in the state, so every node jails the same validators at the same block.
+
+use super::super::stake;
+use super::super::BitSet;
+use ckey::Address;
+use cstate::{StateResult, TopLevelState};
+
+/// The validators whose precommits are not in the bitset.
+fn missed_validators(validators: &[Address], precommits: &BitSet) -> Vec<Address> {
+    validators.iter().enumerate().filter(|(index, _)| !precommits.is_set(*index)).map(|(_, address)| *address).collect()
+}
+
+/// `validators` are the validators who precommitted on the parent of the sealed block,
+/// in the order of the bitset.
+pub fn track_precommits(
+    state: &mut TopLevelState,
+    validators: &[Address],
+    precommits: &BitSet,
+) -> StateResult<Vec<Address>> {
+    let jailed = stake::update_liveness(state, missed_validators(validators, precommits))?;
+    for address in &jailed {
+        cinfo!(ENGINE, "{} is jailed because it missed too many precommits", address);
+    }
+    Ok(jailed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::consensus::stake::{Candidates, Jail};
+    use ckey::{public_to_address, Public};
+    use cstate::tests::helpers;
+    use cstate::TopState;
+    use ctypes::CommonParams;
+
+    fn state_with_candidates(liveness_window: u64, liveness_threshold: u64, pubkeys: &[Public]) -> TopLevelState {
+        let mut state = helpers::get_temp_state_with_metadata();
+        let mut params = CommonParams::default_for_test();
+        params.set_dynamic_validator_params_for_test(30, 10, 3, 20, 30, 4, 1000, 10000, 100);
+        params.set_liveness_params_for_test(liveness_window, liveness_threshold);
+        assert_eq!(Ok(()), state.update_params(0, params));
+
+        let mut candidates = Candidates::load_from_state(&state).unwrap();
+        for pubkey in pubkeys {
+            candidates.add_deposit(pubkey, 1000, 30, b"".to_vec());
+        }
+        candidates.save_to_state(&mut state).unwrap();
+        state
+    }
+
+    #[test]
+    fn validator_missing_more_than_threshold_is_jailed() {
+        let pubkeys: Vec<_> = (0..4).map(|_| Public::random()).collect();
+        let validators: Vec<_> = pubkeys.iter().map(public_to_address).collect();
+        // Jailed if it misses more than 2 of the recent 4 blocks.
+        let mut state = state_with_candidates(4, 5000, &pubkeys);
+
+        // The validator 3 is offline and the validator 2 misses every other block.
+        let seals = [vec![0, 1, 2], vec![0, 1], vec![0, 1, 2], vec![0, 1]];
+        for (block, indices) in seals.iter().enumerate() {
+            let jailed = track_precommits(&mut state, &validators, &BitSet::new_with_indices(indices)).unwrap();
+            if block < 3 {
+                assert_eq!(jailed, Vec::<Address>::new(), "Should wait until the window is filled");
+            } else {
+                assert_eq!(jailed, vec![validators[3]]);
+            }
+        }
+
+        let jail = Jail::load_from_state(&state).unwrap();
+        assert!(jail.get_prisoner(&validators[3]).is_some());
+        assert_eq!(jail.get_prisoner(&validators[2]), None);
+        let candidates = Candidates::load_from_state(&state).unwrap();
+        assert_eq!(candidates.get_candidate(&validators[3]), None);
+        assert!(candidates.get_candidate(&validators[2]).is_some());
+
+        // The jailed validator is still in the validator set until the end of the term.
+        let jailed = track_precommits(&mut state, &validators, &BitSet::new_with_indices(&[0, 1, 2])).unwrap();
+        assert_eq!(jailed, Vec::<Address>::new(), "Should not jail the prisoner again");
+    }
+
+    #[test]
+    fn window_slides_over_old_blocks() {
+        let pubkeys: Vec<_> = (0..4).map(|_| Public::random()).collect();
+        let validators: Vec<_> = pubkeys.iter().map(public_to_address).collect();
+        let mut state = state_with_candidates(4, 5000, &pubkeys);
+
+        // The validator 1 misses 2 blocks in every window.
+        let seals = [vec![0, 2, 3], vec![0, 2, 3], vec![0, 1, 2, 3], vec![0, 1, 2, 3], vec![0, 2, 3], vec![0, 2, 3]];
+        for indices in seals.iter() {
+            let jailed = track_precommits(&mut state, &validators, &BitSet::new_with_indices(indices)).unwrap();
+            assert_eq!(jailed, Vec::<Address>::new());
+        }
+
+        let jailed = track_precommits(&mut state, &validators, &BitSet::new_with_indices(&[0, 2, 3])).unwrap();
+        assert_eq!(jailed, vec![validators[1]]);
+    }
+
+    #[test]
+    fn liveness_is_not_tracked_without_window() {
+        let pubkeys: Vec<_> = (0..4).map(|_| Public::random()).collect();
+        let validators: Vec<_> = pubkeys.iter().map(public_to_address).collect();
+        let mut state = state_with_candidates(0, 0, &pubkeys);
+
+        for _ in 0..10 {
+            let jailed = track_precommits(&mut state, &validators, &BitSet::new()).unwrap();
+            assert_eq!(jailed, Vec::<Address>::new());
+        }
+        assert_eq!(Jail::load_from_state(&state).unwrap().len(), 0);
+    }
+}
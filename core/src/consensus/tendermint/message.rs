@@ -15,6 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::super::BitSet;
+use super::vote_collector::DoubleVote;
 use super::{Height, Step, View};
 use ccrypto::blake256;
 use ckey::{verify_schnorr, Error as KeyError, Public, SchnorrSignature};
@@ -80,6 +81,7 @@ enum MessageID {
     RequestProposal = 0x05,
     RequestCommit = 0x06,
     Commit = 0x07,
+    Evidence = 0x08,
 }
 
 impl Encodable for MessageID {
@@ -99,6 +101,7 @@ impl Decodable for MessageID {
             0x05 => Ok(MessageID::RequestProposal),
             0x06 => Ok(MessageID::RequestCommit),
             0x07 => Ok(MessageID::Commit),
+            0x08 => Ok(MessageID::Evidence),
             _ => Err(DecoderError::Custom("Unexpected MessageID Value")),
         }
     }
@@ -133,6 +136,7 @@ pub enum TendermintMessage {
         block: Bytes,
         votes: Vec<ConsensusMessage>,
     },
+    Evidence(Vec<DoubleVote>),
 }
 
 impl Encodable for TendermintMessage {
@@ -207,6 +211,11 @@ impl Encodable for TendermintMessage {
                 s.append(block);
                 s.append_list(votes);
             }
+            TendermintMessage::Evidence(evidences) => {
+                s.begin_list(2);
+                s.append(&MessageID::Evidence);
+                s.append_list(evidences);
+            }
         }
     }
 }
@@ -328,6 +337,16 @@ impl Decodable for TendermintMessage {
                     votes,
                 }
             }
+            MessageID::Evidence => {
+                let item_count = rlp.item_count()?;
+                if item_count != 2 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 2,
+                    })
+                }
+                TendermintMessage::Evidence(rlp.list_at(1)?)
+            }
         })
     }
 }
@@ -477,6 +496,22 @@ mod tests {
         });
     }
 
+    #[test]
+    fn encode_and_decode_tendermint_message_8() {
+        let vote = |block_hash| ConsensusMessage {
+            signature: SchnorrSignature::random(),
+            signer_index: 0x1234,
+            on: VoteOn {
+                step: VoteStep::new(2, 3, Step::Prevote),
+                block_hash,
+            },
+        };
+        rlp_encode_and_decode_test!(TendermintMessage::Evidence(vec![DoubleVote::new(
+            vote(None),
+            vote(Some(H256::from("07feab4c39250abf60b77d7589a5b61fdf409bd837e936376381d19db1e1f050").into()))
+        )]));
+    }
+
     #[test]
     fn encode_and_decode_consensus_message_1() {
         let message = ConsensusMessage::default();
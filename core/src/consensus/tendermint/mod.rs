@@ -17,9 +17,14 @@
 mod backup;
 mod chain_notify;
 mod engine;
+mod evidence;
+pub mod light_client;
+mod liveness;
 mod message;
 mod network;
 mod params;
+#[cfg(test)]
+mod simulation;
 pub mod types;
 pub mod vote_collector;
 mod vote_regression_checker;
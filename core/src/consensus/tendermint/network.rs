@@ -18,6 +18,7 @@ use super::super::BitSet;
 use super::message::*;
 use super::params::TimeoutParams;
 use super::types::{Height, PeerState, Step, View};
+use super::vote_collector::DoubleVote;
 use super::worker;
 use super::{
     ENGINE_TIMEOUT_BROADCAST_STEP_STATE, ENGINE_TIMEOUT_BROADCAT_STEP_STATE_INTERVAL, ENGINE_TIMEOUT_EMPTY_PROPOSAL,
@@ -48,6 +49,8 @@ pub struct TendermintExtension {
 
 const MIN_PEERS_PROPAGATION: usize = 4;
 const MAX_PEERS_PROPAGATION: usize = 128;
+/// A peer is disconnected once it sends this many invalid evidences.
+const MAX_INVALID_EVIDENCES_PER_PEER: usize = 3;
 
 impl TendermintExtension {
     pub fn new(inner: crossbeam::Sender<worker::Event>, timeouts: TimeoutParams, api: Box<dyn Api>) -> Self {
@@ -146,6 +149,27 @@ impl TendermintExtension {
         }
     }
 
+    /// Evidences are rare and must reach every node, so they are sent to all peers.
+    fn broadcast_evidence(&self, evidences: Vec<DoubleVote>) {
+        ctrace!(ENGINE, "Broadcast evidences({})", evidences.len());
+        let message = Arc::new(TendermintMessage::Evidence(evidences).rlp_bytes());
+        for token in self.peers.keys() {
+            self.api.send(token, Arc::clone(&message));
+        }
+    }
+
+    fn on_invalid_evidences(&mut self, token: &NodeId, count: usize) {
+        let peer_state = match self.peers.get_mut(token) {
+            Some(peer_state) => peer_state,
+            None => return,
+        };
+        peer_state.invalid_evidences += count;
+        if peer_state.invalid_evidences >= MAX_INVALID_EVIDENCES_PER_PEER {
+            cwarn!(ENGINE, "Disconnect peer {} which sent {} invalid evidences", token, peer_state.invalid_evidences);
+            self.api.disconnect(token);
+        }
+    }
+
     fn request_proposal_to_any(&self, height: Height, view: View) {
         for (token, peer) in &self.peers {
             let is_future_height_and_view = {
@@ -395,6 +419,15 @@ impl NetworkExtension<Event> for TendermintExtension {
                     }
                 }
             }
+            Ok(TendermintMessage::Evidence(evidences)) => {
+                ctrace!(ENGINE, "Received evidences({}) from {:?}", evidences.len(), token);
+                self.inner
+                    .send(worker::Event::HandleEvidences {
+                        evidences,
+                        from: *token,
+                    })
+                    .unwrap();
+            }
             _ => cinfo!(ENGINE, "Invalid message from peer {}", token),
         }
     }
@@ -452,6 +485,17 @@ impl NetworkExtension<Event> for TendermintExtension {
             } => {
                 self.broadcast_proposal_block(signature, view, message);
             }
+            Event::BroadcastEvidence {
+                evidences,
+            } => {
+                self.broadcast_evidence(evidences);
+            }
+            Event::InvalidEvidences {
+                from,
+                count,
+            } => {
+                self.on_invalid_evidences(&from, count);
+            }
         }
     }
 }
@@ -487,4 +531,12 @@ pub enum Event {
         view: View,
         message: Bytes,
     },
+    BroadcastEvidence {
+        evidences: Vec<DoubleVote>,
+    },
+    /// The worker found invalid evidences sent by the peer.
+    InvalidEvidences {
+        from: NodeId,
+        count: usize,
+    },
 }
@@ -39,7 +39,10 @@ impl From<cjson::scheme::TendermintParams> for TendermintParams {
     fn from(p: cjson::scheme::TendermintParams) -> Self {
         let dt = TimeoutParams::default();
         TendermintParams {
-            validators: Arc::new(DynamicValidator::new(p.validators)),
+            validators: Arc::new(DynamicValidator::new_with_proposer_selection(
+                p.validators,
+                p.proposer_selection.map(Into::into).unwrap_or_default(),
+            )),
             timeouts: TimeoutParams {
                 propose: p.timeout_propose.map_or(dt.propose, to_duration),
                 propose_delta: p.timeout_propose_delta.map_or(dt.propose_delta, to_duration),
@@ -0,0 +1,1228 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! A Tendermint network in a box for the fault injection tests.
+//!
+//! Every validator runs the real `Worker` thread and `TendermintExtension`,
+//! but the p2p layer is replaced by an in-memory network and the `TimerLoop` by a virtual clock.
+//! The block timestamps and the time the workers see are read from the virtual clock too.
+//! The harness waits for a worker to be idle after every delivery and every timeout,
+//! so a run is decided by the seed alone.
+
+use super::super::BitSet;
+use super::message::{ConsensusMessage, TendermintMessage, VoteOn, VoteStep};
+use super::network::{self, TendermintExtension};
+use super::params::{TimeGapParams, TimeoutParams};
+use super::types::Step;
+use super::worker;
+use crate::account_provider::AccountProvider;
+use crate::block::{Block, IsBlock, SealedBlock};
+use crate::blockchain_info::BlockChainInfo;
+use crate::client::{
+    AccountData, BlockChainClient, BlockChainTrait, ConsensusClient, EngineClient, EngineInfo, ImportBlock,
+    ImportResult, StateInfo, StateOrBlock, TermInfo,
+};
+use crate::consensus::stake::Action as StakeAction;
+use crate::consensus::{DynamicValidator, EngineError, Seal};
+use crate::db::NUM_COLUMNS;
+use crate::encoded;
+use crate::error::{BlockError, BlockImportError, Error as GenericError, ImportError};
+use crate::receipt::Receipt;
+use crate::snapshot_notify;
+use crate::transaction::{LocalizedTransaction, PendingSignedTransactions, SignedTransaction};
+use crate::types::{BlockId, BlockStatus, TransactionId, VerificationQueueInfo as BlockQueueInfo};
+use crate::views::BlockView;
+use ccrypto::blake256;
+use ckey::{sign_schnorr, verify_schnorr, Address, KeyPair, NetworkId, Password, PlatformAddress, Private, Public};
+use cnetwork::{Api, NetworkExtension, NetworkExtensionResult, NodeId};
+use crossbeam_channel as crossbeam;
+use cstate::tests::helpers::empty_top_state;
+use cstate::{StateDB, TopLevelState};
+use ctimer::TimerToken;
+use ctypes::transaction::Action;
+use ctypes::{BlockHash, BlockNumber, CommonParams, Header, Tracker, TxHash};
+use cvm::ChainTimeInfo;
+use kvdb::KeyValueDB;
+use parking_lot::{Mutex, RwLock};
+use primitives::{Bytes, U256};
+use rand::{Rng, SeedableRng};
+use rand_xorshift::XorShiftRng;
+use rlp::{Encodable, Rlp, RlpStream};
+use std::cmp;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::mem;
+use std::net::{IpAddr, Ipv4Addr};
+use std::ops::Range;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Weak};
+use std::thread::JoinHandle;
+use std::time::{Duration, UNIX_EPOCH};
+
+/// The extension gossips to at least four random peers,
+/// so every broadcast reaches every peer only up to five validators.
+const MAX_VALIDATORS: usize = 5;
+/// The interval of the block sync in milliseconds.
+const SYNC_INTERVAL: u64 = 5_000;
+/// The timestamp of the genesis block, where the virtual clock starts.
+const GENESIS_TIMESTAMP: u64 = 1_577_836_800;
+
+fn node_id(index: usize) -> NodeId {
+    NodeId::new(IpAddr::V4(Ipv4Addr::LOCALHOST), 3485 + index as u16)
+}
+
+/// The seconds since the epoch at the given milliseconds of the virtual clock.
+fn unix_time(now: u64) -> u64 {
+    GENESIS_TIMESTAMP + now / 1000
+}
+
+fn millis(duration: Duration) -> u64 {
+    duration.as_millis() as u64
+}
+
+/// The delay and the loss of the messages. The delays are in milliseconds of the virtual clock.
+#[derive(Clone, Copy)]
+pub struct Faults {
+    pub min_delay: u64,
+    pub max_delay: u64,
+    /// The probability that a message is lost.
+    pub drop_rate: f64,
+    /// The probability that a message is held for another `max_delay`, so that it arrives after the later ones.
+    pub reorder_rate: f64,
+}
+
+impl Default for Faults {
+    fn default() -> Self {
+        Self {
+            min_delay: 10,
+            max_delay: 100,
+            drop_rate: 0.0,
+            reorder_rate: 0.0,
+        }
+    }
+}
+
+/// A deviation from the protocol, which is applied to the messages a byzantine validator sends.
+pub trait Behaviour {
+    /// Rewrites the message sent to the `to`th validator. Returning `None` withholds the message.
+    fn tamper(&mut self, signer: &KeyPair, to: usize, message: TendermintMessage) -> Option<TendermintMessage>;
+}
+
+fn is_signed_by(vote: &ConsensusMessage, signer: &KeyPair) -> bool {
+    vote.verify(signer.public()).unwrap_or(false)
+}
+
+/// Sends a conflicting prevote or precommit along with every vote to the odd numbered validators.
+pub struct DoubleVote;
+
+impl Behaviour for DoubleVote {
+    fn tamper(&mut self, signer: &KeyPair, to: usize, message: TendermintMessage) -> Option<TendermintMessage> {
+        let votes = match message {
+            TendermintMessage::ConsensusMessage(votes) => votes,
+            message => return Some(message),
+        };
+        if to % 2 == 0 {
+            return Some(TendermintMessage::ConsensusMessage(votes))
+        }
+
+        let mut tampered = Vec::with_capacity(votes.len() * 2);
+        for bytes in votes {
+            if let Ok(mut vote) = Rlp::new(&bytes).as_val::<ConsensusMessage>() {
+                if vote.on.step.step != Step::Propose && is_signed_by(&vote, signer) {
+                    vote.on.block_hash = match vote.on.block_hash {
+                        Some(_) => None,
+                        None => Some(blake256(&bytes).into()),
+                    };
+                    vote.signature = sign_schnorr(signer.private(), &vote.on.hash()).expect("The key is valid");
+                    tampered.push(vote.rlp_bytes());
+                }
+            }
+            tampered.push(bytes);
+        }
+        Some(TendermintMessage::ConsensusMessage(tampered))
+    }
+}
+
+/// Proposes a different block with the same parent to the odd numbered validators.
+pub struct EquivocatingProposer;
+
+impl Behaviour for EquivocatingProposer {
+    fn tamper(&mut self, signer: &KeyPair, to: usize, message: TendermintMessage) -> Option<TendermintMessage> {
+        let (signature, view, block) = match message {
+            TendermintMessage::ProposalBlock {
+                signature,
+                view,
+                message,
+            } => (signature, view, message),
+            message => return Some(message),
+        };
+        let mut header = BlockView::new(&block).header();
+        let on = VoteOn {
+            step: VoteStep::new(header.number(), view, Step::Propose),
+            block_hash: Some(header.hash()),
+        };
+        if to % 2 == 0 || !verify_schnorr(signer.public(), &signature, &on.hash()).unwrap_or(false) {
+            return Some(TendermintMessage::ProposalBlock {
+                signature,
+                view,
+                message: block,
+            })
+        }
+
+        header.set_timestamp(header.timestamp() + 1);
+        let mut equivocation = RlpStream::new_list(2);
+        equivocation.append(&header);
+        equivocation.append_raw(Rlp::new(&block).at(1).expect("The block has transactions").as_raw(), 1);
+        let on = VoteOn {
+            block_hash: Some(header.hash()),
+            ..on
+        };
+        Some(TendermintMessage::ProposalBlock {
+            signature: sign_schnorr(signer.private(), &on.hash()).expect("The key is valid"),
+            view,
+            message: equivocation.out(),
+        })
+    }
+}
+
+/// Never lets the others see its own precommits.
+pub struct WithheldPrecommits;
+
+impl Behaviour for WithheldPrecommits {
+    fn tamper(&mut self, signer: &KeyPair, _to: usize, message: TendermintMessage) -> Option<TendermintMessage> {
+        match message {
+            TendermintMessage::ConsensusMessage(votes) => {
+                let votes: Vec<_> = votes
+                    .into_iter()
+                    .filter(|bytes| match Rlp::new(bytes).as_val::<ConsensusMessage>() {
+                        Ok(vote) => vote.on.step.step != Step::Precommit || !is_signed_by(&vote, signer),
+                        Err(_) => true,
+                    })
+                    .collect();
+                if votes.is_empty() {
+                    None
+                } else {
+                    Some(TendermintMessage::ConsensusMessage(votes))
+                }
+            }
+            TendermintMessage::Commit {
+                block,
+                votes,
+            } => Some(TendermintMessage::Commit {
+                block,
+                votes: votes.into_iter().filter(|vote| !is_signed_by(vote, signer)).collect(),
+            }),
+            message => Some(message),
+        }
+    }
+}
+
+/// The worker and the extension never call the methods which have no counterpart in the simulation.
+fn unsupported(method: &str) -> ! {
+    unreachable!("SimClient::{} is not used by the simulated validators", method)
+}
+
+/// The chain of a simulated validator, which keeps the blocks in memory.
+///
+/// The block generation requests and the chain notifications are queued and handled by the `Simulation`,
+/// so that the worker never waits for itself.
+struct SimClient {
+    inner: crossbeam::Sender<worker::Event>,
+    blocks: RwLock<HashMap<BlockHash, Bytes>>,
+    /// The hashes of the committed blocks indexed by the block number.
+    canonical: RwLock<Vec<BlockHash>>,
+    kvdb: Arc<dyn KeyValueDB>,
+    sealing_requests: Mutex<Vec<BlockHash>>,
+    /// The imported and the enacted blocks to notify the worker of.
+    notifications: Mutex<Vec<(Vec<BlockHash>, Vec<BlockHash>)>>,
+    own_transactions: Mutex<Vec<SignedTransaction>>,
+}
+
+impl SimClient {
+    fn new(inner: crossbeam::Sender<worker::Event>, genesis: Bytes) -> Self {
+        let genesis_hash = BlockView::new(&genesis).header().hash();
+        let mut blocks = HashMap::new();
+        blocks.insert(genesis_hash, genesis);
+        Self {
+            inner,
+            blocks: RwLock::new(blocks),
+            canonical: RwLock::new(vec![genesis_hash]),
+            kvdb: Arc::new(kvdb_memorydb::create(NUM_COLUMNS.unwrap())),
+            sealing_requests: Default::default(),
+            notifications: Default::default(),
+            own_transactions: Default::default(),
+        }
+    }
+
+    fn best_block_number(&self) -> BlockNumber {
+        self.canonical.read().len() as BlockNumber - 1
+    }
+
+    fn committed(&self, number: BlockNumber) -> Option<BlockHash> {
+        self.canonical.read().get(number as usize).cloned()
+    }
+
+    fn raw_block(&self, hash: &BlockHash) -> Option<Bytes> {
+        self.blocks.read().get(hash).cloned()
+    }
+
+    fn hash_of(&self, id: &BlockId) -> Option<BlockHash> {
+        let canonical = self.canonical.read();
+        match id {
+            BlockId::Hash(hash) => Some(*hash).filter(|hash| self.blocks.read().contains_key(hash)),
+            BlockId::Number(number) => canonical.get(*number as usize).cloned(),
+            BlockId::Earliest => canonical.first().cloned(),
+            BlockId::Latest => canonical.last().cloned(),
+            BlockId::ParentOfLatest => canonical.len().checked_sub(2).map(|index| canonical[index]),
+        }
+    }
+
+    /// Verifies the header with the worker as the `Client` does before importing a block.
+    fn verify(&self, header: &Header) -> Result<(), GenericError> {
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner
+            .send(worker::Event::VerifyHeaderBasic {
+                header: Box::new(header.clone()),
+                result,
+            })
+            .unwrap();
+        receiver.recv().unwrap()?;
+
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner
+            .send(worker::Event::VerifyBlockExternal {
+                header: Box::new(header.clone()),
+                result,
+            })
+            .unwrap();
+        receiver.recv().unwrap()
+    }
+
+    /// Appends the block to the committed chain if it is a child of the best block.
+    fn enact(&self, hash: BlockHash) -> Vec<BlockHash> {
+        let parent_hash = match self.block_header(&hash.into()) {
+            Some(header) => header.parent_hash(),
+            None => return Vec::new(),
+        };
+        let mut canonical = self.canonical.write();
+        if canonical.last() != Some(&parent_hash) {
+            return Vec::new()
+        }
+        canonical.push(hash);
+        vec![hash]
+    }
+
+    fn take_notifications(&self) -> Vec<(Vec<BlockHash>, Vec<BlockHash>)> {
+        mem::replace(&mut *self.notifications.lock(), Vec::new())
+    }
+
+    fn take_sealing_requests(&self) -> Vec<BlockHash> {
+        mem::replace(&mut *self.sealing_requests.lock(), Vec::new())
+    }
+
+    /// The rounds and the signers of the reported double votes.
+    fn double_vote_reports(&self) -> Vec<(VoteStep, usize)> {
+        self.own_transactions
+            .lock()
+            .iter()
+            .filter_map(|tx| match &tx.action {
+                Action::Custom {
+                    bytes,
+                    ..
+                } => match Rlp::new(bytes).as_val() {
+                    Ok(StakeAction::ReportDoubleVote {
+                        message1,
+                        ..
+                    }) => Some((*message1.round(), message1.signer_index())),
+                    _ => None,
+                },
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+impl BlockChainTrait for SimClient {
+    fn chain_info(&self) -> BlockChainInfo {
+        unsupported("chain_info")
+    }
+
+    fn genesis_accounts(&self) -> Vec<PlatformAddress> {
+        unsupported("genesis_accounts")
+    }
+
+    fn block_header(&self, id: &BlockId) -> Option<encoded::Header> {
+        let hash = self.hash_of(id)?;
+        let blocks = self.blocks.read();
+        let block = blocks.get(&hash)?;
+        Some(encoded::Header::new(Rlp::new(block).at(0).unwrap().as_raw().to_vec()))
+    }
+
+    fn best_block_header(&self) -> encoded::Header {
+        self.block_header(&BlockId::Latest).expect("Best block always has header.")
+    }
+
+    /// The headers are imported only with their blocks.
+    fn best_header(&self) -> encoded::Header {
+        self.best_block_header()
+    }
+
+    fn best_proposal_header(&self) -> encoded::Header {
+        unsupported("best_proposal_header")
+    }
+
+    fn block(&self, id: &BlockId) -> Option<encoded::Block> {
+        let hash = self.hash_of(id)?;
+        self.raw_block(&hash).map(encoded::Block::new)
+    }
+
+    fn transaction_block(&self, _id: &TransactionId) -> Option<BlockHash> {
+        unsupported("transaction_block")
+    }
+
+    fn transaction_header(&self, _tracker: &Tracker) -> Option<encoded::Header> {
+        unsupported("transaction_header")
+    }
+}
+
+impl EngineInfo for SimClient {
+    fn network_id(&self) -> NetworkId {
+        NetworkId::default()
+    }
+
+    fn common_params(&self, _block_id: BlockId) -> Option<CommonParams> {
+        Some(CommonParams::default_for_test())
+    }
+
+    fn metadata_seq(&self, _block_id: BlockId) -> Option<u64> {
+        unsupported("metadata_seq")
+    }
+
+    fn block_reward(&self, _block_number: u64) -> u64 {
+        unsupported("block_reward")
+    }
+
+    fn mining_reward(&self, _block_number: u64) -> Option<u64> {
+        unsupported("mining_reward")
+    }
+
+    fn recommended_confirmation(&self) -> u32 {
+        unsupported("recommended_confirmation")
+    }
+
+    fn possible_authors(&self, _block_number: Option<u64>) -> Result<Option<Vec<PlatformAddress>>, EngineError> {
+        unsupported("possible_authors")
+    }
+}
+
+impl EngineClient for SimClient {
+    fn update_sealing(&self, parent_block: BlockId, _allow_empty_block: bool) {
+        if let Some(parent_hash) = self.hash_of(&parent_block) {
+            self.sealing_requests.lock().push(parent_hash);
+        }
+    }
+
+    fn update_best_as_committed(&self, block_hash: BlockHash) {
+        let enacted = self.enact(block_hash);
+        if !enacted.is_empty() {
+            self.notifications.lock().push((Vec::new(), enacted));
+        }
+    }
+
+    fn get_kvdb(&self) -> Arc<dyn KeyValueDB> {
+        Arc::clone(&self.kvdb)
+    }
+}
+
+impl TermInfo for SimClient {
+    fn last_term_finished_block_num(&self, _id: BlockId) -> Option<BlockNumber> {
+        Some(0)
+    }
+
+    /// The validators stay the initial ones while the term is zero.
+    fn current_term_id(&self, _id: BlockId) -> Option<u64> {
+        Some(0)
+    }
+
+    fn term_common_params(&self, _id: BlockId) -> Option<CommonParams> {
+        None
+    }
+}
+
+impl StateInfo for SimClient {
+    fn state_at(&self, _id: BlockId) -> Option<TopLevelState> {
+        None
+    }
+
+    fn is_state_pruned(&self, _id: BlockId) -> bool {
+        false
+    }
+}
+
+impl AccountData for SimClient {
+    fn seq(&self, _address: &Address, _id: BlockId) -> Option<u64> {
+        Some(0)
+    }
+
+    fn balance(&self, _address: &Address, _state: StateOrBlock) -> Option<u64> {
+        unsupported("balance")
+    }
+
+    fn regular_key(&self, _address: &Address, _state: StateOrBlock) -> Option<Public> {
+        unsupported("regular_key")
+    }
+
+    fn regular_key_owner(&self, _address: &Address, _state: StateOrBlock) -> Option<Address> {
+        unsupported("regular_key_owner")
+    }
+}
+
+impl ChainTimeInfo for SimClient {
+    fn transaction_block_age(&self, _: &Tracker, _parent_block_number: BlockNumber) -> Option<u64> {
+        unsupported("transaction_block_age")
+    }
+
+    fn transaction_time_age(&self, _: &Tracker, _parent_timestamp: u64) -> Option<u64> {
+        unsupported("transaction_time_age")
+    }
+}
+
+impl ImportBlock for SimClient {
+    fn import_block(&self, bytes: Bytes) -> Result<BlockHash, BlockImportError> {
+        let header = BlockView::new(&bytes).header();
+        let hash = header.hash();
+        if self.blocks.read().contains_key(&hash) {
+            return Err(BlockImportError::Import(ImportError::AlreadyInChain))
+        }
+        if !self.blocks.read().contains_key(header.parent_hash()) {
+            return Err(BlockImportError::Block(BlockError::UnknownParent(*header.parent_hash())))
+        }
+        self.verify(&header)?;
+
+        // A block has the precommits on its parent, so the parent is committed.
+        let enacted = self.enact(*header.parent_hash());
+        self.blocks.write().insert(hash, bytes);
+        self.notifications.lock().push((vec![hash], enacted));
+        Ok(hash)
+    }
+
+    fn import_header(&self, _bytes: Bytes) -> Result<BlockHash, BlockImportError> {
+        unsupported("import_header")
+    }
+
+    fn import_bootstrap_header(&self, _header: &Header) -> Result<BlockHash, BlockImportError> {
+        unsupported("import_bootstrap_header")
+    }
+
+    fn import_bootstrap_block(&self, _block: &Block) -> Result<BlockHash, BlockImportError> {
+        unsupported("import_bootstrap_block")
+    }
+
+    fn import_sealed_block(&self, block: &SealedBlock) -> ImportResult {
+        let hash = block.header().hash();
+        self.blocks.write().insert(hash, block.rlp_bytes());
+        self.notifications.lock().push((vec![hash], Vec::new()));
+        Ok(hash)
+    }
+
+    fn set_min_timer(&self) {}
+}
+
+impl BlockChainClient for SimClient {
+    fn queue_info(&self) -> BlockQueueInfo {
+        unsupported("queue_info")
+    }
+
+    fn queue_own_transaction(&self, transaction: SignedTransaction) -> Result<(), GenericError> {
+        self.own_transactions.lock().push(transaction);
+        Ok(())
+    }
+
+    /// The simulated validators propose empty blocks, so they have no pending transactions.
+    fn queue_transactions(&self, _transactions: Vec<Bytes>, _peer_id: NodeId) {}
+
+    fn delete_all_pending_transactions(&self) {}
+
+    fn ready_transactions(&self, _range: Range<u64>) -> PendingSignedTransactions {
+        PendingSignedTransactions {
+            transactions: Vec::new(),
+            last_timestamp: None,
+        }
+    }
+
+    fn count_pending_transactions(&self, _range: Range<u64>) -> usize {
+        0
+    }
+
+    fn is_pending_queue_empty(&self) -> bool {
+        true
+    }
+
+    fn block_number(&self, id: &BlockId) -> Option<BlockNumber> {
+        self.block_header(id).map(|header| header.number())
+    }
+
+    fn block_body(&self, id: &BlockId) -> Option<encoded::Body> {
+        let block = self.raw_block(&self.hash_of(id)?)?;
+        let mut body = RlpStream::new_list(1);
+        body.append_raw(Rlp::new(&block).at(1).unwrap().as_raw(), 1);
+        Some(encoded::Body::new(body.out()))
+    }
+
+    fn block_status(&self, id: &BlockId) -> BlockStatus {
+        match self.hash_of(id) {
+            Some(_) => BlockStatus::InChain,
+            None => BlockStatus::Unknown,
+        }
+    }
+
+    fn block_total_score(&self, _id: &BlockId) -> Option<U256> {
+        unsupported("block_total_score")
+    }
+
+    fn block_hash(&self, id: &BlockId) -> Option<BlockHash> {
+        self.hash_of(id)
+    }
+
+    fn transaction(&self, _id: &TransactionId) -> Option<LocalizedTransaction> {
+        unsupported("transaction")
+    }
+
+    fn error_hint(&self, _hash: &TxHash) -> Option<String> {
+        unsupported("error_hint")
+    }
+
+    fn transaction_by_tracker(&self, _: &Tracker) -> Option<LocalizedTransaction> {
+        unsupported("transaction_by_tracker")
+    }
+
+    fn error_hints_by_tracker(&self, _: &Tracker) -> Vec<(TxHash, Option<String>)> {
+        unsupported("error_hints_by_tracker")
+    }
+
+    fn transaction_receipt(&self, _hash: &TxHash) -> Option<Receipt> {
+        unsupported("transaction_receipt")
+    }
+
+    fn block_receipts(&self, _id: &BlockId) -> Option<Vec<Receipt>> {
+        unsupported("block_receipts")
+    }
+
+    fn transactions_by_address(&self, _: &Address, _: u64, _: u64) -> Option<Vec<LocalizedTransaction>> {
+        unsupported("transactions_by_address")
+    }
+}
+
+impl ConsensusClient for SimClient {}
+
+enum Output {
+    Send(NodeId, Arc<Bytes>),
+    SetTimer {
+        token: TimerToken,
+        after: Duration,
+        repeat: bool,
+    },
+    ClearTimer(TimerToken),
+}
+
+/// The network API given to the extension, which records what the extension asks for.
+struct SimApi(Arc<Mutex<Vec<Output>>>);
+
+impl Api for SimApi {
+    fn send(&self, node: &NodeId, message: Arc<Bytes>) {
+        self.0.lock().push(Output::Send(*node, message));
+    }
+
+    fn disconnect(&self, _node: &NodeId) {}
+
+    fn set_timer(&self, token: TimerToken, d: Duration) -> NetworkExtensionResult<()> {
+        self.0.lock().push(Output::SetTimer {
+            token,
+            after: d,
+            repeat: true,
+        });
+        Ok(())
+    }
+
+    fn set_timer_once(&self, token: TimerToken, d: Duration) -> NetworkExtensionResult<()> {
+        self.0.lock().push(Output::SetTimer {
+            token,
+            after: d,
+            repeat: false,
+        });
+        Ok(())
+    }
+
+    fn clear_timer(&self, token: TimerToken) -> NetworkExtensionResult<()> {
+        self.0.lock().push(Output::ClearTimer(token));
+        Ok(())
+    }
+}
+
+struct Node {
+    keypair: KeyPair,
+    client: Arc<SimClient>,
+    extension: TendermintExtension,
+    inner: crossbeam::Sender<worker::Event>,
+    network_events: crossbeam::Receiver<network::Event>,
+    outbox: Arc<Mutex<Vec<Output>>>,
+    /// The generation and the period of the armed timers.
+    timers: HashMap<TimerToken, (u64, Option<u64>)>,
+    behaviour: Option<Box<dyn Behaviour>>,
+    /// The virtual clock in milliseconds, which is shared by the validators.
+    clock: Arc<AtomicU64>,
+    quit: crossbeam::Sender<()>,
+    join: Option<JoinHandle<()>>,
+}
+
+impl Node {
+    fn new(index: usize, keypairs: &[KeyPair], genesis: &[u8], clock: &Arc<AtomicU64>) -> Self {
+        let keypair = keypairs[index].clone();
+        let validators = Arc::new(DynamicValidator::new(keypairs.iter().map(|keypair| *keypair.public()).collect()));
+        let (join, time_gap_params_initializer, extension_initializer, snapshot_notify_sender_initializer, inner, quit) =
+            worker::spawn(validators);
+
+        let client = Arc::new(SimClient::new(inner.clone(), genesis.to_vec()));
+        let (network_sender, network_events) = crossbeam::unbounded();
+        time_gap_params_initializer
+            .send(TimeGapParams {
+                allowed_past_gap: Duration::from_secs(3600),
+                allowed_future_gap: Duration::from_secs(3600),
+            })
+            .unwrap();
+        extension_initializer.send((network_sender, Arc::downgrade(&client) as Weak<dyn ConsensusClient>)).unwrap();
+        let (snapshot_notify_sender, _receiver) = snapshot_notify::create();
+        snapshot_notify_sender_initializer.send(snapshot_notify_sender).unwrap();
+        let worker_clock = Arc::clone(clock);
+        inner
+            .send(worker::Event::SetClock(Box::new(move || {
+                UNIX_EPOCH
+                    + Duration::from_secs(GENESIS_TIMESTAMP)
+                    + Duration::from_millis(worker_clock.load(Ordering::SeqCst))
+            })))
+            .unwrap();
+
+        let ap = AccountProvider::transient_provider();
+        let password: Password = "validator".into();
+        let address = ap.insert_account(*keypair.private(), &password).unwrap();
+        ap.unlock_account_permanently(address, password).unwrap();
+        inner
+            .send(worker::Event::SetSigner {
+                ap,
+                address,
+            })
+            .unwrap();
+
+        let outbox = Arc::new(Mutex::new(Vec::new()));
+        let mut extension =
+            TendermintExtension::new(inner.clone(), TimeoutParams::default(), Box::new(SimApi(Arc::clone(&outbox))));
+        for peer in (0..keypairs.len()).filter(|peer| *peer != index) {
+            extension.on_node_added(&node_id(peer), 0);
+        }
+
+        Self {
+            keypair,
+            client,
+            extension,
+            inner,
+            network_events,
+            outbox,
+            timers: Default::default(),
+            behaviour: None,
+            clock: Arc::clone(clock),
+            quit,
+            join: Some(join),
+        }
+    }
+
+    /// Waits until the worker handles all the events sent before.
+    fn wait_idle(&self) {
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner.send(worker::Event::GetStatus(result)).unwrap();
+        receiver.recv().unwrap();
+    }
+
+    /// Generates a block as the miner does on `update_sealing`.
+    fn seal(&self, parent_hash: BlockHash) {
+        let parent = match self.client.block_header(&parent_hash.into()) {
+            Some(parent) => parent.decode(),
+            None => return,
+        };
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner
+            .send(worker::Event::GenerateSeal {
+                block_number: parent.number() + 1,
+                parent_hash,
+                result,
+            })
+            .unwrap();
+        let seal = match receiver.recv().unwrap().seal_fields() {
+            Some(seal) => seal,
+            None => return,
+        };
+        let (result, receiver) = crossbeam::bounded(1);
+        self.inner
+            .send(worker::Event::CalculateScore {
+                block_number: parent.number() + 1,
+                result,
+            })
+            .unwrap();
+        let score = receiver.recv().unwrap();
+
+        let mut header = Header::new();
+        header.set_parent_hash(parent_hash);
+        header.set_number(parent.number() + 1);
+        header.set_author(self.keypair.address());
+        header.set_timestamp(cmp::max(parent.timestamp() + 1, unix_time(self.clock.load(Ordering::SeqCst))));
+        header.set_transactions_root(*parent.transactions_root());
+        header.set_state_root(*parent.state_root());
+        header.set_score(score);
+        header.set_seal(seal);
+        let block = SealedBlock::new_empty_for_test(header, empty_top_state(StateDB::new_with_memorydb()));
+
+        self.inner.send(worker::Event::ProposalGenerated(Box::new(block.clone()))).unwrap();
+        self.wait_idle();
+        self.client.import_sealed_block(&block).unwrap();
+    }
+}
+
+enum Scheduled {
+    Deliver {
+        from: usize,
+        to: usize,
+        message: Bytes,
+    },
+    Timer {
+        node: usize,
+        token: TimerToken,
+        generation: u64,
+    },
+    Sync,
+}
+
+pub struct Simulation {
+    nodes: Vec<Node>,
+    /// The virtual clock in milliseconds.
+    now: u64,
+    /// The virtual clock the validators read.
+    clock: Arc<AtomicU64>,
+    queue: BTreeMap<(u64, u64), Scheduled>,
+    sequence: u64,
+    rng: XorShiftRng,
+    faults: Faults,
+    /// The group of each validator while the network is partitioned.
+    partition: Option<Vec<usize>>,
+    timer_generation: u64,
+}
+
+impl Simulation {
+    pub fn new(validators: usize, seed: u64, faults: Faults) -> Self {
+        assert!(validators <= MAX_VALIDATORS, "Broadcasts reach only some random peers in a larger network");
+        let keypairs: Vec<_> = (0..validators)
+            .map(|index| KeyPair::from_private(Private::from(blake256(format!("validator{}", index)))).unwrap())
+            .collect();
+
+        let genesis = {
+            let mut header = Header::new();
+            header.set_author(keypairs[0].address());
+            header.set_timestamp(GENESIS_TIMESTAMP);
+            header.set_seal(
+                Seal::Tendermint {
+                    prev_view: 0,
+                    cur_view: 0,
+                    precommits: Vec::new(),
+                    precommit_bitset: BitSet::new(),
+                }
+                .seal_fields()
+                .unwrap(),
+            );
+            let mut genesis = RlpStream::new_list(2);
+            genesis.append(&header);
+            genesis.begin_list(0);
+            genesis.out()
+        };
+
+        let mut seed_bytes = [0x5e; 16];
+        seed_bytes[..8].copy_from_slice(&seed.to_le_bytes());
+        let clock = Arc::new(AtomicU64::new(0));
+        let mut simulation = Self {
+            nodes: (0..validators).map(|index| Node::new(index, &keypairs, &genesis, &clock)).collect(),
+            now: 0,
+            clock,
+            queue: Default::default(),
+            sequence: 0,
+            rng: XorShiftRng::from_seed(seed_bytes),
+            faults,
+            partition: None,
+            timer_generation: 0,
+        };
+        for index in 0..validators {
+            simulation.settle(index);
+        }
+        simulation.schedule(SYNC_INTERVAL, Scheduled::Sync);
+        simulation
+    }
+
+    pub fn set_behaviour(&mut self, index: usize, behaviour: Box<dyn Behaviour>) {
+        self.nodes[index].behaviour = Some(behaviour);
+    }
+
+    /// Cuts the links between the groups. The validators not in any group are isolated.
+    pub fn partition(&mut self, groups: &[&[usize]]) {
+        let mut partition: Vec<_> = (0..self.nodes.len()).map(|index| groups.len() + index).collect();
+        for (group, members) in groups.iter().enumerate() {
+            for member in members.iter() {
+                partition[*member] = group;
+            }
+        }
+        self.partition = Some(partition);
+    }
+
+    pub fn heal(&mut self) {
+        self.partition = None;
+    }
+
+    pub fn now(&self) -> u64 {
+        self.now
+    }
+
+    pub fn height(&self, index: usize) -> BlockNumber {
+        self.nodes[index].client.best_block_number()
+    }
+
+    pub fn honest(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(move |index| self.nodes[*index].behaviour.is_none())
+    }
+
+    /// The double votes the validator has reported.
+    pub fn double_vote_reports(&self, index: usize) -> Vec<(VoteStep, usize)> {
+        self.nodes[index].client.double_vote_reports()
+    }
+
+    /// Runs until `done` holds. Returns false if it does not hold at `deadline`.
+    pub fn run_until<F>(&mut self, deadline: u64, done: F) -> bool
+    where
+        F: Fn(&Self) -> bool, {
+        while !done(self) {
+            let key = match self.queue.keys().next() {
+                Some(key) if key.0 <= deadline => *key,
+                _ => {
+                    self.advance(cmp::max(self.now, deadline));
+                    return false
+                }
+            };
+            let scheduled = self.queue.remove(&key).expect("The key is from the queue");
+            self.advance(key.0);
+            self.dispatch(scheduled);
+        }
+        true
+    }
+
+    pub fn run_for(&mut self, duration: u64) {
+        let deadline = self.now + duration;
+        self.run_until(deadline, |_| false);
+    }
+
+    /// Runs until every honest validator commits `height` blocks. Returns false if it takes longer than `timeout`.
+    pub fn run_until_height(&mut self, height: BlockNumber, timeout: u64) -> bool {
+        let deadline = self.now + timeout;
+        self.run_until(deadline, |simulation| simulation.honest().all(|index| simulation.height(index) >= height))
+    }
+
+    /// Asserts that the honest validators never commit different blocks at the same height.
+    pub fn assert_safety(&self) {
+        let highest = self.honest().map(|index| self.height(index)).max().unwrap_or_default();
+        for number in 1..=highest {
+            let committed: HashSet<_> =
+                self.honest().filter_map(|index| self.nodes[index].client.committed(number)).collect();
+            assert!(committed.len() <= 1, "Different blocks {:?} are committed at #{}", committed, number);
+        }
+    }
+
+    fn advance(&mut self, now: u64) {
+        self.now = now;
+        self.clock.store(now, Ordering::SeqCst);
+    }
+
+    fn connected(&self, a: usize, b: usize) -> bool {
+        self.partition.as_ref().map_or(true, |partition| partition[a] == partition[b])
+    }
+
+    fn schedule(&mut self, at: u64, scheduled: Scheduled) {
+        self.sequence += 1;
+        self.queue.insert((at, self.sequence), scheduled);
+    }
+
+    fn dispatch(&mut self, scheduled: Scheduled) {
+        match scheduled {
+            Scheduled::Deliver {
+                from,
+                to,
+                message,
+            } => {
+                if !self.connected(from, to) {
+                    return
+                }
+                self.nodes[to].extension.on_message(&node_id(from), &message);
+                self.settle(to);
+            }
+            Scheduled::Timer {
+                node,
+                token,
+                generation,
+            } => {
+                match self.nodes[node].timers.get(&token).cloned() {
+                    Some((armed, Some(period))) if armed == generation => {
+                        self.schedule(self.now + period, Scheduled::Timer {
+                            node,
+                            token,
+                            generation,
+                        });
+                    }
+                    Some((armed, None)) if armed == generation => {
+                        self.nodes[node].timers.remove(&token);
+                    }
+                    // The timer is cleared or set again.
+                    _ => return,
+                }
+                self.nodes[node].extension.on_timeout(token);
+                self.settle(node);
+            }
+            Scheduled::Sync => {
+                for index in 0..self.nodes.len() {
+                    self.sync(index);
+                }
+                self.schedule(self.now + SYNC_INTERVAL, Scheduled::Sync);
+            }
+        }
+    }
+
+    /// Passes the outputs of the validator around until its worker has nothing to do.
+    fn settle(&mut self, index: usize) {
+        loop {
+            self.nodes[index].wait_idle();
+            while let Ok(event) = self.nodes[index].network_events.try_recv() {
+                self.nodes[index].extension.on_event(event);
+            }
+            self.flush(index);
+
+            let notifications = self.nodes[index].client.take_notifications();
+            let sealing_requests = self.nodes[index].client.take_sealing_requests();
+            if notifications.is_empty() && sealing_requests.is_empty() {
+                break
+            }
+            for (imported, enacted) in notifications {
+                self.nodes[index]
+                    .inner
+                    .send(worker::Event::NewBlocks {
+                        imported,
+                        enacted,
+                    })
+                    .unwrap();
+            }
+            for parent_hash in sealing_requests {
+                self.nodes[index].seal(parent_hash);
+            }
+        }
+    }
+
+    fn flush(&mut self, from: usize) {
+        let outputs = mem::replace(&mut *self.nodes[from].outbox.lock(), Vec::new());
+        let mut sends = Vec::new();
+        for output in outputs {
+            match output {
+                Output::Send(to, message) => {
+                    let to = (0..self.nodes.len()).find(|index| node_id(*index) == to).expect("Peers are validators");
+                    sends.push((to, message));
+                }
+                Output::SetTimer {
+                    token,
+                    after,
+                    repeat,
+                } => {
+                    self.timer_generation += 1;
+                    let generation = self.timer_generation;
+                    let period = if repeat {
+                        Some(millis(after))
+                    } else {
+                        None
+                    };
+                    self.nodes[from].timers.insert(token, (generation, period));
+                    self.schedule(self.now + millis(after), Scheduled::Timer {
+                        node: from,
+                        token,
+                        generation,
+                    });
+                }
+                Output::ClearTimer(token) => {
+                    self.nodes[from].timers.remove(&token);
+                }
+            }
+        }
+        // The extension shuffles the peers, so the order is fixed here to keep the run deterministic.
+        sends.sort_by_key(|(to, _)| *to);
+        for (to, message) in sends {
+            self.send(from, to, &message);
+        }
+    }
+
+    fn send(&mut self, from: usize, to: usize, message: &[u8]) {
+        let node = &mut self.nodes[from];
+        let message = match &mut node.behaviour {
+            Some(behaviour) => {
+                let message = Rlp::new(message).as_val().expect("The extension sends valid messages");
+                match behaviour.tamper(&node.keypair, to, message) {
+                    Some(message) => message.rlp_bytes(),
+                    None => return,
+                }
+            }
+            None => message.to_vec(),
+        };
+
+        if self.rng.gen_bool(self.faults.drop_rate) {
+            return
+        }
+        let mut delay = self.rng.gen_range(self.faults.min_delay, self.faults.max_delay + 1);
+        if self.rng.gen_bool(self.faults.reorder_rate) {
+            delay += self.faults.max_delay;
+        }
+        self.schedule(self.now + delay, Scheduled::Deliver {
+            from,
+            to,
+            message,
+        });
+    }
+
+    /// Imports the committed blocks of the highest reachable validator as the block sync does.
+    fn sync(&mut self, index: usize) {
+        let best = self.height(index);
+        let peer = match (0..self.nodes.len())
+            .filter(|peer| self.connected(index, *peer))
+            .max_by_key(|peer| self.height(*peer))
+        {
+            Some(peer) => peer,
+            None => return,
+        };
+        // The votes on the last two heights are gossiped by the extension.
+        if self.height(peer) <= best + 1 {
+            return
+        }
+        for number in best + 1..=self.height(peer) {
+            let peer_client = &self.nodes[peer].client;
+            let block = peer_client.committed(number).and_then(|hash| peer_client.raw_block(&hash)).unwrap();
+            // The proposal could be imported already.
+            let _ = self.nodes[index].client.import_block(block);
+        }
+        self.settle(index);
+    }
+}
+
+impl Drop for Simulation {
+    fn drop(&mut self) {
+        for node in &mut self.nodes {
+            let _ = node.quit.send(());
+            if let Some(join) = node.join.take() {
+                let _ = join.join();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TIMEOUT: u64 = 10 * 60 * 1000;
+
+    fn run_with_byzantine(behaviour: fn() -> Box<dyn Behaviour>) {
+        for seed in 0..3 {
+            let mut simulation = Simulation::new(4, seed, Faults::default());
+            simulation.set_behaviour(3, behaviour());
+            assert!(simulation.run_until_height(6, TIMEOUT), "Stuck at {}ms with the seed {}", simulation.now(), seed);
+            simulation.assert_safety();
+        }
+    }
+
+    #[test]
+    fn honest_validators_commit_blocks() {
+        for seed in 0..3 {
+            let mut simulation = Simulation::new(4, seed, Faults::default());
+            assert!(simulation.run_until_height(5, TIMEOUT), "Stuck at {}ms with the seed {}", simulation.now(), seed);
+            simulation.assert_safety();
+        }
+    }
+
+    #[test]
+    fn commit_over_lossy_network() {
+        let faults = Faults {
+            min_delay: 10,
+            max_delay: 300,
+            drop_rate: 0.05,
+            reorder_rate: 0.1,
+        };
+        for seed in 0..3 {
+            let mut simulation = Simulation::new(4, seed, faults);
+            assert!(simulation.run_until_height(5, TIMEOUT), "Stuck at {}ms with the seed {}", simulation.now(), seed);
+            simulation.assert_safety();
+        }
+    }
+
+    #[test]
+    fn commit_after_partition_heals() {
+        for seed in 0..3 {
+            let mut simulation = Simulation::new(4, seed, Faults::default());
+            simulation.partition(&[&[0, 1], &[2, 3]]);
+            simulation.run_for(20_000);
+            // No side has more than two thirds of the votes.
+            assert!(simulation.honest().all(|index| simulation.height(index) == 0));
+
+            simulation.heal();
+            assert!(simulation.run_until_height(3, TIMEOUT), "Stuck at {}ms with the seed {}", simulation.now(), seed);
+            simulation.assert_safety();
+        }
+    }
+
+    #[test]
+    fn double_voter_is_reported() {
+        for seed in 0..3 {
+            let mut simulation = Simulation::new(4, seed, Faults::default());
+            simulation.set_behaviour(3, Box::new(DoubleVote));
+            assert!(simulation.run_until_height(6, TIMEOUT), "Stuck at {}ms with the seed {}", simulation.now(), seed);
+            simulation.assert_safety();
+            let reports: Vec<_> = simulation.honest().map(|index| simulation.double_vote_reports(index)).collect();
+            assert!(reports.iter().any(|reports| !reports.is_empty()), "No one reported with the seed {}", seed);
+            // The blocks are empty here, so every proposer reports the double votes it knows, but only once.
+            for reports in reports {
+                let unique: BTreeSet<_> = reports.iter().collect();
+                assert_eq!(
+                    unique.len(),
+                    reports.len(),
+                    "The same double vote is reported twice with the seed {}",
+                    seed
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn equivocating_proposer_cannot_break_safety() {
+        run_with_byzantine(|| Box::new(EquivocatingProposer));
+    }
+
+    #[test]
+    fn withheld_precommits_cannot_stop_commits() {
+        run_with_byzantine(|| Box::new(WithheldPrecommits));
+    }
+}
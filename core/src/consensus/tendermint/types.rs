@@ -174,6 +174,15 @@ impl Step {
             Step::Commit => 3,
         }
     }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            Step::Propose => "propose",
+            Step::Prevote => "prevote",
+            Step::Precommit => "precommit",
+            Step::Commit => "commit",
+        }
+    }
 }
 
 impl Decodable for Step {
@@ -199,6 +208,8 @@ pub struct PeerState {
     pub vote_step: VoteStep,
     pub proposal: Option<BlockHash>,
     pub messages: BitSet,
+    /// The number of the invalid evidences the peer has sent.
+    pub invalid_evidences: usize,
 }
 
 impl PeerState {
@@ -207,6 +218,7 @@ impl PeerState {
             vote_step: VoteStep::new(0, 0, Step::Propose),
             proposal: None,
             messages: BitSet::new(),
+            invalid_evidences: 0,
         }
     }
 }
@@ -19,7 +19,7 @@ use super::{ConsensusMessage, VoteStep};
 use crate::consensus::BitSet;
 use ckey::SchnorrSignature;
 use ctypes::BlockHash;
-use rlp::{Encodable, RlpStream};
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 use std::collections::{BTreeMap, HashMap};
 use std::iter::Iterator;
 
@@ -36,7 +36,7 @@ struct StepCollector {
     messages: Vec<ConsensusMessage>,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct DoubleVote {
     author_index: usize,
     vote_one: ConsensusMessage,
@@ -44,6 +44,19 @@ pub struct DoubleVote {
 }
 
 impl DoubleVote {
+    pub fn new(vote_one: ConsensusMessage, vote_two: ConsensusMessage) -> Self {
+        DoubleVote {
+            author_index: vote_one.signer_index(),
+            vote_one,
+            vote_two,
+        }
+    }
+
+    /// The round and the signer, which identify the evidence.
+    pub fn key(&self) -> (VoteStep, usize) {
+        (*self.vote_one.round(), self.author_index)
+    }
+
     pub fn to_action(&self) -> Action {
         Action::ReportDoubleVote {
             message1: Box::new(self.vote_one.clone()),
@@ -58,6 +71,19 @@ impl Encodable for DoubleVote {
     }
 }
 
+impl Decodable for DoubleVote {
+    fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != 2 {
+            return Err(DecoderError::RlpIncorrectListLen {
+                got: item_count,
+                expected: 2,
+            })
+        }
+        Ok(DoubleVote::new(rlp.val_at(0)?, rlp.val_at(1)?))
+    }
+}
+
 impl StepCollector {
     /// Some(true): a message is new
     /// Some(false): a message is duplicated
@@ -16,6 +16,7 @@
 
 use super::super::BitSet;
 use super::backup::{backup, restore, BackupView};
+use super::evidence::EvidencePool;
 use super::message::*;
 use super::network;
 use super::params::TimeGapParams;
@@ -31,7 +32,7 @@ use crate::block::*;
 use crate::client::ConsensusClient;
 use crate::consensus::signer::EngineSigner;
 use crate::consensus::validator_set::{DynamicValidator, ValidatorSet};
-use crate::consensus::{EngineError, Seal};
+use crate::consensus::{ConsensusStatus, EngineError, Seal};
 use crate::encoded;
 use crate::error::{BlockError, Error};
 use crate::snapshot_notify::NotifySender as SnapshotNotifySender;
@@ -96,9 +97,15 @@ struct Worker {
     /// Channel to the network extension, must be set later.
     extension: EventSender<network::Event>,
     time_gap_params: TimeGapParams,
+    /// The source of the current time. The simulation replaces it with its virtual clock.
+    clock: Box<dyn Fn() -> SystemTime>,
     timeout_token_nonce: usize,
     vote_regression_checker: VoteRegressionChecker,
     snapshot_notify_sender: SnapshotNotifySender,
+    /// The number of fired step timeouts indexed by the step number.
+    step_timeouts: [u64; 4],
+    /// Double votes waiting to be reported in a block.
+    evidences: EvidencePool,
 }
 
 pub enum Event {
@@ -173,6 +180,14 @@ pub enum Event {
         votes: Vec<ConsensusMessage>,
         result: crossbeam::Sender<Option<Arc<dyn ConsensusClient>>>,
     },
+    GetStatus(crossbeam::Sender<ConsensusStatus>),
+    HandleEvidences {
+        evidences: Vec<DoubleVote>,
+        /// The peer who sent the evidences.
+        from: NodeId,
+    },
+    #[cfg(test)]
+    SetClock(Box<dyn Fn() -> SystemTime + Send>),
 }
 
 impl Worker {
@@ -199,9 +214,12 @@ impl Worker {
             extension,
             votes_received: MutTrigger::new(BitSet::new()),
             time_gap_params,
+            clock: Box::new(SystemTime::now),
             timeout_token_nonce: ENGINE_TIMEOUT_TOKEN_NONCE_BASE,
             vote_regression_checker: VoteRegressionChecker::new(),
             snapshot_notify_sender,
+            step_timeouts: [0; 4],
+            evidences: Default::default(),
         }
     }
 
@@ -381,6 +399,19 @@ impl Worker {
                                 let client = inner.on_commit_message(block, votes);
                                 result.send(client).unwrap();
                             }
+                            Ok(Event::GetStatus(result)) => {
+                                result.send(inner.status()).unwrap();
+                            }
+                            Ok(Event::HandleEvidences {
+                                evidences,
+                                from,
+                            }) => {
+                                inner.on_evidences(evidences, from);
+                            }
+                            #[cfg(test)]
+                            Ok(Event::SetClock(clock)) => {
+                                inner.clock = clock;
+                            }
                             Err(crossbeam::RecvError) => {
                                 cerror!(ENGINE, "The event channel for tendermint thread had been closed.");
                                 break
@@ -408,6 +439,16 @@ impl Worker {
         self.client.upgrade().expect("Client lives longer than consensus")
     }
 
+    fn status(&self) -> ConsensusStatus {
+        let steps = [Step::Propose, Step::Prevote, Step::Precommit, Step::Commit];
+        ConsensusStatus {
+            height: self.height,
+            view: self.view,
+            step: self.step.to_step().name(),
+            timeouts: steps.iter().map(|step| (step.name(), self.step_timeouts[step.number() as usize])).collect(),
+        }
+    }
+
     /// Get previous block hash to determine validator set
     fn prev_block_hash(&self) -> BlockHash {
         self.prev_block_header_of_height(self.height)
@@ -752,6 +793,7 @@ impl Worker {
                     }
                 } else {
                     cinfo!(ENGINE, "I am a proposer, I'll create a block");
+                    self.report_evidences();
                     self.update_sealing(parent_block_hash);
                     self.step = TendermintState::ProposeWaitBlockGeneration {
                         parent_hash: parent_block_hash,
@@ -841,7 +883,7 @@ impl Worker {
     fn is_generation_time_relevant(&self, block_header: &Header) -> bool {
         let acceptable_past_gap = self.time_gap_params.allowed_past_gap;
         let acceptable_future_gap = self.time_gap_params.allowed_future_gap;
-        let now = SystemTime::now();
+        let now = (self.clock)();
         let allowed_min = now - acceptable_past_gap;
         let allowed_max = now + acceptable_future_gap;
         let block_generation_time = UNIX_EPOCH.checked_add(Duration::from_secs(block_header.timestamp()));
@@ -1065,6 +1107,8 @@ impl Worker {
 
     fn restore(&mut self) {
         let client = self.client();
+        self.evidences = EvidencePool::load(client.get_kvdb().as_ref());
+        self.gossip_evidences(client.best_block_header().number());
         let backup = restore(client.get_kvdb().as_ref());
         if let Some(backup) = backup {
             let backup_step = match backup.step {
@@ -1320,6 +1364,7 @@ impl Worker {
         if self.is_expired_timeout_token(token) {
             return
         }
+        self.step_timeouts[self.step.to_step().number() as usize] += 1;
 
         let next_step = match self.step {
             TendermintState::Propose => {
@@ -1464,7 +1509,7 @@ impl Worker {
 
             if let Err(double) = self.votes.collect(message.clone()) {
                 cerror!(ENGINE, "Double vote found {:?}", double);
-                self.report_double_vote(&double);
+                self.add_evidence(double);
                 return Err(EngineError::DoubleVote(sender))
             }
             ctrace!(ENGINE, "Handling a valid {:?} from {}.", message, sender);
@@ -1473,12 +1518,104 @@ impl Worker {
         Ok(())
     }
 
+    /// Keeps the double vote until it is reported in a block, and spreads it to the other nodes.
+    /// A newly learned double vote is spread at once, and the pool is gossiped again at intervals.
+    fn add_evidence(&mut self, double: DoubleVote) {
+        let db = self.client().get_kvdb();
+        if !self.evidences.insert(db.as_ref(), double.clone()) {
+            return
+        }
+        self.broadcast_evidences(vec![double]);
+    }
+
+    /// The proposer reports the evidences so that they are included in its own block,
+    /// and the others don't send the same reports.
+    fn report_evidences(&mut self) {
+        for double in self.evidences.take_unreported() {
+            self.report_double_vote(&double);
+        }
+    }
+
+    /// Gossips the evidences in the pool again for the nodes that have missed them.
+    /// It's rate-limited by the pool, so the peers are not flooded with the same evidences at every block.
+    fn gossip_evidences(&mut self, best_block_number: BlockNumber) {
+        let evidences = self.evidences.take_to_gossip(best_block_number);
+        if !evidences.is_empty() {
+            self.broadcast_evidences(evidences);
+        }
+    }
+
+    fn broadcast_evidences(&self, evidences: Vec<DoubleVote>) {
+        self.extension
+            .send(network::Event::BroadcastEvidence {
+                evidences,
+            })
+            .unwrap();
+    }
+
+    /// Evidences are accepted from any node since they are verified against the validator set of the round.
+    /// The invalid ones are notified to the extension, so that it can drop the peer sending them.
+    fn on_evidences(&mut self, evidences: Vec<DoubleVote>, from: NodeId) {
+        let c = match self.client.upgrade() {
+            Some(client) => client,
+            None => {
+                cdebug!(ENGINE, "Evidences arrived before the client is registered");
+                return
+            }
+        };
+        let params = c.common_params(BlockId::Latest).expect("Common params of the best block always exist");
+        let best_block_number = c.best_block_header().number();
+        let mut invalid = 0;
+        for double in evidences {
+            if self.evidences.contains(&double) || EvidencePool::is_expired(&double, best_block_number) {
+                continue
+            }
+            // The evidence from a peer ahead of this node cannot be verified yet.
+            if double.key().0.height > best_block_number + 1 {
+                continue
+            }
+            let validators: Arc<dyn ValidatorSet> = Arc::clone(&self.validators) as _;
+            match double.to_action().verify(&params, Some(Arc::clone(&c)), Some(validators)) {
+                Ok(()) => self.add_evidence(double),
+                Err(err) => {
+                    cinfo!(ENGINE, "Invalid evidence {:?}: {}", double, err);
+                    invalid += 1;
+                }
+            }
+        }
+        if invalid > 0 {
+            self.extension
+                .send(network::Event::InvalidEvidences {
+                    from,
+                    count: invalid,
+                })
+                .unwrap();
+        }
+    }
+
+    fn remove_reported_evidences(&mut self, c: &dyn ConsensusClient, enacted: &[BlockHash]) {
+        if self.evidences.is_empty() {
+            return
+        }
+        for hash in enacted {
+            if let Some(block) = c.block(&BlockId::Hash(*hash)) {
+                self.evidences.remove_reported(c.get_kvdb().as_ref(), &block.transactions());
+            }
+        }
+        let best_block_number = c.best_block_header().number();
+        self.evidences.expire(c.get_kvdb().as_ref(), best_block_number);
+        self.gossip_evidences(best_block_number);
+    }
+
     fn report_double_vote(&self, double: &DoubleVote) {
         let network_id = self.client().network_id();
         let seq = match self.signer.address() {
             Some(address) => self.client().latest_seq(address),
             None => {
-                cerror!(ENGINE, "Found double vote, but signer was not assigned yet");
+                cinfo!(
+                    ENGINE,
+                    "Found double vote, but signer was not assigned yet. It is left to the other nodes to report"
+                );
                 return
             }
         };
@@ -1649,6 +1786,7 @@ impl Worker {
         };
 
         self.send_snapshot_notify(c.as_ref(), enacted.as_slice());
+        self.remove_reported_evidences(c.as_ref(), enacted.as_slice());
 
         if self.step.is_commit() && (imported.len() + enacted.len() == 1) {
             let (_, committed_block_hash) = self.step.committed().expect("Commit state always has block_hash");
@@ -1875,7 +2013,7 @@ impl Worker {
 
             if let Err(double) = self.votes.collect(message) {
                 cerror!(ENGINE, "Double Vote found {:?}", double);
-                self.report_double_vote(&double);
+                self.add_evidence(double);
                 return None
             }
         }
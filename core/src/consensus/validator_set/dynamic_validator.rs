@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::{RoundRobinValidator, ValidatorSet};
+use super::{ProposerSelection, RoundRobinValidator, ValidatorSet, WeightedRoundRobin};
 use crate::client::ConsensusClient;
 use crate::consensus::bit_set::BitSet;
 use crate::consensus::stake::{CurrentValidators, NextValidators, PreviousValidators, Validator};
@@ -22,20 +22,29 @@ use crate::consensus::EngineError;
 use ckey::{public_to_address, Address, Public};
 use ctypes::util::unexpected::OutOfBounds;
 use ctypes::BlockHash;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
 use std::sync::{Arc, Weak};
 
 /// Validator set containing a known set of public keys.
 pub struct DynamicValidator {
     initial_list: RoundRobinValidator,
     client: RwLock<Option<Weak<dyn ConsensusClient>>>,
+    proposer_selection: ProposerSelection,
+    /// The round-robin at the first view of the child of the block.
+    round_robin: Mutex<Option<(BlockHash, WeightedRoundRobin)>>,
 }
 
 impl DynamicValidator {
     pub fn new(initial_validators: Vec<Public>) -> Self {
+        Self::new_with_proposer_selection(initial_validators, Default::default())
+    }
+
+    pub fn new_with_proposer_selection(initial_validators: Vec<Public>, proposer_selection: ProposerSelection) -> Self {
         DynamicValidator {
             initial_list: RoundRobinValidator::new(initial_validators),
             client: Default::default(),
+            proposer_selection,
+            round_robin: Default::default(),
         }
     }
 
@@ -120,10 +129,47 @@ impl DynamicValidator {
         self.previous_validators(hash).map(|validators| validators.into_iter().map(|val| *val.pubkey()).collect())
     }
 
+    /// The priorities are accumulated from the beginning of the term,
+    /// so every block of a term is one turn of the round-robin.
+    fn weighted_round_robin(&self, parent: BlockHash, validators: &[Validator]) -> WeightedRoundRobin {
+        let mut cache = self.round_robin.lock();
+        if let Some((hash, round_robin)) = cache.as_ref() {
+            if *hash == parent {
+                return round_robin.clone()
+            }
+        }
+        let client: Arc<dyn ConsensusClient> =
+            self.client.read().as_ref().and_then(Weak::upgrade).expect("Client is not initialized");
+        let block_id = parent.into();
+        let parent_number = client.block_number(&block_id).expect("Parent must exist");
+        let term_begin = client.last_term_finished_block_num(block_id).expect("Parent must exist");
+
+        let mut round_robin = WeightedRoundRobin::new(
+            validators.iter().map(|validator| (public_to_address(validator.pubkey()), validator.delegation())),
+        );
+        round_robin.skip(parent_number - term_begin);
+        *cache = Some((parent, round_robin.clone()));
+        round_robin
+    }
+
+    fn view_proposer_index(&self, parent: BlockHash, validators: &[Validator], view: usize) -> usize {
+        match self.proposer_selection {
+            ProposerSelection::Weight => view % validators.len(),
+            ProposerSelection::WeightedRoundRobin => {
+                let mut round_robin = self.weighted_round_robin(parent, validators);
+                round_robin.skip(view as u64);
+                let proposer = round_robin.next_proposer();
+                validators
+                    .iter()
+                    .position(|validator| public_to_address(validator.pubkey()) == proposer)
+                    .expect("The proposer is one of the validators")
+            }
+        }
+    }
+
     pub fn proposer_index(&self, parent: BlockHash, prev_proposer_index: usize, proposed_view: usize) -> usize {
         if let Some(validators) = self.next_validators(parent) {
-            let num_validators = validators.len();
-            proposed_view % num_validators
+            self.view_proposer_index(parent, &validators, proposed_view)
         } else {
             let num_validators = self.initial_list.count(&parent);
             (prev_proposer_index + proposed_view + 1) % num_validators
@@ -216,10 +262,9 @@ impl ValidatorSet for DynamicValidator {
     }
 
     fn next_block_proposer(&self, parent: &BlockHash, view: u64) -> Option<Address> {
-        if let Some(validators) = self.validators_pubkey(*parent) {
-            let n_validators = validators.len();
-            let index = view as usize % n_validators;
-            Some(public_to_address(validators.get(index).unwrap()))
+        if let Some(validators) = self.next_validators(*parent) {
+            let index = self.view_proposer_index(*parent, &validators, view as usize);
+            Some(public_to_address(validators[index].pubkey()))
         } else {
             self.initial_list.next_block_proposer(parent, view)
         }
@@ -23,9 +23,11 @@ use ctypes::BlockHash;
 use std::sync::Weak;
 
 mod dynamic_validator;
+mod proposer_selection;
 pub mod validator_list;
 
 pub use self::dynamic_validator::DynamicValidator;
+pub use self::proposer_selection::{simulate_proposers, ProposerSelection, WeightedRoundRobin};
 
 /// A validator set.
 pub trait ValidatorSet: Send + Sync {
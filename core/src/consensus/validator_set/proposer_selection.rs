@@ -0,0 +1,218 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::consensus::stake::{NextValidators, Validator};
+use cjson;
+use ckey::{public_to_address, Address, Public};
+use std::cmp::Reverse;
+
+/// The way to choose the proposer of a view among the elected validators.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProposerSelection {
+    /// The validator with the largest remaining weight proposes first.
+    /// The weight of a validator decreases whenever a block is authored.
+    Weight,
+    /// The proposers are chosen in proportion to the delegations as in the Tendermint paper.
+    WeightedRoundRobin,
+}
+
+impl Default for ProposerSelection {
+    fn default() -> Self {
+        ProposerSelection::Weight
+    }
+}
+
+impl From<cjson::scheme::ProposerSelection> for ProposerSelection {
+    fn from(selection: cjson::scheme::ProposerSelection) -> Self {
+        match selection {
+            cjson::scheme::ProposerSelection::Weight => ProposerSelection::Weight,
+            cjson::scheme::ProposerSelection::WeightedRoundRobin => ProposerSelection::WeightedRoundRobin,
+        }
+    }
+}
+
+/// The weighted round-robin of the Tendermint paper.
+///
+/// At every turn, each validator adds its delegation to its priority.
+/// The validator with the highest priority proposes and the total delegation is subtracted from its priority.
+/// Ties are broken by the address, so the order of the validators does not matter.
+///
+/// The delegations are divided by their greatest common divisor, which does not change the order of the proposers.
+/// Then the priorities return to zero after every `total` turns, so the order of the proposers repeats with that period.
+#[derive(Clone, Debug)]
+pub struct WeightedRoundRobin {
+    validators: Vec<(Address, i128, i128)>,
+    total: i128,
+}
+
+impl WeightedRoundRobin {
+    pub fn new(validators: impl IntoIterator<Item = (Address, u64)>) -> Self {
+        let mut validators: Vec<_> =
+            validators.into_iter().map(|(address, delegation)| (address, i128::from(delegation), 0)).collect();
+        assert!(!validators.is_empty(), "Cannot operate with an empty validator set.");
+        // Everyone takes turns equally if no one has a delegation.
+        if validators.iter().all(|(_, power, _)| *power == 0) {
+            validators.iter_mut().for_each(|(_, power, _)| *power = 1);
+        }
+        let divisor = validators.iter().fold(0, |divisor, (_, power, _)| gcd(divisor, *power));
+        validators.iter_mut().for_each(|(_, power, _)| *power /= divisor);
+        let total = validators.iter().map(|(_, power, _)| power).sum();
+        Self {
+            validators,
+            total,
+        }
+    }
+
+    /// Returns the proposer of the next turn.
+    pub fn next_proposer(&mut self) -> Address {
+        for (_, power, priority) in self.validators.iter_mut() {
+            *priority += *power;
+        }
+        let (address, _, priority) = self
+            .validators
+            .iter_mut()
+            .max_by_key(|(address, _, priority)| (*priority, Reverse(*address)))
+            .expect("The validator set is not empty");
+        *priority -= self.total;
+        *address
+    }
+
+    /// Skips the given turns. The turns are bounded by the period, so it doesn't hang on a huge number of turns.
+    pub fn skip(&mut self, turns: u64) {
+        let turns = u128::from(turns) % self.total as u128;
+        for _ in 0..turns {
+            self.next_proposer();
+        }
+    }
+}
+
+fn gcd(mut a: i128, mut b: i128) -> i128 {
+    while b != 0 {
+        let r = a % b;
+        a = b;
+        b = r;
+    }
+    a
+}
+
+/// Counts the blocks each validator proposes over `heights` blocks, assuming every block is made at the first view.
+/// The validators are elected again at every `term_length` blocks.
+pub fn simulate_proposers(
+    selection: ProposerSelection,
+    delegations: &[u64],
+    heights: u64,
+    term_length: Option<u64>,
+) -> Vec<u64> {
+    let pubkeys: Vec<_> = (0..delegations.len()).map(|index| Public::from(index as u64 + 1)).collect();
+    let addresses: Vec<_> = pubkeys.iter().map(public_to_address).collect();
+    let elect_by_weight = || {
+        let mut validators: Vec<_> = delegations
+            .iter()
+            .zip(pubkeys.iter())
+            .map(|(delegation, pubkey)| Validator::new(*delegation, 0, *pubkey))
+            .collect();
+        validators.sort_unstable();
+        NextValidators::from(validators)
+    };
+    let elect_by_delegation = || WeightedRoundRobin::new(addresses.iter().cloned().zip(delegations.iter().cloned()));
+
+    let mut proposals = vec![0; delegations.len()];
+    let mut by_weight = elect_by_weight();
+    let mut by_delegation = elect_by_delegation();
+    for height in 0..heights {
+        if term_length.map_or(false, |term_length| height != 0 && height % term_length == 0) {
+            by_weight = elect_by_weight();
+            by_delegation = elect_by_delegation();
+        }
+        let proposer = match selection {
+            ProposerSelection::Weight => {
+                // The validators are sorted in the ascending order, and the last one proposes at the first view.
+                let proposer = public_to_address(by_weight.last().expect("The validator set is not empty").pubkey());
+                by_weight.update_weight(&proposer);
+                proposer
+            }
+            ProposerSelection::WeightedRoundRobin => by_delegation.next_proposer(),
+        };
+        let index = addresses.iter().position(|address| *address == proposer).expect("The proposer is a validator");
+        proposals[index] += 1;
+    }
+    proposals
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proposals_are_proportional_to_delegations() {
+        let proposals = simulate_proposers(ProposerSelection::WeightedRoundRobin, &[1, 2, 3, 4], 1000, None);
+        assert_eq!(vec![100, 200, 300, 400], proposals);
+    }
+
+    #[test]
+    fn every_validator_proposes_once_in_a_cycle_of_equal_delegations() {
+        let mut round_robin = WeightedRoundRobin::new((1..=4).map(|index| (Address::from(index), 7)));
+        let mut proposers: Vec<_> = (0..4).map(|_| round_robin.next_proposer()).collect();
+        proposers.sort();
+        assert_eq!((1..=4).map(Address::from).collect::<Vec<_>>(), proposers);
+    }
+
+    #[test]
+    fn order_of_validators_does_not_matter() {
+        let validators = vec![(Address::from(1), 10), (Address::from(2), 30), (Address::from(3), 30)];
+        let mut forward = WeightedRoundRobin::new(validators.clone());
+        let mut backward = WeightedRoundRobin::new(validators.into_iter().rev());
+        for _ in 0..100 {
+            assert_eq!(forward.next_proposer(), backward.next_proposer());
+        }
+    }
+
+    #[test]
+    fn light_validator_gets_its_share_after_the_heavy_one() {
+        let mut round_robin = WeightedRoundRobin::new(vec![(Address::from(1), 1), (Address::from(2), 9)]);
+        let proposers: Vec<_> = (0..10).map(|_| round_robin.next_proposer()).collect();
+        assert_eq!(1, proposers.iter().filter(|proposer| **proposer == Address::from(1)).count());
+        assert_ne!(Address::from(1), proposers[0]);
+    }
+
+    #[test]
+    fn proposers_repeat_with_the_period() {
+        let validators = vec![(Address::from(1), 20), (Address::from(2), 30), (Address::from(3), 50)];
+        let mut round_robin = WeightedRoundRobin::new(validators);
+        // The period is (20 + 30 + 50) / 10
+        let first: Vec<_> = (0..10).map(|_| round_robin.next_proposer()).collect();
+        let second: Vec<_> = (0..10).map(|_| round_robin.next_proposer()).collect();
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn skipping_a_huge_number_of_turns_is_bounded_by_the_period() {
+        let validators = vec![(Address::from(1), 1), (Address::from(2), 2), (Address::from(3), 4)];
+        let mut skipped = WeightedRoundRobin::new(validators.clone());
+        skipped.skip(std::u64::MAX);
+        let mut expected = WeightedRoundRobin::new(validators);
+        expected.skip(std::u64::MAX % 7);
+        for _ in 0..7 {
+            assert_eq!(expected.next_proposer(), skipped.next_proposer());
+        }
+    }
+
+    #[test]
+    fn no_delegation_means_equal_turns() {
+        let proposals = simulate_proposers(ProposerSelection::WeightedRoundRobin, &[0, 0, 0], 30, Some(6));
+        assert_eq!(vec![10, 10, 10], proposals);
+    }
+}
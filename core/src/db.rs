@@ -36,8 +36,12 @@ pub const COL_MEMPOOL: Option<u32> = Some(4);
 pub const COL_ERROR_HINT: Option<u32> = Some(5);
 /// Column for Storing Peer list
 pub const COL_PEER: Option<u32> = Some(6);
+/// Column for Transaction receipts
+pub const COL_RECEIPT: Option<u32> = Some(7);
+/// Column for the index from addresses to transactions
+pub const COL_ADDRESS_INDEX: Option<u32> = Some(8);
 /// Number of columns in DB
-pub const NUM_COLUMNS: Option<u32> = Some(7);
+pub const NUM_COLUMNS: Option<u32> = Some(9);
 
 /// Modes for updating caches.
 #[derive(Clone, Copy)]
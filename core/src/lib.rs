@@ -51,6 +51,7 @@ mod error;
 mod invoice;
 mod miner;
 mod peer_db;
+mod receipt;
 mod scheme;
 mod service;
 mod transaction;
@@ -66,14 +67,17 @@ pub use crate::block::Block;
 pub use crate::client::snapshot_notify;
 pub use crate::client::{
     AccountData, AssetClient, BlockChainClient, BlockChainTrait, ChainNotify, Client, ClientConfig, DatabaseClient,
-    EngineClient, EngineInfo, ExecuteClient, ImportBlock, MiningBlockChainClient, Shard, SnapshotClient, StateInfo,
-    TermInfo, TestBlockChainClient, TextClient,
+    EngineClient, EngineInfo, ExecuteClient, ImportBlock, MiningBlockChainClient, PruningMode, Shard, SnapshotClient,
+    StateInfo, StateProofClient, TermInfo, TestBlockChainClient, TextClient,
 };
-pub use crate::consensus::{EngineType, TimeGapParams};
-pub use crate::db::{COL_PEER, COL_STATE, NUM_COLUMNS};
+pub use crate::consensus::light_client;
+pub use crate::consensus::stake;
+pub use crate::consensus::{simulate_proposers, ConsensusStatus, EngineType, ProposerSelection, TimeGapParams};
+pub use crate::db::{COL_EXTRA, COL_PEER, COL_STATE, NUM_COLUMNS};
 pub use crate::error::{BlockImportError, Error, ImportError};
 pub use crate::miner::{MemPoolFees, Miner, MinerOptions, MinerService};
 pub use crate::peer_db::PeerDb;
+pub use crate::receipt::Receipt;
 pub use crate::scheme::Scheme;
 pub use crate::service::ClientService;
 pub use crate::transaction::{
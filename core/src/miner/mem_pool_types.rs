@@ -499,9 +499,21 @@ impl MemPoolFees {
             } => self.min_asset_mint_cost,
             Action::TransferAsset {
                 ..
+            }
+            | Action::ClawbackAsset {
+                ..
             } => self.min_asset_transfer_cost,
             Action::ChangeAssetScheme {
                 ..
+            }
+            | Action::SetAssetFrozen {
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                ..
             } => self.min_asset_scheme_change_cost,
             Action::IncreaseAssetSupply {
                 ..
@@ -560,10 +560,12 @@ impl MinerService for Miner {
     type State = TopLevelState;
 
     fn status(&self) -> MinerStatus {
-        let status = self.mem_pool.read().status();
+        let mem_pool = self.mem_pool.read();
+        let status = mem_pool.status();
         MinerStatus {
             transactions_in_pending_queue: status.pending,
             transactions_in_future_queue: status.future,
+            minimum_fee: mem_pool.effective_minimum_fee(),
         }
     }
 
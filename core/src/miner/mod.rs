@@ -151,6 +151,8 @@ pub struct MinerStatus {
     pub transactions_in_pending_queue: usize,
     /// Number of transactions in queue with state `future` (not yet ready to be included in block)
     pub transactions_in_future_queue: usize,
+    /// The minimum fee a transaction must pay to enter the full queue
+    pub minimum_fee: u64,
 }
 
 /// Represents the result of importing tranasction.
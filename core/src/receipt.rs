@@ -0,0 +1,43 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ctypes::errors::RuntimeError;
+use ctypes::transaction::AssetOutPoint;
+use ctypes::{BlockHash, BlockNumber, TxHash};
+use primitives::H256;
+
+/// The result of executing a transaction.
+#[derive(Clone, Debug, PartialEq, RlpEncodable, RlpDecodable)]
+pub struct Receipt {
+    pub hash: TxHash,
+    /// The block in which the transaction was executed
+    pub block_hash: BlockHash,
+    pub block_number: BlockNumber,
+    /// `None` if the transaction succeeded
+    pub error: Option<RuntimeError>,
+    /// The fee charged to the fee payer. It is zero if the transaction failed.
+    pub fee: u64,
+    /// The asset outputs created by the transaction
+    pub created_assets: Vec<AssetOutPoint>,
+    /// The keys of the action data written by the transaction
+    pub touched_action_data: Vec<H256>,
+}
+
+impl Receipt {
+    pub fn is_success(&self) -> bool {
+        self.error.is_none()
+    }
+}
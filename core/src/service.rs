@@ -80,6 +80,8 @@ pub enum ClientIoMessage {
     /// Update the best block by the given hash
     /// Only used in Tendermint
     UpdateBestAsCommitted(BlockHash),
+    /// Index a chunk of the blocks that are not indexed by the addresses yet
+    CatchUpAddressIndex,
 }
 
 /// IO interface for the Client handler
@@ -88,7 +90,12 @@ struct ClientIoHandler {
 }
 
 impl IoHandler<ClientIoMessage> for ClientIoHandler {
-    fn message(&self, _io: &IoContext<ClientIoMessage>, net_message: ClientIoMessage) -> IoHandlerResult<()> {
+    fn initialize(&self, io: &IoContext<ClientIoMessage>) -> IoHandlerResult<()> {
+        io.channel().send(ClientIoMessage::CatchUpAddressIndex)?;
+        Ok(())
+    }
+
+    fn message(&self, io: &IoContext<ClientIoMessage>, net_message: ClientIoMessage) -> IoHandlerResult<()> {
         match net_message {
             ClientIoMessage::BlockVerified => {
                 self.client.import_verified_blocks();
@@ -108,6 +115,12 @@ impl IoHandler<ClientIoMessage> for ClientIoHandler {
             ClientIoMessage::UpdateBestAsCommitted(block_hash) => {
                 self.client.update_best_as_committed(block_hash);
             }
+            ClientIoMessage::CatchUpAddressIndex => {
+                // The rest is indexed by the next message, not to hold the import lock for long.
+                if !self.client.catch_up_address_index() {
+                    io.channel().send(ClientIoMessage::CatchUpAddressIndex)?;
+                }
+            }
         }
         Ok(())
     }
@@ -16,7 +16,7 @@
 
 mod chain_type;
 
-use ccore::{MemPoolFees, MinerOptions, TimeGapParams};
+use ccore::{ClientConfig, MemPoolFees, MinerOptions, PruningMode, TimeGapParams};
 use cidr::IpCidr;
 use ckey::PlatformAddress;
 use clap;
@@ -28,6 +28,7 @@ use std::time::Duration;
 use toml;
 
 pub use self::chain_type::ChainType;
+use crate::metrics::MetricsConfig;
 use crate::rpc::{RpcHttpConfig, RpcIpcConfig, RpcWsConfig};
 
 #[derive(Deserialize)]
@@ -43,6 +44,8 @@ pub struct Config {
     pub snapshot: Snapshot,
     #[serde(default)]
     pub email_alarm: EmailAlarm,
+    #[serde(default)]
+    pub metrics: Metrics,
 }
 
 impl Config {
@@ -55,6 +58,7 @@ impl Config {
         self.ws.merge(&other.ws);
         self.snapshot.merge(&other.snapshot);
         self.email_alarm.merge(&other.email_alarm);
+        self.metrics.merge(&other.metrics);
     }
 
     pub fn miner_options(&self) -> Result<MinerOptions, String> {
@@ -108,6 +112,19 @@ impl Config {
         })
     }
 
+    pub fn client_config(&self) -> Result<ClientConfig, String> {
+        let pruning = match &self.operating.pruning {
+            Some(pruning) => pruning.parse()?,
+            None => PruningMode::Archive,
+        };
+        Ok(ClientConfig {
+            address_index: self.operating.address_index.unwrap_or(false),
+            pruning,
+            pruning_history: self.operating.pruning_history.unwrap_or_else(|| ClientConfig::default().pruning_history),
+            ..Default::default()
+        })
+    }
+
     pub fn rpc_http_config(&self) -> RpcHttpConfig {
         debug_assert!(!self.rpc.disable.unwrap());
 
@@ -139,6 +156,15 @@ impl Config {
         }
     }
 
+    pub fn metrics_config(&self) -> MetricsConfig {
+        debug_assert!(!self.metrics.disable.unwrap());
+
+        MetricsConfig {
+            interface: self.metrics.interface.clone().unwrap(),
+            port: self.metrics.port.unwrap(),
+        }
+    }
+
     pub fn network_config(&self) -> Result<NetworkConfig, String> {
         debug_assert!(!self.network.disable.unwrap());
 
@@ -213,6 +239,9 @@ pub struct Operating {
     pub keys_path: Option<String>,
     pub password_path: Option<String>,
     pub chain: Option<ChainType>,
+    pub address_index: Option<bool>,
+    pub pruning: Option<String>,
+    pub pruning_history: Option<u64>,
 }
 
 #[derive(Deserialize)]
@@ -259,6 +288,9 @@ pub struct Network {
     pub sync: Option<bool>,
     pub snapshot_hash: Option<H256>,
     pub snapshot_number: Option<u64>,
+    pub snapshot_quorum: Option<usize>,
+    pub snapshot_checkpoint_hash: Option<H256>,
+    pub snapshot_checkpoint_number: Option<u64>,
     pub transaction_relay: Option<bool>,
     pub discovery: Option<bool>,
     pub discovery_type: Option<String>,
@@ -300,6 +332,18 @@ pub struct Snapshot {
     pub path: Option<String>,
     // Snapshot's age in blocks
     pub expiration: Option<u64>,
+    // The number of the latest snapshots to keep
+    pub max_count: Option<usize>,
+    // The total size of the snapshots in GB
+    pub max_size: Option<u64>,
+}
+
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Metrics {
+    pub disable: Option<bool>,
+    pub interface: Option<String>,
+    pub port: Option<u16>,
 }
 
 #[derive(Deserialize)]
@@ -354,6 +398,15 @@ impl Operating {
         if other.chain.is_some() {
             self.chain = other.chain.clone();
         }
+        if other.address_index.is_some() {
+            self.address_index = other.address_index;
+        }
+        if other.pruning.is_some() {
+            self.pruning = other.pruning.clone();
+        }
+        if other.pruning_history.is_some() {
+            self.pruning_history = other.pruning_history;
+        }
     }
 
     pub fn overwrite_with(&mut self, matches: &clap::ArgMatches<'_>) -> Result<(), String> {
@@ -378,6 +431,15 @@ impl Operating {
         if let Some(chain) = matches.value_of("chain") {
             self.chain = Some(chain.parse().unwrap());
         }
+        if matches.is_present("address-index") {
+            self.address_index = Some(true);
+        }
+        if let Some(pruning) = matches.value_of("pruning") {
+            self.pruning = Some(pruning.to_string());
+        }
+        if let Some(pruning_history) = matches.value_of("pruning-history") {
+            self.pruning_history = Some(pruning_history.parse().map_err(|_| "Invalid pruning history")?);
+        }
         Ok(())
     }
 }
@@ -548,6 +610,15 @@ impl Network {
         if other.snapshot_number.is_some() {
             self.snapshot_number = other.snapshot_number;
         }
+        if other.snapshot_quorum.is_some() {
+            self.snapshot_quorum = other.snapshot_quorum;
+        }
+        if other.snapshot_checkpoint_hash.is_some() {
+            self.snapshot_checkpoint_hash = other.snapshot_checkpoint_hash;
+        }
+        if other.snapshot_checkpoint_number.is_some() {
+            self.snapshot_checkpoint_number = other.snapshot_checkpoint_number;
+        }
         if other.transaction_relay.is_some() {
             self.transaction_relay = other.transaction_relay;
         }
@@ -606,6 +677,17 @@ impl Network {
         if let Some(snapshot_number) = matches.value_of("snapshot-number") {
             self.snapshot_number = Some(snapshot_number.parse().map_err(|_| "Invalid snapshot-number")?);
         }
+        if let Some(snapshot_quorum) = matches.value_of("snapshot-quorum") {
+            self.snapshot_quorum = Some(snapshot_quorum.parse().map_err(|_| "Invalid snapshot-quorum")?);
+        }
+        if let Some(snapshot_checkpoint_hash) = matches.value_of("snapshot-checkpoint-hash") {
+            self.snapshot_checkpoint_hash =
+                Some(snapshot_checkpoint_hash.parse().map_err(|_| "Invalid snapshot-checkpoint-hash")?);
+        }
+        if let Some(snapshot_checkpoint_number) = matches.value_of("snapshot-checkpoint-number") {
+            self.snapshot_checkpoint_number =
+                Some(snapshot_checkpoint_number.parse().map_err(|_| "Invalid snapshot-checkpoint-number")?);
+        }
         if matches.is_present("no-tx-relay") {
             self.transaction_relay = Some(false);
         }
@@ -721,6 +803,12 @@ impl Snapshot {
         if other.expiration.is_some() {
             self.expiration = other.expiration;
         }
+        if other.max_count.is_some() {
+            self.max_count = other.max_count;
+        }
+        if other.max_size.is_some() {
+            self.max_size = other.max_size;
+        }
     }
 
     pub fn overwrite_with(&mut self, matches: &clap::ArgMatches<'_>) -> Result<(), String> {
@@ -731,6 +819,12 @@ impl Snapshot {
         if let Some(snapshot_path) = matches.value_of("snapshot-path") {
             self.path = Some(snapshot_path.to_string());
         }
+        if let Some(max_count) = matches.value_of("snapshot-max-count") {
+            self.max_count = Some(max_count.parse().map_err(|_| "Invalid snapshot-max-count")?);
+        }
+        if let Some(max_size) = matches.value_of("snapshot-max-size") {
+            self.max_size = Some(max_size.parse().map_err(|_| "Invalid snapshot-max-size")?);
+        }
         Ok(())
     }
 }
@@ -763,6 +857,44 @@ impl EmailAlarm {
     }
 }
 
+impl Metrics {
+    pub fn merge(&mut self, other: &Metrics) {
+        if other.disable.is_some() {
+            self.disable = other.disable;
+        }
+        if other.interface.is_some() {
+            self.interface = other.interface.clone();
+        }
+        if other.port.is_some() {
+            self.port = other.port;
+        }
+    }
+
+    pub fn overwrite_with(&mut self, matches: &clap::ArgMatches<'_>) -> Result<(), String> {
+        if matches.is_present("metrics") {
+            self.disable = Some(false);
+        }
+
+        if let Some(interface) = matches.value_of("metrics-interface") {
+            self.interface = Some(interface.to_string());
+        }
+        if let Some(port) = matches.value_of("metrics-port") {
+            self.port = Some(port.parse().map_err(|_| "Invalid port")?);
+        }
+        Ok(())
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            disable: Some(true),
+            interface: None,
+            port: None,
+        }
+    }
+}
+
 impl Default for EmailAlarm {
     fn default() -> Self {
         Self {
@@ -806,5 +938,6 @@ pub fn load_config(matches: &clap::ArgMatches<'_>) -> Result<Config, String> {
     config.ws.overwrite_with(&matches)?;
     config.snapshot.overwrite_with(&matches)?;
     config.email_alarm.overwrite_with(&matches)?;
+    config.metrics.overwrite_with(&matches)?;
     Ok(config)
 }
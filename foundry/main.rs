@@ -28,8 +28,11 @@ extern crate codechain_keystore as ckeystore;
 extern crate codechain_logger as clogger;
 extern crate codechain_network as cnetwork;
 extern crate codechain_rpc as crpc;
+extern crate codechain_state as cstate;
 extern crate codechain_sync as csync;
 extern crate codechain_timer as ctimer;
+extern crate codechain_types as ctypes;
+extern crate codechain_vm as cvm;
 
 use panic_hook;
 
@@ -37,6 +40,7 @@ mod config;
 mod constants;
 mod dummy_network_service;
 mod json;
+mod metrics;
 mod rpc;
 mod rpc_apis;
 mod run_node;
@@ -0,0 +1,150 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ccore::{BlockChainClient, Client, EngineInfo, Miner, MinerService};
+use cnetwork::{once_event_callback, EventSender, NetworkControl};
+use crpc::{start_metrics, HttpServer};
+use csync::BlockSyncEvent;
+use std::fmt::Write;
+use std::io;
+use std::sync::Arc;
+
+#[derive(Debug, PartialEq)]
+pub struct MetricsConfig {
+    pub interface: String,
+    pub port: u16,
+}
+
+/// The subsystems whose counters and gauges are exported.
+pub struct MetricsSources {
+    pub client: Arc<Client>,
+    pub miner: Arc<Miner>,
+    pub network_control: Arc<dyn NetworkControl>,
+    pub block_sync: Option<EventSender<BlockSyncEvent>>,
+}
+
+impl MetricsSources {
+    /// Renders the current values in the Prometheus text format.
+    fn render(&self) -> String {
+        let mut out = Metrics::default();
+
+        let miner_status = self.miner.status();
+        out.gauge("mem_pool_pending_transactions", "The number of the transactions ready to be included in a block");
+        out.sample("mem_pool_pending_transactions", &[], miner_status.transactions_in_pending_queue as u64);
+        out.gauge("mem_pool_future_transactions", "The number of the transactions waiting for the previous seqs");
+        out.sample("mem_pool_future_transactions", &[], miner_status.transactions_in_future_queue as u64);
+        out.gauge("mem_pool_minimum_fee", "The minimum fee to enter the mem pool when it is full");
+        out.sample("mem_pool_minimum_fee", &[], miner_status.minimum_fee);
+
+        let queue_info = self.client.queue_info();
+        out.gauge("verification_queue_blocks", "The number of the blocks in the verification queue");
+        out.sample("verification_queue_blocks", &[("queue", "unverified")], queue_info.unverified_queue_size as u64);
+        out.sample("verification_queue_blocks", &[("queue", "verifying")], queue_info.verifying_queue_size as u64);
+        out.sample("verification_queue_blocks", &[("queue", "verified")], queue_info.verified_queue_size as u64);
+
+        if let Some(status) = self.client.engine().consensus_status() {
+            out.gauge("consensus_height", "The height of the current consensus round");
+            out.sample("consensus_height", &[], status.height);
+            out.gauge("consensus_view", "The view of the current consensus round");
+            out.sample("consensus_view", &[], status.view);
+            out.gauge("consensus_step", "1 for the current consensus step");
+            for (step, _) in &status.timeouts {
+                out.sample("consensus_step", &[("step", *step)], (*step == status.step) as u64);
+            }
+            out.counter("consensus_timeouts_total", "The number of the fired timeouts of each consensus step");
+            for (step, count) in &status.timeouts {
+                out.sample("consensus_timeouts_total", &[("step", *step)], *count);
+            }
+        }
+
+        if let Ok(peer_count) = self.network_control.get_peer_count() {
+            out.gauge("network_peers", "The number of the connected peers");
+            out.sample("network_peers", &[], peer_count as u64);
+        }
+        if let Ok(usage) = self.network_control.recent_network_usage() {
+            out.gauge("network_recent_bytes", "The bytes sent by each extension in the last 10 seconds");
+            for (extension, bytes) in &usage {
+                out.sample("network_recent_bytes", &[("extension", extension.as_str())], *bytes as u64);
+            }
+        }
+
+        if let Some(block_sync) = &self.block_sync {
+            let (sender, receiver) = once_event_callback();
+            // The progress is unavailable if the sync extension has stopped.
+            let progress =
+                block_sync.send(BlockSyncEvent::GetSnapshotProgress(sender)).ok().and_then(|_| receiver.recv().ok());
+            if let Some(progress) = progress {
+                out.gauge("snapshot_stage", "1 for the current stage of the snapshot restoration");
+                out.sample("snapshot_stage", &[("stage", progress.stage)], 1);
+                out.gauge("snapshot_restored_chunks", "The number of the restored chunks of the restoring trie");
+                out.sample("snapshot_restored_chunks", &[], progress.restored_chunks as u64);
+                out.gauge("snapshot_pending_chunks", "The number of the chunks of the restoring trie to download");
+                out.sample("snapshot_pending_chunks", &[], progress.pending_chunks as u64);
+            }
+        }
+
+        let (hits, misses) = cstate::cache_lookup_counts();
+        out.counter("state_cache_hits_total", "The number of the state lookups found in the caches");
+        out.sample("state_cache_hits_total", &[], hits as u64);
+        out.counter("state_cache_misses_total", "The number of the state lookups read from the tries");
+        out.sample("state_cache_misses_total", &[], misses as u64);
+
+        out.0
+    }
+}
+
+#[derive(Default)]
+struct Metrics(String);
+
+impl Metrics {
+    fn gauge(&mut self, name: &str, help: &str) {
+        self.header(name, "gauge", help);
+    }
+
+    fn counter(&mut self, name: &str, help: &str) {
+        self.header(name, "counter", help);
+    }
+
+    fn header(&mut self, name: &str, kind: &str, help: &str) {
+        writeln!(self.0, "# HELP foundry_{} {}", name, help).unwrap();
+        writeln!(self.0, "# TYPE foundry_{} {}", name, kind).unwrap();
+    }
+
+    fn sample(&mut self, name: &str, labels: &[(&str, &str)], value: u64) {
+        write!(self.0, "foundry_{}", name).unwrap();
+        if !labels.is_empty() {
+            let labels: Vec<_> = labels.iter().map(|(key, value)| format!("{}=\"{}\"", key, value)).collect();
+            write!(self.0, "{{{}}}", labels.join(",")).unwrap();
+        }
+        writeln!(self.0, " {}", value).unwrap();
+    }
+}
+
+pub fn metrics_start(sources: MetricsSources, config: MetricsConfig) -> Result<HttpServer, String> {
+    let url = format!("{}:{}", config.interface, config.port);
+    let addr = url.parse().map_err(|_| format!("Invalid metrics listen host/port given: {}", url))?;
+    match start_metrics(&addr, move || sources.render()) {
+        Err(ref err) if err.kind() == io::ErrorKind::AddrInUse => Err(format!(
+            "Metrics address {} is already in use, make sure that another instance of a CodeChain node is not running or change the address using the --metrics-port option.",
+            url
+        )),
+        Err(e) => Err(format!("Metrics error: {:?}", e)),
+        Ok(server) => {
+            cinfo!(RPC, "Metrics Listening on {}/metrics", url);
+            Ok(server)
+        }
+    }
+}
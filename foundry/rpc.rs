@@ -17,7 +17,8 @@
 use crate::config::Config;
 use crate::rpc_apis;
 use crpc::{
-    jsonrpc_core, start_http, start_ipc, start_ws, HttpServer, IpcServer, MetaIoHandler, Middleware, WsError, WsServer,
+    jsonrpc_core, start_http, start_ipc, start_ws, HttpServer, IpcServer, MetaIoHandler, Metadata, Middleware, WsError,
+    WsServer,
 };
 use futures::future::Either;
 use serde_json;
@@ -32,7 +33,7 @@ pub struct RpcHttpConfig {
 }
 
 pub fn rpc_http_start(
-    server: MetaIoHandler<(), impl Middleware<()>>,
+    server: MetaIoHandler<Metadata, impl Middleware<Metadata>>,
     config: RpcHttpConfig,
 ) -> Result<HttpServer, String> {
     let url = format!("{}:{}", config.interface, config.port);
@@ -62,7 +63,7 @@ pub struct RpcIpcConfig {
 }
 
 pub fn rpc_ipc_start(
-    server: MetaIoHandler<(), impl Middleware<()>>,
+    server: MetaIoHandler<Metadata, impl Middleware<Metadata>>,
     config: RpcIpcConfig,
 ) -> Result<IpcServer, String> {
     let start_result = start_ipc(&config.socket_addr, server);
@@ -85,7 +86,10 @@ pub struct RpcWsConfig {
     pub max_connections: usize,
 }
 
-pub fn rpc_ws_start(server: MetaIoHandler<(), impl Middleware<()>>, config: RpcWsConfig) -> Result<WsServer, String> {
+pub fn rpc_ws_start(
+    server: MetaIoHandler<Metadata, impl Middleware<Metadata>>,
+    config: RpcWsConfig,
+) -> Result<WsServer, String> {
     let url = format!("{}:{}", config.interface, config.port);
     let addr = url.parse().map_err(|_| format!("Invalid WebSockets listen host/port given: {}", url))?;
     let start_result = start_ws(&addr, server, config.max_connections);
@@ -101,7 +105,10 @@ pub fn rpc_ws_start(server: MetaIoHandler<(), impl Middleware<()>>, config: RpcW
     }
 }
 
-pub fn setup_rpc_server(config: &Config, deps: &rpc_apis::ApiDependencies) -> MetaIoHandler<(), impl Middleware<()>> {
+pub fn setup_rpc_server(
+    config: &Config,
+    deps: &rpc_apis::ApiDependencies,
+) -> MetaIoHandler<Metadata, impl Middleware<Metadata>> {
     let mut handler = MetaIoHandler::with_middleware(LogMiddleware::new());
     deps.extend_api(config, &mut handler);
     rpc_apis::setup_rpc(handler)
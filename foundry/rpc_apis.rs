@@ -18,7 +18,8 @@ use crate::config::Config;
 use ccore::{AccountProvider, Client, Miner};
 use clogger::SLOGGER;
 use cnetwork::{EventSender, NetworkControl};
-use crpc::{MetaIoHandler, Middleware, Params, Value};
+use crpc::v1::PubSubClient;
+use crpc::{MetaIoHandler, Metadata, Middleware, Params, Value};
 use csync::BlockSyncEvent;
 use std::sync::Arc;
 
@@ -28,10 +29,11 @@ pub struct ApiDependencies {
     pub network_control: Arc<dyn NetworkControl>,
     pub account_provider: Arc<AccountProvider>,
     pub block_sync: Option<EventSender<BlockSyncEvent>>,
+    pub pubsub: PubSubClient<Client>,
 }
 
 impl ApiDependencies {
-    pub fn extend_api(&self, config: &Config, handler: &mut MetaIoHandler<(), impl Middleware<()>>) {
+    pub fn extend_api(&self, config: &Config, handler: &mut MetaIoHandler<Metadata, impl Middleware<Metadata>>) {
         use crpc::v1::*;
         handler.extend_with(ChainClient::new(Arc::clone(&self.client)).to_delegate());
         handler.extend_with(MempoolClient::new(Arc::clone(&self.client)).to_delegate());
@@ -43,7 +45,9 @@ impl ApiDependencies {
             );
         }
         handler.extend_with(EngineClient::new(Arc::clone(&self.client), Arc::clone(&self.miner)).to_delegate());
+        handler.extend_with(StakeClient::new(Arc::clone(&self.client)).to_delegate());
         handler.extend_with(NetClient::new(Arc::clone(&self.network_control)).to_delegate());
+        handler.extend_with(self.pubsub.clone().to_delegate());
         handler.extend_with(
             AccountClient::new(Arc::clone(&self.account_provider), Arc::clone(&self.client), Arc::clone(&self.miner))
                 .to_delegate(),
@@ -51,7 +55,7 @@ impl ApiDependencies {
     }
 }
 
-pub fn setup_rpc<M: Middleware<()>>(mut handler: MetaIoHandler<(), M>) -> MetaIoHandler<(), M> {
+pub fn setup_rpc<M: Middleware<Metadata>>(mut handler: MetaIoHandler<Metadata, M>) -> MetaIoHandler<Metadata, M> {
     handler.add_method("ping", |_params: Params| Ok(Value::String("pong".to_string())));
     handler.add_method("version", |_params: Params| Ok(Value::String(env!("CARGO_PKG_VERSION").to_string())));
     handler.add_method("commitHash", |_params: Params| Ok(Value::String(env!("VERGEN_SHA").to_string())));
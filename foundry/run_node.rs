@@ -18,6 +18,7 @@ use crate::config::{self, load_config};
 use crate::constants::{DEFAULT_DB_PATH, DEFAULT_KEYS_PATH};
 use crate::dummy_network_service::DummyNetworkService;
 use crate::json::PasswordFile;
+use crate::metrics::{metrics_start, MetricsSources};
 use crate::rpc::{rpc_http_start, rpc_ipc_start, rpc_ws_start, setup_rpc_server};
 use crate::rpc_apis::ApiDependencies;
 use ccore::{snapshot_notify, EngineClient};
@@ -32,7 +33,8 @@ use ckeystore::KeyStore;
 use clap::ArgMatches;
 use clogger::{self, EmailAlarm, LoggerConfig};
 use cnetwork::{Filters, ManagingPeerdb, NetworkConfig, NetworkControl, NetworkService, RoutingTable, SocketAddr};
-use csync::snapshot::Service as SnapshotService;
+use crpc::v1::PubSubClient;
+use csync::snapshot::{Retention as SnapshotRetention, Service as SnapshotService};
 use csync::{BlockSyncExtension, BlockSyncSender, TransactionSyncExtension};
 use ctimer::TimerLoop;
 use ctrlc::CtrlC;
@@ -90,7 +92,7 @@ fn discovery_start(
     Ok(())
 }
 
-fn client_start(
+pub fn client_start(
     client_config: &ClientConfig,
     timer_loop: &TimerLoop,
     db: Arc<dyn KeyValueDB>,
@@ -247,7 +249,7 @@ pub fn run_node(matches: &ArgMatches<'_>) -> Result<(), String> {
     let ap = prepare_account_provider(&keys_path)?;
     unlock_accounts(&*ap, &pf)?;
 
-    let client_config: ClientConfig = Default::default();
+    let client_config = config.client_config()?;
     let db = open_db(&config.operating, &client_config)?;
 
     let miner = new_miner(&config, &scheme, ap.clone(), Arc::clone(&db))?;
@@ -282,7 +284,27 @@ pub fn run_node(matches: &ArgMatches<'_>) -> Result<(), String> {
                         (Some(hash), Some(num)) => Some((hash, num)),
                         _ => None,
                     };
-                    service.register_extension(move |api| BlockSyncExtension::new(client, api, snapshot_target))
+                    let snapshot_dir = if config.snapshot.disable.unwrap() {
+                        None
+                    } else {
+                        config.snapshot.path.clone()
+                    };
+                    let snapshot_quorum = config.network.snapshot_quorum;
+                    let snapshot_checkpoint =
+                        match (config.network.snapshot_checkpoint_hash, config.network.snapshot_checkpoint_number) {
+                            (Some(hash), Some(num)) => Some((hash, num)),
+                            _ => None,
+                        };
+                    service.register_extension(move |api| {
+                        BlockSyncExtension::new(
+                            client,
+                            api,
+                            snapshot_target,
+                            snapshot_dir,
+                            snapshot_quorum,
+                            snapshot_checkpoint,
+                        )
+                    })
                 };
                 let sync = Arc::new(BlockSyncSender::from(sync_sender.clone()));
                 client.client().add_notify(Arc::downgrade(&sync) as Weak<dyn ChainNotify>);
@@ -303,12 +325,15 @@ pub fn run_node(matches: &ArgMatches<'_>) -> Result<(), String> {
     };
 
     let (rpc_server, ipc_server, ws_server) = {
+        let pubsub = PubSubClient::new(client.client());
+        client.client().add_notify(pubsub.chain_notify());
         let rpc_apis_deps = ApiDependencies {
             client: client.client(),
             miner: Arc::clone(&miner),
             network_control: Arc::clone(&network_service),
             account_provider: ap,
-            block_sync: maybe_sync_sender,
+            block_sync: maybe_sync_sender.clone(),
+            pubsub,
         };
 
         let rpc_server = {
@@ -341,13 +366,31 @@ pub fn run_node(matches: &ArgMatches<'_>) -> Result<(), String> {
         (rpc_server, ipc_server, ws_server)
     };
 
+    let metrics_server = {
+        if !config.metrics.disable.unwrap() {
+            let sources = MetricsSources {
+                client: client.client(),
+                miner: Arc::clone(&miner),
+                network_control: Arc::clone(&network_service),
+                block_sync: maybe_sync_sender,
+            };
+            Some(metrics_start(sources, config.metrics_config())?)
+        } else {
+            None
+        }
+    };
+
     let _snapshot_service = {
         let client = client.client();
         let (tx, rx) = snapshot_notify::create();
         client.engine().register_snapshot_notify_sender(tx);
         if !config.snapshot.disable.unwrap() {
-            let service =
-                Arc::new(SnapshotService::new(client, rx, config.snapshot.path.unwrap(), config.snapshot.expiration));
+            let retention = SnapshotRetention {
+                expiration: config.snapshot.expiration,
+                max_count: config.snapshot.max_count,
+                max_size: config.snapshot.max_size.map(|gb| gb * 1024 * 1024 * 1024),
+            };
+            let service = Arc::new(SnapshotService::new(client, rx, config.snapshot.path.unwrap(), retention));
             Some(service)
         } else {
             None
@@ -374,6 +417,10 @@ pub fn run_node(matches: &ArgMatches<'_>) -> Result<(), String> {
         server.close_handle().close();
         server.wait().map_err(|err| format!("Error while closing jsonrpc ws server: {}", err))?;
     }
+    if let Some(server) = metrics_server {
+        server.close_handle().close();
+        server.wait();
+    }
 
     Ok(())
 }
@@ -0,0 +1,329 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::config::load_config;
+use crate::run_node::{client_start, open_db};
+use ccore::{
+    Block, BlockChainClient, BlockChainTrait, BlockId, BlockImportError, BlockStatus, ClientService, ImportBlock,
+    ImportError, Miner,
+};
+use clap::ArgMatches;
+use clogger::{self, LoggerConfig};
+use ctimer::TimerLoop;
+use ctypes::{BlockHash, BlockNumber, Header};
+use rustc_serialize::hex::{FromHex, ToHex};
+use std::fs::File;
+use std::io::{self, BufRead, BufReader, BufWriter, Read, Write};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+/// The limit of the size of a block read from a file, not to allocate a buffer by a broken length prefix.
+const MAX_BLOCK_SIZE: usize = 64 * 1024 * 1024;
+
+/// A block in the JSON format. Each line of the file has one block.
+#[derive(Serialize, Deserialize)]
+struct JsonBlock {
+    number: BlockNumber,
+    hash: BlockHash,
+    rlp: String,
+}
+
+#[derive(Clone, Copy)]
+enum Format {
+    Rlp,
+    Json,
+}
+
+impl Format {
+    fn from_matches(matches: &ArgMatches<'_>) -> Result<Self, String> {
+        match matches.value_of("format").unwrap_or("rlp") {
+            "rlp" => Ok(Format::Rlp),
+            "json" => Ok(Format::Json),
+            format => Err(format!("Unknown format {}", format)),
+        }
+    }
+}
+
+pub fn run_export_blocks_command(global_matches: &ArgMatches<'_>, matches: &ArgMatches<'_>) -> Result<(), String> {
+    let format = Format::from_matches(matches)?;
+    let from: BlockNumber = match matches.value_of("from") {
+        Some(from) => from.parse().map_err(|_| format!("Invalid block number {}", from))?,
+        None => 1,
+    };
+    let to: Option<BlockNumber> = match matches.value_of("to") {
+        Some(to) => Some(to.parse().map_err(|_| format!("Invalid block number {}", to))?),
+        None => None,
+    };
+
+    let timer_loop = TimerLoop::new(2);
+    let service = open_client(global_matches, &timer_loop)?;
+    let client = service.client();
+
+    let best_block_number = client.chain_info().best_block_number;
+    let to = to.unwrap_or(best_block_number);
+    if to > best_block_number {
+        return Err(format!("The best block is #{} but #{} is requested", best_block_number, to))
+    }
+    if from > to {
+        return Err(format!("Invalid range: {} > {}", from, to))
+    }
+
+    let mut out: Box<dyn Write> = match matches.value_of("FILE") {
+        Some(path) => {
+            Box::new(BufWriter::new(File::create(path).map_err(|e| format!("Cannot create {}: {}", path, e))?))
+        }
+        None => Box::new(BufWriter::new(io::stdout())),
+    };
+    for number in from..=to {
+        let block = client.block(&BlockId::Number(number)).ok_or_else(|| format!("Block #{} is not found", number))?;
+        write_block(&mut out, format, number, block.hash(), block.into_inner())
+            .map_err(|e| format!("Failed to write block #{}: {}", number, e))?;
+    }
+    out.flush().map_err(|e| e.to_string())?;
+
+    cinfo!(CLIENT, "Exported blocks from #{} to #{}", from, to);
+    Ok(())
+}
+
+pub fn run_import_blocks_command(global_matches: &ArgMatches<'_>, matches: &ArgMatches<'_>) -> Result<(), String> {
+    let format = Format::from_matches(matches)?;
+    let path = matches.value_of("FILE").expect("FILE arg is required and its index is 1");
+    let file = File::open(path).map_err(|e| format!("Cannot open {}: {}", path, e))?;
+    let mut reader = BufReader::new(file);
+
+    let timer_loop = TimerLoop::new(2);
+    let service = open_client(global_matches, &timer_loop)?;
+    let client = service.client();
+
+    let mut queued = Vec::new();
+    let mut skipped = 0;
+    loop {
+        let bytes = match format {
+            Format::Rlp => read_rlp_block(&mut reader)?,
+            Format::Json => read_json_block(&mut reader)?,
+        };
+        let (header, bytes) = match bytes {
+            Some(block) => block,
+            None => break,
+        };
+
+        while client.queue_info().is_full() {
+            thread::sleep(Duration::from_millis(100));
+        }
+        match client.import_block(bytes) {
+            Ok(_) => queued.push((header.number(), header.hash())),
+            Err(BlockImportError::Import(ImportError::AlreadyInChain))
+            | Err(BlockImportError::Import(ImportError::AlreadyQueued)) => skipped += 1,
+            Err(err) => return Err(format!("Failed to import a block: {:?}", err)),
+        }
+    }
+
+    // Wait until the verification queue imports all the blocks.
+    while !client.queue_info().is_empty() {
+        thread::sleep(Duration::from_millis(100));
+    }
+
+    // The blocks failed verification are dropped from the queue silently.
+    let failed: Vec<_> =
+        queued.iter().filter(|(_, hash)| client.block_status(&BlockId::Hash(*hash)) != BlockStatus::InChain).collect();
+    if let Some((number, hash)) = failed.first() {
+        return Err(format!("Failed to import {} blocks, the first of which is #{} ({})", failed.len(), number, hash))
+    }
+
+    let chain_info = client.chain_info();
+    cinfo!(
+        CLIENT,
+        "Imported {} blocks and skipped {} known blocks. The best block is #{} ({})",
+        queued.len(),
+        skipped,
+        chain_info.best_block_number,
+        chain_info.best_block_hash
+    );
+    Ok(())
+}
+
+pub fn open_client(matches: &ArgMatches<'_>, timer_loop: &TimerLoop) -> Result<ClientService, String> {
+    clogger::init(&LoggerConfig::new(0), None).expect("Logger must be successfully initialized");
+
+    let config = load_config(matches)?;
+    let scheme = match &config.operating.chain {
+        Some(chain) => chain.scheme()?,
+        None => return Err("chain is not specified".to_string()),
+    };
+    let client_config = config.client_config()?;
+    let db = open_db(&config.operating, &client_config)?;
+    let miner = Miner::new(config.miner_options()?, &scheme, None, Arc::clone(&db));
+    let service = client_start(&client_config, timer_loop, db, &scheme, miner)?;
+
+    scheme.engine.register_chain_notify(service.client().as_ref());
+    service.client().engine().complete_register();
+    Ok(service)
+}
+
+fn write_block(
+    out: &mut dyn Write,
+    format: Format,
+    number: BlockNumber,
+    hash: BlockHash,
+    bytes: Vec<u8>,
+) -> io::Result<()> {
+    match format {
+        Format::Rlp => out.write_all(&bytes),
+        Format::Json => {
+            let block = JsonBlock {
+                number,
+                hash,
+                rlp: bytes.to_hex(),
+            };
+            let line = serde_json::to_string(&block).expect("JsonBlock is always serializable");
+            writeln!(out, "{}", line)
+        }
+    }
+}
+
+fn decode_header(bytes: &[u8]) -> Result<Header, String> {
+    rlp::decode::<Block>(bytes).map(|block| block.header).map_err(|e| format!("Invalid block: {}", e))
+}
+
+/// Reads a block, which is an RLP list, from the concatenated RLP stream.
+fn read_rlp_block(reader: &mut impl Read) -> Result<Option<(Header, Vec<u8>)>, String> {
+    let mut prefix = [0u8; 1];
+    match reader.read_exact(&mut prefix) {
+        Ok(()) => {}
+        Err(ref e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e.to_string()),
+    }
+
+    let mut bytes = prefix.to_vec();
+    let payload_len = match prefix[0] {
+        0xc0..=0xf7 => u64::from(prefix[0] - 0xc0),
+        0xf8..=0xff => {
+            let mut len_bytes = vec![0u8; (prefix[0] - 0xf7) as usize];
+            reader.read_exact(&mut len_bytes).map_err(|e| e.to_string())?;
+            bytes.extend_from_slice(&len_bytes);
+            len_bytes.iter().fold(0u64, |len, byte| (len << 8) | u64::from(*byte))
+        }
+        _ => return Err("The file is not a stream of RLP encoded blocks".to_string()),
+    };
+    if payload_len > MAX_BLOCK_SIZE as u64 {
+        return Err(format!("A block has {} bytes, which is larger than the limit {}", payload_len, MAX_BLOCK_SIZE))
+    }
+
+    let start = bytes.len();
+    bytes.resize(start + payload_len as usize, 0);
+    reader.read_exact(&mut bytes[start..]).map_err(|e| e.to_string())?;
+    Ok(Some((decode_header(&bytes)?, bytes)))
+}
+
+fn read_json_block(reader: &mut impl BufRead) -> Result<Option<(Header, Vec<u8>)>, String> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).map_err(|e| e.to_string())? == 0 {
+            return Ok(None)
+        }
+        if !line.trim().is_empty() {
+            break
+        }
+    }
+    let block: JsonBlock = serde_json::from_str(&line).map_err(|e| format!("Invalid block: {}", e))?;
+    let bytes = block.rlp.from_hex().map_err(|e| format!("Invalid block #{}: {}", block.number, e))?;
+    let header = decode_header(&bytes)?;
+    if header.number() != block.number || header.hash() != block.hash {
+        return Err(format!("Block #{} ({}) doesn't match its RLP", block.number, block.hash))
+    }
+    Ok(Some((header, bytes)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ctypes::header::Seal;
+
+    fn block(number: BlockNumber) -> (Header, Vec<u8>) {
+        let mut header = Header::default();
+        header.set_number(number);
+        let block = Block {
+            header: header.clone(),
+            transactions: Vec::new(),
+        };
+        (header, block.rlp_bytes(&Seal::With))
+    }
+
+    fn hash_and_bytes((header, bytes): &(Header, Vec<u8>)) -> (BlockHash, Vec<u8>) {
+        (header.hash(), bytes.clone())
+    }
+
+    fn export(format: Format, blocks: &[(Header, Vec<u8>)]) -> Vec<u8> {
+        let mut out = Vec::new();
+        for (header, bytes) in blocks {
+            write_block(&mut out, format, header.number(), header.hash(), bytes.clone()).unwrap();
+        }
+        out
+    }
+
+    #[test]
+    fn export_and_import_rlp_blocks() {
+        let blocks = vec![block(1), block(2)];
+        let exported = export(Format::Rlp, &blocks);
+
+        let mut reader = &exported[..];
+        assert_eq!(
+            Ok(Some(hash_and_bytes(&blocks[0]))),
+            read_rlp_block(&mut reader).map(|block| block.as_ref().map(hash_and_bytes))
+        );
+        assert_eq!(
+            Ok(Some(hash_and_bytes(&blocks[1]))),
+            read_rlp_block(&mut reader).map(|block| block.as_ref().map(hash_and_bytes))
+        );
+        assert_eq!(Ok(None), read_rlp_block(&mut reader));
+    }
+
+    #[test]
+    fn export_and_import_json_blocks() {
+        let blocks = vec![block(1), block(2)];
+        let exported = export(Format::Json, &blocks);
+
+        let mut reader = &exported[..];
+        assert_eq!(
+            Ok(Some(hash_and_bytes(&blocks[0]))),
+            read_json_block(&mut reader).map(|block| block.as_ref().map(hash_and_bytes))
+        );
+        assert_eq!(
+            Ok(Some(hash_and_bytes(&blocks[1]))),
+            read_json_block(&mut reader).map(|block| block.as_ref().map(hash_and_bytes))
+        );
+        assert_eq!(Ok(None), read_json_block(&mut reader));
+    }
+
+    #[test]
+    fn json_block_with_wrong_hash_is_rejected() {
+        let (header, bytes) = block(1);
+        let mut exported = Vec::new();
+        write_block(&mut exported, Format::Json, header.number(), BlockHash::default(), bytes).unwrap();
+
+        assert!(read_json_block(&mut &exported[..]).is_err());
+    }
+
+    #[test]
+    fn rlp_block_larger_than_the_limit_is_rejected() {
+        // A list whose length prefix says it has 2^56 bytes of payload.
+        let exported = [0xf8 + 7, 1, 0, 0, 0, 0, 0, 0, 0];
+
+        assert!(read_rlp_block(&mut &exported[..]).is_err());
+    }
+}
@@ -0,0 +1,198 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ccore::light_client::{Error as LightClientError, LightClient, Validators};
+use ccore::stake::NEXT_VALIDATORS_KEY;
+use ckey::PlatformAddress;
+use clap::ArgMatches;
+use cstate::StateProof;
+use ctypes::{BlockHash, BlockNumber, Header};
+use primitives::{H256, U256};
+use rustc_serialize::hex::FromHex;
+use serde::de::DeserializeOwned;
+use serde_json::{json, Value};
+use std::io::{Read, Write};
+use std::net::TcpStream;
+use std::thread;
+use std::time::Duration;
+
+/// The fields of the block returned by `chain_getBlockByNumber`, which make the header.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonHeader {
+    parent_hash: BlockHash,
+    timestamp: u64,
+    number: BlockNumber,
+    author: PlatformAddress,
+    extra_data: Vec<u8>,
+    transactions_root: H256,
+    state_root: H256,
+    score: U256,
+    seal: Vec<Vec<u8>>,
+    hash: BlockHash,
+}
+
+impl JsonHeader {
+    fn into_header(self) -> Result<Header, String> {
+        let mut header = Header::new();
+        header.set_parent_hash(self.parent_hash);
+        header.set_timestamp(self.timestamp);
+        header.set_number(self.number);
+        header.set_author(*self.author.try_address().map_err(|e| e.to_string())?);
+        header.set_extra_data(self.extra_data);
+        header.set_transactions_root(self.transactions_root);
+        header.set_state_root(self.state_root);
+        header.set_score(self.score);
+        header.set_seal(self.seal);
+        if header.hash() != self.hash {
+            return Err(format!("The hash of block #{} is {} but {} is given", self.number, header.hash(), self.hash))
+        }
+        Ok(header)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct JsonStateProof {
+    root: H256,
+    key: String,
+    value: Option<String>,
+    nodes: Vec<String>,
+}
+
+impl JsonStateProof {
+    fn into_proof(self) -> Result<StateProof, String> {
+        let from_hex = |hex: &str| hex.from_hex().map_err(|e| format!("Invalid proof: {}", e));
+        Ok(StateProof {
+            root: self.root,
+            key: from_hex(&self.key)?,
+            value: self.value.as_ref().map(|value| from_hex(value)).transpose()?,
+            nodes: self.nodes.iter().map(|node| from_hex(node)).collect::<Result<_, _>>()?,
+        })
+    }
+}
+
+/// A JSON-RPC client of a full node over HTTP.
+struct Rpc {
+    address: String,
+}
+
+impl Rpc {
+    fn call<T: DeserializeOwned>(&self, method: &str, params: Value) -> Result<T, String> {
+        let body = json!({"jsonrpc": "2.0", "method": method, "params": params, "id": 0}).to_string();
+        let mut stream =
+            TcpStream::connect(&self.address).map_err(|e| format!("Cannot connect to {}: {}", self.address, e))?;
+        write!(
+            stream,
+            "POST / HTTP/1.1\r\nHost: {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            self.address,
+            body.len(),
+            body
+        )
+        .map_err(|e| format!("Failed to send {}: {}", method, e))?;
+
+        let mut response = String::new();
+        stream.read_to_string(&mut response).map_err(|e| format!("Failed to receive {}: {}", method, e))?;
+        let body = response.splitn(2, "\r\n\r\n").nth(1).ok_or_else(|| format!("Invalid response of {}", method))?;
+        let mut response: Value =
+            serde_json::from_str(body).map_err(|e| format!("Invalid response of {}: {}", method, e))?;
+        if let Some(error) = response.get("error") {
+            return Err(format!("{} failed: {}", method, error))
+        }
+        serde_json::from_value(response["result"].take()).map_err(|e| format!("Invalid result of {}: {}", method, e))
+    }
+
+    fn header(&self, number: BlockNumber) -> Result<Option<Header>, String> {
+        let block: Option<JsonHeader> = self.call("chain_getBlockByNumber", json!([number]))?;
+        block.map(JsonHeader::into_header).transpose()
+    }
+
+    fn header_by_hash(&self, hash: BlockHash) -> Result<Header, String> {
+        let block: Option<JsonHeader> = self.call("chain_getBlockByHash", json!([hash]))?;
+        block.ok_or_else(|| format!("Block {} is not found", hash))?.into_header()
+    }
+
+    fn next_validators_proof(&self, number: BlockNumber) -> Result<StateProof, String> {
+        let proof: Option<JsonStateProof> =
+            self.call("chain_getActionDataProof", json!([*NEXT_VALIDATORS_KEY, number]))?;
+        proof.ok_or_else(|| format!("The state of block #{} is not found", number))?.into_proof()
+    }
+
+    /// The validators of the child of the given block.
+    fn next_validators(&self, header: &Header) -> Result<Validators, String> {
+        let proof = self.next_validators_proof(header.number())?;
+        Validators::from_proof(&proof, header.state_root())
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("The validators are not elected yet at #{}", header.number()))
+    }
+}
+
+pub fn run_light_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let checkpoint: BlockHash = {
+        let checkpoint = matches.value_of("checkpoint").expect("checkpoint arg is required");
+        checkpoint.parse::<H256>().map_err(|_| format!("Invalid checkpoint {}", checkpoint))?.into()
+    };
+    let rpc = Rpc {
+        address: matches.value_of("rpc").unwrap_or("127.0.0.1:8080").to_string(),
+    };
+
+    let checkpoint = rpc.header_by_hash(checkpoint)?;
+    if checkpoint.number() == 0 {
+        return Err("The genesis block cannot be the checkpoint".to_string())
+    }
+    let parent = rpc.header_by_hash(*checkpoint.parent_hash())?;
+    let term_seconds = {
+        let mut params: Value = rpc.call("chain_getCommonParams", json!([checkpoint.number()]))?;
+        let term_seconds: Option<U256> =
+            serde_json::from_value(params["termSeconds"].take()).map_err(|e| format!("Invalid termSeconds: {}", e))?;
+        term_seconds.map(|term_seconds| term_seconds.low_u64()).unwrap_or_default()
+    };
+    // The checkpoint is trusted, and so are the states of it and its parent.
+    let validators = rpc.next_validators(&parent)?;
+    let next_validators = rpc.next_validators(&checkpoint)?;
+    println!(
+        "Start from the checkpoint #{}({}) with {} validators",
+        checkpoint.number(),
+        checkpoint.hash(),
+        validators.len()
+    );
+    let mut client = LightClient::new(checkpoint, validators, next_validators, term_seconds);
+
+    loop {
+        let number = client.head().number() + 1;
+        let header = match rpc.header(number)? {
+            Some(header) => header,
+            None => {
+                thread::sleep(Duration::from_secs(1));
+                continue
+            }
+        };
+        // The validators can be removed in the middle of a term, so they are read at every block.
+        let proof = rpc.next_validators_proof(number)?;
+        match client.verify(header, Some(&proof)) {
+            Ok(()) => {}
+            // The head is not committed yet, so the header at its number is fetched again.
+            Err(LightClientError::NotChild(mismatch)) if client.head() != client.committed() => {
+                println!("#{} is not the child of the head: {}", number, mismatch.found);
+                client.rollback();
+                continue
+            }
+            Err(e) => return Err(format!("Block #{} is not valid: {}", number, e)),
+        }
+        let committed = client.committed();
+        println!("#{}({}) is committed", committed.number(), committed.hash());
+    }
+}
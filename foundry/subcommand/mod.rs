@@ -15,10 +15,20 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 mod account_command;
+mod blocks_command;
 mod convert_command;
+mod light_command;
+mod proposers_command;
+mod script_command;
+mod snapshot_command;
 
 use self::account_command::run_account_command;
+use self::blocks_command::{run_export_blocks_command, run_import_blocks_command};
 use self::convert_command::run_convert_command;
+use self::light_command::run_light_command;
+use self::proposers_command::run_proposers_command;
+use self::script_command::run_script_command;
+use self::snapshot_command::run_snapshot_command;
 use clap::ArgMatches;
 
 pub fn run_subcommand(matches: &ArgMatches<'_>) -> Result<(), String> {
@@ -26,6 +36,12 @@ pub fn run_subcommand(matches: &ArgMatches<'_>) -> Result<(), String> {
     match subcommand.name.as_str() {
         "account" => run_account_command(&subcommand.matches),
         "convert" => run_convert_command(&subcommand.matches),
+        "export-blocks" => run_export_blocks_command(matches, &subcommand.matches),
+        "import-blocks" => run_import_blocks_command(matches, &subcommand.matches),
+        "light" => run_light_command(&subcommand.matches),
+        "proposers" => run_proposers_command(&subcommand.matches),
+        "script" => run_script_command(&subcommand.matches),
+        "snapshot" => run_snapshot_command(matches, &subcommand.matches),
         "commit-hash" => {
             println!("{}", env!("VERGEN_SHA"));
             Ok(())
@@ -0,0 +1,65 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ccore::{simulate_proposers, ProposerSelection};
+use clap::ArgMatches;
+
+pub fn run_proposers_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let selection = match matches.value_of("selection").unwrap_or("weight") {
+        "weight" => ProposerSelection::Weight,
+        "weightedRoundRobin" => ProposerSelection::WeightedRoundRobin,
+        selection => return Err(format!("Unknown proposer selection {}", selection)),
+    };
+    let heights: u64 = match matches.value_of("heights") {
+        Some(heights) => heights.parse().map_err(|_| format!("Invalid number of heights {}", heights))?,
+        None => 10_000,
+    };
+    let term_length: Option<u64> = match matches.value_of("term-length") {
+        Some(term_length) => {
+            let term_length = term_length.parse().map_err(|_| format!("Invalid term length {}", term_length))?;
+            if term_length == 0 {
+                return Err("The term length must be positive".to_string())
+            }
+            Some(term_length)
+        }
+        None => None,
+    };
+    let delegations = matches
+        .values_of("DELEGATIONS")
+        .expect("DELEGATIONS arg is required")
+        .map(|delegation| delegation.parse().map_err(|_| format!("Invalid delegation {}", delegation)))
+        .collect::<Result<Vec<u64>, _>>()?;
+
+    let proposals = simulate_proposers(selection, &delegations, heights, term_length);
+    let total_delegation: u64 = delegations.iter().sum();
+    println!("validator\tdelegation\tproposals\tshare\texpected");
+    for (index, (delegation, proposals)) in delegations.iter().zip(proposals).enumerate() {
+        let expected = if total_delegation == 0 {
+            1.0 / delegations.len() as f64
+        } else {
+            *delegation as f64 / total_delegation as f64
+        };
+        println!(
+            "{}\t{}\t{}\t{:.2}%\t{:.2}%",
+            index,
+            delegation,
+            proposals,
+            proposals as f64 * 100.0 / heights as f64,
+            expected * 100.0
+        );
+    }
+    Ok(())
+}
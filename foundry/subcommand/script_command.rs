@@ -0,0 +1,179 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use clap::ArgMatches;
+use crpc::v1::UnsignedTransaction;
+use ctypes::transaction::{Action, ShardTransaction};
+use ctypes::{BlockNumber, Tracker};
+use cvm::{
+    assemble, decode_at_era, disassemble, execute_with_trace, lock_script_hash, ChainTimeInfo, Instruction,
+    RuntimeError, ScriptResult, VMConfig,
+};
+use rustc_serialize::hex::{FromHex, ToHex};
+use std::convert::TryInto;
+use std::fs;
+
+pub fn run_script_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let subcommand = matches.subcommand.as_ref().unwrap();
+    match subcommand.name.as_str() {
+        "assemble" => run_assemble_command(&subcommand.matches),
+        "disassemble" => run_disassemble_command(&subcommand.matches),
+        "run" => run_run_command(&subcommand.matches),
+        _ => Err("Invalid subcommand".to_string()),
+    }
+}
+
+fn run_assemble_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let path = matches.value_of("FILE").expect("FILE arg is required");
+    let script = assemble_file(path)?;
+    println!("script: 0x{}", script.to_hex());
+    println!("lock script hash: 0x{}", lock_script_hash(&script).to_hex());
+    Ok(())
+}
+
+fn run_disassemble_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let script = parse_hex(matches.value_of("SCRIPT").expect("SCRIPT arg is required"))?;
+    let era = parse_number(matches, "era")?.unwrap_or(0);
+    let source = disassemble(&script, era).map_err(|e| format!("Invalid script: {:?}", e))?;
+    println!("{}", source);
+    Ok(())
+}
+
+fn run_run_command(matches: &ArgMatches<'_>) -> Result<(), String> {
+    let path = matches.value_of("TRANSACTION").expect("TRANSACTION arg is required");
+    let json = fs::read(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    let tx: UnsignedTransaction =
+        serde_json::from_slice(&json).map_err(|e| format!("Invalid transaction {}: {}", path, e))?;
+    let action: Action = tx.action.try_into().map_err(|_| format!("Invalid action in {}", path))?;
+    let inputs = match &action {
+        Action::TransferAsset {
+            inputs,
+            ..
+        } => inputs.clone(),
+        _ => return Err("Only the TransferAsset transactions have scripts".to_string()),
+    };
+    let transaction = Option::<ShardTransaction>::from(action).expect("TransferAsset is a shard transaction");
+
+    let index = parse_number(matches, "index")?.unwrap_or(0) as usize;
+    let input = inputs.get(index).ok_or_else(|| format!("The transaction has {} inputs", inputs.len()))?;
+    let lock_script = match matches.value_of("lock-script") {
+        Some(path) => assemble_file(path)?,
+        None => input.lock_script.clone(),
+    };
+    let unlock_script = match matches.value_of("unlock-script") {
+        Some(path) => assemble_file(path)?,
+        None => input.unlock_script.clone(),
+    };
+    let params = match matches.values_of("param") {
+        Some(params) => params.map(parse_hex).collect::<Result<Vec<_>, _>>()?,
+        None => Vec::new(),
+    };
+
+    let era = parse_number(matches, "era")?.unwrap_or(0);
+    let lock = decode_script(&lock_script, era, "lock")?;
+    let unlock = decode_script(&unlock_script, era, "unlock")?;
+    let config = VMConfig::with_max_cost(parse_number(matches, "max-cost")?.unwrap_or(0));
+    let ages = Ages {
+        block_age: parse_number(matches, "block-age")?,
+        time_age: parse_number(matches, "time-age")?,
+    };
+    let block_number = parse_number(matches, "block-number")?.unwrap_or(0);
+    let timestamp = parse_number(matches, "timestamp")?.unwrap_or(0);
+
+    let (result, cost, trace) = execute_with_trace(
+        &unlock,
+        &params,
+        &lock,
+        &transaction,
+        config,
+        input,
+        matches.is_present("burn"),
+        &ages,
+        block_number,
+        timestamp,
+    );
+    // The node compares it with the lock script hash of the asset that is spent.
+    println!("lock script hash: 0x{}", lock_script_hash(&lock_script).to_hex());
+    println!("pc\tcost\tmemory\tinstruction\tstack");
+    for step in trace {
+        let stack: Vec<_> = step.stack.iter().map(|item| format!("0x{}", item.to_hex())).collect();
+        let skipped = if step.executed {
+            ""
+        } else {
+            " (skipped)"
+        };
+        println!(
+            "{}\t{}\t{}\t{}{}\t[{}]",
+            step.pc,
+            step.cost,
+            step.memory,
+            step.instruction,
+            skipped,
+            stack.join(", ")
+        );
+    }
+    let result = match result {
+        Ok(ScriptResult::Unlocked) => "unlocked".to_string(),
+        Ok(ScriptResult::Burnt) => "burnt".to_string(),
+        Ok(ScriptResult::Fail) => "failed".to_string(),
+        Err(RuntimeError::OutOfGas) => "out of gas".to_string(),
+        Err(err) => format!("failed: {:?}", err),
+    };
+    println!("result: {}", result);
+    println!("cost: {}", cost);
+    Ok(())
+}
+
+/// The ages of the input, which are read from the chain when the node runs the script.
+struct Ages {
+    block_age: Option<u64>,
+    time_age: Option<u64>,
+}
+
+impl ChainTimeInfo for Ages {
+    fn transaction_block_age(&self, _tracker: &Tracker, _parent_block_number: BlockNumber) -> Option<u64> {
+        self.block_age
+    }
+
+    fn transaction_time_age(&self, _tracker: &Tracker, _parent_timestamp: u64) -> Option<u64> {
+        self.time_age
+    }
+}
+
+fn assemble_file(path: &str) -> Result<Vec<u8>, String> {
+    let source = fs::read_to_string(path).map_err(|e| format!("Cannot read {}: {}", path, e))?;
+    assemble(&source).map_err(|e| format!("{}: {}", path, e))
+}
+
+fn decode_script(script: &[u8], era: u64, name: &str) -> Result<Vec<Instruction>, String> {
+    decode_at_era(script, era).map_err(|e| format!("Invalid {} script: {:?}", name, e))
+}
+
+fn parse_hex(value: &str) -> Result<Vec<u8>, String> {
+    let hex = if value.starts_with("0x") {
+        &value[2..]
+    } else {
+        value
+    };
+    hex.from_hex().map_err(|e| format!("Invalid hex {}: {}", value, e))
+}
+
+fn parse_number(matches: &ArgMatches<'_>, name: &str) -> Result<Option<u64>, String> {
+    match matches.value_of(name) {
+        Some(value) => value.parse().map(Some).map_err(|_| format!("Invalid {} {}", name, value)),
+        None => Ok(None),
+    }
+}
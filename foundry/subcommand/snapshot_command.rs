@@ -0,0 +1,68 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::blocks_command::open_client;
+use ccore::{BlockChainTrait, BlockId};
+use clap::ArgMatches;
+use csync::snapshot::verify_snapshot;
+use ctimer::TimerLoop;
+use ctypes::BlockHash;
+use primitives::H256;
+use std::path::Path;
+use std::str::FromStr;
+
+pub fn run_snapshot_command(global_matches: &ArgMatches<'_>, matches: &ArgMatches<'_>) -> Result<(), String> {
+    match matches.subcommand() {
+        ("verify", Some(matches)) => {
+            let dir = matches.value_of("DIR").expect("DIR arg is required and its index is 1");
+            let state_root = match matches.value_of("state-root") {
+                Some(state_root) => Some(state_root.parse().map_err(|_| "Invalid state-root")?),
+                None => None,
+            };
+            verify(global_matches, Path::new(dir), state_root)
+        }
+        _ => {
+            println!("{}", matches.usage());
+            Ok(())
+        }
+    }
+}
+
+fn verify(global_matches: &ArgMatches<'_>, dir: &Path, state_root: Option<H256>) -> Result<(), String> {
+    let state_root = match state_root {
+        Some(state_root) => state_root,
+        None => {
+            // The name of the snapshot directory is the block hash.
+            let block_hash = dir
+                .file_name()
+                .and_then(|name| name.to_str())
+                .and_then(|name| H256::from_str(name).ok())
+                .map(BlockHash::from)
+                .ok_or_else(|| format!("Cannot get the block hash from {}. Specify --state-root", dir.display()))?;
+            let timer_loop = TimerLoop::new(2);
+            let service = open_client(global_matches, &timer_loop)?;
+            let header = service
+                .client()
+                .block_header(&BlockId::Hash(block_hash))
+                .ok_or_else(|| format!("Block {} is not found. Specify --state-root", block_hash))?;
+            header.state_root()
+        }
+    };
+
+    let count = verify_snapshot(dir, state_root).map_err(|err| format!("Invalid snapshot: {}", err))?;
+    println!("{} chunks are verified against the state root {}", count, state_root);
+    Ok(())
+}
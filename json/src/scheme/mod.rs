@@ -43,4 +43,4 @@ pub use self::shard::Shard;
 pub use self::simple_poa::{SimplePoA, SimplePoAParams};
 pub use self::solo::{Solo, SoloParams};
 pub use self::state::{Accounts, Shards};
-pub use self::tendermint::{Tendermint, TendermintParams};
+pub use self::tendermint::{ProposerSelection, Tendermint, TendermintParams};
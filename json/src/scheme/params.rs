@@ -69,6 +69,25 @@ pub struct Params {
     /// A monotonically increasing number to denote the consensus version.
     /// It is increased when we fork.
     pub era: Option<Uint>,
+
+    /// The number of terms the revoked stakes stay slashable before they are released.
+    pub unbonding_period: Option<Uint>,
+
+    /// The fractions, in basis points, of the stakes slashed for a double vote and for a downtime.
+    pub double_vote_slash_rate: Option<Uint>,
+    pub downtime_slash_rate: Option<Uint>,
+
+    /// The number of the recent blocks in which the precommits of the validators are tracked,
+    /// and the share of them, in basis points, a validator can miss before it is jailed.
+    pub liveness_window: Option<Uint>,
+    pub liveness_threshold: Option<Uint>,
+
+    /// The deposit needed to submit a governance proposal and the number of terms the proposal is voted on.
+    pub proposal_deposit: Option<Uint>,
+    pub voting_period: Option<Uint>,
+
+    /// The cost a lock script can consume to unlock an input.
+    pub max_script_cost: Option<Uint>,
 }
 
 #[cfg(test)]
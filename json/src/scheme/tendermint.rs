@@ -46,6 +46,16 @@ pub struct TendermintParams {
     pub allowed_past_timegap: Option<Uint>,
     /// allowed future time gap in milliseconds.
     pub allowed_future_timegap: Option<Uint>,
+    /// How to choose the proposer of each view. The default is weight.
+    pub proposer_selection: Option<ProposerSelection>,
+}
+
+/// Proposer selection deserialization.
+#[derive(Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ProposerSelection {
+    Weight,
+    WeightedRoundRobin,
 }
 
 /// Tendermint engine deserialization.
@@ -61,7 +71,7 @@ mod tests {
     use ckey::Public;
     use serde_json;
 
-    use super::Tendermint;
+    use super::{ProposerSelection, Tendermint};
 
     #[test]
     fn tendermint_deserialization() {
@@ -74,5 +84,19 @@ mod tests {
         let deserialized: Tendermint = serde_json::from_str(s).unwrap();
         let vs = vec![Public::from_str("2a8a69439f2396c9a328289fdc3905d9736da9e14eb1a282cfd2c036cc21a17a5d05595160b7924e5ecf3f2628b440e601f3a531e92fa81571a70e6c695b2d08").unwrap()];
         assert_eq!(deserialized.params.validators, vs);
+        assert_eq!(deserialized.params.proposer_selection, None);
+    }
+
+    #[test]
+    fn proposer_selection_deserialization() {
+        let s = r#"{
+            "params": {
+                "validators": [],
+                "proposerSelection": "weightedRoundRobin"
+            }
+        }"#;
+
+        let deserialized: Tendermint = serde_json::from_str(s).unwrap();
+        assert_eq!(deserialized.params.proposer_selection, Some(ProposerSelection::WeightedRoundRobin));
     }
 }
@@ -48,6 +48,8 @@ pub type Result<T> = result::Result<T, Error>;
 
 pub trait Api {
     fn send(&self, node: &NodeId, message: Arc<Bytes>);
+    /// Disconnects and bans the node.
+    fn disconnect(&self, node: &NodeId);
 
     fn set_timer(&self, timer: TimerToken, d: Duration) -> Result<()>;
     fn set_timer_once(&self, timer: TimerToken, d: Duration) -> Result<()>;
@@ -19,13 +19,16 @@ extern crate codechain_crypto as ccrypto;
 extern crate codechain_json as cjson;
 extern crate codechain_key as ckey;
 extern crate codechain_keystore as ckeystore;
+#[macro_use]
 extern crate codechain_logger as clogger;
 extern crate codechain_network as cnetwork;
 extern crate codechain_state as cstate;
 extern crate codechain_sync as csync;
 extern crate codechain_types as ctypes;
+extern crate codechain_vm as cvm;
 #[macro_use]
 extern crate lazy_static;
+#[macro_use]
 extern crate log;
 #[macro_use]
 extern crate serde_derive;
@@ -44,10 +47,11 @@ pub use rustc_serialize::hex;
 pub use jsonrpc_core::{Compatibility, Error, MetaIoHandler, Middleware, Params, Value};
 
 pub use jsonrpc_http_server::Server as HttpServer;
-pub use rpc_server::start_http;
+pub use rpc_server::{start_http, start_metrics};
 
 pub use jsonrpc_ipc_server::Server as IpcServer;
 pub use rpc_server::start_ipc;
 
 pub use jsonrpc_ws_server::{Error as WsError, Server as WsServer};
 pub use rpc_server::start_ws;
+pub use v1::Metadata;
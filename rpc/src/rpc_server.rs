@@ -15,13 +15,20 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 // TODO: panic handler
+use crate::v1::Metadata;
 use jsonrpc_core;
-use jsonrpc_http_server::{self, Host, Server as HttpServer, ServerBuilder as HttpServerBuilder};
+use jsonrpc_http_server::hyper::header::HeaderValue;
+use jsonrpc_http_server::hyper::{self, Method, StatusCode};
+use jsonrpc_http_server::{
+    self, Host, RequestMiddlewareAction, Response, Server as HttpServer, ServerBuilder as HttpServerBuilder,
+};
 use jsonrpc_ipc_server::{Server as IpcServer, ServerBuilder as IpcServerBuilder};
-use jsonrpc_ws_server::{Error as WsError, Server as WsServer, ServerBuilder as WsServerBuilder};
+use jsonrpc_pubsub::Session;
+use jsonrpc_ws_server::{Error as WsError, RequestContext, Server as WsServer, ServerBuilder as WsServerBuilder};
 use std::default::Default;
 use std::io;
 use std::net::SocketAddr;
+use std::sync::Arc;
 
 /// Start http server asynchronously and returns result with `Server` handle on success or an error.
 pub fn start_http<M: jsonrpc_core::Metadata>(
@@ -49,6 +56,27 @@ where
         .start_http(addr)
 }
 
+/// Start http server which answers `GET /metrics` with the text returned by `collect`.
+///
+/// The text is expected to be in the Prometheus exposition format. The other requests are not served.
+pub fn start_metrics<F>(addr: &SocketAddr, collect: F) -> Result<HttpServer, io::Error>
+where
+    F: Fn() -> String + Send + Sync + 'static, {
+    HttpServerBuilder::new(jsonrpc_core::IoHandler::new())
+        .request_middleware(move |request: hyper::Request<hyper::Body>| -> RequestMiddlewareAction {
+            if request.method() != Method::GET || request.uri().path() != "/metrics" {
+                return Response::not_found().into()
+            }
+            Response {
+                code: StatusCode::OK,
+                content_type: HeaderValue::from_static("text/plain; version=0.0.4"),
+                content: collect(),
+            }
+            .into()
+        })
+        .start_http(addr)
+}
+
 /// Start ipc server asynchronously and returns result with `Server` handle on success or an error.
 pub fn start_ipc<M: jsonrpc_core::Metadata>(
     addr: &str,
@@ -60,13 +88,17 @@ where
 }
 
 /// Start WS server and return `Server` handle.
-pub fn start_ws<M: jsonrpc_core::Metadata>(
+///
+/// Each connection keeps its own session so that it can subscribe to events.
+pub fn start_ws(
     addr: &SocketAddr,
-    handler: jsonrpc_core::MetaIoHandler<M, impl jsonrpc_core::Middleware<M>>,
+    handler: jsonrpc_core::MetaIoHandler<Metadata, impl jsonrpc_core::Middleware<Metadata>>,
     max_connections: usize,
-) -> Result<WsServer, WsError>
-where
-    M: Default, {
-    // FIXME: Add Hosts, Origins and Session States
-    WsServerBuilder::new(handler).max_connections(max_connections).start(addr)
+) -> Result<WsServer, WsError> {
+    // FIXME: Add Hosts and Origins
+    WsServerBuilder::with_meta_extractor(handler, |context: &RequestContext| {
+        Metadata::new(Arc::new(Session::new(context.sender())))
+    })
+    .max_connections(max_connections)
+    .start(addr)
 }
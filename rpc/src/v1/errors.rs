@@ -67,6 +67,9 @@ mod codes {
     pub const ASSET_TRANSACTION_ONLY_IN_EXECUTE_TRANSACITON: i64 = -32047;
     pub const STATE_NOT_EXIST: i64 = -32048;
     pub const ACTION_DATA_HANDLER_NOT_FOUND: i64 = -32049;
+    pub const INVALID_SUBSCRIPTION: i64 = -32050;
+    pub const ADDRESS_INDEX_DISABLED: i64 = -32051;
+    pub const STATE_PRUNED: i64 = -32052;
     pub const UNKNOWN_ERROR: i64 = -32099;
 }
 
@@ -273,6 +276,30 @@ pub fn invalid_custom_action(err: String) -> Error {
     }
 }
 
+pub fn invalid_subscription() -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::INVALID_SUBSCRIPTION),
+        message: "Invalid subscription id".into(),
+        data: None,
+    }
+}
+
+pub fn address_index_disabled() -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::ADDRESS_INDEX_DISABLED),
+        message: "The address index is disabled. Restart the node with --address-index".into(),
+        data: None,
+    }
+}
+
+pub fn state_pruned() -> Error {
+    Error {
+        code: ErrorCode::ServerError(codes::STATE_PRUNED),
+        message: "The state of a given block number is pruned".into(),
+        data: None,
+    }
+}
+
 pub fn io(error: std::io::Error) -> Error {
     Error {
         code: ErrorCode::InternalError,
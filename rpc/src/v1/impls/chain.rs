@@ -16,9 +16,13 @@
 
 use super::super::errors;
 use super::super::traits::Chain;
-use super::super::types::{AssetScheme, Block, BlockNumberAndHash, OwnedAsset, Text, Transaction, UnsignedTransaction};
+use super::super::types::{
+    AssetProof, AssetScheme, Block, BlockNumberAndHash, OwnedAsset, Receipt, StateProof, Text, Transaction,
+    UnsignedTransaction, VMResult,
+};
 use ccore::{
-    AccountData, AssetClient, BlockId, EngineInfo, ExecuteClient, MiningBlockChainClient, Shard, TermInfo, TextClient,
+    AccountData, AssetClient, BlockId, EngineInfo, ExecuteClient, MiningBlockChainClient, Shard, StateInfo,
+    StateProofClient, TermInfo, TextClient,
 };
 use ccrypto::Blake;
 use cjson::scheme::Params;
@@ -32,6 +36,8 @@ use primitives::{Bytes as BytesArray, H160, H256};
 use std::convert::{TryFrom, TryInto};
 use std::sync::Arc;
 
+const MAX_TRANSACTIONS_BY_ADDRESS: u64 = 1000;
+
 pub struct ChainClient<C>
 where
     C: AssetClient + MiningBlockChainClient + Shard + ExecuteClient + EngineInfo, {
@@ -60,6 +66,8 @@ where
         + FindActionHandler
         + TextClient
         + TermInfo
+        + StateProofClient
+        + StateInfo
         + 'static,
 {
     fn get_transaction(&self, transaction_hash: TxHash) -> Result<Option<Transaction>> {
@@ -87,6 +95,21 @@ where
         Ok(self.client.transaction_by_tracker(&tracker).map(From::from))
     }
 
+    fn get_transactions_by_address(
+        &self,
+        address: PlatformAddress,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Transaction>> {
+        let address = address.try_address().map_err(errors::core)?;
+        let offset = offset.unwrap_or(0);
+        let limit = limit.unwrap_or(MAX_TRANSACTIONS_BY_ADDRESS).min(MAX_TRANSACTIONS_BY_ADDRESS);
+        match self.client.transactions_by_address(address, offset, limit) {
+            Some(transactions) => Ok(transactions.into_iter().map(From::from).collect()),
+            None => Err(errors::address_index_disabled()),
+        }
+    }
+
     fn get_asset_scheme_by_tracker(
         &self,
         tracker: Tracker,
@@ -109,7 +132,7 @@ where
         let parent_block_id = block_number.map(|n| (n - 1).into()).unwrap_or(BlockId::ParentOfLatest);
         if let Some(common_params) = self.client.common_params(parent_block_id) {
             let network_id = common_params.network_id();
-            let block_id = block_number.map(BlockId::from).unwrap_or(BlockId::Latest);
+            let block_id = state_block_id(&*self.client, block_number)?;
             Ok(self
                 .client
                 .get_asset_scheme(asset_type, shard_id, block_id)
@@ -124,7 +147,7 @@ where
         if block_number == Some(0) {
             return Ok(None)
         }
-        let block_id = block_number.map(BlockId::from).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self
             .client
             .get_text(transaction_hash, block_id)
@@ -139,11 +162,42 @@ where
         shard_id: ShardId,
         block_number: Option<u64>,
     ) -> Result<Option<OwnedAsset>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         let asset = self.client.get_asset(tracker, index, shard_id, block_id).map_err(errors::transaction_state)?;
         Ok(asset.map(From::from))
     }
 
+    fn get_account_proof(&self, address: PlatformAddress, block_number: Option<u64>) -> Result<Option<StateProof>> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        let address = address.try_address().map_err(errors::core)?;
+        let proof = self.client.account_proof(address, block_id).map_err(errors::transaction_state)?;
+        Ok(proof.map(From::from))
+    }
+
+    fn get_shard_proof(&self, shard_id: ShardId, block_number: Option<u64>) -> Result<Option<StateProof>> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        let proof = self.client.shard_proof(shard_id, block_id).map_err(errors::transaction_state)?;
+        Ok(proof.map(From::from))
+    }
+
+    fn get_action_data_proof(&self, key: H256, block_number: Option<u64>) -> Result<Option<StateProof>> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        let proof = self.client.action_data_proof(&key, block_id).map_err(errors::transaction_state)?;
+        Ok(proof.map(From::from))
+    }
+
+    fn get_asset_proof(
+        &self,
+        tracker: Tracker,
+        index: usize,
+        shard_id: ShardId,
+        block_number: Option<u64>,
+    ) -> Result<Option<AssetProof>> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        let proof = self.client.asset_proof(tracker, index, shard_id, block_id).map_err(errors::transaction_state)?;
+        Ok(proof.map(From::from))
+    }
+
     fn is_asset_spent(
         &self,
         tracker: Tracker,
@@ -151,30 +205,30 @@ where
         shard_id: ShardId,
         block_number: Option<u64>,
     ) -> Result<Option<bool>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         self.client.is_asset_spent(tracker, index, shard_id, block_id).map_err(errors::transaction_state)
     }
 
     fn get_seq(&self, address: PlatformAddress, block_number: Option<u64>) -> Result<Option<u64>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         let address = address.try_address().map_err(errors::core)?;
         Ok(self.client.seq(address, block_id))
     }
 
     fn get_balance(&self, aaddress: PlatformAddress, block_number: Option<u64>) -> Result<Option<Uint>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         let address = aaddress.try_address().map_err(errors::core)?;
         Ok(self.client.balance(address, block_id.into()).map(Into::into))
     }
 
     fn get_regular_key(&self, address: PlatformAddress, block_number: Option<u64>) -> Result<Option<Public>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         let address = address.try_address().map_err(errors::core)?;
         Ok(self.client.regular_key(address, block_id.into()))
     }
 
     fn get_regular_key_owner(&self, public: Public, block_number: Option<u64>) -> Result<Option<PlatformAddress>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.regular_key_owner(&public_to_address(&public), block_id.into()).and_then(|address| {
             let network_id = self.client.network_id();
             Some(PlatformAddress::new_v1(network_id, address))
@@ -186,22 +240,22 @@ where
     }
 
     fn get_number_of_shards(&self, block_number: Option<u64>) -> Result<Option<ShardId>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.number_of_shards(block_id.into()))
     }
 
     fn get_shard_id_by_hash(&self, create_shard_tx_hash: TxHash, block_number: Option<u64>) -> Result<Option<ShardId>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.shard_id_by_hash(&create_shard_tx_hash, block_id.into()))
     }
 
     fn get_shard_root(&self, shard_id: ShardId, block_number: Option<u64>) -> Result<Option<H256>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.shard_root(shard_id, block_id.into()))
     }
 
     fn get_shard_owners(&self, shard_id: ShardId, block_number: Option<u64>) -> Result<Option<Vec<PlatformAddress>>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.shard_owners(shard_id, block_id.into()).map(|owners| {
             let network_id = self.client.network_id();
             owners.into_iter().map(|owner| PlatformAddress::new_v1(network_id, owner)).collect()
@@ -209,7 +263,7 @@ where
     }
 
     fn get_shard_users(&self, shard_id: ShardId, block_number: Option<u64>) -> Result<Option<Vec<PlatformAddress>>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.shard_users(shard_id, block_id.into()).map(|users| {
             let network_id = self.client.network_id();
             users.into_iter().map(|user| PlatformAddress::new_v1(network_id, user)).collect()
@@ -245,6 +299,32 @@ where
         }))
     }
 
+    fn get_transaction_receipt(&self, transaction_hash: TxHash) -> Result<Option<Receipt>> {
+        let receipt = match self.client.transaction_receipt(&transaction_hash) {
+            Some(receipt) => Receipt::from(receipt),
+            None => return Ok(None),
+        };
+        Ok(Some(match self.client.transaction(&transaction_hash.into()) {
+            Some(tx) => receipt.in_block(tx.block_number, tx.block_hash, tx.transaction_index),
+            None => receipt,
+        }))
+    }
+
+    fn get_block_receipts(&self, block_hash: BlockHash) -> Result<Option<Vec<Receipt>>> {
+        let id = BlockId::Hash(block_hash);
+        let block_number = match self.client.block_number(&id) {
+            Some(block_number) => block_number,
+            None => return Ok(None),
+        };
+        Ok(self.client.block_receipts(&id).map(|receipts| {
+            receipts
+                .into_iter()
+                .enumerate()
+                .map(|(index, receipt)| Receipt::from(receipt).in_block(block_number, block_hash, index))
+                .collect()
+        }))
+    }
+
     fn get_block_transaction_count_by_hash(&self, block_hash: BlockHash) -> Result<Option<usize>> {
         Ok(self.client.block(&BlockId::Hash(block_hash)).map(|block| block.transactions_count()))
     }
@@ -288,12 +368,12 @@ where
     }
 
     fn get_common_params(&self, block_number: Option<u64>) -> Result<Option<Params>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.common_params(block_id).map(Params::from))
     }
 
     fn get_term_metadata(&self, block_number: Option<u64>) -> Result<Option<(u64, u64)>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         let last_term_finished_block_num = self.client.last_term_finished_block_num(block_id);
         let current_term_id = self.client.current_term_id(block_id);
         match (last_term_finished_block_num, current_term_id) {
@@ -306,7 +386,7 @@ where
     }
 
     fn get_metadata_seq(&self, block_number: Option<u64>) -> Result<Option<u64>> {
-        let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        let block_id = state_block_id(&*self.client, block_number)?;
         Ok(self.client.metadata_seq(block_id))
     }
 
@@ -333,7 +413,8 @@ where
         tx: UnsignedTransaction,
         params: Vec<Vec<BytesArray>>,
         indices: Vec<usize>,
-    ) -> Result<Vec<String>> {
+        trace: Option<bool>,
+    ) -> Result<Vec<VMResult>> {
         let action = tx.action.try_into().map_err(errors::conversion)?;
         if let Action::TransferAsset {
             inputs,
@@ -341,9 +422,22 @@ where
         } = &action
         {
             let transaction = Option::<ShardTransactionType>::from(action.clone()).unwrap();
-            Ok(self.client.execute_vm(&transaction, inputs, &params, &indices).map_err(errors::core)?)
+            let results = self
+                .client
+                .execute_vm(&transaction, inputs, &params, &indices, trace.unwrap_or(false))
+                .map_err(errors::core)?;
+            Ok(results.into_iter().map(VMResult::from).collect())
         } else {
             Err(errors::transfer_only())
         }
     }
 }
+
+/// Returns the id of the block whose state is read, or an error if the state is pruned.
+fn state_block_id<C: StateInfo>(client: &C, block_number: Option<u64>) -> Result<BlockId> {
+    let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+    if client.is_state_pruned(block_id) {
+        return Err(errors::state_pruned())
+    }
+    Ok(block_id)
+}
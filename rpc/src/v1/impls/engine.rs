@@ -81,6 +81,9 @@ where
             ))
         })?;
         let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+        if self.client.is_state_pruned(block_id) {
+            return Err(errors::state_pruned())
+        }
         let state = self.client.state_at(block_id).ok_or_else(errors::state_not_exist)?;
 
         match handler.query(&key_fragment, &state) {
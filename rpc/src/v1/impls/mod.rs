@@ -20,7 +20,9 @@ mod devel;
 mod engine;
 mod mempool;
 mod net;
+mod pubsub;
 mod snapshot;
+mod stake;
 
 pub use self::account::AccountClient;
 pub use self::chain::ChainClient;
@@ -28,4 +30,6 @@ pub use self::devel::DevelClient;
 pub use self::engine::EngineClient;
 pub use self::mempool::MempoolClient;
 pub use self::net::NetClient;
+pub use self::pubsub::PubSubClient;
 pub use self::snapshot::SnapshotClient;
+pub use self::stake::StakeClient;
@@ -0,0 +1,379 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::super::errors;
+use super::super::traits::PubSub;
+use super::super::types::{BlockNumberAndHash, NewHeads};
+use super::super::Metadata;
+use ccore::{BlockChainClient, BlockId, ChainNotify, EngineInfo};
+use ctypes::{BlockHash, BlockNumber, TxHash};
+use jsonrpc_core::futures::Future;
+use jsonrpc_core::Result;
+use jsonrpc_pubsub::typed::{Sink, Subscriber};
+use jsonrpc_pubsub::{PubSubMetadata, Session, SubscriptionId};
+use parking_lot::{Mutex, RwLock};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Sender};
+use std::sync::{Arc, Weak};
+use std::thread;
+
+pub struct PubSubClient<C> {
+    handler: Arc<PubSubHandler<C>>,
+}
+
+impl<C> PubSubClient<C>
+where
+    C: BlockChainClient + EngineInfo + 'static,
+{
+    pub fn new(client: Arc<C>) -> Self {
+        let subscribers = Arc::new(Subscribers::default());
+        let (sender, receiver) = channel();
+        {
+            let subscribers = Arc::clone(&subscribers);
+            thread::Builder::new()
+                .name("rpc-pubsub".to_string())
+                .spawn(move || {
+                    while let Ok(notification) = receiver.recv() {
+                        subscribers.notify(notification);
+                    }
+                })
+                .expect("Failed to create a pubsub notifier thread");
+        }
+        let last_finalized = Mutex::new(finalized_block_number(&*client));
+        PubSubClient {
+            handler: Arc::new(PubSubHandler {
+                client,
+                subscribers,
+                sender: Mutex::new(sender),
+                last_finalized,
+            }),
+        }
+    }
+
+    /// The handle to be registered to the client with `add_notify`.
+    pub fn chain_notify(&self) -> Weak<dyn ChainNotify> {
+        Arc::downgrade(&self.handler) as Weak<dyn ChainNotify>
+    }
+}
+
+impl<C> Clone for PubSubClient<C> {
+    fn clone(&self) -> Self {
+        PubSubClient {
+            handler: Arc::clone(&self.handler),
+        }
+    }
+}
+
+impl<C> PubSub for PubSubClient<C>
+where
+    C: BlockChainClient + EngineInfo + 'static,
+{
+    type Metadata = Metadata;
+
+    fn subscribe_new_heads(&self, meta: Self::Metadata, subscriber: Subscriber<NewHeads>) {
+        subscribe(&self.handler.subscribers, |subscribers| &subscribers.new_heads, &meta, subscriber);
+    }
+
+    fn unsubscribe_new_heads(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        unsubscribe(&self.handler.subscribers.new_heads, meta.as_ref(), &id)
+    }
+
+    fn subscribe_finalized(&self, meta: Self::Metadata, subscriber: Subscriber<BlockNumberAndHash>) {
+        subscribe(&self.handler.subscribers, |subscribers| &subscribers.finalized, &meta, subscriber);
+    }
+
+    fn unsubscribe_finalized(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        unsubscribe(&self.handler.subscribers.finalized, meta.as_ref(), &id)
+    }
+
+    fn subscribe_pending_transactions(&self, meta: Self::Metadata, subscriber: Subscriber<Vec<TxHash>>) {
+        subscribe(&self.handler.subscribers, |subscribers| &subscribers.pending_transactions, &meta, subscriber);
+    }
+
+    fn unsubscribe_pending_transactions(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool> {
+        unsubscribe(&self.handler.subscribers.pending_transactions, meta.as_ref(), &id)
+    }
+}
+
+/// Translates the chain events into the notifications of the subscriptions.
+struct PubSubHandler<C> {
+    client: Arc<C>,
+    subscribers: Arc<Subscribers>,
+    /// The notifications are sent by a dedicated thread not to block the importer.
+    sender: Mutex<Sender<Notification>>,
+    last_finalized: Mutex<BlockNumber>,
+}
+
+impl<C> PubSubHandler<C>
+where
+    C: BlockChainClient + EngineInfo,
+{
+    fn send(&self, notification: Notification) {
+        // The importer must not be stopped by the notifier, so the notification is dropped on failure.
+        if let Err(err) = self.sender.lock().send(notification) {
+            cwarn!(RPC, "Failed to send a pubsub notification: {}", err);
+        }
+    }
+
+    fn canonical_block(&self, number: BlockNumber) -> Option<BlockNumberAndHash> {
+        self.client.block_hash(&BlockId::Number(number)).map(|hash| BlockNumberAndHash {
+            number,
+            hash,
+        })
+    }
+}
+
+impl<C> ChainNotify for PubSubHandler<C>
+where
+    C: BlockChainClient + EngineInfo,
+{
+    fn new_blocks(
+        &self,
+        _imported: Vec<BlockHash>,
+        _invalid: Vec<BlockHash>,
+        mut enacted: Vec<BlockHash>,
+        retracted: Vec<BlockHash>,
+        _sealed: Vec<BlockHash>,
+    ) {
+        if enacted.is_empty() && retracted.is_empty() {
+            return
+        }
+
+        if !self.subscribers.new_heads.read().is_empty() {
+            let best_header = self.client.best_block_header();
+            // The enacted blocks are collected from a hash map.
+            enacted.sort_by_key(|hash| self.client.block_number(&BlockId::Hash(*hash)));
+            self.send(Notification::NewHeads(NewHeads {
+                best: BlockNumberAndHash {
+                    number: best_header.number(),
+                    hash: best_header.hash(),
+                },
+                enacted,
+                retracted,
+            }));
+        }
+
+        let finalized = finalized_block_number(&*self.client);
+        let mut last_finalized = self.last_finalized.lock();
+        if finalized <= *last_finalized {
+            return
+        }
+        if !self.subscribers.finalized.read().is_empty() {
+            for number in (*last_finalized + 1)..=finalized {
+                if let Some(block) = self.canonical_block(number) {
+                    self.send(Notification::Finalized(block));
+                }
+            }
+        }
+        *last_finalized = finalized;
+    }
+
+    fn new_pending_transactions(&self, hashes: Vec<TxHash>) {
+        if hashes.is_empty() || self.subscribers.pending_transactions.read().is_empty() {
+            return
+        }
+        self.send(Notification::PendingTransactions(hashes));
+    }
+}
+
+/// A block is considered to be final when it gets the recommended number of confirmations.
+fn finalized_block_number<C: BlockChainClient + EngineInfo>(client: &C) -> BlockNumber {
+    let best_block_number = client.best_block_header().number();
+    let confirmation = u64::from(client.recommended_confirmation().max(1));
+    (best_block_number + 1).saturating_sub(confirmation)
+}
+
+enum Notification {
+    NewHeads(NewHeads),
+    Finalized(BlockNumberAndHash),
+    PendingTransactions(Vec<TxHash>),
+}
+
+/// Identifies the connection that owns a subscription.
+type SessionId = usize;
+
+/// The sinks with the sessions that made the subscriptions.
+type Sinks<T> = RwLock<HashMap<SubscriptionId, (SessionId, Sink<T>)>>;
+
+#[derive(Default)]
+struct Subscribers {
+    new_heads: Sinks<NewHeads>,
+    finalized: Sinks<BlockNumberAndHash>,
+    pending_transactions: Sinks<Vec<TxHash>>,
+}
+
+impl Subscribers {
+    fn notify(&self, notification: Notification) {
+        match notification {
+            Notification::NewHeads(value) => broadcast(&self.new_heads, value),
+            Notification::Finalized(value) => broadcast(&self.finalized, value),
+            Notification::PendingTransactions(value) => broadcast(&self.pending_transactions, value),
+        }
+    }
+
+    fn remove_session(&self, session_id: SessionId) {
+        remove_session(&self.new_heads, session_id);
+        remove_session(&self.finalized, session_id);
+        remove_session(&self.pending_transactions, session_id);
+    }
+}
+
+fn session_id(session: &Session) -> SessionId {
+    session as *const Session as SessionId
+}
+
+fn subscribe<T, F>(subscribers: &Arc<Subscribers>, sinks: F, meta: &Metadata, subscriber: Subscriber<T>)
+where
+    F: Fn(&Subscribers) -> &Sinks<T>, {
+    // The subscriptions without a session are rejected before reaching here.
+    let session = match meta.session() {
+        Some(session) => session,
+        None => return,
+    };
+    let session_id = session_id(&session);
+    let id = SubscriptionId::String(format!("0x{:016x}", rand::random::<u64>()));
+    // assign_id fails only when the subscriber is already disconnected.
+    if let Ok(sink) = subscriber.assign_id(id.clone()) {
+        sinks(subscribers).write().insert(id, (session_id, sink));
+        // The address of a closed session can be reused by a new one.
+        let subscribers = Arc::downgrade(subscribers);
+        session.on_drop(move || {
+            if let Some(subscribers) = subscribers.upgrade() {
+                subscribers.remove_session(session_id);
+            }
+        });
+    }
+}
+
+/// Only the session that made the subscription can cancel it.
+fn unsubscribe<T>(sinks: &Sinks<T>, meta: Option<&Metadata>, id: &SubscriptionId) -> Result<bool> {
+    let session_id = meta.and_then(PubSubMetadata::session).map(|session| session_id(&session));
+    let mut sinks = sinks.write();
+    match sinks.get(id) {
+        Some((owner, _)) if Some(*owner) == session_id => {
+            sinks.remove(id);
+            Ok(true)
+        }
+        _ => Err(errors::invalid_subscription()),
+    }
+}
+
+fn remove_session<T>(sinks: &Sinks<T>, session_id: SessionId) {
+    sinks.write().retain(|_, (owner, _)| *owner != session_id);
+}
+
+fn broadcast<T: Serialize + Clone>(sinks: &Sinks<T>, value: T) {
+    let disconnected: Vec<_> = sinks
+        .read()
+        .iter()
+        .filter_map(|(id, (_, sink))| match sink.notify(Ok(value.clone())).wait() {
+            Ok(_) => None,
+            Err(_) => Some(id.clone()),
+        })
+        .collect();
+    if !disconnected.is_empty() {
+        let mut sinks = sinks.write();
+        for id in disconnected {
+            sinks.remove(&id);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsonrpc_core::futures::sync::mpsc;
+    use jsonrpc_core::futures::Stream;
+
+    fn metadata() -> Metadata {
+        let (sender, _receiver) = mpsc::channel(8);
+        Metadata::new(Arc::new(Session::new(sender)))
+    }
+
+    fn subscribe_new_heads(subscribers: &Arc<Subscribers>, meta: &Metadata) -> SubscriptionId {
+        let (subscriber, id, _notifications) = Subscriber::new_test("chain_newHeads");
+        subscribe(subscribers, |subscribers| &subscribers.new_heads, meta, subscriber);
+        id.wait().unwrap().unwrap()
+    }
+
+    fn new_heads() -> NewHeads {
+        NewHeads {
+            best: BlockNumberAndHash {
+                number: 1,
+                hash: Default::default(),
+            },
+            enacted: vec![Default::default()],
+            retracted: vec![],
+        }
+    }
+
+    #[test]
+    fn only_the_owner_can_unsubscribe() {
+        let subscribers = Arc::new(Subscribers::default());
+        let owner = metadata();
+        let other = metadata();
+        let id = subscribe_new_heads(&subscribers, &owner);
+
+        assert!(unsubscribe(&subscribers.new_heads, Some(&other), &id).is_err());
+        assert!(unsubscribe(&subscribers.new_heads, None, &id).is_err());
+        assert_eq!(Ok(true), unsubscribe(&subscribers.new_heads, Some(&owner), &id));
+        assert!(unsubscribe(&subscribers.new_heads, Some(&owner), &id).is_err());
+    }
+
+    #[test]
+    fn subscriptions_are_removed_with_the_session() {
+        let subscribers = Arc::new(Subscribers::default());
+        let owner = metadata();
+        let other = metadata();
+        subscribe_new_heads(&subscribers, &owner);
+        let id = subscribe_new_heads(&subscribers, &other);
+        assert_eq!(2, subscribers.new_heads.read().len());
+
+        drop(owner);
+        assert_eq!(1, subscribers.new_heads.read().len());
+        assert!(subscribers.new_heads.read().contains_key(&id));
+    }
+
+    #[test]
+    fn subscribers_are_notified() {
+        let subscribers = Arc::new(Subscribers::default());
+        let meta = metadata();
+        let (subscriber, id, notifications) = Subscriber::new_test("chain_newHeads");
+        subscribe(&subscribers, |subscribers| &subscribers.new_heads, &meta, subscriber);
+        let id = id.wait().unwrap().unwrap();
+
+        subscribers.notify(Notification::NewHeads(new_heads()));
+        let notification = notifications.wait().next().unwrap().unwrap();
+        let notification: serde_json::Value = serde_json::from_str(&notification).unwrap();
+        assert_eq!("chain_newHeads", notification["method"]);
+        assert_eq!(serde_json::to_value(&id).unwrap(), notification["params"]["subscription"]);
+        assert_eq!(1, notification["params"]["result"]["best"]["number"]);
+    }
+
+    #[test]
+    fn disconnected_subscribers_are_removed() {
+        let subscribers = Arc::new(Subscribers::default());
+        let meta = metadata();
+        let (subscriber, id, notifications) = Subscriber::new_test("chain_newHeads");
+        subscribe(&subscribers, |subscribers| &subscribers.new_heads, &meta, subscriber);
+        id.wait().unwrap().unwrap();
+        drop(notifications);
+
+        subscribers.notify(Notification::NewHeads(new_heads()));
+        assert!(subscribers.new_heads.read().is_empty());
+    }
+}
@@ -16,13 +16,11 @@
 
 use super::super::errors;
 use super::super::traits::Snapshot;
-use super::super::types::BlockNumberAndHash;
-use ccore::{BlockChainClient, BlockId};
+use super::super::types::{BlockNumberAndHash, SnapshotInfo};
+use ccore::BlockChainClient;
+use csync::snapshot::{delete_snapshot, snapshot_info, snapshot_list};
 use ctypes::BlockHash;
 use jsonrpc_core::Result;
-use primitives::H256;
-use std::fs;
-use std::str::FromStr;
 use std::sync::Arc;
 
 pub struct SnapshotClient<C>
@@ -50,36 +48,33 @@ where
 {
     fn get_snapshot_list(&self) -> Result<Vec<BlockNumberAndHash>> {
         if let Some(snapshot_path) = &self.snapshot_path {
-            let mut result = Vec::new();
-            for entry in fs::read_dir(snapshot_path).map_err(errors::io)? {
-                let entry = entry.map_err(errors::io)?;
+            let snapshots = snapshot_list(&*self.client, snapshot_path).map_err(errors::io)?;
+            Ok(snapshots
+                .into_iter()
+                .map(|(number, hash)| BlockNumberAndHash {
+                    number,
+                    hash,
+                })
+                .collect())
+        } else {
+            Ok(Vec::new())
+        }
+    }
 
-                // Check if the entry is a directory
-                let file_type = entry.file_type().map_err(errors::io)?;
-                if !file_type.is_dir() {
-                    continue
-                }
+    fn get_snapshot_info(&self, block_hash: BlockHash) -> Result<Option<SnapshotInfo>> {
+        if let Some(snapshot_path) = &self.snapshot_path {
+            let info = snapshot_info(&*self.client, snapshot_path, &block_hash).map_err(errors::io)?;
+            Ok(info.map(From::from))
+        } else {
+            Ok(None)
+        }
+    }
 
-                let path = entry.path();
-                let name = match path.file_name().expect("Directories always have file name").to_str() {
-                    Some(n) => n,
-                    None => continue,
-                };
-                let hash = match H256::from_str(name) {
-                    Ok(h) => BlockHash::from(h),
-                    Err(_) => continue,
-                };
-                if let Some(number) = self.client.block_number(&BlockId::Hash(hash)) {
-                    result.push(BlockNumberAndHash {
-                        number,
-                        hash,
-                    });
-                }
-            }
-            result.sort_unstable_by(|a, b| b.number.cmp(&a.number));
-            Ok(result)
+    fn delete_snapshot(&self, block_hash: BlockHash) -> Result<bool> {
+        if let Some(snapshot_path) = &self.snapshot_path {
+            delete_snapshot(snapshot_path, &block_hash).map_err(errors::io)
         } else {
-            Ok(Vec::new())
+            Ok(false)
         }
     }
 }
@@ -0,0 +1,165 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::super::errors;
+use super::super::traits::Stake;
+use super::super::types::{
+    Candidate, Delegation, PendingRewards, Prisoner, Proposal, Reward, Slash, Unbonding, Validator,
+};
+use ccore::stake::{
+    Banned, Candidates, Commissions, CurrentValidators, Delegation as DelegationType, IntermediateRewards, Jail,
+    Proposals, Rewards, Slashes, Unbondings,
+};
+use ccore::{BlockId, EngineInfo, StateInfo, TermInfo};
+use ckey::{public_to_address, PlatformAddress};
+use cstate::TopLevelState;
+use jsonrpc_core::Result;
+use std::sync::Arc;
+
+pub struct StakeClient<C>
+where
+    C: StateInfo + EngineInfo + TermInfo, {
+    client: Arc<C>,
+}
+
+impl<C> StakeClient<C>
+where
+    C: StateInfo + EngineInfo + TermInfo,
+{
+    pub fn new(client: Arc<C>) -> Self {
+        StakeClient {
+            client,
+        }
+    }
+
+    fn state_at(&self, block_number: Option<u64>) -> Result<TopLevelState> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        self.client.state_at(block_id).ok_or_else(errors::state_not_exist)
+    }
+}
+
+impl<C> Stake for StakeClient<C>
+where
+    C: StateInfo + EngineInfo + TermInfo + 'static,
+{
+    fn get_current_validators(&self, block_number: Option<u64>) -> Result<Vec<Validator>> {
+        let state = self.state_at(block_number)?;
+        let validators = CurrentValidators::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(validators.iter().map(|validator| Validator::from_core(validator, network_id)).collect())
+    }
+
+    fn get_candidates(&self, block_number: Option<u64>) -> Result<Vec<Candidate>> {
+        let state = self.state_at(block_number)?;
+        let candidates = Candidates::load_from_state(&state).map_err(errors::transaction_state)?;
+        let commissions = Commissions::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(Vec::from(candidates)
+            .into_iter()
+            .map(|candidate| {
+                let commission_rate = commissions.rate_of(&public_to_address(&candidate.pubkey));
+                Candidate::from_core(candidate, commission_rate, network_id)
+            })
+            .collect())
+    }
+
+    fn get_delegations(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Delegation>> {
+        let delegator = delegator.try_address().map_err(errors::core)?;
+        let state = self.state_at(block_number)?;
+        let delegation = DelegationType::load_from_state(&state, delegator).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(delegation
+            .iter()
+            .map(|(delegatee, quantity)| Delegation {
+                delegatee: PlatformAddress::new_v1(network_id, *delegatee),
+                quantity: *quantity,
+            })
+            .collect())
+    }
+
+    fn get_jailed(&self, block_number: Option<u64>) -> Result<Vec<Prisoner>> {
+        let state = self.state_at(block_number)?;
+        let jail = Jail::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(jail.prisoners().map(|prisoner| Prisoner::from_core(prisoner, network_id)).collect())
+    }
+
+    fn get_banned(&self, block_number: Option<u64>) -> Result<Vec<PlatformAddress>> {
+        let state = self.state_at(block_number)?;
+        let banned = Banned::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(banned.iter().map(|address| PlatformAddress::new_v1(network_id, *address)).collect())
+    }
+
+    fn get_pending_rewards(&self, block_number: Option<u64>) -> Result<PendingRewards> {
+        let state = self.state_at(block_number)?;
+        let rewards = IntermediateRewards::load_from_state(&state).map_err(errors::transaction_state)?;
+        Ok(PendingRewards::from_core(rewards.current(), rewards.calculated(), self.client.network_id()))
+    }
+
+    fn get_claimable_rewards(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Reward>> {
+        let delegator = delegator.try_address().map_err(errors::core)?;
+        let state = self.state_at(block_number)?;
+        let rewards = Rewards::load_from_state(&state, delegator).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(rewards
+            .iter()
+            .map(|(delegatee, quantity)| Reward {
+                address: PlatformAddress::new_v1(network_id, *delegatee),
+                quantity: *quantity,
+            })
+            .collect())
+    }
+
+    fn get_unbondings(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Unbonding>> {
+        let delegator = delegator.try_address().map_err(errors::core)?;
+        let state = self.state_at(block_number)?;
+        let unbondings = Unbondings::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(unbondings
+            .iter()
+            .filter(|unbonding| unbonding.delegator == *delegator)
+            .map(|unbonding| Unbonding::from_core(unbonding, network_id))
+            .collect())
+    }
+
+    fn get_slashes(&self, block_number: Option<u64>) -> Result<Vec<Slash>> {
+        let state = self.state_at(block_number)?;
+        let slashes = Slashes::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(slashes.iter().map(|slash| Slash::from_core(slash, network_id)).collect())
+    }
+
+    fn get_proposals(&self, block_number: Option<u64>) -> Result<Vec<Proposal>> {
+        let state = self.state_at(block_number)?;
+        let proposals = Proposals::load_from_state(&state).map_err(errors::transaction_state)?;
+        let network_id = self.client.network_id();
+        Ok(proposals.iter().map(|proposal| Proposal::from_core(proposal, network_id)).collect())
+    }
+
+    fn get_term_id(&self, block_number: Option<u64>) -> Result<Option<u64>> {
+        let block_id = state_block_id(&*self.client, block_number)?;
+        Ok(self.client.current_term_id(block_id))
+    }
+}
+
+fn state_block_id<C: StateInfo>(client: &C, block_number: Option<u64>) -> Result<BlockId> {
+    let block_id = block_number.map(BlockId::Number).unwrap_or(BlockId::Latest);
+    if client.is_state_pruned(block_id) {
+        return Err(errors::state_pruned())
+    }
+    Ok(block_id)
+}
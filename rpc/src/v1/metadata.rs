@@ -0,0 +1,42 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use jsonrpc_pubsub::{PubSubMetadata, Session};
+use std::sync::Arc;
+
+/// RPC request metadata.
+///
+/// Only the connections that keep a session, such as WebSockets, can subscribe to events.
+#[derive(Clone, Default)]
+pub struct Metadata {
+    session: Option<Arc<Session>>,
+}
+
+impl Metadata {
+    pub fn new(session: Arc<Session>) -> Self {
+        Metadata {
+            session: Some(session),
+        }
+    }
+}
+
+impl jsonrpc_core::Metadata for Metadata {}
+
+impl PubSubMetadata for Metadata {
+    fn session(&self) -> Option<Arc<Session>> {
+        self.session.clone()
+    }
+}
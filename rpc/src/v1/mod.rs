@@ -16,8 +16,11 @@
 
 mod errors;
 mod impls;
+mod metadata;
 mod traits;
 mod types;
 
 pub use self::impls::*;
+pub use self::metadata::Metadata;
 pub use self::traits::*;
+pub use self::types::UnsignedTransaction;
@@ -14,7 +14,10 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::super::types::{AssetScheme, Block, BlockNumberAndHash, OwnedAsset, Text, Transaction, UnsignedTransaction};
+use super::super::types::{
+    AssetProof, AssetScheme, Block, BlockNumberAndHash, OwnedAsset, Receipt, StateProof, Text, Transaction,
+    UnsignedTransaction, VMResult,
+};
 use cjson::scheme::Params;
 use cjson::uint::Uint;
 use ckey::{NetworkId, PlatformAddress, Public};
@@ -43,6 +46,15 @@ pub trait Chain {
     #[rpc(name = "chain_getTransactionByTracker")]
     fn get_transaction_by_tracker(&self, tracker: Tracker) -> Result<Option<Transaction>>;
 
+    /// Gets the canonical transactions related to given address in the order they are included.
+    #[rpc(name = "chain_getTransactionsByAddress")]
+    fn get_transactions_by_address(
+        &self,
+        address: PlatformAddress,
+        offset: Option<u64>,
+        limit: Option<u64>,
+    ) -> Result<Vec<Transaction>>;
+
     /// Gets asset scheme with given transaction tracker.
     #[rpc(name = "chain_getAssetSchemeByTracker")]
     fn get_asset_scheme_by_tracker(
@@ -75,6 +87,28 @@ pub trait Chain {
         block_number: Option<u64>,
     ) -> Result<Option<OwnedAsset>>;
 
+    /// Gets the Merkle proof of the account with the given address.
+    #[rpc(name = "chain_getAccountProof")]
+    fn get_account_proof(&self, address: PlatformAddress, block_number: Option<u64>) -> Result<Option<StateProof>>;
+
+    /// Gets the Merkle proof of the shard with the given shard id.
+    #[rpc(name = "chain_getShardProof")]
+    fn get_shard_proof(&self, shard_id: ShardId, block_number: Option<u64>) -> Result<Option<StateProof>>;
+
+    /// Gets the Merkle proof of the action data with the given key.
+    #[rpc(name = "chain_getActionDataProof")]
+    fn get_action_data_proof(&self, key: H256, block_number: Option<u64>) -> Result<Option<StateProof>>;
+
+    /// Gets the Merkle proofs of the shard and the asset in it.
+    #[rpc(name = "chain_getAssetProof")]
+    fn get_asset_proof(
+        &self,
+        tracker: Tracker,
+        index: usize,
+        shard_id: ShardId,
+        block_number: Option<u64>,
+    ) -> Result<Option<AssetProof>>;
+
     /// Checks whether an asset is spent or not.
     #[rpc(name = "chain_isAssetSpent")]
     fn is_asset_spent(
@@ -145,6 +179,14 @@ pub trait Chain {
     #[rpc(name = "chain_getBlockByHash")]
     fn get_block_by_hash(&self, block_hash: BlockHash) -> Result<Option<Block>>;
 
+    /// Gets the receipt of the transaction with given hash.
+    #[rpc(name = "chain_getTransactionReceipt")]
+    fn get_transaction_receipt(&self, transaction_hash: TxHash) -> Result<Option<Receipt>>;
+
+    /// Gets the receipts of all transactions in the block with given hash.
+    #[rpc(name = "chain_getBlockReceipts")]
+    fn get_block_receipts(&self, block_hash: BlockHash) -> Result<Option<Vec<Receipt>>>;
+
     ///Gets the count of transactions in a block with given hash.
     #[rpc(name = "chain_getBlockTransactionCountByHash")]
     fn get_block_transaction_count_by_hash(&self, block_hash: BlockHash) -> Result<Option<usize>>;
@@ -188,5 +230,6 @@ pub trait Chain {
         tx: UnsignedTransaction,
         params: Vec<Vec<BytesArray>>,
         indices: Vec<usize>,
-    ) -> Result<Vec<String>>;
+        trace: Option<bool>,
+    ) -> Result<Vec<VMResult>>;
 }
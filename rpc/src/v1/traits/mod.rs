@@ -20,7 +20,9 @@ mod devel;
 mod engine;
 mod mempool;
 mod net;
+mod pubsub;
 mod snapshot;
+mod stake;
 
 pub use self::account::Account;
 pub use self::chain::Chain;
@@ -28,4 +30,6 @@ pub use self::devel::Devel;
 pub use self::engine::Engine;
 pub use self::mempool::Mempool;
 pub use self::net::Net;
+pub use self::pubsub::PubSub;
 pub use self::snapshot::Snapshot;
+pub use self::stake::Stake;
@@ -0,0 +1,54 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::super::types::{BlockNumberAndHash, NewHeads};
+use ctypes::TxHash;
+use jsonrpc_core::Result;
+use jsonrpc_pubsub::typed::Subscriber;
+use jsonrpc_pubsub::SubscriptionId;
+
+#[rpc(server)]
+pub trait PubSub {
+    type Metadata;
+
+    /// Subscribes to the changes of the best block, including the blocks enacted and retracted by a reorg.
+    #[pubsub(subscription = "chain_newHeads", subscribe, name = "chain_subscribeNewHeads")]
+    fn subscribe_new_heads(&self, meta: Self::Metadata, subscriber: Subscriber<NewHeads>);
+
+    /// Unsubscribes from the changes of the best block.
+    #[pubsub(subscription = "chain_newHeads", unsubscribe, name = "chain_unsubscribeNewHeads")]
+    fn unsubscribe_new_heads(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+
+    /// Subscribes to the blocks that reach the recommended confirmation of the engine.
+    #[pubsub(subscription = "chain_finalized", subscribe, name = "chain_subscribeFinalized")]
+    fn subscribe_finalized(&self, meta: Self::Metadata, subscriber: Subscriber<BlockNumberAndHash>);
+
+    /// Unsubscribes from the finalized blocks.
+    #[pubsub(subscription = "chain_finalized", unsubscribe, name = "chain_unsubscribeFinalized")]
+    fn unsubscribe_finalized(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+
+    /// Subscribes to the hashes of the transactions newly added to the mem pool.
+    #[pubsub(subscription = "mempool_pendingTransactions", subscribe, name = "mempool_subscribePendingTransactions")]
+    fn subscribe_pending_transactions(&self, meta: Self::Metadata, subscriber: Subscriber<Vec<TxHash>>);
+
+    /// Unsubscribes from the pending transactions.
+    #[pubsub(
+        subscription = "mempool_pendingTransactions",
+        unsubscribe,
+        name = "mempool_unsubscribePendingTransactions"
+    )]
+    fn unsubscribe_pending_transactions(&self, meta: Option<Self::Metadata>, id: SubscriptionId) -> Result<bool>;
+}
@@ -14,7 +14,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::super::types::BlockNumberAndHash;
+use super::super::types::{BlockNumberAndHash, SnapshotInfo};
+use ctypes::BlockHash;
 use jsonrpc_core::Result;
 
 #[rpc(server)]
@@ -22,4 +23,12 @@ pub trait Snapshot {
     /// Gets list of block numbers and block hashes of the snapshots.
     #[rpc(name = "snapshot_getList")]
     fn get_snapshot_list(&self) -> Result<Vec<BlockNumberAndHash>>;
+
+    /// Gets the chunk count, the size and the state root of the snapshot.
+    #[rpc(name = "snapshot_getInfo")]
+    fn get_snapshot_info(&self, block_hash: BlockHash) -> Result<Option<SnapshotInfo>>;
+
+    /// Deletes the snapshot. Returns false if there is no such snapshot.
+    #[rpc(name = "snapshot_delete")]
+    fn delete_snapshot(&self, block_hash: BlockHash) -> Result<bool>;
 }
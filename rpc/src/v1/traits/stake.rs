@@ -0,0 +1,68 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::super::types::{
+    Candidate, Delegation, PendingRewards, Prisoner, Proposal, Reward, Slash, Unbonding, Validator,
+};
+use ckey::PlatformAddress;
+use jsonrpc_core::Result;
+
+#[rpc(server)]
+pub trait Stake {
+    /// Gets the validators of the current term.
+    #[rpc(name = "stake_getCurrentValidators")]
+    fn get_current_validators(&self, block_number: Option<u64>) -> Result<Vec<Validator>>;
+
+    /// Gets the candidates who nominated themselves in the order of their priority.
+    #[rpc(name = "stake_getCandidates")]
+    fn get_candidates(&self, block_number: Option<u64>) -> Result<Vec<Candidate>>;
+
+    /// Gets the delegations of the given delegator.
+    #[rpc(name = "stake_getDelegations")]
+    fn get_delegations(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Delegation>>;
+
+    /// Gets the jailed validators.
+    #[rpc(name = "stake_getJailed")]
+    fn get_jailed(&self, block_number: Option<u64>) -> Result<Vec<Prisoner>>;
+
+    /// Gets the banned addresses.
+    #[rpc(name = "stake_getBanned")]
+    fn get_banned(&self, block_number: Option<u64>) -> Result<Vec<PlatformAddress>>;
+
+    /// Gets the rewards which are not paid yet.
+    #[rpc(name = "stake_getPendingRewards")]
+    fn get_pending_rewards(&self, block_number: Option<u64>) -> Result<PendingRewards>;
+
+    /// Gets the rewards of the given delegator which are not claimed yet, by the delegatee who earned them.
+    #[rpc(name = "stake_getClaimableRewards")]
+    fn get_claimable_rewards(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Reward>>;
+
+    /// Gets the stakes of the given delegator which are waiting for the end of the unbonding period.
+    #[rpc(name = "stake_getUnbondings")]
+    fn get_unbondings(&self, delegator: PlatformAddress, block_number: Option<u64>) -> Result<Vec<Unbonding>>;
+
+    /// Gets the records of the slashed stakes.
+    #[rpc(name = "stake_getSlashes")]
+    fn get_slashes(&self, block_number: Option<u64>) -> Result<Vec<Slash>>;
+
+    /// Gets the governance proposals which are being voted on.
+    #[rpc(name = "stake_getProposals")]
+    fn get_proposals(&self, block_number: Option<u64>) -> Result<Vec<Proposal>>;
+
+    /// Gets the id of the current term.
+    #[rpc(name = "stake_getTermId")]
+    fn get_term_id(&self, block_number: Option<u64>) -> Result<Option<u64>>;
+}
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use super::super::errors::ConversionError;
-use super::{AssetMintOutput, AssetTransferInput, AssetTransferOutput};
+use super::{AssetMintOutput, AssetOutPoint, AssetTransferInput, AssetTransferOutput};
 use cjson::uint::Uint;
 use ckey::{NetworkId, PlatformAddress, Public, Signature};
 use ctypes::transaction::{Action as ActionType, AssetMintOutput as AssetMintOutputType};
@@ -80,6 +80,49 @@ pub enum Action {
         burn: AssetTransferInput,
         receiver: PlatformAddress,
     },
+    #[serde(rename_all = "camelCase")]
+    SetAssetFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        asset_tracker: Tracker,
+        index: usize,
+        frozen: bool,
+
+        approvals: Vec<Signature>,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetScriptHashFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        lock_script_hash: H160,
+        frozen: bool,
+
+        approvals: Vec<Signature>,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateAllowedScriptHashes {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        added: Vec<H160>,
+        removed: Vec<H160>,
+
+        approvals: Vec<Signature>,
+    },
+    #[serde(rename_all = "camelCase")]
+    ClawbackAsset {
+        network_id: NetworkId,
+        prev_out: AssetOutPoint,
+        lock_script_hash: H160,
+        parameters: Vec<String>,
+
+        approvals: Vec<Signature>,
+    },
     Pay {
         receiver: PlatformAddress,
         quantity: Uint,
@@ -194,6 +237,57 @@ pub enum ActionWithTracker {
 
         tracker: Tracker,
     },
+    #[serde(rename_all = "camelCase")]
+    SetAssetFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        asset_tracker: Tracker,
+        index: usize,
+        frozen: bool,
+
+        approvals: Vec<Signature>,
+
+        tracker: Tracker,
+    },
+    #[serde(rename_all = "camelCase")]
+    SetScriptHashFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        lock_script_hash: H160,
+        frozen: bool,
+
+        approvals: Vec<Signature>,
+
+        tracker: Tracker,
+    },
+    #[serde(rename_all = "camelCase")]
+    UpdateAllowedScriptHashes {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: u64,
+        added: Vec<H160>,
+        removed: Vec<H160>,
+
+        approvals: Vec<Signature>,
+
+        tracker: Tracker,
+    },
+    #[serde(rename_all = "camelCase")]
+    ClawbackAsset {
+        network_id: NetworkId,
+        prev_out: AssetOutPoint,
+        lock_script_hash: H160,
+        parameters: Vec<String>,
+
+        approvals: Vec<Signature>,
+
+        tracker: Tracker,
+    },
     Pay {
         receiver: PlatformAddress,
         quantity: Uint,
@@ -335,6 +429,76 @@ impl ActionWithTracker {
                 receiver: PlatformAddress::new_v1(network_id, receiver),
                 tracker: tracker.unwrap(),
             },
+            ActionType::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+                approvals,
+            } => ActionWithTracker::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as u64,
+                asset_tracker,
+                index,
+                frozen,
+                approvals,
+                tracker: tracker.unwrap(),
+            },
+            ActionType::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+                approvals,
+            } => ActionWithTracker::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as u64,
+                lock_script_hash,
+                frozen,
+                approvals,
+                tracker: tracker.unwrap(),
+            },
+            ActionType::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+                approvals,
+            } => ActionWithTracker::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as u64,
+                added,
+                removed,
+                approvals,
+                tracker: tracker.unwrap(),
+            },
+            ActionType::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+                approvals,
+            } => ActionWithTracker::ClawbackAsset {
+                network_id,
+                prev_out: prev_out.into(),
+                lock_script_hash,
+                parameters: parameters.into_iter().map(|param| param.to_hex()).collect(),
+                approvals,
+                tracker: tracker.unwrap(),
+            },
             ActionType::Pay {
                 receiver,
                 quantity,
@@ -536,6 +700,75 @@ impl TryFrom<Action> for ActionType {
                 burn: burn.into(),
                 receiver: receiver.try_into_address()?,
             },
+            Action::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+                approvals,
+            } => ActionType::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as usize,
+                asset_tracker,
+                index,
+                frozen,
+                approvals,
+            },
+            Action::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+                approvals,
+            } => ActionType::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as usize,
+                lock_script_hash,
+                frozen,
+                approvals,
+            },
+            Action::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+                approvals,
+            } => ActionType::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq: seq as usize,
+                added,
+                removed,
+                approvals,
+            },
+            Action::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+                approvals,
+            } => {
+                let parameters = parameters.into_iter().map(|param| param.from_hex()).collect::<Result<_, _>>()?;
+                ActionType::ClawbackAsset {
+                    network_id,
+                    prev_out: prev_out.into(),
+                    lock_script_hash,
+                    parameters,
+                    approvals,
+                }
+            }
             Action::Pay {
                 receiver,
                 quantity,
@@ -34,6 +34,7 @@ pub struct OwnedAsset {
     asset: Asset,
     lock_script_hash: H160,
     parameters: Vec<String>,
+    frozen: bool,
 }
 
 impl From<AssetType> for Asset {
@@ -54,6 +55,7 @@ impl From<OwnedAssetType> for OwnedAsset {
             },
             lock_script_hash: *asset.lock_script_hash(),
             parameters: asset.parameters().iter().map(Deref::deref).map(<[u8]>::to_hex).collect(),
+            frozen: asset.is_frozen(),
         }
     }
 }
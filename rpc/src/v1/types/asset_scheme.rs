@@ -30,6 +30,7 @@ pub struct AssetScheme {
     allowed_script_hashes: Vec<H160>,
     pool: Vec<Asset>,
     seq: u64,
+    frozen_script_hashes: Vec<H160>,
 }
 
 impl AssetScheme {
@@ -45,6 +46,7 @@ impl AssetScheme {
             allowed_script_hashes: asset_scheme.allowed_script_hashes().to_owned(),
             pool: asset_scheme.pool().iter().map(|asset| asset.clone().into()).collect(),
             seq: asset_scheme.seq() as u64,
+            frozen_script_hashes: asset_scheme.frozen_script_hashes().to_owned(),
         }
     }
 }
@@ -72,9 +72,17 @@ impl Block {
     }
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Clone, Debug, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct BlockNumberAndHash {
     pub number: BlockNumber,
     pub hash: BlockHash,
 }
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NewHeads {
+    pub best: BlockNumberAndHash,
+    pub enacted: Vec<BlockHash>,
+    pub retracted: Vec<BlockHash>,
+}
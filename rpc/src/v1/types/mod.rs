@@ -20,23 +20,33 @@ mod asset_input;
 mod asset_output;
 mod asset_scheme;
 mod block;
+mod proof;
+mod receipt;
+mod snapshot;
+mod stake;
 mod text;
 mod transaction;
 mod unsigned_transaction;
+mod vm;
 mod work;
 
 use self::asset::Asset;
-use self::asset_input::AssetTransferInput;
+use self::asset_input::{AssetOutPoint, AssetTransferInput};
 use self::asset_output::{AssetMintOutput, AssetTransferOutput};
 
 pub use self::action::{Action, ActionWithTracker};
 pub use self::asset::OwnedAsset;
 pub use self::asset_scheme::AssetScheme;
 pub use self::block::Block;
-pub use self::block::BlockNumberAndHash;
+pub use self::block::{BlockNumberAndHash, NewHeads};
+pub use self::proof::{AssetProof, StateProof};
+pub use self::receipt::Receipt;
+pub use self::snapshot::SnapshotInfo;
+pub use self::stake::{Candidate, Delegation, PendingRewards, Prisoner, Proposal, Reward, Slash, Unbonding, Validator};
 pub use self::text::Text;
 pub use self::transaction::{PendingTransactions, Transaction};
 pub use self::unsigned_transaction::UnsignedTransaction;
+pub use self::vm::VMResult;
 pub use self::work::Work;
 
 use ctypes::TxHash;
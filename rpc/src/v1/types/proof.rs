@@ -0,0 +1,55 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use cstate::StateProof as StateProofType;
+use primitives::H256;
+use rustc_serialize::hex::ToHex;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StateProof {
+    pub root: H256,
+    pub key: String,
+    pub value: Option<String>,
+    pub nodes: Vec<String>,
+}
+
+impl From<StateProofType> for StateProof {
+    fn from(from: StateProofType) -> Self {
+        StateProof {
+            root: from.root,
+            key: from.key.to_hex(),
+            value: from.value.map(|value| value.to_hex()),
+            nodes: from.nodes.iter().map(|node| node.to_hex()).collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AssetProof {
+    pub shard: StateProof,
+    pub asset: Option<StateProof>,
+}
+
+impl From<(StateProofType, Option<StateProofType>)> for AssetProof {
+    fn from((shard, asset): (StateProofType, Option<StateProofType>)) -> Self {
+        AssetProof {
+            shard: shard.into(),
+            asset: asset.map(From::from),
+        }
+    }
+}
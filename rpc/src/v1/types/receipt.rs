@@ -0,0 +1,62 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::asset_input::AssetOutPoint;
+use ccore::Receipt as ReceiptType;
+use cjson::uint::Uint;
+use ctypes::errors::RuntimeError;
+use ctypes::{BlockHash, BlockNumber, TxHash};
+use primitives::H256;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Receipt {
+    pub transaction_hash: TxHash,
+    pub block_number: Option<BlockNumber>,
+    pub block_hash: Option<BlockHash>,
+    pub transaction_index: Option<usize>,
+    pub success: bool,
+    pub error: Option<RuntimeError>,
+    pub fee: Uint,
+    pub created_assets: Vec<AssetOutPoint>,
+    pub touched_action_data: Vec<H256>,
+}
+
+impl Receipt {
+    /// Sets the position of the transaction in the chain.
+    pub fn in_block(mut self, block_number: BlockNumber, block_hash: BlockHash, transaction_index: usize) -> Self {
+        self.block_number = Some(block_number);
+        self.block_hash = Some(block_hash);
+        self.transaction_index = Some(transaction_index);
+        self
+    }
+}
+
+impl From<ReceiptType> for Receipt {
+    fn from(from: ReceiptType) -> Self {
+        Receipt {
+            transaction_hash: from.hash,
+            block_number: None,
+            block_hash: None,
+            transaction_index: None,
+            success: from.is_success(),
+            error: from.error,
+            fee: from.fee.into(),
+            created_assets: from.created_assets.into_iter().map(From::from).collect(),
+            touched_action_data: from.touched_action_data,
+        }
+    }
+}
@@ -0,0 +1,41 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use csync::snapshot::SnapshotInfo as SnapshotInfoType;
+use ctypes::{BlockHash, BlockNumber};
+use primitives::H256;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub number: BlockNumber,
+    pub hash: BlockHash,
+    pub state_root: H256,
+    pub chunk_count: usize,
+    pub size: u64,
+}
+
+impl From<SnapshotInfoType> for SnapshotInfo {
+    fn from(from: SnapshotInfoType) -> Self {
+        SnapshotInfo {
+            number: from.number,
+            hash: from.hash,
+            state_root: from.state_root,
+            chunk_count: from.chunk_count,
+            size: from.size,
+        }
+    }
+}
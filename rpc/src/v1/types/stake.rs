@@ -0,0 +1,252 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use ccore::stake::{
+    Candidate as CandidateType, Prisoner as PrisonerType, Proposal as ProposalType, ProposalContent,
+    Slash as SlashType, SlashReason as SlashReasonType, Unbonding as UnbondingType, Validator as ValidatorType,
+};
+use cjson::bytes::Bytes;
+use cjson::scheme::Params;
+use ckey::{public_to_address, Address, NetworkId, PlatformAddress, Public};
+use std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Validator {
+    pub address: PlatformAddress,
+    pub pubkey: Public,
+    pub delegation: u64,
+    pub deposit: u64,
+    pub weight: u64,
+}
+
+impl Validator {
+    pub fn from_core(validator: &ValidatorType, network_id: NetworkId) -> Self {
+        Self {
+            address: PlatformAddress::new_v1(network_id, public_to_address(validator.pubkey())),
+            pubkey: *validator.pubkey(),
+            delegation: validator.delegation(),
+            deposit: validator.deposit(),
+            weight: validator.weight(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Candidate {
+    pub address: PlatformAddress,
+    pub pubkey: Public,
+    pub deposit: u64,
+    pub nomination_ends_at: u64,
+    pub metadata: Bytes,
+    pub commission_rate: u64,
+}
+
+impl Candidate {
+    pub fn from_core(candidate: CandidateType, commission_rate: u64, network_id: NetworkId) -> Self {
+        Self {
+            address: PlatformAddress::new_v1(network_id, public_to_address(&candidate.pubkey)),
+            pubkey: candidate.pubkey,
+            deposit: candidate.deposit,
+            nomination_ends_at: candidate.nomination_ends_at,
+            metadata: candidate.metadata.into(),
+            commission_rate,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Delegation {
+    pub delegatee: PlatformAddress,
+    pub quantity: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Prisoner {
+    pub address: PlatformAddress,
+    pub deposit: u64,
+    pub custody_until: u64,
+    pub released_at: u64,
+}
+
+impl Prisoner {
+    pub fn from_core(prisoner: &PrisonerType, network_id: NetworkId) -> Self {
+        Self {
+            address: PlatformAddress::new_v1(network_id, prisoner.address),
+            deposit: prisoner.deposit,
+            custody_until: prisoner.custody_until,
+            released_at: prisoner.released_at,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Unbonding {
+    pub delegatee: PlatformAddress,
+    pub quantity: u64,
+    pub released_at: u64,
+    pub redelegatee: Option<PlatformAddress>,
+}
+
+impl Unbonding {
+    pub fn from_core(unbonding: &UnbondingType, network_id: NetworkId) -> Self {
+        Self {
+            delegatee: PlatformAddress::new_v1(network_id, unbonding.delegatee),
+            quantity: unbonding.quantity,
+            released_at: unbonding.released_at,
+            redelegatee: unbonding.redelegatee.map(|address| PlatformAddress::new_v1(network_id, address)),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SlashReason {
+    DoubleVote,
+    Downtime,
+}
+
+impl From<SlashReasonType> for SlashReason {
+    fn from(reason: SlashReasonType) -> Self {
+        match reason {
+            SlashReasonType::DoubleVote => SlashReason::DoubleVote,
+            SlashReasonType::Downtime => SlashReason::Downtime,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashedDelegation {
+    pub delegator: PlatformAddress,
+    pub quantity: u64,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Slash {
+    pub offender: PlatformAddress,
+    pub reason: SlashReason,
+    pub term: u64,
+    pub deposit: u64,
+    pub delegations: Vec<SlashedDelegation>,
+}
+
+impl Slash {
+    pub fn from_core(slash: &SlashType, network_id: NetworkId) -> Self {
+        Self {
+            offender: PlatformAddress::new_v1(network_id, slash.offender),
+            reason: slash.reason.into(),
+            term: slash.term,
+            deposit: slash.deposit,
+            delegations: slash
+                .delegations
+                .iter()
+                .map(|delegation| SlashedDelegation {
+                    delegator: PlatformAddress::new_v1(network_id, delegation.delegator),
+                    quantity: delegation.quantity,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Vote {
+    pub voter: PlatformAddress,
+    pub approve: bool,
+}
+
+/// A governance proposal. Either `params` or `text` is set.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Proposal {
+    pub id: u64,
+    pub proposer: PlatformAddress,
+    pub deposit: u64,
+    pub params: Option<Params>,
+    pub text: Option<Bytes>,
+    pub voting_ends_at: u64,
+    pub votes: Vec<Vote>,
+}
+
+impl Proposal {
+    pub fn from_core(proposal: &ProposalType, network_id: NetworkId) -> Self {
+        let (params, text) = match &proposal.content {
+            ProposalContent::ChangeParams(params) => (Some((**params).into()), None),
+            ProposalContent::Text(text) => (None, Some(text.clone().into())),
+        };
+        Self {
+            id: proposal.id,
+            proposer: PlatformAddress::new_v1(network_id, proposal.proposer),
+            deposit: proposal.deposit,
+            params,
+            text,
+            voting_ends_at: proposal.voting_ends_at,
+            votes: proposal
+                .votes
+                .iter()
+                .map(|(voter, approve)| Vote {
+                    voter: PlatformAddress::new_v1(network_id, *voter),
+                    approve: *approve,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Reward {
+    pub address: PlatformAddress,
+    pub quantity: u64,
+}
+
+/// The rewards which are not paid yet.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PendingRewards {
+    /// The rewards accumulated during the current term.
+    pub current: Vec<Reward>,
+    /// The rewards of the previous term, which are paid at the end of the current term.
+    pub calculated: Vec<Reward>,
+}
+
+impl PendingRewards {
+    pub fn from_core(
+        current: &BTreeMap<Address, u64>,
+        calculated: &BTreeMap<Address, u64>,
+        network_id: NetworkId,
+    ) -> Self {
+        let rewards = |map: &BTreeMap<Address, u64>| {
+            map.iter()
+                .map(|(address, quantity)| Reward {
+                    address: PlatformAddress::new_v1(network_id, *address),
+                    quantity: *quantity,
+                })
+                .collect()
+        };
+        Self {
+            current: rewards(current),
+            calculated: rewards(calculated),
+        }
+    }
+}
@@ -0,0 +1,63 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use cjson::bytes::Bytes;
+use cvm::Step;
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VMResult {
+    pub result: String,
+    pub cost: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace: Option<Vec<VMStep>>,
+}
+
+impl From<(String, u64, Option<Vec<Step>>)> for VMResult {
+    fn from((result, cost, trace): (String, u64, Option<Vec<Step>>)) -> Self {
+        Self {
+            result,
+            cost,
+            trace: trace.map(|steps| steps.into_iter().map(VMStep::from).collect()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VMStep {
+    pub pc: usize,
+    pub instruction: String,
+    pub stack: Vec<Bytes>,
+    pub memory: usize,
+    pub cost: u64,
+    pub executed: bool,
+    pub tag: Option<Bytes>,
+}
+
+impl From<Step> for VMStep {
+    fn from(step: Step) -> Self {
+        Self {
+            pc: step.pc,
+            instruction: step.instruction.to_string(),
+            stack: step.stack.into_iter().map(Bytes::from).collect(),
+            memory: step.memory,
+            cost: step.cost,
+            executed: step.executed,
+            tag: step.tag.map(Bytes::from),
+        }
+    }
+}
@@ -81,6 +81,10 @@ impl ShardCache {
         self.asset.get(a, db)
     }
 
+    pub fn asset_mut(&self, a: &OwnedAssetAddress, db: &dyn Trie) -> TrieResult<RefMut<'_, OwnedAsset>> {
+        self.asset.get_mut(a, db)
+    }
+
     pub fn create_asset<F>(&self, a: &OwnedAssetAddress, f: F) -> TrieResult<OwnedAsset>
     where
         F: FnOnce() -> OwnedAsset, {
@@ -29,6 +29,14 @@ fn touched_count() -> usize {
     TOUCHED_COUNT.fetch_add(1, Ordering::SeqCst)
 }
 
+static HIT_COUNT: AtomicUsize = AtomicUsize::new(0);
+static MISS_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Returns the number of the lookups found in the caches and the number of the lookups read from the tries.
+pub fn cache_lookup_counts() -> (usize, usize) {
+    (HIT_COUNT.load(Ordering::Relaxed), MISS_COUNT.load(Ordering::Relaxed))
+}
+
 #[derive(Clone, Debug)]
 struct Entry<Item>
 where
@@ -197,8 +205,10 @@ where
         // check local cache first
         if let Some(cached_item) = self.cache.borrow_mut().get_mut(a) {
             cached_item.touched = touched_count();
+            HIT_COUNT.fetch_add(1, Ordering::Relaxed);
             return Ok(cached_item.item.clone())
         }
+        MISS_COUNT.fetch_add(1, Ordering::Relaxed);
 
         // not found in the cache, get from the DB and insert into cache
         let maybe_item = db.get(a.as_ref())?.map(|bytes| ::rlp::decode::<Item>(&bytes).unwrap());
@@ -210,7 +220,10 @@ where
     /// If it doesn't exist, make item equal the evaluation of `default`.
     pub fn get_mut(&self, a: &Item::Address, db: &dyn Trie) -> cmerkle::Result<RefMut<'_, Item>> {
         let contains_key = self.cache.borrow().contains_key(a);
-        if !contains_key {
+        if contains_key {
+            HIT_COUNT.fetch_add(1, Ordering::Relaxed);
+        } else {
+            MISS_COUNT.fetch_add(1, Ordering::Relaxed);
             let maybe_item = db.get(a.as_ref())?.map(|bytes| ::rlp::decode::<Item>(&bytes).unwrap());
             self.insert(a, Entry::<Item>::new_clean(maybe_item));
         }
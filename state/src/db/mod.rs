@@ -14,6 +14,8 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
+mod pruning;
 mod state_db;
 
+pub use self::pruning::StatePruner;
 pub use self::state_db::StateDB;
@@ -0,0 +1,407 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::Shard;
+use ccrypto::BLAKE_NULL_RLP;
+use cdb::HashDB;
+use cmerkle::Node;
+use kvdb::{DBTransaction, KeyValueDB};
+use primitives::H256;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+const REF_COUNT_PREFIX: u8 = b'R';
+const ERA_PREFIX: u8 = b'E';
+const PINNED_PREFIX: u8 = b'P';
+const PRUNED_ERA_KEY: &[u8] = b"PRUNED_ERA";
+
+/// Deletes the trie nodes that are not reachable from the state roots of the recent eras.
+///
+/// Every counted node has a reference count in the state column, which is the number of the nodes and the roots
+/// referring to it. The roots are journaled under their eras, and the counts of the nodes reachable from them
+/// decrease when the era is pruned. A node is deleted when its count drops to zero.
+#[derive(Clone)]
+pub struct StatePruner {
+    backing: Arc<dyn KeyValueDB>,
+    column: Option<u32>,
+    history: u64,
+}
+
+impl StatePruner {
+    pub fn new(backing: Arc<dyn KeyValueDB>, column: Option<u32>, history: u64) -> Self {
+        Self {
+            backing,
+            column,
+            history,
+        }
+    }
+
+    /// The number of the recent eras whose states are kept.
+    pub fn history(&self) -> u64 {
+        self.history
+    }
+
+    /// Counts the references from `root` and journals it under `era` to prune it later.
+    pub fn journal_root(&self, db: &dyn HashDB, batch: &mut DBTransaction, era: u64, root: H256) {
+        let mut counts = RefCounts::new(self);
+        counts.increase(db, root);
+        counts.write(batch);
+
+        let mut roots = self.era_roots(era);
+        roots.push(root);
+        batch.put(self.column, &era_key(era), &rlp::encode_list(&roots));
+    }
+
+    /// Counts the references from `root` once, and never releases them.
+    pub fn pin_root(&self, db: &dyn HashDB, batch: &mut DBTransaction, root: H256) {
+        let key = pinned_key(&root);
+        if self.get(&key).is_some() {
+            return
+        }
+        let mut counts = RefCounts::new(self);
+        counts.increase(db, root);
+        counts.write(batch);
+        batch.put(self.column, &key, &[]);
+    }
+
+    /// Prunes the roots journaled under the eras older than the last `history` eras from `best_era`.
+    pub fn prune(&self, db: &dyn HashDB, batch: &mut DBTransaction, best_era: u64) {
+        if best_era <= self.history {
+            return
+        }
+        let until = best_era - self.history;
+        // The genesis era is never pruned.
+        let from = self.pruned_era().map_or(1, |era| era + 1);
+        if from > until {
+            return
+        }
+
+        let mut counts = RefCounts::new(self);
+        for era in from..=until {
+            for root in self.era_roots(era) {
+                counts.decrease(db, batch, root);
+            }
+            batch.delete(self.column, &era_key(era));
+        }
+        counts.write(batch);
+        batch.put(self.column, PRUNED_ERA_KEY, &rlp::encode(&until));
+        ctrace!(STATE, "Pruned the states from era {} to era {}", from, until);
+    }
+
+    /// The last era whose states are pruned.
+    pub fn pruned_era(&self) -> Option<u64> {
+        self.get(PRUNED_ERA_KEY).map(|bytes| rlp::decode(&bytes).expect("The pruned era must be valid"))
+    }
+
+    fn era_roots(&self, era: u64) -> Vec<H256> {
+        self.get(&era_key(era)).map(|bytes| rlp::decode_list(&bytes)).unwrap_or_default()
+    }
+
+    fn ref_count(&self, hash: &H256) -> u64 {
+        self.get(&ref_count_key(hash))
+            .map_or(0, |bytes| rlp::decode(&bytes).expect("The reference count must be valid"))
+    }
+
+    fn get(&self, key: &[u8]) -> Option<Vec<u8>> {
+        self.backing
+            .get(self.column, key)
+            .expect("Low level database error. Some issue with disk?")
+            .map(|value| value.to_vec())
+    }
+}
+
+/// The reference counts changed in a batch.
+struct RefCounts<'a> {
+    pruner: &'a StatePruner,
+    counts: HashMap<H256, u64>,
+}
+
+impl<'a> RefCounts<'a> {
+    fn new(pruner: &'a StatePruner) -> Self {
+        Self {
+            pruner,
+            counts: HashMap::new(),
+        }
+    }
+
+    fn count(&mut self, hash: H256) -> &mut u64 {
+        let pruner = self.pruner;
+        self.counts.entry(hash).or_insert_with(|| pruner.ref_count(&hash))
+    }
+
+    fn increase(&mut self, db: &dyn HashDB, hash: H256) {
+        if hash == BLAKE_NULL_RLP {
+            return
+        }
+        let count = self.count(hash);
+        *count += 1;
+        if *count > 1 {
+            return
+        }
+        match db.get(&hash) {
+            Some(node) => {
+                for child in children(&node) {
+                    self.increase(db, child);
+                }
+            }
+            None => cwarn!(STATE, "Cannot count the references from the missing node {}", hash),
+        }
+    }
+
+    fn decrease(&mut self, db: &dyn HashDB, batch: &mut DBTransaction, hash: H256) {
+        if hash == BLAKE_NULL_RLP {
+            return
+        }
+        let count = self.count(hash);
+        // The nodes written before the pruning was enabled are not counted.
+        if *count == 0 {
+            return
+        }
+        *count -= 1;
+        if *count > 0 {
+            return
+        }
+        if let Some(node) = db.get(&hash) {
+            for child in children(&node) {
+                self.decrease(db, batch, child);
+            }
+        }
+        batch.delete(self.pruner.column, &hash);
+    }
+
+    fn write(self, batch: &mut DBTransaction) {
+        let column = self.pruner.column;
+        for (hash, count) in self.counts {
+            if count == 0 {
+                batch.delete(column, &ref_count_key(&hash));
+            } else {
+                batch.put(column, &ref_count_key(&hash), &rlp::encode(&count));
+            }
+        }
+    }
+}
+
+/// The nodes referred by the given node. The root of a shard is a child of the node storing the shard.
+fn children(node: &[u8]) -> Vec<H256> {
+    match Node::decoded(node) {
+        Some(Node::Branch(_, children)) => children.iter().filter_map(|child| *child).collect(),
+        Some(Node::Leaf(_, value)) => Shard::root_of_encoded(value).into_iter().collect(),
+        None => Vec::new(),
+    }
+}
+
+fn ref_count_key(hash: &H256) -> Vec<u8> {
+    let mut key = Vec::with_capacity(33);
+    key.push(REF_COUNT_PREFIX);
+    key.extend_from_slice(hash);
+    key
+}
+
+fn pinned_key(hash: &H256) -> Vec<u8> {
+    let mut key = Vec::with_capacity(33);
+    key.push(PINNED_PREFIX);
+    key.extend_from_slice(hash);
+    key
+}
+
+fn era_key(era: u64) -> Vec<u8> {
+    let mut key = Vec::with_capacity(9);
+    key.push(ERA_PREFIX);
+    key.extend_from_slice(&era.to_be_bytes());
+    key
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use cdb::{new_journaldb, Algorithm, AsHashDB, JournalDB};
+    use ckey::Address;
+    use cmerkle::{Trie, TrieFactory, TrieMut};
+
+    const COLUMN: Option<u32> = Some(0);
+
+    struct TestStates {
+        backing: Arc<dyn KeyValueDB>,
+        db: Box<dyn JournalDB>,
+        pruner: StatePruner,
+        root: H256,
+        shard_root: H256,
+    }
+
+    impl TestStates {
+        fn new(history: u64) -> Self {
+            let backing: Arc<dyn KeyValueDB> = Arc::new(kvdb_memorydb::create(1));
+            let mut db = new_journaldb(backing.clone(), Algorithm::Archive, COLUMN);
+            let mut root = H256::new();
+            let _ = TrieFactory::create(db.as_hashdb_mut(), &mut root);
+            let mut shard_root = H256::new();
+            let _ = TrieFactory::create(db.as_hashdb_mut(), &mut shard_root);
+            Self {
+                backing: backing.clone(),
+                db,
+                pruner: StatePruner::new(backing, COLUMN, history),
+                root,
+                shard_root,
+            }
+        }
+
+        /// Inserts the items and journals the new state root under `era`.
+        fn commit(&mut self, era: u64, items: &[(&str, &str)]) -> H256 {
+            {
+                let mut trie = TrieFactory::from_existing(self.db.as_hashdb_mut(), &mut self.root).unwrap();
+                for (key, value) in items {
+                    trie.insert(key.as_bytes(), value.as_bytes()).unwrap();
+                }
+            }
+            self.journal(era);
+            self.root
+        }
+
+        /// Inserts the items into the shard trie, stores the shard in the state and journals the new state root
+        /// under `era`. It returns the new state root and the new shard root.
+        fn commit_shard(&mut self, era: u64, items: &[(&str, &str)]) -> (H256, H256) {
+            {
+                let mut trie = TrieFactory::from_existing(self.db.as_hashdb_mut(), &mut self.shard_root).unwrap();
+                for (key, value) in items {
+                    trie.insert(key.as_bytes(), value.as_bytes()).unwrap();
+                }
+            }
+            let shard = Shard::new(self.shard_root, vec![Address::default()], Vec::new());
+            {
+                let mut trie = TrieFactory::from_existing(self.db.as_hashdb_mut(), &mut self.root).unwrap();
+                trie.insert(b"shard", &rlp::encode(&shard)).unwrap();
+            }
+            self.journal(era);
+            (self.root, self.shard_root)
+        }
+
+        fn journal(&mut self, era: u64) {
+            let mut batch = DBTransaction::new();
+            self.pruner.journal_root(self.db.as_hashdb(), &mut batch, era, self.root);
+            self.db.journal_under(&mut batch, era, &self.root).unwrap();
+            self.backing.write(batch).unwrap();
+        }
+
+        fn prune(&self, best_era: u64) {
+            let mut batch = DBTransaction::new();
+            self.pruner.prune(self.db.as_hashdb(), &mut batch, best_era);
+            self.backing.write(batch).unwrap();
+        }
+
+        fn contains(&self, node: &H256) -> bool {
+            self.backing.get(COLUMN, node).unwrap().is_some()
+        }
+
+        fn get(&self, root: &H256, key: &[u8]) -> Option<Vec<u8>> {
+            TrieFactory::readonly(self.db.as_hashdb(), root).unwrap().get(key).unwrap().map(|value| value.to_vec())
+        }
+    }
+
+    #[test]
+    fn old_roots_are_pruned() {
+        let mut states = TestStates::new(1);
+        let root1 = states.commit(1, &[("alpha", "1"), ("beta", "1")]);
+        let root2 = states.commit(2, &[("alpha", "2")]);
+        let root3 = states.commit(3, &[("gamma", "3")]);
+
+        states.prune(3);
+
+        assert!(!states.contains(&root1));
+        assert!(!states.contains(&root2));
+        assert!(states.contains(&root3));
+        assert_eq!(Some(b"2".to_vec()), states.get(&root3, b"alpha"));
+        assert_eq!(Some(b"1".to_vec()), states.get(&root3, b"beta"));
+        assert_eq!(Some(b"3".to_vec()), states.get(&root3, b"gamma"));
+        assert_eq!(Some(2), states.pruner.pruned_era());
+    }
+
+    #[test]
+    fn old_shard_roots_are_pruned() {
+        let mut states = TestStates::new(1);
+        let (root1, shard_root1) = states.commit_shard(1, &[("alpha", "1"), ("beta", "1")]);
+        let (root2, shard_root2) = states.commit_shard(2, &[("alpha", "2")]);
+
+        states.prune(2);
+
+        assert!(!states.contains(&root1));
+        assert!(!states.contains(&shard_root1));
+        assert!(states.contains(&root2));
+        assert!(states.contains(&shard_root2));
+        assert_eq!(1, states.pruner.ref_count(&shard_root2));
+        assert_eq!(Some(b"2".to_vec()), states.get(&shard_root2, b"alpha"));
+        assert_eq!(Some(b"1".to_vec()), states.get(&shard_root2, b"beta"));
+
+        // The shard trie that is not changed is still referred by the new state.
+        let root3 = states.commit(3, &[("gamma", "3")]);
+        states.prune(3);
+
+        assert!(!states.contains(&root2));
+        assert!(states.contains(&root3));
+        assert!(states.contains(&shard_root2));
+        assert_eq!(1, states.pruner.ref_count(&shard_root2));
+        assert_eq!(Some(b"1".to_vec()), states.get(&shard_root2, b"beta"));
+    }
+
+    #[test]
+    fn recent_eras_are_kept() {
+        let mut states = TestStates::new(2);
+        let root1 = states.commit(1, &[("alpha", "1")]);
+        let root2 = states.commit(2, &[("alpha", "2")]);
+
+        states.prune(2);
+
+        assert!(states.contains(&root1));
+        assert!(states.contains(&root2));
+        assert_eq!(None, states.pruner.pruned_era());
+
+        let root3 = states.commit(3, &[("alpha", "3")]);
+        states.prune(3);
+
+        assert!(!states.contains(&root1));
+        assert!(states.contains(&root2));
+        assert!(states.contains(&root3));
+        assert_eq!(Some(1), states.pruner.pruned_era());
+    }
+
+    #[test]
+    fn pruning_twice_is_noop() {
+        let mut states = TestStates::new(1);
+        states.commit(1, &[("alpha", "1")]);
+        let root2 = states.commit(2, &[("alpha", "2")]);
+
+        states.prune(2);
+        states.prune(2);
+
+        assert!(states.contains(&root2));
+        assert_eq!(Some(b"2".to_vec()), states.get(&root2, b"alpha"));
+        assert_eq!(1, states.pruner.ref_count(&root2));
+    }
+
+    #[test]
+    fn pinned_root_is_never_pruned() {
+        let mut states = TestStates::new(1);
+        let root1 = states.commit(1, &[("alpha", "1")]);
+        let mut batch = DBTransaction::new();
+        states.pruner.pin_root(states.db.as_hashdb(), &mut batch, root1);
+        states.backing.write(batch).unwrap();
+        states.commit(2, &[("alpha", "2")]);
+
+        states.prune(2);
+
+        assert!(states.contains(&root1));
+        assert_eq!(Some(b"1".to_vec()), states.get(&root1, b"alpha"));
+    }
+}
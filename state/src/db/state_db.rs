@@ -30,6 +30,7 @@
 // You should have received a copy of the GNU General Public License
 // along with Parity.  If not, see <http://www.gnu.org/licenses/>.
 
+use super::StatePruner;
 use crate::cache::{GlobalCache, ShardCache, TopCache};
 use crate::impls::TopLevelState;
 use cdb::{new_journaldb, Algorithm, AsHashDB, DatabaseError, HashDB, JournalDB};
@@ -46,6 +47,8 @@ pub struct StateDB {
     db: Box<dyn JournalDB>,
     cache: GlobalCache,
     current_hash: Option<H256>,
+    /// Prunes the old states. The states are kept forever if it's None.
+    pruner: Option<StatePruner>,
 }
 
 impl StateDB {
@@ -55,6 +58,17 @@ impl StateDB {
             db,
             cache: Default::default(),
             current_hash: None,
+            pruner: None,
+        }
+    }
+
+    /// Create a new instance that prunes the old states
+    pub fn new_with_pruner(db: Box<dyn JournalDB>, pruner: StatePruner) -> StateDB {
+        StateDB {
+            db,
+            cache: Default::default(),
+            current_hash: None,
+            pruner: Some(pruner),
         }
     }
 
@@ -71,6 +85,28 @@ impl StateDB {
         Ok(records)
     }
 
+    /// Count the references from the state root to prune it after the era becomes old.
+    /// It should be called before `journal_under` that flushes the nodes in the memory.
+    pub fn journal_root(&self, batch: &mut DBTransaction, era: u64, root: H256) {
+        if let Some(pruner) = &self.pruner {
+            pruner.journal_root(self.db.as_hashdb(), batch, era, root);
+        }
+    }
+
+    /// Keep the state of the root even if the pruning is enabled.
+    pub fn pin_root(&self, batch: &mut DBTransaction, root: H256) {
+        if let Some(pruner) = &self.pruner {
+            pruner.pin_root(self.db.as_hashdb(), batch, root);
+        }
+    }
+
+    /// Prune the states of the eras older than the pruning history from the best era.
+    pub fn prune(&self, batch: &mut DBTransaction, best_era: u64) {
+        if let Some(pruner) = &self.pruner {
+            pruner.prune(self.db.as_hashdb(), batch, best_era);
+        }
+    }
+
     /// Mark a given candidate from an ancient era as canonical, enacting its removals from the
     /// backing database and reverting any non-canonical historical commit's insertions.
     pub fn mark_canonical(
@@ -84,7 +120,7 @@ impl StateDB {
 
     /// Check if pruning is enabled on the database.
     pub fn is_pruned(&self) -> bool {
-        self.db.is_pruned()
+        self.pruner.is_some() || self.db.is_pruned()
     }
 
     /// Check if the database is empty.
@@ -116,6 +152,7 @@ impl StateDB {
             db: self.db.boxed_clone(),
             cache,
             current_hash,
+            pruner: self.pruner.clone(),
         }
     }
 
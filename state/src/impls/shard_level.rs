@@ -19,7 +19,7 @@ use crate::checkpoint::{CheckpointId, StateWithCheckpoint};
 use crate::traits::{ShardState, ShardStateView};
 use crate::{
     Asset, AssetScheme, AssetSchemeAddress, OwnedAsset, OwnedAssetAddress, ShardText, ShardTextAddress, StateDB,
-    StateResult,
+    StateProof, StateResult,
 };
 use ccrypto::{Blake, BLAKE_NULL_RLP};
 use cdb::AsHashDB;
@@ -32,7 +32,7 @@ use ctypes::transaction::{
 };
 use ctypes::util::unexpected::Mismatch;
 use ctypes::{BlockNumber, ShardId, Tracker};
-use cvm::{decode, execute, ChainTimeInfo, ScriptResult, VMConfig};
+use cvm::{decode_at_era, execute, ChainTimeInfo, ScriptResult, VMConfig};
 use primitives::{Bytes, H160, H256};
 use std::cell::{RefCell, RefMut};
 use std::collections::HashSet;
@@ -44,6 +44,8 @@ pub struct ShardLevelState<'db> {
     cache: &'db mut ShardCache,
     id_of_checkpoints: Vec<CheckpointId>,
     shard_id: ShardId,
+    max_script_cost: u64,
+    era: u64,
 }
 
 impl<'db> ShardLevelState<'db> {
@@ -56,6 +58,8 @@ impl<'db> ShardLevelState<'db> {
             cache,
             id_of_checkpoints: Default::default(),
             shard_id,
+            max_script_cost: 0,
+            era: 0,
         })
     }
 
@@ -76,9 +80,18 @@ impl<'db> ShardLevelState<'db> {
             cache,
             id_of_checkpoints: Default::default(),
             shard_id,
+            max_script_cost: 0,
+            era: 0,
         })
     }
 
+    /// Limits the cost of the lock scripts, and decides the available instructions by the era.
+    /// Zero cost means no limit.
+    pub fn set_script_params(&mut self, max_script_cost: u64, era: u64) {
+        self.max_script_cost = max_script_cost;
+        self.era = era;
+    }
+
     /// Creates immutable shard state
     pub fn read_only(
         shard_id: ShardId,
@@ -213,6 +226,49 @@ impl<'db> ShardLevelState<'db> {
                 assert_eq!(*shard_id, self.shard_id);
                 self.store_text(transaction.tracker(), content.to_string())
             }
+            ShardTransaction::SetAssetFrozen {
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+                ..
+            } => {
+                assert_eq!(*shard_id, self.shard_id);
+                self.set_asset_frozen(sender, approvers, asset_type, *seq, *asset_tracker, *index, *frozen)
+            }
+            ShardTransaction::SetScriptHashFrozen {
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+                ..
+            } => {
+                assert_eq!(*shard_id, self.shard_id);
+                self.set_script_hash_frozen(sender, approvers, asset_type, *seq, lock_script_hash, *frozen)
+            }
+            ShardTransaction::UpdateAllowedScriptHashes {
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+                ..
+            } => {
+                assert_eq!(*shard_id, self.shard_id);
+                self.update_allowed_script_hashes(sender, approvers, asset_type, *seq, added, removed)
+            }
+            ShardTransaction::ClawbackAsset {
+                prev_out,
+                lock_script_hash,
+                parameters,
+                ..
+            } => {
+                assert_eq!(prev_out.shard_id, self.shard_id);
+                self.clawback_asset(transaction.tracker(), sender, approvers, prev_out, lock_script_hash, parameters)
+            }
         }
     }
 
@@ -380,14 +436,10 @@ impl<'db> ShardLevelState<'db> {
         }
 
         let mut asset_scheme = self.get_asset_scheme_mut(self.shard_id, *asset_type)?;
-        if asset_scheme.seq() != seq {
-            return Err(RuntimeError::InvalidSeqOfAssetScheme {
-                asset_type: *asset_type,
-                shard_id: self.shard_id,
-                expected: asset_scheme.seq(),
-                actual: seq,
-            }
-            .into())
+        self.check_seq_of_asset_scheme(&asset_scheme, *asset_type, seq)?;
+        // The frozen assets can only be released by the registrar.
+        if registrar.is_none() && asset_scheme.has_frozen_assets() {
+            return Err(RuntimeError::CannotRemoveRegistrarOfFrozenAssets.into())
         }
 
         asset_scheme.change_data(
@@ -443,39 +495,187 @@ impl<'db> ShardLevelState<'db> {
         Ok(())
     }
 
-    fn check_input_asset(
-        &self,
-        input: &AssetTransferInput,
+    fn check_seq_of_asset_scheme(&self, asset_scheme: &AssetScheme, asset_type: H160, seq: usize) -> StateResult<()> {
+        if asset_scheme.seq() != seq {
+            return Err(RuntimeError::InvalidSeqOfAssetScheme {
+                asset_type,
+                shard_id: self.shard_id,
+                expected: asset_scheme.seq(),
+                actual: seq,
+            }
+            .into())
+        }
+        Ok(())
+    }
+
+    // FIXME: Remove this clippy config
+    #[cfg_attr(feature = "cargo-clippy", allow(clippy::too_many_arguments))]
+    fn set_asset_frozen(
+        &mut self,
         sender: &Address,
         approvers: &[Address],
-    ) -> StateResult<(OwnedAsset, bool)> {
+        asset_type: &H160,
+        seq: usize,
+        tracker: Tracker,
+        index: usize,
+        frozen: bool,
+    ) -> StateResult<()> {
+        if !self.approved_by_registrar(*asset_type, sender, approvers)? {
+            return Err(RuntimeError::InsufficientPermission.into())
+        }
+
+        let asset = self.asset(tracker, index)?.ok_or_else(|| RuntimeError::AssetNotFound {
+            shard_id: self.shard_id,
+            tracker,
+            index,
+        })?;
+        if asset.asset_type() != asset_type {
+            return Err(RuntimeError::UnexpectedAssetType {
+                index,
+                mismatch: Mismatch {
+                    expected: *asset.asset_type(),
+                    found: *asset_type,
+                },
+            }
+            .into())
+        }
+
+        let mut asset_scheme = self.get_asset_scheme_mut(self.shard_id, *asset_type)?;
+        self.check_seq_of_asset_scheme(&asset_scheme, *asset_type, seq)?;
+        if asset.is_frozen() != frozen {
+            if frozen {
+                asset_scheme.increase_frozen_assets();
+            } else {
+                asset_scheme.decrease_frozen_assets();
+            }
+        }
+        asset_scheme.increase_seq();
+
+        self.get_asset_mut(tracker, index)?.set_frozen(frozen);
+        ctrace!(TX, "Set the frozen flag of asset {}:{}:{} to {}", self.shard_id, tracker, index, frozen);
+        Ok(())
+    }
+
+    fn set_script_hash_frozen(
+        &mut self,
+        sender: &Address,
+        approvers: &[Address],
+        asset_type: &H160,
+        seq: usize,
+        lock_script_hash: &H160,
+        frozen: bool,
+    ) -> StateResult<()> {
+        if !self.approved_by_registrar(*asset_type, sender, approvers)? {
+            return Err(RuntimeError::InsufficientPermission.into())
+        }
+
+        let mut asset_scheme = self.get_asset_scheme_mut(self.shard_id, *asset_type)?;
+        self.check_seq_of_asset_scheme(&asset_scheme, *asset_type, seq)?;
+        asset_scheme.set_script_hash_frozen(*lock_script_hash, frozen);
+        asset_scheme.increase_seq();
+        ctrace!(
+            TX,
+            "Set the frozen flag of {:?} in {}:{:?} to {}",
+            lock_script_hash,
+            self.shard_id,
+            asset_type,
+            frozen
+        );
+        Ok(())
+    }
+
+    fn update_allowed_script_hashes(
+        &mut self,
+        sender: &Address,
+        approvers: &[Address],
+        asset_type: &H160,
+        seq: usize,
+        added: &[H160],
+        removed: &[H160],
+    ) -> StateResult<()> {
+        if !self.approved_by_registrar(*asset_type, sender, approvers)? {
+            return Err(RuntimeError::InsufficientPermission.into())
+        }
+
+        let mut asset_scheme = self.get_asset_scheme_mut(self.shard_id, *asset_type)?;
+        self.check_seq_of_asset_scheme(&asset_scheme, *asset_type, seq)?;
+        // An empty list allows every script hash, so it must not become empty by removal.
+        let allowed_script_hashes = asset_scheme.allowed_script_hashes();
+        if !allowed_script_hashes.is_empty()
+            && added.is_empty()
+            && allowed_script_hashes.iter().all(|hash| removed.contains(hash))
+        {
+            return Err(RuntimeError::CannotEmptyAllowedScriptHashes.into())
+        }
+        asset_scheme.update_allowed_script_hashes(added, removed);
+        asset_scheme.increase_seq();
+        Ok(())
+    }
+
+    fn clawback_asset(
+        &mut self,
+        transaction_tracker: Tracker,
+        sender: &Address,
+        approvers: &[Address],
+        prev_out: &AssetOutPoint,
+        lock_script_hash: &H160,
+        parameters: &[Bytes],
+    ) -> StateResult<()> {
         let AssetOutPoint {
             index,
             tracker,
             asset_type,
             shard_id,
             quantity,
-        } = input.prev_out;
-
-        assert_eq!(self.shard_id, shard_id);
-        let approved_by_regulator = self.approved_by_registrar(asset_type, sender, approvers)?;
-        if !approved_by_regulator {
-            let asset_scheme = self.asset_scheme(asset_type)?.ok_or_else(|| RuntimeError::AssetSchemeNotFound {
-                shard_id,
-                asset_type,
-            })?;
-            if let Some(approver) = asset_scheme.approver() {
-                if sender != approver && !approvers.contains(approver) {
-                    return Err(RuntimeError::NotApproved(*approver).into())
-                }
-            }
+        } = *prev_out;
+        if !self.approved_by_registrar(asset_type, sender, approvers)? {
+            return Err(RuntimeError::InsufficientPermission.into())
         }
 
+        // The frozen assets can be clawed back.
         let asset = self.asset(tracker, index)?.ok_or_else(|| RuntimeError::AssetNotFound {
             shard_id,
             tracker,
             index,
         })?;
+        self.check_asset_of_out_point(&asset, prev_out)?;
+
+        {
+            let mut asset_scheme = self.get_asset_scheme_mut(self.shard_id, asset_type)?;
+            if !asset_scheme.is_allowed_script_hash(lock_script_hash) {
+                return Err(RuntimeError::ScriptNotAllowed(*lock_script_hash).into())
+            }
+            if asset_scheme.is_frozen_script_hash(lock_script_hash) {
+                return Err(RuntimeError::ScriptHashFrozen(*lock_script_hash).into())
+            }
+            if asset.is_frozen() {
+                asset_scheme.decrease_frozen_assets();
+            }
+        }
+
+        self.kill_asset(tracker, index);
+        self.create_asset(transaction_tracker, 0, asset_type, *lock_script_hash, parameters.to_vec(), quantity)?;
+        ctrace!(
+            TX,
+            "Clawed back asset {}:{}:{} to {}:{}:{}",
+            shard_id,
+            tracker,
+            index,
+            shard_id,
+            transaction_tracker,
+            0
+        );
+        Ok(())
+    }
+
+    fn check_asset_of_out_point(&self, asset: &OwnedAsset, prev_out: &AssetOutPoint) -> StateResult<()> {
+        let AssetOutPoint {
+            index,
+            tracker,
+            asset_type,
+            shard_id,
+            quantity,
+        } = *prev_out;
         if asset.quantity() != quantity {
             return Err(RuntimeError::InvalidAssetQuantity {
                 shard_id,
@@ -496,6 +696,56 @@ impl<'db> ShardLevelState<'db> {
             }
             .into())
         }
+        Ok(())
+    }
+
+    fn check_input_asset(
+        &self,
+        input: &AssetTransferInput,
+        sender: &Address,
+        approvers: &[Address],
+    ) -> StateResult<(OwnedAsset, bool)> {
+        let AssetOutPoint {
+            index,
+            tracker,
+            asset_type,
+            shard_id,
+            ..
+        } = input.prev_out;
+
+        assert_eq!(self.shard_id, shard_id);
+        let asset_scheme = self.asset_scheme(asset_type)?.ok_or_else(|| RuntimeError::AssetSchemeNotFound {
+            shard_id,
+            asset_type,
+        })?;
+        let approved_by_regulator = self.approved_by_registrar(asset_type, sender, approvers)?;
+        if !approved_by_regulator {
+            if let Some(approver) = asset_scheme.approver() {
+                if sender != approver && !approvers.contains(approver) {
+                    return Err(RuntimeError::NotApproved(*approver).into())
+                }
+            }
+        }
+
+        let asset = self.asset(tracker, index)?.ok_or_else(|| RuntimeError::AssetNotFound {
+            shard_id,
+            tracker,
+            index,
+        })?;
+        self.check_asset_of_out_point(&asset, &input.prev_out)?;
+
+        // The registrar can't move the frozen assets either; it claws them back instead.
+        if asset.is_frozen() {
+            return Err(RuntimeError::AssetFrozen {
+                shard_id,
+                tracker,
+                index,
+            }
+            .into())
+        }
+        if asset_scheme.is_frozen_script_hash(asset.lock_script_hash()) {
+            return Err(RuntimeError::ScriptHashFrozen(*asset.lock_script_hash()).into())
+        }
         Ok((asset, approved_by_regulator))
     }
 
@@ -552,28 +802,30 @@ impl<'db> ShardLevelState<'db> {
             .into())
         }
 
-        let script_result = match (decode(&input.lock_script), decode(&input.unlock_script)) {
-            (Ok(lock_script), Ok(unlock_script)) => execute(
-                &unlock_script,
-                &asset.parameters(),
-                &lock_script,
-                to_hash,
-                VMConfig::default(),
-                input,
-                burn,
-                client,
-                parent_block_number,
-                parent_block_timestamp,
-            ),
-            // FIXME : Deliver full decode error
-            _ => return Err(RuntimeError::InvalidScript.into()),
-        };
+        let script_result =
+            match (decode_at_era(&input.lock_script, self.era), decode_at_era(&input.unlock_script, self.era)) {
+                (Ok(lock_script), Ok(unlock_script)) => execute(
+                    &unlock_script,
+                    &asset.parameters(),
+                    &lock_script,
+                    to_hash,
+                    VMConfig::with_max_cost(self.max_script_cost),
+                    input,
+                    burn,
+                    client,
+                    parent_block_number,
+                    parent_block_timestamp,
+                ),
+                // FIXME : Deliver full decode error
+                _ => return Err(RuntimeError::InvalidScript.into()),
+            };
 
         match (script_result, burn) {
             (Ok(ScriptResult::Burnt), true) => Ok(()),
             (Ok(ScriptResult::Burnt), false) => Err(UnlockFailureReason::ScriptShouldBeBurnt),
             (Ok(ScriptResult::Unlocked), false) => Ok(()),
             (Ok(ScriptResult::Unlocked), true) => Err(UnlockFailureReason::ScriptShouldNotBeBurnt),
+            (Err(cvm::RuntimeError::OutOfGas), _) => Err(UnlockFailureReason::ScriptOutOfGas),
             (Ok(ScriptResult::Fail), _) | (Err(_), _) => Err(UnlockFailureReason::ScriptError),
         }
         .map_err(|reason| {
@@ -681,6 +933,12 @@ impl<'db> ShardLevelState<'db> {
         self.cache.asset_scheme_mut(&AssetSchemeAddress::new(asset_type, shard_id), &trie)
     }
 
+    fn get_asset_mut(&self, tracker: Tracker, index: usize) -> cmerkle::Result<RefMut<'_, OwnedAsset>> {
+        let db = self.db.borrow();
+        let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
+        self.cache.asset_mut(&OwnedAssetAddress::new(tracker, index, self.shard_id), &trie)
+    }
+
     pub fn create_asset(
         &self,
         tracker: Tracker,
@@ -724,6 +982,12 @@ impl<'db> ShardStateView for ShardLevelState<'db> {
         let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
         self.cache.shard_text(&ShardTextAddress::new(tracker, self.shard_id), &trie)
     }
+
+    fn asset_proof(&self, tracker: Tracker, index: usize) -> Result<StateProof, TrieError> {
+        let db = self.db.borrow();
+        let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
+        StateProof::from_trie(&trie, OwnedAssetAddress::new(tracker, index, self.shard_id).as_ref())
+    }
 }
 
 impl<'db> StateWithCheckpoint for ShardLevelState<'db> {
@@ -813,6 +1077,12 @@ impl<'db> ShardStateView for ReadOnlyShardLevelState<'db> {
         let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
         self.cache.shard_text(&ShardTextAddress::new(tracker, self.shard_id), &trie)
     }
+
+    fn asset_proof(&self, tracker: Tracker, index: usize) -> Result<StateProof, TrieError> {
+        let db = self.db.borrow();
+        let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
+        StateProof::from_trie(&trie, OwnedAssetAddress::new(tracker, index, self.shard_id).as_ref())
+    }
 }
 
 #[cfg(test)]
@@ -1813,6 +2083,244 @@ mod tests {
         ]);
     }
 
+    #[test]
+    fn frozen_asset_cannot_be_transferred_but_can_be_clawed_back() {
+        let sender = address();
+        let mut state_db = RefCell::new(get_temp_state_db());
+        let mut shard_cache = ShardCache::default();
+        let mut state = get_temp_shard_state(&mut state_db, SHARD_ID, &mut shard_cache);
+
+        let registrar = address();
+        let metadata = "metadata".to_string();
+        let lock_script_hash = H160::from("b042ad154a3359d276835c903587ebafefea22af");
+        let amount = 30;
+        let mint =
+            asset_mint!(asset_mint_output!(lock_script_hash, supply: amount), metadata.clone(), registrar: registrar);
+        let mint_tracker = mint.tracker();
+        let asset_type = Blake::blake(*mint_tracker);
+
+        assert_eq!(Ok(()), state.apply(&mint, &sender, &[sender], &[], &get_test_client(), 0, 0));
+
+        let freeze = ShardTransaction::SetAssetFrozen {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 0,
+            asset_tracker: mint_tracker,
+            index: 0,
+            frozen: true,
+        };
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::InsufficientPermission)),
+            state.apply(&freeze, &sender, &[], &[], &get_test_client(), 0, 0)
+        );
+        assert_eq!(Ok(()), state.apply(&freeze, &registrar, &[], &[], &get_test_client(), 0, 0));
+
+        let transfer = asset_transfer!(
+            inputs: asset_transfer_inputs![(asset_out_point!(mint_tracker, 0, asset_type, amount))],
+            asset_transfer_outputs![(H160::random(), asset_type, amount)]
+        );
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::AssetFrozen {
+                shard_id: SHARD_ID,
+                tracker: mint_tracker,
+                index: 0,
+            })),
+            state.apply(&transfer, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+
+        let remove_registrar = ShardTransaction::ChangeAssetScheme {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 1,
+            metadata: metadata.clone(),
+            approver: None,
+            registrar: None,
+            allowed_script_hashes: Vec::new(),
+        };
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::CannotRemoveRegistrarOfFrozenAssets)),
+            state.apply(&remove_registrar, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+
+        let new_lock_script_hash = H160::random();
+        let clawback = ShardTransaction::ClawbackAsset {
+            network_id: "tc".into(),
+            prev_out: asset_out_point!(mint_tracker, 0, asset_type, amount),
+            lock_script_hash: new_lock_script_hash,
+            parameters: vec![],
+        };
+        let clawback_tracker = clawback.tracker();
+        assert_eq!(Ok(()), state.apply(&clawback, &sender, &[], &[registrar], &get_test_client(), 0, 0));
+        assert_eq!(0, state.asset_scheme(asset_type).unwrap().unwrap().frozen_assets());
+
+        check_shard_level_state!(state, [
+            (scheme: (asset_type) => { metadata: metadata, supply: amount, registrar: registrar }),
+            (asset: (mint_tracker, 0)),
+            (asset: (clawback_tracker, 0) => { asset_type: asset_type, quantity: amount })
+        ]);
+        let asset = state.asset(clawback_tracker, 0).unwrap().unwrap();
+        assert_eq!(&new_lock_script_hash, asset.lock_script_hash());
+        assert!(!asset.is_frozen());
+
+        assert_eq!(Ok(()), state.apply(&remove_registrar, &registrar, &[], &[], &get_test_client(), 0, 0));
+    }
+
+    #[test]
+    fn cannot_clawback_asset_to_disallowed_or_frozen_script_hash() {
+        let sender = address();
+        let mut state_db = RefCell::new(get_temp_state_db());
+        let mut shard_cache = ShardCache::default();
+        let mut state = get_temp_shard_state(&mut state_db, SHARD_ID, &mut shard_cache);
+
+        let registrar = address();
+        let metadata = "metadata".to_string();
+        let lock_script_hash = H160::from("b042ad154a3359d276835c903587ebafefea22af");
+        let amount = 30;
+        let mint = asset_mint!(
+            asset_mint_output!(lock_script_hash, supply: amount),
+            metadata,
+            registrar: registrar,
+            allowed_script_hashes: vec![lock_script_hash]
+        );
+        let mint_tracker = mint.tracker();
+        let asset_type = Blake::blake(*mint_tracker);
+
+        assert_eq!(Ok(()), state.apply(&mint, &sender, &[sender], &[], &get_test_client(), 0, 0));
+
+        let disallowed_script_hash = H160::random();
+        let clawback = ShardTransaction::ClawbackAsset {
+            network_id: "tc".into(),
+            prev_out: asset_out_point!(mint_tracker, 0, asset_type, amount),
+            lock_script_hash: disallowed_script_hash,
+            parameters: vec![],
+        };
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::ScriptNotAllowed(disallowed_script_hash))),
+            state.apply(&clawback, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+
+        let freeze = ShardTransaction::SetScriptHashFrozen {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 0,
+            lock_script_hash,
+            frozen: true,
+        };
+        assert_eq!(Ok(()), state.apply(&freeze, &registrar, &[], &[], &get_test_client(), 0, 0));
+
+        let clawback = ShardTransaction::ClawbackAsset {
+            network_id: "tc".into(),
+            prev_out: asset_out_point!(mint_tracker, 0, asset_type, amount),
+            lock_script_hash,
+            parameters: vec![],
+        };
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::ScriptHashFrozen(lock_script_hash))),
+            state.apply(&clawback, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+        check_shard_level_state!(state, [
+            (asset: (mint_tracker, 0) => { asset_type: asset_type, quantity: amount })
+        ]);
+    }
+
+    #[test]
+    fn assets_of_frozen_script_hash_cannot_be_transferred() {
+        let sender = address();
+        let mut state_db = RefCell::new(get_temp_state_db());
+        let mut shard_cache = ShardCache::default();
+        let mut state = get_temp_shard_state(&mut state_db, SHARD_ID, &mut shard_cache);
+
+        let registrar = address();
+        let metadata = "metadata".to_string();
+        let lock_script_hash = H160::from("b042ad154a3359d276835c903587ebafefea22af");
+        let amount = 30;
+        let mint =
+            asset_mint!(asset_mint_output!(lock_script_hash, supply: amount), metadata.clone(), registrar: registrar);
+        let mint_tracker = mint.tracker();
+        let asset_type = Blake::blake(*mint_tracker);
+
+        assert_eq!(Ok(()), state.apply(&mint, &sender, &[sender], &[], &get_test_client(), 0, 0));
+
+        let freeze = ShardTransaction::SetScriptHashFrozen {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 0,
+            lock_script_hash,
+            frozen: true,
+        };
+        assert_eq!(Ok(()), state.apply(&freeze, &registrar, &[], &[], &get_test_client(), 0, 0));
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::InvalidSeqOfAssetScheme {
+                asset_type,
+                shard_id: SHARD_ID,
+                expected: 1,
+                actual: 0,
+            })),
+            state.apply(&freeze, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+
+        let burn =
+            asset_transfer!(burns: asset_transfer_inputs![(asset_out_point!(mint_tracker, 0, asset_type, amount))]);
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::ScriptHashFrozen(lock_script_hash))),
+            state.apply(&burn, &registrar, &[], &[], &get_test_client(), 0, 0)
+        );
+    }
+
+    #[test]
+    fn update_allowed_script_hashes() {
+        let sender = address();
+        let mut state_db = RefCell::new(get_temp_state_db());
+        let mut shard_cache = ShardCache::default();
+        let mut state = get_temp_shard_state(&mut state_db, SHARD_ID, &mut shard_cache);
+
+        let registrar = address();
+        let metadata = "metadata".to_string();
+        let lock_script_hash = H160::random();
+        let allowed_script_hash = H160::random();
+        let mint = asset_mint!(
+            asset_mint_output!(lock_script_hash, supply: 30),
+            metadata,
+            registrar: registrar,
+            allowed_script_hashes: vec![lock_script_hash]
+        );
+        let mint_tracker = mint.tracker();
+        let asset_type = Blake::blake(*mint_tracker);
+
+        assert_eq!(Ok(()), state.apply(&mint, &sender, &[sender], &[], &get_test_client(), 0, 0));
+
+        let update = ShardTransaction::UpdateAllowedScriptHashes {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 0,
+            added: vec![allowed_script_hash],
+            removed: vec![lock_script_hash],
+        };
+        assert_eq!(Ok(()), state.apply(&update, &sender, &[], &[registrar], &get_test_client(), 0, 0));
+
+        let asset_scheme = state.asset_scheme(asset_type).unwrap().unwrap();
+        assert_eq!(asset_scheme.allowed_script_hashes(), &[allowed_script_hash]);
+        assert_eq!(asset_scheme.seq(), 1);
+
+        let empty = ShardTransaction::UpdateAllowedScriptHashes {
+            network_id: "tc".into(),
+            shard_id: SHARD_ID,
+            asset_type,
+            seq: 1,
+            added: Vec::new(),
+            removed: vec![allowed_script_hash],
+        };
+        assert_eq!(
+            Err(StateError::Runtime(RuntimeError::CannotEmptyAllowedScriptHashes)),
+            state.apply(&empty, &sender, &[], &[registrar], &get_test_client(), 0, 0)
+        );
+    }
+
     #[test]
     fn store_shard_text() {
         let sender = address();
@@ -105,6 +105,17 @@ macro_rules! asset_mint {
             allowed_script_hashes: $allowed,
         }
     };
+    ($output:expr, $metadata:expr, registrar: $admin:expr, allowed_script_hashes: $allowed:expr) => {
+        $crate::ctypes::transaction::ShardTransaction::MintAsset {
+            network_id: $crate::impls::test_helper::NETWORK_ID.into(),
+            shard_id: $crate::impls::test_helper::SHARD_ID,
+            metadata: $metadata,
+            output: $output,
+            approver: None,
+            registrar: Some($admin),
+            allowed_script_hashes: $allowed,
+        }
+    };
 }
 
 macro_rules! asset_mint_output {
@@ -42,7 +42,7 @@ use crate::traits::{ShardState, ShardStateView, StateWithCache, TopState, TopSta
 use crate::Asset;
 use crate::{
     Account, ActionData, FindActionHandler, Metadata, MetadataAddress, RegularAccount, RegularAccountAddress, Shard,
-    ShardAddress, ShardLevelState, StateDB, StateResult, Text,
+    ShardAddress, ShardLevelState, StateDB, StateProof, StateResult, Text,
 };
 use ccrypto::BLAKE_NULL_RLP;
 use cdb::{AsHashDB, DatabaseError};
@@ -93,6 +93,12 @@ pub struct TopLevelState {
     top_cache: TopCache,
     shard_caches: HashMap<ShardId, ShardCache>,
     id_of_checkpoints: Vec<CheckpointId>,
+    /// Keys of the action data written by the latest applied transaction
+    touched_action_data: Vec<H256>,
+    /// The cost a lock script can consume. Zero means no limit.
+    max_script_cost: u64,
+    /// The era of the common params, which decides the instructions available in the lock scripts.
+    era: u64,
 }
 
 impl TopStateView for TopLevelState {
@@ -148,6 +154,12 @@ impl TopStateView for TopLevelState {
         let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
         Ok(self.top_cache.action_data(key, &trie)?.map(Into::into))
     }
+
+    fn top_level_proof(&self, key: &[u8]) -> TrieResult<StateProof> {
+        let db = self.db.borrow();
+        let trie = TrieFactory::readonly(db.as_hashdb(), &self.root)?;
+        StateProof::from_trie(&trie, key)
+    }
 }
 
 impl StateWithCache for TopLevelState {
@@ -243,6 +255,9 @@ impl TopLevelState {
             top_cache,
             shard_caches,
             id_of_checkpoints: Default::default(),
+            touched_action_data: Default::default(),
+            max_script_cost: 0,
+            era: 0,
         };
 
         Ok(state)
@@ -260,6 +275,7 @@ impl TopLevelState {
         parent_block_timestamp: u64,
         current_block_timestamp: u64,
     ) -> StateResult<()> {
+        self.touched_action_data.clear();
         self.create_checkpoint(FEE_CHECKPOINT);
         let result = self.apply_internal(
             tx,
@@ -276,11 +292,23 @@ impl TopLevelState {
             }
             Err(_) => {
                 self.revert_to_checkpoint(FEE_CHECKPOINT);
+                self.touched_action_data.clear();
             }
         }
         result
     }
 
+    /// Sets the parameters of the lock scripts run by the transactions applied afterwards.
+    pub fn set_script_params(&mut self, params: &CommonParams) {
+        self.max_script_cost = params.max_script_cost();
+        self.era = params.era();
+    }
+
+    /// Returns the keys of the action data written by the latest transaction applied with `apply`.
+    pub fn touched_action_data(&self) -> &[H256] {
+        &self.touched_action_data
+    }
+
     // Change the public to an owner address if it is a regular key.
     fn public_to_owner_address(&self, public: &Public) -> StateResult<Address> {
         Ok(if self.regular_account_exists_and_not_null(public)? {
@@ -414,6 +442,22 @@ impl TopLevelState {
             Action::IncreaseAssetSupply {
                 approvals,
                 ..
+            }
+            | Action::SetAssetFrozen {
+                approvals,
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                approvals,
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                approvals,
+                ..
+            }
+            | Action::ClawbackAsset {
+                approvals,
+                ..
             } => {
                 let transaction = Option::<ShardTransaction>::from(action.clone()).expect("It's a shard transaction");
                 let transaction_tracker = transaction.tracker();
@@ -606,6 +650,7 @@ impl TopLevelState {
 
         let shard_cache = self.shard_caches.entry(shard_id).or_default();
         let mut shard_level_state = ShardLevelState::from_existing(shard_id, &mut self.db, shard_root, shard_cache)?;
+        shard_level_state.set_script_params(self.max_script_cost, self.era);
         shard_level_state.apply(
             &transaction,
             sender,
@@ -684,8 +729,16 @@ impl TopLevelState {
         self.top_cache.action_data_mut(key, &trie)
     }
 
+    fn touch_action_data(&mut self, key: &H256) {
+        if !self.touched_action_data.contains(key) {
+            self.touched_action_data.push(*key);
+        }
+    }
+
     pub fn journal_under(&self, batch: &mut DBTransaction, now: u64) -> Result<u32, DatabaseError> {
-        self.db.borrow_mut().journal_under(batch, now, self.root)
+        let mut db = self.db.borrow_mut();
+        db.journal_root(batch, now, self.root);
+        db.journal_under(batch, now, self.root)
     }
 
     pub fn top_cache(&self) -> &TopCache {
@@ -797,6 +850,9 @@ impl Clone for TopLevelState {
             id_of_checkpoints: self.id_of_checkpoints.clone(),
             top_cache: self.top_cache.clone(),
             shard_caches: self.shard_caches.clone(),
+            touched_action_data: self.touched_action_data.clone(),
+            max_script_cost: self.max_script_cost,
+            era: self.era,
         }
     }
 }
@@ -981,13 +1037,14 @@ impl TopState for TopLevelState {
     }
 
     fn update_action_data(&mut self, key: &H256, data: Bytes) -> StateResult<()> {
-        let mut action_data = self.get_action_data_mut(key)?;
-        *action_data = data.into();
+        *self.get_action_data_mut(key)? = data.into();
+        self.touch_action_data(key);
         Ok(())
     }
 
     fn remove_action_data(&mut self, key: &H256) {
-        self.top_cache.remove_action_data(key)
+        self.top_cache.remove_action_data(key);
+        self.touch_action_data(key);
     }
 
     fn update_params(&mut self, metadata_seq: u64, params: CommonParams) -> StateResult<()> {
@@ -1268,6 +1325,26 @@ mod tests_state {
         assert_eq!(Ok(18), state.balance(&b));
     }
 
+    #[test]
+    fn account_proof() {
+        let mut state = get_temp_state();
+        let a = Address::default();
+        let b = 1u64.into();
+        assert_eq!(Ok(()), state.add_balance(&a, 100));
+        let root = state.commit();
+        assert!(root.is_ok(), "{:?}", root);
+
+        let proof = state.account_proof(&a).unwrap();
+        assert_eq!(root.unwrap(), proof.root);
+        assert!(proof.verify());
+        let account = ::rlp::decode::<Account>(proof.value.as_ref().unwrap()).unwrap();
+        assert_eq!(100, account.balance());
+
+        let proof = state.account_proof(&b).unwrap();
+        assert_eq!(None, proof.value);
+        assert!(proof.verify());
+    }
+
     #[test]
     fn alter_seq() {
         let mut state = get_temp_state();
@@ -47,6 +47,7 @@ pub struct OwnedAsset {
     asset: Asset,
     lock_script_hash: H160,
     parameters: Vec<Bytes>,
+    frozen: bool,
 }
 
 impl OwnedAsset {
@@ -58,6 +59,7 @@ impl OwnedAsset {
             },
             lock_script_hash,
             parameters,
+            frozen: false,
         }
     }
 
@@ -76,6 +78,14 @@ impl OwnedAsset {
     pub fn quantity(&self) -> u64 {
         self.asset.quantity()
     }
+
+    pub fn is_frozen(&self) -> bool {
+        self.frozen
+    }
+
+    pub fn set_frozen(&mut self, frozen: bool) {
+        self.frozen = frozen;
+    }
 }
 
 impl Default for OwnedAsset {
@@ -87,6 +97,7 @@ impl Default for OwnedAsset {
             },
             lock_script_hash: H160::zero(),
             parameters: vec![],
+            frozen: false,
         }
     }
 }
@@ -103,7 +114,13 @@ const PREFIX: u8 = super::OWNED_ASSET_PREFIX;
 
 impl Encodable for OwnedAsset {
     fn rlp_append(&self, s: &mut RlpStream) {
-        s.begin_list(6)
+        // NOTE: The frozen field is appended only when it is set, to keep the encoding of the existing assets.
+        let item_count = if self.frozen {
+            7
+        } else {
+            6
+        };
+        s.begin_list(item_count)
             .append(&PREFIX)
             .append(self.asset.asset_type())
             .append(&self.asset.quantity())
@@ -111,13 +128,16 @@ impl Encodable for OwnedAsset {
             .append(&self.parameters)
             // NOTE: The order_hash field removed.
             .append(&Option::<H256>::None);
+        if self.frozen {
+            s.append(&true);
+        }
     }
 }
 
 impl Decodable for OwnedAsset {
     fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
         let item_count = rlp.item_count()?;
-        if rlp.item_count()? != 6 {
+        if item_count != 6 && item_count != 7 {
             return Err(DecoderError::RlpInvalidLength {
                 expected: 6,
                 got: item_count,
@@ -141,6 +161,7 @@ impl Decodable for OwnedAsset {
             },
             lock_script_hash: rlp.val_at(3)?,
             parameters: rlp.val_at(4)?,
+            frozen: item_count == 7 && rlp.val_at(6)?,
         })
     }
 }
@@ -255,4 +276,19 @@ mod tests {
             quantity: 0,
         });
     }
+
+    #[test]
+    fn encode_and_decode_owned_asset() {
+        let asset = OwnedAsset::new(H160::random(), H160::random(), vec![vec![1, 2]], 10);
+        assert_eq!(6, Rlp::new(&rlp::encode(&asset)).item_count().unwrap());
+        rlp_encode_and_decode_test!(asset);
+    }
+
+    #[test]
+    fn encode_and_decode_frozen_owned_asset() {
+        let mut asset = OwnedAsset::new(H160::random(), H160::random(), vec![], 10);
+        asset.set_frozen(true);
+        assert_eq!(7, Rlp::new(&rlp::encode(&asset)).item_count().unwrap());
+        rlp_encode_and_decode_test!(asset);
+    }
 }
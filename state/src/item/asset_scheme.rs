@@ -32,6 +32,8 @@ pub struct AssetScheme {
     allowed_script_hashes: Vec<H160>,
     pool: Vec<Asset>,
     seq: usize,
+    frozen_script_hashes: Vec<H160>,
+    frozen_assets: u64,
 }
 
 impl AssetScheme {
@@ -50,6 +52,8 @@ impl AssetScheme {
             allowed_script_hashes,
             pool: Vec::new(),
             seq: 0,
+            frozen_script_hashes: Vec::new(),
+            frozen_assets: 0,
         }
     }
 
@@ -69,6 +73,8 @@ impl AssetScheme {
             allowed_script_hashes,
             pool,
             seq: 0,
+            frozen_script_hashes: Vec::new(),
+            frozen_assets: 0,
         }
     }
 
@@ -109,6 +115,22 @@ impl AssetScheme {
         allowed_hashes.is_empty() || allowed_hashes.contains(lock_script_hash)
     }
 
+    pub fn frozen_script_hashes(&self) -> &[H160] {
+        &self.frozen_script_hashes
+    }
+
+    pub fn is_frozen_script_hash(&self, lock_script_hash: &H160) -> bool {
+        self.frozen_script_hashes.contains(lock_script_hash)
+    }
+
+    pub fn frozen_assets(&self) -> u64 {
+        self.frozen_assets
+    }
+
+    pub fn has_frozen_assets(&self) -> bool {
+        self.frozen_assets != 0 || !self.frozen_script_hashes.is_empty()
+    }
+
     pub fn pool(&self) -> &[Asset] {
         &self.pool
     }
@@ -126,6 +148,34 @@ impl AssetScheme {
         self.allowed_script_hashes = allowed_script_hashes;
     }
 
+    pub fn set_script_hash_frozen(&mut self, lock_script_hash: H160, frozen: bool) {
+        if frozen {
+            if !self.frozen_script_hashes.contains(&lock_script_hash) {
+                self.frozen_script_hashes.push(lock_script_hash);
+            }
+        } else {
+            self.frozen_script_hashes.retain(|hash| hash != &lock_script_hash);
+        }
+    }
+
+    pub fn increase_frozen_assets(&mut self) {
+        self.frozen_assets += 1;
+    }
+
+    pub fn decrease_frozen_assets(&mut self) {
+        debug_assert!(self.frozen_assets > 0, "The number of frozen assets shouldn't be negative");
+        self.frozen_assets = self.frozen_assets.saturating_sub(1);
+    }
+
+    pub fn update_allowed_script_hashes(&mut self, added: &[H160], removed: &[H160]) {
+        self.allowed_script_hashes.retain(|hash| !removed.contains(hash));
+        for hash in added {
+            if !self.allowed_script_hashes.contains(hash) {
+                self.allowed_script_hashes.push(*hash);
+            }
+        }
+    }
+
     pub fn increase_supply(&mut self, quantity: u64) -> Result<u64, RuntimeError> {
         let headroom = std::u64::MAX - self.supply;
         if quantity > headroom {
@@ -158,11 +208,16 @@ impl Default for AssetScheme {
 
 impl Encodable for AssetScheme {
     fn rlp_append(&self, s: &mut RlpStream) {
-        if self.seq == 0 {
-            s.begin_list(7);
+        let item_count = if self.frozen_assets != 0 {
+            10
+        } else if !self.frozen_script_hashes.is_empty() {
+            9
+        } else if self.seq != 0 {
+            8
         } else {
-            s.begin_list(8);
-        }
+            7
+        };
+        s.begin_list(item_count);
         s.append(&PREFIX)
             .append(&self.metadata)
             .append(&self.supply)
@@ -170,17 +225,25 @@ impl Encodable for AssetScheme {
             .append(&self.registrar)
             .append_list(&self.allowed_script_hashes)
             .append_list(&self.pool);
-        if self.seq != 0 {
+        if item_count >= 8 {
             s.append(&self.seq);
         }
+        if item_count >= 9 {
+            s.append_list(&self.frozen_script_hashes);
+        }
+        if item_count >= 10 {
+            s.append(&self.frozen_assets);
+        }
     }
 }
 
 impl Decodable for AssetScheme {
     fn decode(rlp: &Rlp<'_>) -> Result<Self, DecoderError> {
-        let seq = match rlp.item_count()? {
-            7 => 0,
-            8 => rlp.val_at(7)?,
+        let (seq, frozen_script_hashes, frozen_assets) = match rlp.item_count()? {
+            7 => (0, Vec::new(), 0),
+            8 => (rlp.val_at(7)?, Vec::new(), 0),
+            9 => (rlp.val_at(7)?, rlp.list_at(8)?, 0),
+            10 => (rlp.val_at(7)?, rlp.list_at(8)?, rlp.val_at(9)?),
             item_count => {
                 return Err(DecoderError::RlpInvalidLength {
                     got: item_count,
@@ -202,6 +265,8 @@ impl Decodable for AssetScheme {
             allowed_script_hashes: rlp.list_at(5)?,
             pool: rlp.list_at(6)?,
             seq,
+            frozen_script_hashes,
+            frozen_assets,
         })
     }
 }
@@ -234,6 +299,8 @@ impl CacheableItem for AssetScheme {
 
 #[cfg(test)]
 mod tests {
+    use rlp::rlp_encode_and_decode_test;
+
     use super::*;
 
     #[test]
@@ -267,4 +334,36 @@ mod tests {
         let asset_scheme_address = AssetSchemeAddress::from_hash(hash).unwrap();
         assert_eq!(shard_id, asset_scheme_address.shard_id());
     }
+
+    #[test]
+    fn encode_and_decode_asset_scheme_with_frozen_script_hashes() {
+        let mut scheme = AssetScheme::new("metadata".to_string(), 10, None, None, vec![H160::random()]);
+        rlp_encode_and_decode_test!(scheme.clone());
+        scheme.set_script_hash_frozen(H160::random(), true);
+        assert_eq!(9, Rlp::new(&rlp::encode(&scheme)).item_count().unwrap());
+        rlp_encode_and_decode_test!(scheme);
+    }
+
+    #[test]
+    fn encode_and_decode_asset_scheme_with_frozen_assets() {
+        let mut scheme = AssetScheme::new("metadata".to_string(), 10, None, None, Vec::new());
+        scheme.increase_frozen_assets();
+        assert!(scheme.has_frozen_assets());
+        assert_eq!(10, Rlp::new(&rlp::encode(&scheme)).item_count().unwrap());
+        rlp_encode_and_decode_test!(scheme.clone());
+
+        scheme.decrease_frozen_assets();
+        assert!(!scheme.has_frozen_assets());
+        assert_eq!(7, Rlp::new(&rlp::encode(&scheme)).item_count().unwrap());
+    }
+
+    #[test]
+    fn update_allowed_script_hashes() {
+        let hash1 = H160::random();
+        let hash2 = H160::random();
+        let hash3 = H160::random();
+        let mut scheme = AssetScheme::new("metadata".to_string(), 10, None, None, vec![hash1, hash2]);
+        scheme.update_allowed_script_hashes(&[hash3, hash1], &[hash2]);
+        assert_eq!(scheme.allowed_script_hashes(), &[hash1, hash3]);
+    }
 }
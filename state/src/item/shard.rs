@@ -62,6 +62,15 @@ impl Shard {
     pub fn set_users(&mut self, users: Vec<Address>) {
         self.users = users;
     }
+
+    /// Returns the root of the shard if `bytes` is an encoded shard.
+    pub(crate) fn root_of_encoded(bytes: &[u8]) -> Option<H256> {
+        let rlp = Rlp::new(bytes);
+        if !rlp.is_list() || rlp.item_count() != Ok(4) || rlp.val_at::<u8>(0) != Ok(PREFIX) {
+            return None
+        }
+        rlp.val_at(1).ok()
+    }
 }
 
 impl Default for Shard {
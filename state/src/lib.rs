@@ -36,13 +36,15 @@ mod db;
 mod error;
 mod impls;
 mod item;
+mod proof;
 mod traits;
 
 pub mod tests;
 
 pub use crate::action_handler::{ActionDataKeyBuilder, ActionHandler, FindActionHandler, HitHandler};
+pub use crate::cache::cache_lookup_counts;
 pub use crate::checkpoint::{CheckpointId, StateWithCheckpoint};
-pub use crate::db::StateDB;
+pub use crate::db::{StateDB, StatePruner};
 pub use crate::error::Error as StateError;
 pub use crate::impls::{ShardLevelState, TopLevelState};
 pub use crate::item::account::Account;
@@ -54,6 +56,7 @@ pub use crate::item::metadata::{Metadata, MetadataAddress};
 pub use crate::item::regular_account::{RegularAccount, RegularAccountAddress};
 pub use crate::item::shard::{Shard, ShardAddress};
 pub use crate::item::text::Text;
+pub use crate::proof::StateProof;
 pub use crate::traits::{ShardState, ShardStateView, StateWithCache, TopState, TopStateView};
 
 use crate::cache::CacheableItem;
@@ -0,0 +1,51 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use cmerkle::{verify_proof, ProofResult, Result as TrieResult, Trie};
+use primitives::{Bytes, H256};
+
+/// A Merkle proof of an item in a state trie.
+#[derive(Clone, Debug, PartialEq)]
+pub struct StateProof {
+    /// The root of the trie
+    pub root: H256,
+    /// The key of the item in the trie
+    pub key: Bytes,
+    /// The RLP encoded item, or `None` if the item doesn't exist
+    pub value: Option<Bytes>,
+    /// The encoded trie nodes from the root to the item
+    pub nodes: Vec<Bytes>,
+}
+
+impl StateProof {
+    pub(crate) fn from_trie(trie: &dyn Trie, key: &[u8]) -> TrieResult<Self> {
+        Ok(Self {
+            root: *trie.root(),
+            key: key.to_vec(),
+            value: trie.get(key)?,
+            nodes: trie.get_proof(key)?,
+        })
+    }
+
+    /// Check that the nodes prove the value against the root.
+    pub fn verify(&self) -> bool {
+        match verify_proof(&self.root, &self.key, &self.nodes) {
+            ProofResult::Included(value) => self.value.as_ref() == Some(&value),
+            ProofResult::Excluded => self.value.is_none(),
+            ProofResult::Invalid => false,
+        }
+    }
+}
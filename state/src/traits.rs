@@ -15,8 +15,8 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::{
-    Account, ActionData, AssetScheme, CacheableItem, Metadata, OwnedAsset, RegularAccount, Shard, ShardText, StateDB,
-    StateResult, Text,
+    Account, ActionData, AssetScheme, CacheableItem, Metadata, OwnedAsset, RegularAccount, Shard, ShardAddress,
+    ShardText, StateDB, StateProof, StateResult, Text,
 };
 use ckey::{public_to_address, Address, Public, Signature};
 use cmerkle::Result as TrieResult;
@@ -124,6 +124,37 @@ pub trait TopStateView {
     fn text(&self, key: &H256) -> TrieResult<Option<Text>>;
 
     fn action_data(&self, key: &H256) -> TrieResult<Option<ActionData>>;
+
+    /// Get the Merkle proof of the item at `key` in the top-level trie.
+    fn top_level_proof(&self, key: &[u8]) -> TrieResult<StateProof>;
+
+    fn account_proof(&self, a: &Address) -> TrieResult<StateProof> {
+        self.top_level_proof(a.as_ref())
+    }
+
+    fn shard_proof(&self, shard_id: ShardId) -> TrieResult<StateProof> {
+        self.top_level_proof(ShardAddress::new(shard_id).as_ref())
+    }
+
+    fn action_data_proof(&self, key: &H256) -> TrieResult<StateProof> {
+        self.top_level_proof(key.as_ref())
+    }
+
+    /// Get the Merkle proof of the shard in the top-level trie and that of the asset in the shard-level trie.
+    /// The latter is `None` if the shard doesn't exist.
+    fn asset_proof(
+        &self,
+        shard_id: ShardId,
+        tracker: Tracker,
+        index: usize,
+    ) -> TrieResult<(StateProof, Option<StateProof>)> {
+        let shard_proof = self.shard_proof(shard_id)?;
+        let asset_proof = match self.shard_state(shard_id)? {
+            Some(state) => Some(state.asset_proof(tracker, index)?),
+            None => None,
+        };
+        Ok((shard_proof, asset_proof))
+    }
 }
 
 pub trait ShardStateView {
@@ -133,6 +164,8 @@ pub trait ShardStateView {
     fn asset(&self, tracker: Tracker, index: usize) -> TrieResult<Option<OwnedAsset>>;
     /// Get shard text.
     fn text(&self, tracker: Tracker) -> TrieResult<Option<ShardText>>;
+    /// Get the Merkle proof of the asset in the shard-level trie.
+    fn asset_proof(&self, tracker: Tracker, index: usize) -> TrieResult<StateProof>;
 }
 
 pub trait ShardState {
@@ -0,0 +1,166 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use super::super::message::RequestMessage;
+use ccore::{BlockChainTrait, BlockId, Client, COL_EXTRA};
+use cdb::AsHashDB;
+use cmerkle::snapshot::{ChunkDecompressor, Error as SnapshotError, Restore};
+use ctypes::{BlockHash, BlockNumber};
+use kvdb::DBTransaction;
+use primitives::H256;
+use std::collections::HashSet;
+use std::io::Cursor;
+use std::sync::Arc;
+
+const MAX_CHUNK_REQUEST_LENGTH: usize = 16;
+const RESTORED_CHUNK_PREFIX: &[u8] = b"restored-chunk";
+
+/// Downloads the chunks of a trie in the snapshot block.
+///
+/// The roots of the restored chunks are recorded in the DB with their nodes,
+/// so the restoration resumes from them after a restart.
+pub struct ChunkDownloader {
+    client: Arc<Client>,
+
+    block: BlockHash,
+    number: BlockNumber,
+    restore: Restore,
+    downloading: HashSet<H256>,
+    restored: usize,
+}
+
+impl ChunkDownloader {
+    pub fn new(client: Arc<Client>, block: BlockHash, trie_root: H256) -> Self {
+        let number = client.block_number(&BlockId::Hash(block)).expect("Snapshot header must exist");
+        Self {
+            client,
+
+            block,
+            number,
+            restore: Restore::new(trie_root),
+            downloading: HashSet::new(),
+            restored: 0,
+        }
+    }
+
+    pub fn block(&self) -> BlockHash {
+        self.block
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.restore.is_complete()
+    }
+
+    /// The number of the chunks restored by this downloader, including the ones restored before a restart.
+    pub fn restored_chunks(&self) -> usize {
+        self.restored
+    }
+
+    /// The number of the chunks known to be required but not restored yet.
+    pub fn pending_chunks(&self) -> usize {
+        self.restore.pending_chunks().count()
+    }
+
+    pub fn create_request(&mut self) -> Option<RequestMessage> {
+        // The chunks which are failed to download are requested again first.
+        let mut chunk_roots: Vec<H256> = self
+            .restore
+            .pending_chunks()
+            .filter(|chunk_root| !self.downloading.contains(chunk_root))
+            .take(MAX_CHUNK_REQUEST_LENGTH)
+            .cloned()
+            .collect();
+        while chunk_roots.len() < MAX_CHUNK_REQUEST_LENGTH {
+            let chunk_root = match self.restore.next_to_feed() {
+                Some(chunk_root) => chunk_root,
+                None => break,
+            };
+            if self.is_restored(&chunk_root) {
+                let state_db = self.client.state_db().read();
+                self.restore.feed_restored(state_db.as_hashdb(), chunk_root);
+                self.restored += 1;
+                continue
+            }
+            chunk_roots.push(chunk_root);
+        }
+        if chunk_roots.is_empty() {
+            None
+        } else {
+            self.downloading.extend(&chunk_roots);
+            Some(RequestMessage::StateChunk(self.block, chunk_roots))
+        }
+    }
+
+    /// Verifies the compressed chunk and restores it. The chunk is requested again if it's invalid.
+    pub fn import_chunk(&mut self, chunk_root: H256, chunk: &[u8]) -> Result<(), SnapshotError> {
+        self.downloading.remove(&chunk_root);
+        if !self.restore.is_pending(&chunk_root) {
+            // Already restored from another peer
+            return Ok(())
+        }
+        let recovered = ChunkDecompressor::new(Cursor::new(chunk)).decompress()?.recover(chunk_root)?;
+
+        let mut state_db = self.client.state_db().write();
+        self.restore.feed(state_db.as_hashdb_mut(), recovered);
+        let mut batch = DBTransaction::new();
+        state_db.journal_under(&mut batch, self.number, *self.block).expect("DB commit failed");
+        batch.put(COL_EXTRA, &restored_chunk_key(&chunk_root), &[]);
+        self.client.db().write_buffered(batch);
+        self.client.db().flush().expect("DB flush failed");
+        self.restored += 1;
+        Ok(())
+    }
+
+    pub fn reset_downloading(&mut self, chunk_roots: &[H256]) {
+        cdebug!(SYNC, "Reset downloading chunks {:?}", chunk_roots);
+        for chunk_root in chunk_roots {
+            self.downloading.remove(chunk_root);
+        }
+        self.downloading.shrink_to_fit();
+    }
+
+    fn is_restored(&self, chunk_root: &H256) -> bool {
+        self.client.db().get(COL_EXTRA, &restored_chunk_key(chunk_root)).expect("Low level database error").is_some()
+    }
+}
+
+impl std::fmt::Debug for ChunkDownloader {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ChunkDownloader")
+            .field("block", &self.block)
+            .field("restore", &self.restore)
+            .field("downloading", &self.downloading.len())
+            .field("restored", &self.restored)
+            .finish()
+    }
+}
+
+/// Removes the records of the restored chunks. It should be called after the restoration is finished.
+pub fn clear_restored_chunks(client: &Client) {
+    let db = client.db();
+    let mut batch = DBTransaction::new();
+    for (key, _) in db.iter_from_prefix(COL_EXTRA, RESTORED_CHUNK_PREFIX) {
+        batch.delete(COL_EXTRA, &key);
+    }
+    db.write_buffered(batch);
+    db.flush().expect("DB flush failed");
+}
+
+fn restored_chunk_key(chunk_root: &H256) -> Vec<u8> {
+    let mut key = RESTORED_CHUNK_PREFIX.to_vec();
+    key.extend_from_slice(chunk_root);
+    key
+}
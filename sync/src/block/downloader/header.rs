@@ -80,6 +80,16 @@ impl HeaderDownloader {
         }
     }
 
+    /// Restarts downloading from the best block of the local chain.
+    /// It should be called after the best block is changed without the downloader, e.g. by restoring a snapshot.
+    pub fn reset_pivot(&mut self) {
+        self.pivot = self.client.best_block_header().hash();
+        self.request_time = None;
+        self.downloaded.clear();
+        self.queued.clear();
+        self.trial = 0;
+    }
+
     fn is_valid(&self) -> bool {
         self.trial < MAX_RETRY
     }
@@ -14,15 +14,16 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::downloader::{BodyDownloader, HeaderDownloader};
+use super::downloader::{clear_restored_chunks, BodyDownloader, ChunkDownloader, HeaderDownloader};
 use super::message::{Message, RequestMessage, ResponseMessage};
+use crate::snapshot::{snapshot_list, snapshot_path};
 use ccore::encoded::Header as EncodedHeader;
 use ccore::{
     Block, BlockChainClient, BlockChainTrait, BlockId, BlockImportError, BlockStatus, ChainNotify, Client, ImportBlock,
     ImportError, StateInfo, UnverifiedTransaction,
 };
 use cdb::AsHashDB;
-use cmerkle::{Trie, TrieFactory};
+use cmerkle::{skewed_merkle_root, Trie, TrieFactory};
 use cnetwork::{Api, EventSender, NetworkExtension, NodeId};
 use cstate::{FindActionHandler, TopStateView};
 use ctimer::TimerToken;
@@ -35,8 +36,9 @@ use rand::thread_rng;
 use rlp::{Encodable, Rlp};
 use std::collections::hash_map::Entry;
 use std::collections::{HashMap, HashSet};
+use std::fs;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use token_generator::TokenGenerator;
 
 const SYNC_TIMER_TOKEN: TimerToken = 0;
@@ -46,6 +48,11 @@ const SYNC_EXPIRE_TOKEN_END: TimerToken = SYNC_EXPIRE_TOKEN_BEGIN + SYNC_EXPIRE_
 
 const SYNC_TIMER_INTERVAL: u64 = 1000;
 const SYNC_EXPIRE_REQUEST_INTERVAL: u64 = 15000;
+const SNAPSHOT_DISCOVERY_WAIT: u64 = 10_000;
+const SNAPSHOT_DISCOVERY_TIMEOUT: u64 = 60_000;
+const MAX_SNAPSHOTS_PER_PEER: usize = 16;
+const MAX_INVALID_CHUNKS_PER_PEER: usize = 3;
+const MAX_ANCHOR_HEADERS: u64 = 128;
 
 #[derive(Debug, PartialEq)]
 pub struct TokenInfo {
@@ -55,38 +62,65 @@ pub struct TokenInfo {
 
 #[derive(Debug)]
 enum State {
+    /// Waiting for the snapshots advertised by the peers since the instant
+    SnapshotDiscovery(Instant),
+    /// Following the parent hashes from the trusted checkpoint to the discovered snapshot block.
+    /// The second one is the block whose header is requested next.
+    SnapshotAnchor((BlockNumber, BlockHash), (BlockNumber, BlockHash)),
     SnapshotHeader(BlockHash, u64),
-    SnapshotBody(BlockHash),
-    SnapshotTopChunk(H256),
-    SnapshotShardChunk(ShardId, H256),
+    SnapshotTopChunk(ChunkDownloader),
+    /// Verifying the commit of the snapshot block with the validators in the restored top level state.
+    /// The state is trusted because the snapshot header is given by the operator or linked to the trusted checkpoint.
+    SnapshotCommit(BlockHash),
+    SnapshotShardChunk(ShardId, ChunkDownloader),
+    /// The transactions root of the parent block is required to verify the body
+    SnapshotBody(BlockHash, Option<H256>),
     Full,
 }
 
 impl State {
-    fn initial(client: &Client, snapshot_target: Option<(H256, u64)>) -> Self {
+    fn initial(client: &Arc<Client>, snapshot_target: Option<(H256, u64)>, snapshot_discovery: bool) -> Self {
         let (hash, num) = match snapshot_target {
             Some((h, n)) => (h.into(), n),
+            None if snapshot_discovery => {
+                let best_header = client.best_block_header();
+                if best_header.number() == 0 {
+                    return State::SnapshotDiscovery(Instant::now())
+                }
+                // The body of the snapshot block is imported at the end of the restoration.
+                if client.block_body(&best_header.hash().into()).is_some() {
+                    return State::Full
+                }
+                (best_header.hash(), best_header.number())
+            }
             None => return State::Full,
         };
         let header = match client.block_header(&num.into()) {
             Some(h) if h.hash() == hash => h,
             _ => return State::SnapshotHeader(hash, num),
         };
-        if client.block_body(&hash.into()).is_none() {
-            return State::SnapshotBody(hash)
+        if client.block_body(&hash.into()).is_some() {
+            return State::Full
         }
 
         let state_db = client.state_db().read();
         let state_root = header.state_root();
         let top_trie = TrieFactory::readonly(state_db.as_hashdb(), &state_root);
         if !top_trie.map(|t| t.is_complete()).unwrap_or(false) {
-            return State::SnapshotTopChunk(state_root)
+            return State::SnapshotTopChunk(ChunkDownloader::new(Arc::clone(client), hash, state_root))
         }
 
+        // The restoration may be stopped before the commit is verified.
+        State::SnapshotCommit(hash)
+    }
+
+    /// The state to restore the shards from `from`, or to download the body if all of them are restored.
+    fn restore_shards_from(client: &Arc<Client>, hash: BlockHash, from: ShardId) -> Self {
+        let state_db = client.state_db().read();
         let top_state = client.state_at(hash.into()).expect("Top level state at the snapshot header exists");
         let metadata = top_state.metadata().unwrap().expect("Metadata must exist for the snapshot block");
         let shard_num = *metadata.number_of_shards();
-        let empty_shard = (0..shard_num).find_map(|n| {
+        let empty_shard = (from..shard_num).find_map(|n| {
             let shard_root = top_state.shard_root(n).unwrap().expect("Shard root must exist");
             let trie = TrieFactory::readonly(state_db.as_hashdb(), &shard_root);
             if !trie.map(|t| t.is_complete()).unwrap_or(false) {
@@ -96,11 +130,37 @@ impl State {
             }
         });
         if let Some((shard_id, shard_root)) = empty_shard {
-            return State::SnapshotShardChunk(shard_id, shard_root)
+            return State::SnapshotShardChunk(shard_id, ChunkDownloader::new(Arc::clone(client), hash, shard_root))
         }
 
-        State::Full
+        State::SnapshotBody(hash, None)
     }
+
+    fn progress(&self) -> SnapshotProgress {
+        let (stage, downloader) = match self {
+            State::SnapshotDiscovery(..) => ("discovery", None),
+            State::SnapshotAnchor(..) => ("anchor", None),
+            State::SnapshotHeader(..) => ("header", None),
+            State::SnapshotTopChunk(downloader) => ("top_chunk", Some(downloader)),
+            State::SnapshotCommit(..) => ("commit", None),
+            State::SnapshotShardChunk(_, downloader) => ("shard_chunk", Some(downloader)),
+            State::SnapshotBody(..) => ("body", None),
+            State::Full => ("full", None),
+        };
+        SnapshotProgress {
+            stage,
+            restored_chunks: downloader.map(ChunkDownloader::restored_chunks).unwrap_or(0),
+            pending_chunks: downloader.map(ChunkDownloader::pending_chunks).unwrap_or(0),
+        }
+    }
+}
+
+/// The progress of the snapshot restoration. The chunk counts are of the trie being restored.
+#[derive(Clone, Copy, Debug)]
+pub struct SnapshotProgress {
+    pub stage: &'static str,
+    pub restored_chunks: usize,
+    pub pending_chunks: usize,
 }
 
 pub struct Extension {
@@ -116,13 +176,39 @@ pub struct Extension {
     api: Box<dyn Api>,
     last_request: u64,
     seq: u64,
+    snapshot_dir: Option<String>,
+    /// The number of the peers that should advertise a snapshot to restore it. The discovery is disabled if it's None.
+    snapshot_quorum: Option<usize>,
+    /// The trusted block that the discovered snapshot blocks should be the ancestors of.
+    snapshot_checkpoint: Option<(BlockNumber, BlockHash)>,
+    snapshots: Vec<(BlockNumber, BlockHash)>,
+    peer_snapshots: HashMap<NodeId, Vec<(BlockNumber, BlockHash)>>,
+    rejected_snapshots: HashSet<BlockHash>,
+    /// At most one chunk request is sent to each peer at once.
+    chunk_requests: HashMap<NodeId, (u64, Instant)>,
+    invalid_chunks: HashMap<NodeId, usize>,
 }
 
 impl Extension {
-    pub fn new(client: Arc<Client>, api: Box<dyn Api>, snapshot_target: Option<(H256, u64)>) -> Extension {
+    pub fn new(
+        client: Arc<Client>,
+        api: Box<dyn Api>,
+        snapshot_target: Option<(H256, u64)>,
+        snapshot_dir: Option<String>,
+        snapshot_quorum: Option<usize>,
+        snapshot_checkpoint: Option<(H256, u64)>,
+    ) -> Extension {
         api.set_timer(SYNC_TIMER_TOKEN, Duration::from_millis(SYNC_TIMER_INTERVAL)).expect("Timer set succeeds");
 
-        let state = State::initial(&client, snapshot_target);
+        let snapshot_checkpoint = snapshot_checkpoint.map(|(hash, number)| (number, hash.into()));
+        let snapshot_quorum = if snapshot_checkpoint.is_none() && snapshot_quorum.is_some() {
+            cwarn!(SYNC, "Snapshot discovery is disabled because the trusted checkpoint is not given");
+            None
+        } else {
+            snapshot_quorum
+        };
+
+        let state = State::initial(&client, snapshot_target, snapshot_quorum.is_some());
         cdebug!(SYNC, "Initial state is {:?}", state);
         let mut header = client.best_header();
         let mut hollow_headers = vec![header.decode()];
@@ -143,7 +229,7 @@ impl Extension {
             body_downloader.add_target(child, is_empty);
         }
         cinfo!(SYNC, "Sync extension initialized");
-        Extension {
+        let mut extension = Extension {
             state,
             requests: Default::default(),
             connected_nodes: Default::default(),
@@ -156,7 +242,17 @@ impl Extension {
             api,
             last_request: Default::default(),
             seq: Default::default(),
-        }
+            snapshot_dir,
+            snapshot_quorum,
+            snapshot_checkpoint,
+            snapshots: Default::default(),
+            peer_snapshots: Default::default(),
+            rejected_snapshots: Default::default(),
+            chunk_requests: Default::default(),
+            invalid_chunks: Default::default(),
+        };
+        extension.update_snapshots();
+        extension
     }
 
     fn dismiss_request(&mut self, id: &NodeId, request_id: u64) {
@@ -233,22 +329,185 @@ impl Extension {
 
             if let Some(request) = self.body_downloader.create_request() {
                 cdebug!(SYNC, "Request body to {} {:?}", id, request);
-                let request_id = self.last_request;
-                self.last_request += 1;
-                requests.push((request_id, request.clone()));
-                self.api.send(id, Arc::new(Message::Request(request_id, request).rlp_bytes()));
+                self.send_bodies_request(id, request);
+            }
+        }
+        self.check_sync_variable();
+    }
+
+    /// Sends a body request that expires after `SYNC_EXPIRE_REQUEST_INTERVAL`.
+    fn send_bodies_request(&mut self, id: &NodeId, request: RequestMessage) {
+        if let Some(requests) = self.requests.get_mut(id) {
+            let request_id = self.last_request;
+            self.last_request += 1;
+            requests.push((request_id, request.clone()));
+            self.api.send(id, Arc::new(Message::Request(request_id, request).rlp_bytes()));
+
+            let token = &self.tokens[id];
+            let token_info = self.tokens_info.get_mut(token).unwrap();
+
+            let _ = self.api.clear_timer(*token);
+            self.api
+                .set_timer_once(*token, Duration::from_millis(SYNC_EXPIRE_REQUEST_INTERVAL))
+                .expect("Timer set succeeds");
+            token_info.request_id = Some(request_id);
+        }
+    }
+
+    fn send_snapshots(&self, id: &NodeId) {
+        self.api.send(id, Arc::new(Message::Snapshots(self.snapshots.clone()).rlp_bytes()));
+    }
+
+    /// Reads the snapshots in the snapshot directory, and returns whether they are changed.
+    fn update_snapshots(&mut self) -> bool {
+        let snapshots = match &self.snapshot_dir {
+            Some(dir) => match snapshot_list(&*self.client, dir) {
+                Ok(mut snapshots) => {
+                    snapshots.truncate(MAX_SNAPSHOTS_PER_PEER);
+                    snapshots
+                }
+                Err(err) => {
+                    cdebug!(SYNC, "Cannot read the snapshots in {}: {}", dir, err);
+                    return false
+                }
+            },
+            None => return false,
+        };
+        if snapshots == self.snapshots {
+            return false
+        }
+        self.snapshots = snapshots;
+        true
+    }
 
-                let token = &self.tokens[id];
-                let token_info = self.tokens_info.get_mut(token).unwrap();
+    /// The peers advertising the snapshot of the block. All the peers are returned if no peer advertises it.
+    fn snapshot_peers(&self, block: &BlockHash) -> Vec<NodeId> {
+        let peers: Vec<_> = self
+            .header_downloaders
+            .keys()
+            .filter(|id| {
+                self.peer_snapshots.get(id).map_or(false, |snapshots| snapshots.iter().any(|(_, hash)| hash == block))
+            })
+            .cloned()
+            .collect();
+        if peers.is_empty() {
+            self.header_downloaders.keys().cloned().collect()
+        } else {
+            peers
+        }
+    }
 
-                let _ = self.api.clear_timer(*token);
-                self.api
-                    .set_timer_once(*token, Duration::from_millis(SYNC_EXPIRE_REQUEST_INTERVAL))
-                    .expect("Timer set succeeds");
-                token_info.request_id = Some(request_id);
+    /// Picks the latest snapshot advertised by enough peers.
+    fn discover_snapshot(&mut self, since: Instant) {
+        if since.elapsed() < Duration::from_millis(SNAPSHOT_DISCOVERY_WAIT) {
+            return
+        }
+        let quorum = self.snapshot_quorum.expect("Snapshot discovery is enabled");
+        let checkpoint = self.snapshot_checkpoint.expect("Snapshot discovery requires the checkpoint");
+        let mut votes: HashMap<(BlockNumber, BlockHash), usize> = HashMap::new();
+        for snapshots in self.peer_snapshots.values() {
+            for snapshot in snapshots {
+                if snapshot.0 > 0 && snapshot.0 <= checkpoint.0 && !self.rejected_snapshots.contains(&snapshot.1) {
+                    *votes.entry(*snapshot).or_default() += 1;
+                }
             }
         }
-        self.check_sync_variable();
+        let target = votes.into_iter().filter(|(_, count)| *count >= quorum).map(|(snapshot, _)| snapshot).max();
+
+        if let Some((number, hash)) = target {
+            cinfo!(SYNC, "Snapshot of block #{} ({}) is discovered from the peers", number, hash);
+            if (number, hash) == checkpoint {
+                self.transition_to(State::SnapshotHeader(hash, number));
+            } else {
+                self.transition_to(State::SnapshotAnchor((number, hash), checkpoint));
+            }
+        } else if since.elapsed() >= Duration::from_millis(SNAPSHOT_DISCOVERY_TIMEOUT)
+            && self.client.best_block_header().number() == 0
+        {
+            cinfo!(SYNC, "No snapshot is advertised by {} peers. Sync from the genesis block", quorum);
+            self.transition_to(State::Full);
+        }
+    }
+
+    fn send_chunk_requests(&mut self) {
+        let expired: Vec<_> = self
+            .chunk_requests
+            .iter()
+            .filter(|(_, (_, requested_at))| {
+                requested_at.elapsed() >= Duration::from_millis(SYNC_EXPIRE_REQUEST_INTERVAL)
+            })
+            .map(|(id, (request_id, _))| (*id, *request_id))
+            .collect();
+        for (id, request_id) in expired {
+            cdebug!(SYNC, "Chunk request to {} is expired", id);
+            self.chunk_requests.remove(&id);
+            let expired_request = self.requests.get(&id).and_then(|requests| {
+                requests.iter().find(|(i, _)| *i == request_id).map(|(_, request)| request.clone())
+            });
+            if let Some(RequestMessage::StateChunk(_, chunk_roots)) = expired_request {
+                self.reset_chunk_downloading(&chunk_roots);
+            }
+            self.dismiss_request(&id, request_id);
+        }
+
+        let block = match &self.state {
+            State::SnapshotTopChunk(downloader) | State::SnapshotShardChunk(_, downloader) => downloader.block(),
+            _ => return,
+        };
+        let mut peers = self.snapshot_peers(&block);
+        peers.shuffle(&mut thread_rng());
+        for id in peers {
+            if self.chunk_requests.contains_key(&id) || !self.requests.contains_key(&id) {
+                continue
+            }
+            let request = match &mut self.state {
+                State::SnapshotTopChunk(downloader) | State::SnapshotShardChunk(_, downloader) => {
+                    downloader.create_request()
+                }
+                _ => unreachable!(),
+            };
+            let request = match request {
+                Some(request) => request,
+                // All the remaining chunks are being downloaded.
+                None => break,
+            };
+            ctrace!(SYNC, "Send chunk request to {}", id);
+            let request_id = self.last_request;
+            self.last_request += 1;
+            self.requests.get_mut(&id).expect("Checked above").push((request_id, request.clone()));
+            self.api.send(&id, Arc::new(Message::Request(request_id, request).rlp_bytes()));
+            self.chunk_requests.insert(id, (request_id, Instant::now()));
+        }
+
+        let next_state = match &self.state {
+            State::SnapshotTopChunk(downloader) if downloader.is_complete() => State::SnapshotCommit(block),
+            State::SnapshotShardChunk(shard_id, downloader) if downloader.is_complete() => {
+                State::restore_shards_from(&self.client, block, shard_id + 1)
+            }
+            _ => return,
+        };
+        self.transition_to(next_state);
+    }
+
+    fn reset_chunk_downloading(&mut self, chunk_roots: &[H256]) {
+        if let State::SnapshotTopChunk(downloader) | State::SnapshotShardChunk(_, downloader) = &mut self.state {
+            downloader.reset_downloading(chunk_roots);
+        }
+    }
+
+    fn send_snapshot_body_request(&mut self, block: BlockHash) {
+        let has_body_request = self.requests.values().flatten().any(|r| match r {
+            (_, RequestMessage::Bodies(..)) => true,
+            _ => false,
+        });
+        if has_body_request {
+            return
+        }
+        let peers = self.snapshot_peers(&block);
+        if let Some(id) = peers.choose(&mut thread_rng()) {
+            cdebug!(SYNC, "Request the body of the snapshot block {} to {}", block, id);
+            self.send_bodies_request(id, RequestMessage::Bodies(vec![block]));
+        }
     }
 
     fn check_sync_variable(&self) {
@@ -311,6 +570,7 @@ impl NetworkExtension<Event> for Extension {
     fn on_node_added(&mut self, id: &NodeId, _version: u64) {
         cinfo!(SYNC, "New peer detected #{}", id);
         self.send_status(id);
+        self.send_snapshots(id);
 
         let t = self.connected_nodes.insert(*id);
         debug_assert!(t, "{} is already added to peer list", id);
@@ -335,10 +595,14 @@ impl NetworkExtension<Event> for Extension {
             cinfo!(SYNC, "Peer removed #{}", id);
 
             self.header_downloaders.remove(id);
+            self.peer_snapshots.remove(id);
+            self.chunk_requests.remove(id);
 
             for (_, request) in self.requests.remove(id).into_iter().flatten() {
-                if let RequestMessage::Bodies(hashes) = request {
-                    self.body_downloader.reset_downloading(&hashes);
+                match request {
+                    RequestMessage::Bodies(hashes) => self.body_downloader.reset_downloading(&hashes),
+                    RequestMessage::StateChunk(_, chunk_roots) => self.reset_chunk_downloading(&chunk_roots),
+                    _ => {}
                 }
             }
 
@@ -364,6 +628,7 @@ impl NetworkExtension<Event> for Extension {
                     best_hash,
                     genesis_hash,
                 } => self.on_peer_status(id, seq, best_hash, genesis_hash),
+                Message::Snapshots(snapshots) => self.on_peer_snapshots(id, snapshots),
                 Message::Request(request_id, request) => self.on_peer_request(id, request_id, request),
                 Message::Response(request_id, response) => self.on_peer_response(id, request_id, response),
             }
@@ -379,6 +644,16 @@ impl NetworkExtension<Event> for Extension {
                 peer_ids.shuffle(&mut thread_rng());
 
                 match self.state {
+                    State::SnapshotDiscovery(since) => self.discover_snapshot(since),
+                    State::SnapshotAnchor(snapshot, next) => {
+                        let start_number = snapshot.0.max((next.0 + 1).saturating_sub(MAX_ANCHOR_HEADERS));
+                        for id in &peer_ids {
+                            self.send_header_request(id, RequestMessage::Headers {
+                                start_number,
+                                max_count: next.0 - start_number + 1,
+                            });
+                        }
+                    }
                     State::SnapshotHeader(_, num) => {
                         for id in &peer_ids {
                             self.send_header_request(id, RequestMessage::Headers {
@@ -387,9 +662,28 @@ impl NetworkExtension<Event> for Extension {
                             });
                         }
                     }
-                    State::SnapshotBody(..) => unimplemented!(),
-                    State::SnapshotTopChunk(..) => unimplemented!(),
-                    State::SnapshotShardChunk(..) => unimplemented!(),
+                    State::SnapshotTopChunk(..) | State::SnapshotShardChunk(..) => self.send_chunk_requests(),
+                    State::SnapshotCommit(block) => {
+                        // The child header has the commit of the snapshot block.
+                        let num = self.client.block_number(&BlockId::Hash(block)).expect("Snapshot header exists");
+                        for id in &peer_ids {
+                            self.send_header_request(id, RequestMessage::Headers {
+                                start_number: num + 1,
+                                max_count: 1,
+                            });
+                        }
+                    }
+                    State::SnapshotBody(block, None) => {
+                        // The parent header is required to verify the transactions root.
+                        let num = self.client.block_number(&BlockId::Hash(block)).expect("Snapshot header exists");
+                        for id in &peer_ids {
+                            self.send_header_request(id, RequestMessage::Headers {
+                                start_number: num - 1,
+                                max_count: 1,
+                            });
+                        }
+                    }
+                    State::SnapshotBody(block, Some(_)) => self.send_snapshot_body_request(block),
                     State::Full => {
                         for id in &peer_ids {
                             let request =
@@ -449,6 +743,9 @@ impl NetworkExtension<Event> for Extension {
                     channel.send(*peer).unwrap();
                 }
             }
+            Event::GetSnapshotProgress(channel) => {
+                channel.send(self.state.progress()).unwrap();
+            }
             Event::NewHeaders {
                 imported,
                 enacted,
@@ -468,6 +765,7 @@ impl NetworkExtension<Event> for Extension {
 
 pub enum Event {
     GetPeers(EventSender<NodeId>),
+    GetSnapshotProgress(EventSender<SnapshotProgress>),
     NewHeaders {
         imported: Vec<BlockHash>,
         enacted: Vec<BlockHash>,
@@ -485,14 +783,21 @@ impl Extension {
             State::SnapshotHeader(hash, ..) => {
                 if imported.contains(&hash) {
                     let header = self.client.block_header(&BlockId::Hash(hash)).expect("Imported header must exist");
-                    Some(State::SnapshotTopChunk(header.state_root()))
+                    Some(State::SnapshotTopChunk(ChunkDownloader::new(
+                        Arc::clone(&self.client),
+                        hash,
+                        header.state_root(),
+                    )))
                 } else {
                     None
                 }
             }
-            State::SnapshotBody(..) => unimplemented!(),
-            State::SnapshotTopChunk(..) => unimplemented!(),
-            State::SnapshotShardChunk(..) => unimplemented!(),
+            State::SnapshotDiscovery(..)
+            | State::SnapshotAnchor(..)
+            | State::SnapshotTopChunk(..)
+            | State::SnapshotCommit(..)
+            | State::SnapshotShardChunk(..)
+            | State::SnapshotBody(..) => None,
             State::Full => {
                 for peer in self.header_downloaders.values_mut() {
                     peer.mark_as_imported(imported.clone());
@@ -523,8 +828,7 @@ impl Extension {
                 None
             }
         } {
-            cdebug!(SYNC, "Transitioning state to {:?}", next_state);
-            self.state = next_state;
+            self.transition_to(next_state);
         }
     }
 
@@ -533,6 +837,11 @@ impl Extension {
         self.body_downloader.remove_target(&invalid);
 
         self.send_status_broadcast();
+        if self.update_snapshots() {
+            for id in self.connected_nodes.iter() {
+                self.send_snapshots(id);
+            }
+        }
     }
 }
 
@@ -559,6 +868,16 @@ impl Extension {
         cinfo!(SYNC, "Peer #{} status update: seq: {}, best_hash: {}", from, seq, best_hash);
     }
 
+    fn on_peer_snapshots(&mut self, from: &NodeId, mut snapshots: Vec<(BlockNumber, BlockHash)>) {
+        if !self.header_downloaders.contains_key(from) {
+            cinfo!(SYNC, "Snapshots from invalid peer #{} received", from);
+            return
+        }
+        snapshots.truncate(MAX_SNAPSHOTS_PER_PEER);
+        cdebug!(SYNC, "Peer #{} has snapshots {:?}", from, snapshots);
+        self.peer_snapshots.insert(*from, snapshots);
+    }
+
     fn on_peer_request(&self, from: &NodeId, id: u64, request: RequestMessage) {
         if !self.header_downloaders.contains_key(from) {
             cinfo!(SYNC, "Request from invalid peer #{} received", from);
@@ -596,9 +915,7 @@ impl Extension {
                 ..
             } => true,
             RequestMessage::Bodies(hashes) => !hashes.is_empty(),
-            RequestMessage::StateChunk {
-                ..
-            } => unimplemented!(),
+            RequestMessage::StateChunk(_, chunk_roots) => !chunk_roots.is_empty(),
         }
     }
 
@@ -636,8 +953,16 @@ impl Extension {
         ResponseMessage::Bodies(bodies)
     }
 
-    fn create_state_chunk_response(&self, _hash: BlockHash, _tree_root: Vec<H256>) -> ResponseMessage {
-        unimplemented!()
+    fn create_state_chunk_response(&self, hash: BlockHash, chunk_roots: Vec<H256>) -> ResponseMessage {
+        // An empty chunk is sent if the chunk doesn't exist.
+        let chunks = chunk_roots
+            .iter()
+            .map(|chunk_root| match &self.snapshot_dir {
+                Some(dir) => fs::read(snapshot_path(dir, &hash, chunk_root)).unwrap_or_default(),
+                None => Vec::new(),
+            })
+            .collect();
+        ResponseMessage::StateChunk(chunks)
     }
 
     fn on_peer_response(&mut self, from: &NodeId, id: u64, mut response: ResponseMessage) {
@@ -674,10 +999,24 @@ impl Extension {
                         }
                     }
                     self.dismiss_request(from, id);
-                    self.on_body_response(hashes, bodies);
+                    if let State::SnapshotBody(block, Some(parent_transactions_root)) = self.state {
+                        self.on_snapshot_body_response(from, block, parent_transactions_root, hashes, bodies);
+                    } else {
+                        self.on_body_response(hashes, bodies);
+                    }
                     self.check_sync_variable();
                 }
-                ResponseMessage::StateChunk(..) => unimplemented!(),
+                ResponseMessage::StateChunk(chunks) => {
+                    let chunk_roots = match request {
+                        RequestMessage::StateChunk(_, chunk_roots) => chunk_roots,
+                        _ => unreachable!(),
+                    };
+                    if self.chunk_requests.get(from).map_or(false, |(request_id, _)| *request_id == id) {
+                        self.chunk_requests.remove(from);
+                    }
+                    self.dismiss_request(from, id);
+                    self.on_chunk_response(from, &chunk_roots, chunks);
+                }
             }
         }
     }
@@ -731,12 +1070,18 @@ impl Extension {
                 }
                 true
             }
-            (
-                RequestMessage::StateChunk {
-                    ..
-                },
-                ResponseMessage::StateChunk(..),
-            ) => unimplemented!(),
+            (RequestMessage::StateChunk(_, chunk_roots), ResponseMessage::StateChunk(chunks)) => {
+                if chunk_roots.len() != chunks.len() {
+                    cwarn!(
+                        SYNC,
+                        "Received chunks' length({}) is not same with the requested chunk roots({})",
+                        chunks.len(),
+                        chunk_roots.len()
+                    );
+                    return false
+                }
+                true
+            }
             _ => {
                 cwarn!(SYNC, "Invalid response type");
                 false
@@ -766,9 +1111,24 @@ impl Extension {
                     headers.len()
                 ),
             },
-            State::SnapshotBody(..) => {}
-            State::SnapshotTopChunk(..) => {}
-            State::SnapshotShardChunk(..) => {}
+            State::SnapshotAnchor(snapshot, next) => self.link_snapshot_to_checkpoint(from, snapshot, next, headers),
+            State::SnapshotCommit(block) => match headers {
+                [child] if *child.parent_hash() == block => self.verify_snapshot_commit(from, block, child),
+                _ => cdebug!(SYNC, "Peer {} responded with a header which is not a child of {}", from, block),
+            },
+            State::SnapshotBody(block, None) => {
+                let header = self.client.block_header(&BlockId::Hash(block)).expect("Snapshot header exists");
+                match headers {
+                    [parent] if parent.hash() == header.parent_hash() => {
+                        self.state = State::SnapshotBody(block, Some(*parent.transactions_root()));
+                    }
+                    _ => cdebug!(SYNC, "Peer {} responded with a header which is not a parent of {}", from, block),
+                }
+            }
+            State::SnapshotDiscovery(..)
+            | State::SnapshotTopChunk(..)
+            | State::SnapshotShardChunk(..)
+            | State::SnapshotBody(_, Some(_)) => {}
             State::Full => {
                 let (mut completed, peer_is_caught_up) = if let Some(peer) = self.header_downloaders.get_mut(from) {
                     let encoded: Vec<_> = headers.iter().map(|h| EncodedHeader::new(h.rlp_bytes().to_vec())).collect();
@@ -810,6 +1170,123 @@ impl Extension {
         }
     }
 
+    fn link_snapshot_to_checkpoint(
+        &mut self,
+        from: &NodeId,
+        snapshot: (BlockNumber, BlockHash),
+        next: (BlockNumber, BlockHash),
+        headers: &[Header],
+    ) {
+        match anchor_snapshot(snapshot, next, headers) {
+            Anchoring::Unlinked => {
+                cdebug!(SYNC, "Peer {} responded with headers which are not linked to {}", from, next.1)
+            }
+            Anchoring::Continue(next) => self.state = State::SnapshotAnchor(snapshot, next),
+            Anchoring::Anchored => {
+                cinfo!(SYNC, "The snapshot block {} is linked to the checkpoint", snapshot.1);
+                self.transition_to(State::SnapshotHeader(snapshot.1, snapshot.0));
+            }
+            Anchoring::Forged => {
+                cwarn!(
+                    SYNC,
+                    "The snapshot block #{} ({}) is not an ancestor of the checkpoint",
+                    snapshot.0,
+                    snapshot.1
+                );
+                self.rejected_snapshots.insert(snapshot.1);
+                self.transition_to(State::SnapshotDiscovery(Instant::now()));
+            }
+        }
+    }
+
+    fn verify_snapshot_commit(&mut self, from: &NodeId, block: BlockHash, child: &Header) {
+        match self.client.verify_bootstrap_commit(child) {
+            Ok(()) => {
+                cinfo!(SYNC, "The commit of the snapshot block {} is verified", block);
+                self.transition_to(State::restore_shards_from(&self.client, block, 0));
+            }
+            Err(err) => {
+                cwarn!(SYNC, "Peer {} sent an invalid commit of the snapshot block {}: {:?}", from, block, err);
+                if self.snapshot_quorum.is_some() {
+                    self.rejected_snapshots.insert(block);
+                    self.transition_to(State::SnapshotDiscovery(Instant::now()));
+                }
+            }
+        }
+    }
+
+    fn on_chunk_response(&mut self, from: &NodeId, chunk_roots: &[H256], chunks: Vec<Vec<u8>>) {
+        let downloader = match &mut self.state {
+            State::SnapshotTopChunk(downloader) | State::SnapshotShardChunk(_, downloader) => downloader,
+            _ => return,
+        };
+        let mut invalid_chunks = 0;
+        for (chunk_root, chunk) in chunk_roots.iter().zip(chunks) {
+            if chunk.is_empty() {
+                cdebug!(SYNC, "Peer {} doesn't have the chunk {}", from, chunk_root);
+                downloader.reset_downloading(&[*chunk_root]);
+                continue
+            }
+            if let Err(err) = downloader.import_chunk(*chunk_root, &chunk) {
+                cwarn!(SYNC, "Peer {} sent an invalid chunk {}: {}", from, chunk_root, err);
+                invalid_chunks += 1;
+            }
+        }
+
+        if invalid_chunks > 0 {
+            let count = self.invalid_chunks.entry(*from).or_default();
+            *count += invalid_chunks;
+            if *count >= MAX_INVALID_CHUNKS_PER_PEER {
+                cwarn!(SYNC, "Disconnect peer {} which sent {} invalid chunks", from, count);
+                self.api.disconnect(from);
+            }
+        }
+        // Fill the slot of the peer right away.
+        self.send_chunk_requests();
+    }
+
+    fn on_snapshot_body_response(
+        &mut self,
+        from: &NodeId,
+        block: BlockHash,
+        parent_transactions_root: H256,
+        hashes: Vec<BlockHash>,
+        bodies: Vec<Vec<UnverifiedTransaction>>,
+    ) {
+        let transactions = match hashes.into_iter().zip(bodies).find(|(hash, _)| *hash == block) {
+            Some((_, transactions)) => transactions,
+            None => return,
+        };
+        let header = self.client.block_header(&BlockId::Hash(block)).expect("Snapshot header exists").decode();
+        let transactions_root =
+            skewed_merkle_root(parent_transactions_root, transactions.iter().map(Encodable::rlp_bytes));
+        if *header.transactions_root() != transactions_root {
+            cwarn!(SYNC, "Peer {} sent an invalid body of the snapshot block {}", from, block);
+            return
+        }
+        let block = Block {
+            header,
+            transactions,
+        };
+        match self.client.import_bootstrap_block(&block) {
+            Ok(_) | Err(BlockImportError::Import(ImportError::AlreadyInChain)) => {
+                cinfo!(SYNC, "Snapshot of block #{} ({}) is restored", block.header.number(), block.header.hash());
+                for downloader in self.header_downloaders.values_mut() {
+                    downloader.reset_pivot();
+                }
+                self.body_downloader.remove_target(&[block.header.hash()]);
+                clear_restored_chunks(&self.client);
+                self.transition_to(State::Full);
+            }
+            Err(err) => cwarn!(SYNC, "Cannot import the body of the snapshot block {}: {:?}", block.header.hash(), err),
+        }
+    }
+
+    fn transition_to(&mut self, state: State) {
+        cdebug!(SYNC, "Transitioning state to {:?}", state);
+        self.state = state;
+    }
+
     fn on_body_response(&mut self, hashes: Vec<BlockHash>, bodies: Vec<Vec<UnverifiedTransaction>>) {
         ctrace!(SYNC, "Received body response with lenth({}) {:?}", hashes.len(), hashes);
         {
@@ -852,6 +1329,43 @@ impl Extension {
     }
 }
 
+/// The result of following the parent hashes from the checkpoint to the snapshot block.
+#[derive(Debug, PartialEq)]
+enum Anchoring {
+    /// The headers don't end at the expected block.
+    Unlinked,
+    /// The headers are linked, and the header of the given block is required next.
+    Continue((BlockNumber, BlockHash)),
+    /// The snapshot block is an ancestor of the checkpoint.
+    Anchored,
+    /// The ancestor of the checkpoint at the height of the snapshot block is another block.
+    Forged,
+}
+
+/// Checks that the headers, which are already checked to be a chain, end at `next`.
+fn anchor_snapshot(
+    snapshot: (BlockNumber, BlockHash),
+    next: (BlockNumber, BlockHash),
+    headers: &[Header],
+) -> Anchoring {
+    let (first, last) = match (headers.first(), headers.last()) {
+        (Some(first), Some(last)) => (first, last),
+        _ => return Anchoring::Unlinked,
+    };
+    if (last.number(), last.hash()) != next {
+        return Anchoring::Unlinked
+    }
+    if first.number() > snapshot.0 {
+        Anchoring::Continue((first.number() - 1, *first.parent_hash()))
+    } else if first.number() < snapshot.0 {
+        Anchoring::Unlinked
+    } else if first.hash() == snapshot.1 {
+        Anchoring::Anchored
+    } else {
+        Anchoring::Forged
+    }
+}
+
 pub struct BlockSyncSender(EventSender<Event>);
 
 impl From<EventSender<Event>> for BlockSyncSender {
@@ -895,3 +1409,62 @@ impl ChainNotify for BlockSyncSender {
             .unwrap();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chain(parent: &Header, length: usize, state_root: H256) -> Vec<Header> {
+        let mut headers: Vec<Header> = Vec::with_capacity(length);
+        for _ in 0..length {
+            let parent = headers.last().unwrap_or(parent);
+            let mut header = Header::default();
+            header.set_parent_hash(parent.hash());
+            header.set_number(parent.number() + 1);
+            header.set_state_root(state_root);
+            headers.push(header);
+        }
+        headers
+    }
+
+    fn id(header: &Header) -> (BlockNumber, BlockHash) {
+        (header.number(), header.hash())
+    }
+
+    #[test]
+    fn snapshot_linked_to_checkpoint_is_anchored() {
+        let genesis = Header::default();
+        let headers = chain(&genesis, 10, H256::random());
+        let snapshot = id(&headers[2]);
+        let checkpoint = id(&headers[9]);
+
+        let next = match anchor_snapshot(snapshot, checkpoint, &headers[5..]) {
+            Anchoring::Continue(next) => next,
+            anchoring => panic!("Unexpected {:?}", anchoring),
+        };
+        assert_eq!(id(&headers[4]), next);
+        assert_eq!(Anchoring::Anchored, anchor_snapshot(snapshot, next, &headers[2..5]));
+    }
+
+    #[test]
+    fn snapshot_with_forged_validators_is_rejected() {
+        let genesis = Header::default();
+        let headers = chain(&genesis, 10, H256::random());
+        // The forged state has other validators, who sign the commit of the forged snapshot block.
+        let forged = chain(&headers[1], 1, H256::random()).remove(0);
+        assert_eq!(headers[2].number(), forged.number());
+
+        assert_eq!(Anchoring::Forged, anchor_snapshot(id(&forged), id(&headers[9]), &headers[2..]));
+    }
+
+    #[test]
+    fn headers_not_ending_at_the_expected_block_are_ignored() {
+        let genesis = Header::default();
+        let headers = chain(&genesis, 10, H256::random());
+        let other = chain(&genesis, 10, H256::random());
+
+        assert_eq!(Anchoring::Unlinked, anchor_snapshot(id(&headers[2]), id(&headers[9]), &other[2..]));
+        assert_eq!(Anchoring::Unlinked, anchor_snapshot(id(&headers[2]), id(&headers[9]), &headers[2..9]));
+        assert_eq!(Anchoring::Unlinked, anchor_snapshot(id(&headers[2]), id(&headers[9]), &[]));
+    }
+}
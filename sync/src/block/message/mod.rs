@@ -14,7 +14,7 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use ctypes::BlockHash;
+use ctypes::{BlockHash, BlockNumber};
 use primitives::U256;
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
 
@@ -32,6 +32,7 @@ pub enum MessageID {
     Headers = 0x03,
     GetBodies = 0x04,
     Bodies = 0x05,
+    Snapshots = 0x06,
     GetStateChunk = 0x0a,
     StateChunk = 0x0b,
 }
@@ -51,6 +52,7 @@ impl Decodable for MessageID {
             0x03 => Ok(MessageID::Headers),
             0x04 => Ok(MessageID::GetBodies),
             0x05 => Ok(MessageID::Bodies),
+            0x06 => Ok(MessageID::Snapshots),
             0x0a => Ok(MessageID::GetStateChunk),
             0x0b => Ok(MessageID::StateChunk),
             _ => Err(DecoderError::Custom("Unexpected MessageID Value")),
@@ -65,6 +67,8 @@ pub enum Message {
         best_hash: BlockHash,
         genesis_hash: BlockHash,
     },
+    /// The snapshots that the peer can serve, from the latest one.
+    Snapshots(Vec<(BlockNumber, BlockHash)>),
     Request(u64, RequestMessage),
     Response(u64, ResponseMessage),
 }
@@ -85,6 +89,17 @@ impl Encodable for Message {
                 s.append(best_hash);
                 s.append(genesis_hash);
             }
+            Message::Snapshots(snapshots) => {
+                s.begin_list(2);
+                s.append(&MessageID::Snapshots);
+
+                s.begin_list(snapshots.len());
+                for (number, hash) in snapshots {
+                    s.begin_list(2);
+                    s.append(number);
+                    s.append(hash);
+                }
+            }
             Message::Request(request_id, request) => {
                 s.begin_list(3);
                 s.append(&request.message_id());
@@ -129,6 +144,30 @@ impl Decodable for Message {
                     genesis_hash: message.val_at(2)?,
                 })
             }
+            MessageID::Snapshots => {
+                let item_count = rlp.item_count()?;
+                if item_count != 2 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 2,
+                    })
+                }
+                let snapshots = rlp
+                    .at(1)?
+                    .iter()
+                    .map(|snapshot| {
+                        let item_count = snapshot.item_count()?;
+                        if item_count != 2 {
+                            return Err(DecoderError::RlpIncorrectListLen {
+                                got: item_count,
+                                expected: 2,
+                            })
+                        }
+                        Ok((snapshot.val_at(0)?, snapshot.val_at(1)?))
+                    })
+                    .collect::<Result<_, _>>()?;
+                Ok(Message::Snapshots(snapshots))
+            }
             _ => {
                 let item_count = rlp.item_count()?;
                 if item_count != 3 {
@@ -170,6 +209,16 @@ mod tests {
         });
     }
 
+    #[test]
+    fn snapshots_message_rlp() {
+        rlp_encode_and_decode_test!(Message::Snapshots(vec![(20, H256::from(2).into()), (10, H256::from(1).into())]));
+    }
+
+    #[test]
+    fn empty_snapshots_message_rlp() {
+        rlp_encode_and_decode_test!(Message::Snapshots(vec![]));
+    }
+
     #[test]
     fn request_bodies_message_rlp() {
         let request_id = 10;
@@ -18,4 +18,6 @@ mod downloader;
 mod extension;
 mod message;
 
-pub use self::extension::{BlockSyncSender, Event as BlockSyncEvent, Extension as BlockSyncExtension};
+pub use self::extension::{
+    BlockSyncSender, Event as BlockSyncEvent, Extension as BlockSyncExtension, SnapshotProgress,
+};
@@ -36,7 +36,7 @@ mod block;
 pub mod snapshot;
 mod transaction;
 
-pub use crate::block::{BlockSyncEvent, BlockSyncExtension, BlockSyncSender};
+pub use crate::block::{BlockSyncEvent, BlockSyncExtension, BlockSyncSender, SnapshotProgress};
 pub use crate::transaction::TransactionSyncExtension;
 
 #[cfg(test)]
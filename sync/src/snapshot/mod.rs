@@ -17,12 +17,13 @@
 use ccore::snapshot_notify::{NotifyReceiverSource, ReceiverCanceller};
 use ccore::{BlockChainClient, BlockChainTrait, BlockId, Client};
 use cdb::{AsHashDB, HashDB};
-use cmerkle::snapshot::{ChunkCompressor, Error as SnapshotError, Snapshot};
+use cmerkle::snapshot::{ChunkCompressor, ChunkDecompressor, Error as SnapshotError, Restore, Snapshot};
 use cstate::{StateDB, TopLevelState, TopStateView};
-use ctypes::BlockHash;
+use ctypes::{BlockHash, BlockNumber};
 use primitives::H256;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{self, Cursor};
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
 use std::sync::Arc;
 use std::thread::{spawn, JoinHandle};
@@ -32,6 +33,28 @@ pub struct Service {
     canceller: Option<ReceiverCanceller>,
 }
 
+/// Decides which snapshots are removed after a new snapshot is taken.
+/// The latest snapshot is always kept.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Retention {
+    /// The snapshots older than this number of blocks are removed.
+    pub expiration: Option<u64>,
+    /// The number of the latest snapshots to keep.
+    pub max_count: Option<usize>,
+    /// The old snapshots are removed until the total size of the snapshots is below this bytes.
+    pub max_size: Option<u64>,
+}
+
+#[derive(Debug)]
+pub struct SnapshotInfo {
+    pub number: BlockNumber,
+    pub hash: BlockHash,
+    pub state_root: H256,
+    pub chunk_count: usize,
+    /// The total size of the chunk files in bytes
+    pub size: u64,
+}
+
 pub fn snapshot_dir(root_dir: &str, block: &BlockHash) -> PathBuf {
     let mut path = PathBuf::new();
     path.push(root_dir);
@@ -45,12 +68,125 @@ pub fn snapshot_path(root_dir: &str, block: &BlockHash, chunk_root: &H256) -> Pa
     path
 }
 
+/// Lists the snapshots in `root_dir` with their block numbers, from the latest one.
+/// The snapshots of the unknown blocks are ignored.
+pub fn snapshot_list<C: BlockChainClient + ?Sized>(
+    client: &C,
+    root_dir: &str,
+) -> Result<Vec<(BlockNumber, BlockHash)>, io::Error> {
+    let mut result = Vec::new();
+    for entry in fs::read_dir(root_dir)? {
+        let entry = entry?;
+
+        // Check if the entry is a directory
+        if !entry.file_type()?.is_dir() {
+            continue
+        }
+
+        let path = entry.path();
+        let name = match path.file_name().expect("Directories always have file name").to_str() {
+            Some(n) => n,
+            None => continue,
+        };
+        let hash = match H256::from_str(name) {
+            Ok(h) => BlockHash::from(h),
+            Err(_) => continue,
+        };
+        if let Some(number) = client.block_number(&BlockId::Hash(hash)) {
+            result.push((number, hash));
+        }
+    }
+    result.sort_unstable_by(|a, b| b.0.cmp(&a.0));
+    Ok(result)
+}
+
+/// Returns None if there is no snapshot of the block in `root_dir`.
+pub fn snapshot_info<C: BlockChainClient + ?Sized>(
+    client: &C,
+    root_dir: &str,
+    block: &BlockHash,
+) -> Result<Option<SnapshotInfo>, io::Error> {
+    let header = match client.block_header(&BlockId::Hash(*block)) {
+        Some(header) => header,
+        None => return Ok(None),
+    };
+    let dir = snapshot_dir(root_dir, block);
+    if !dir.is_dir() {
+        return Ok(None)
+    }
+    let (chunk_count, size) = chunk_files_usage(&dir)?;
+    Ok(Some(SnapshotInfo {
+        number: header.number(),
+        hash: *block,
+        state_root: header.state_root(),
+        chunk_count,
+        size,
+    }))
+}
+
+/// Removes the snapshot of the block. Returns false if there is no such snapshot.
+pub fn delete_snapshot(root_dir: &str, block: &BlockHash) -> Result<bool, io::Error> {
+    let path = snapshot_dir(root_dir, block);
+    if !path.is_dir() {
+        return Ok(false)
+    }
+    // Rename it first not to serve the chunks being removed.
+    let rename_to = PathBuf::from(root_dir).join(format!("{:x}.old", **block));
+    fs::rename(path, &rename_to)?;
+    fs::remove_dir_all(rename_to)?;
+    Ok(true)
+}
+
+/// Reads all the chunks of the top level state and the shards from `dir`, and checks them against `state_root`.
+/// Returns the number of the verified chunks.
+pub fn verify_snapshot(dir: &Path, state_root: H256) -> Result<usize, SnapshotError> {
+    let mut db = StateDB::new_with_memorydb();
+    let mut count = verify_trie(&mut db, dir, state_root)?;
+
+    let top_state = TopLevelState::from_existing(db.clone(&state_root), state_root)?;
+    let metadata = top_state.metadata()?.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "No metadata"))?;
+    for shard_id in 0..*metadata.number_of_shards() {
+        let shard_root = top_state
+            .shard_root(shard_id)?
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, format!("No root of shard #{}", shard_id)))?;
+        count += verify_trie(&mut db, dir, shard_root)?;
+    }
+    Ok(count)
+}
+
+fn verify_trie(db: &mut StateDB, dir: &Path, root: H256) -> Result<usize, SnapshotError> {
+    let mut count = 0;
+    let mut restore = Restore::new(root);
+    while let Some(chunk_root) = restore.next_to_feed() {
+        let path = dir.join(format!("{:x}", chunk_root));
+        let chunk =
+            fs::read(&path).map_err(|err| io::Error::new(err.kind(), format!("{}: {}", path.display(), err)))?;
+        let recovered = ChunkDecompressor::new(Cursor::new(chunk)).decompress()?.recover(chunk_root)?;
+        restore.feed(db.as_hashdb_mut(), recovered);
+        count += 1;
+    }
+    Ok(count)
+}
+
+fn chunk_files_usage(dir: &Path) -> Result<(usize, u64), io::Error> {
+    let mut count = 0;
+    let mut size = 0;
+    for entry in fs::read_dir(dir)? {
+        let metadata = entry?.metadata()?;
+        if metadata.is_file() {
+            count += 1;
+            size += metadata.len();
+        }
+    }
+    Ok((count, size))
+}
+
 impl Service {
     pub fn new(
         client: Arc<Client>,
         notify_receiver_source: NotifyReceiverSource,
         root_dir: String,
-        expiration: Option<u64>,
+        retention: Retention,
     ) -> Self {
         let NotifyReceiverSource(canceller, receiver) = notify_receiver_source;
         let join_handle = spawn(move || {
@@ -78,12 +214,9 @@ impl Service {
                     }
                 }
 
-                if let Some(expiration) = expiration {
-                    if let Err(err) = cleanup_expired(&client, &root_dir, expiration) {
-                        cerror!(SYNC, "Snapshot cleanup error after block hash {}, err: {}", block_hash, err);
-                    }
+                if let Err(err) = cleanup(&client, &root_dir, retention) {
+                    cerror!(SYNC, "Snapshot cleanup error after block hash {}, err: {}", block_hash, err);
                 }
-                // TODO: Prune old snapshots
             }
             cinfo!(SYNC, "Snapshot service is stopped")
         });
@@ -123,43 +256,28 @@ fn snapshot_trie(db: &dyn HashDB, block_hash: BlockHash, root: H256, root_dir: &
     Ok(())
 }
 
-fn cleanup_expired(client: &Client, root_dir: &str, expiration: u64) -> Result<(), SnapshotError> {
-    for entry in fs::read_dir(root_dir)? {
-        let entry = match entry {
-            Ok(entry) => entry,
+fn cleanup(client: &Client, root_dir: &str, retention: Retention) -> Result<(), SnapshotError> {
+    let best_number = client.best_block_header().number();
+    let mut total_size = 0;
+    for (index, (number, hash)) in snapshot_list(client, root_dir)?.into_iter().enumerate() {
+        let size = match chunk_files_usage(&snapshot_dir(root_dir, &hash)) {
+            Ok((_, size)) => size,
             Err(err) => {
-                cerror!(SYNC, "Snapshot cleanup can't retrieve entry. err: {}", err);
+                cerror!(SYNC, "Snapshot cleanup can't retrieve the size of {}, err: {}", hash, err);
                 continue
             }
         };
-        let path = entry.path();
-
-        match entry.file_type().map(|x| x.is_dir()) {
-            Ok(true) => {}
-            Ok(false) => continue,
-            Err(err) => {
-                cerror!(SYNC, "Snapshot cleanup can't retrieve file info: {}, err: {}", path.to_string_lossy(), err);
-                continue
-            }
-        }
-
-        let name = match path.file_name().expect("Directories always have file name").to_str() {
-            Some(n) => n,
-            None => continue,
-        };
-        let hash = match H256::from_str(name) {
-            Ok(h) => BlockHash::from(h),
-            Err(_) => continue,
-        };
-        let number = if let Some(number) = client.block_number(&BlockId::Hash(hash)) {
-            number
-        } else {
-            cerror!(SYNC, "Snapshot cleanup can't retrieve block number for block_hash: {}", hash);
+        total_size += size;
+        if index == 0 {
             continue
-        };
+        }
 
-        if number + expiration < client.best_block_header().number() {
-            cleanup_snapshot(root_dir, hash)
+        let is_expired = retention.expiration.map_or(false, |expiration| number + expiration < best_number);
+        let is_over_count = retention.max_count.map_or(false, |max_count| index >= max_count);
+        let is_over_size = retention.max_size.map_or(false, |max_size| total_size > max_size);
+        if is_expired || is_over_count || is_over_size {
+            cleanup_snapshot(root_dir, hash);
+            total_size -= size;
         }
     }
     Ok(())
@@ -167,21 +285,10 @@ fn cleanup_expired(client: &Client, root_dir: &str, expiration: u64) -> Result<(
 
 /// Remove all files in `root_dir/block_hash`
 fn cleanup_snapshot(root_dir: &str, block_hash: BlockHash) {
-    let path = snapshot_dir(root_dir, &block_hash);
-    let rename_to = PathBuf::from(root_dir).join(format!("{:x}.old", *block_hash));
-    // It is okay to ignore errors. We just wanted them to be removed.
-    match fs::rename(path, &rename_to) {
-        Ok(()) => {}
-        Err(err) => {
-            cerror!(SYNC, "Snapshot cleanup: renaming {} failed, reason: {}", block_hash, err);
-        }
-    }
     // Ignore the error. Cleanup failure is not a critical error.
-    match fs::remove_dir_all(rename_to) {
-        Ok(()) => {}
-        Err(err) => {
-            cerror!(SYNC, "Snapshot cleanup: removing {} failed, reason: {}", block_hash, err);
-        }
+    match delete_snapshot(root_dir, &block_hash) {
+        Ok(_) => cinfo!(SYNC, "Snapshot of block {} is removed", block_hash),
+        Err(err) => cerror!(SYNC, "Snapshot cleanup: removing {} failed, reason: {}", block_hash, err),
     }
 }
 
@@ -66,6 +66,20 @@ pub struct CommonParams {
     max_candidate_metadata_size: usize,
 
     era: u64,
+
+    unbonding_period: u64,
+
+    double_vote_slash_rate: u64,
+    downtime_slash_rate: u64,
+
+    liveness_window: u64,
+    liveness_threshold: u64,
+
+    proposal_deposit: u64,
+    voting_period: u64,
+
+    /// The cost a lock script can consume to unlock an input. Zero means no limit.
+    max_script_cost: u64,
 }
 
 impl CommonParams {
@@ -176,6 +190,38 @@ impl CommonParams {
         self.era
     }
 
+    pub fn unbonding_period(&self) -> u64 {
+        self.unbonding_period
+    }
+
+    pub fn double_vote_slash_rate(&self) -> u64 {
+        self.double_vote_slash_rate
+    }
+
+    pub fn downtime_slash_rate(&self) -> u64 {
+        self.downtime_slash_rate
+    }
+
+    pub fn liveness_window(&self) -> u64 {
+        self.liveness_window
+    }
+
+    pub fn liveness_threshold(&self) -> u64 {
+        self.liveness_threshold
+    }
+
+    pub fn proposal_deposit(&self) -> u64 {
+        self.proposal_deposit
+    }
+
+    pub fn voting_period(&self) -> u64 {
+        self.voting_period
+    }
+
+    pub fn max_script_cost(&self) -> u64 {
+        self.max_script_cost
+    }
+
     pub fn verify(&self) -> Result<(), String> {
         if self.term_seconds != 0 {
             if self.nomination_expiration == 0 {
@@ -218,6 +264,26 @@ impl CommonParams {
                 ))
             }
         }
+        if self.double_vote_slash_rate > MAX_SLASH_RATE {
+            return Err(format!(
+                "The double vote slash rate({}) should not be larger than {}",
+                self.double_vote_slash_rate, MAX_SLASH_RATE
+            ))
+        }
+        if self.downtime_slash_rate > MAX_SLASH_RATE {
+            return Err(format!(
+                "The downtime slash rate({}) should not be larger than {}",
+                self.downtime_slash_rate, MAX_SLASH_RATE
+            ))
+        }
+        if self.liveness_window != 0
+            && (self.liveness_threshold == 0 || self.liveness_threshold > MAX_LIVENESS_THRESHOLD)
+        {
+            return Err(format!(
+                "The liveness threshold({}) should be between 1 and {}",
+                self.liveness_threshold, MAX_LIVENESS_THRESHOLD
+            ))
+        }
         Ok(())
     }
 
@@ -241,14 +307,48 @@ impl CommonParams {
 const DEFAULT_PARAMS_SIZE: usize = 23;
 const NUMBER_OF_STAKE_PARAMS: usize = 9;
 const NUMBER_OF_ERA_PARAMS: usize = 1;
+const NUMBER_OF_UNBONDING_PARAMS: usize = 1;
+const NUMBER_OF_SLASHING_PARAMS: usize = 2;
+const NUMBER_OF_LIVENESS_PARAMS: usize = 2;
+const NUMBER_OF_GOVERNANCE_PARAMS: usize = 2;
+const NUMBER_OF_VM_PARAMS: usize = 1;
 const STAKE_PARAM_SIZE: usize = DEFAULT_PARAMS_SIZE + NUMBER_OF_STAKE_PARAMS;
 const ERA_PARAM_SIZE: usize = STAKE_PARAM_SIZE + NUMBER_OF_ERA_PARAMS;
+const UNBONDING_PARAM_SIZE: usize = ERA_PARAM_SIZE + NUMBER_OF_UNBONDING_PARAMS;
+const SLASHING_PARAM_SIZE: usize = UNBONDING_PARAM_SIZE + NUMBER_OF_SLASHING_PARAMS;
+const LIVENESS_PARAM_SIZE: usize = SLASHING_PARAM_SIZE + NUMBER_OF_LIVENESS_PARAMS;
+const GOVERNANCE_PARAM_SIZE: usize = LIVENESS_PARAM_SIZE + NUMBER_OF_GOVERNANCE_PARAMS;
+const VM_PARAM_SIZE: usize = GOVERNANCE_PARAM_SIZE + NUMBER_OF_VM_PARAMS;
 
-const VALID_SIZE: &[usize] = &[DEFAULT_PARAMS_SIZE, STAKE_PARAM_SIZE, ERA_PARAM_SIZE];
+const VALID_SIZE: &[usize] = &[
+    DEFAULT_PARAMS_SIZE,
+    STAKE_PARAM_SIZE,
+    ERA_PARAM_SIZE,
+    UNBONDING_PARAM_SIZE,
+    SLASHING_PARAM_SIZE,
+    LIVENESS_PARAM_SIZE,
+    GOVERNANCE_PARAM_SIZE,
+    VM_PARAM_SIZE,
+];
+
+/// The slash rates are in basis points.
+pub const MAX_SLASH_RATE: u64 = 10_000;
+/// The liveness threshold is in basis points.
+pub const MAX_LIVENESS_THRESHOLD: u64 = 10_000;
 
 impl From<Params> for CommonParams {
     fn from(p: Params) -> Self {
-        let size = if p.era.is_some() {
+        let size = if p.max_script_cost.is_some() {
+            VM_PARAM_SIZE
+        } else if p.proposal_deposit.is_some() || p.voting_period.is_some() {
+            GOVERNANCE_PARAM_SIZE
+        } else if p.liveness_window.is_some() || p.liveness_threshold.is_some() {
+            LIVENESS_PARAM_SIZE
+        } else if p.double_vote_slash_rate.is_some() || p.downtime_slash_rate.is_some() {
+            SLASHING_PARAM_SIZE
+        } else if p.unbonding_period.is_some() {
+            UNBONDING_PARAM_SIZE
+        } else if p.era.is_some() {
             ERA_PARAM_SIZE
         } else if p.term_seconds.is_some() {
             STAKE_PARAM_SIZE
@@ -290,6 +390,14 @@ impl From<Params> for CommonParams {
             min_deposit: p.min_deposit.map(From::from).unwrap_or_default(),
             max_candidate_metadata_size: p.max_candidate_metadata_size.map(From::from).unwrap_or_default(),
             era: p.era.map(From::from).unwrap_or_default(),
+            unbonding_period: p.unbonding_period.map(From::from).unwrap_or_default(),
+            double_vote_slash_rate: p.double_vote_slash_rate.map(From::from).unwrap_or_default(),
+            downtime_slash_rate: p.downtime_slash_rate.map(From::from).unwrap_or_default(),
+            liveness_window: p.liveness_window.map(From::from).unwrap_or_default(),
+            liveness_threshold: p.liveness_threshold.map(From::from).unwrap_or_default(),
+            proposal_deposit: p.proposal_deposit.map(From::from).unwrap_or_default(),
+            voting_period: p.voting_period.map(From::from).unwrap_or_default(),
+            max_script_cost: p.max_script_cost.map(From::from).unwrap_or_default(),
         }
     }
 }
@@ -337,6 +445,24 @@ impl From<CommonParams> for Params {
         if p.size >= ERA_PARAM_SIZE {
             result.era = Some(p.era().into());
         }
+        if p.size >= UNBONDING_PARAM_SIZE {
+            result.unbonding_period = Some(p.unbonding_period().into());
+        }
+        if p.size >= SLASHING_PARAM_SIZE {
+            result.double_vote_slash_rate = Some(p.double_vote_slash_rate().into());
+            result.downtime_slash_rate = Some(p.downtime_slash_rate().into());
+        }
+        if p.size >= LIVENESS_PARAM_SIZE {
+            result.liveness_window = Some(p.liveness_window().into());
+            result.liveness_threshold = Some(p.liveness_threshold().into());
+        }
+        if p.size >= GOVERNANCE_PARAM_SIZE {
+            result.proposal_deposit = Some(p.proposal_deposit().into());
+            result.voting_period = Some(p.voting_period().into());
+        }
+        if p.size >= VM_PARAM_SIZE {
+            result.max_script_cost = Some(p.max_script_cost().into());
+        }
         result
     }
 }
@@ -382,6 +508,24 @@ impl Encodable for CommonParams {
         if self.size >= ERA_PARAM_SIZE {
             s.append(&self.era);
         }
+        if self.size >= UNBONDING_PARAM_SIZE {
+            s.append(&self.unbonding_period);
+        }
+        if self.size >= SLASHING_PARAM_SIZE {
+            s.append(&self.double_vote_slash_rate);
+            s.append(&self.downtime_slash_rate);
+        }
+        if self.size >= LIVENESS_PARAM_SIZE {
+            s.append(&self.liveness_window);
+            s.append(&self.liveness_threshold);
+        }
+        if self.size >= GOVERNANCE_PARAM_SIZE {
+            s.append(&self.proposal_deposit);
+            s.append(&self.voting_period);
+        }
+        if self.size >= VM_PARAM_SIZE {
+            s.append(&self.max_script_cost);
+        }
     }
 }
 
@@ -451,6 +595,36 @@ impl Decodable for CommonParams {
             Default::default()
         };
 
+        let unbonding_period = if size >= UNBONDING_PARAM_SIZE {
+            rlp.val_at(33)?
+        } else {
+            Default::default()
+        };
+
+        let (double_vote_slash_rate, downtime_slash_rate) = if size >= SLASHING_PARAM_SIZE {
+            (rlp.val_at(34)?, rlp.val_at(35)?)
+        } else {
+            Default::default()
+        };
+
+        let (liveness_window, liveness_threshold) = if size >= LIVENESS_PARAM_SIZE {
+            (rlp.val_at(36)?, rlp.val_at(37)?)
+        } else {
+            Default::default()
+        };
+
+        let (proposal_deposit, voting_period) = if size >= GOVERNANCE_PARAM_SIZE {
+            (rlp.val_at(38)?, rlp.val_at(39)?)
+        } else {
+            Default::default()
+        };
+
+        let max_script_cost = if size >= VM_PARAM_SIZE {
+            rlp.val_at(40)?
+        } else {
+            Default::default()
+        };
+
         Ok(Self {
             size,
             max_extra_data_size,
@@ -486,6 +660,14 @@ impl Decodable for CommonParams {
             min_deposit,
             max_candidate_metadata_size,
             era,
+            unbonding_period,
+            double_vote_slash_rate,
+            downtime_slash_rate,
+            liveness_window,
+            liveness_threshold,
+            proposal_deposit,
+            voting_period,
+            max_script_cost,
         })
     }
 }
@@ -534,6 +716,34 @@ impl CommonParams {
         self.min_deposit = min_deposit;
         self.max_candidate_metadata_size = max_candidate_metadata_size;
     }
+
+    pub fn set_unbonding_period_for_test(&mut self, unbonding_period: u64) {
+        self.size = std::cmp::max(self.size, UNBONDING_PARAM_SIZE);
+        self.unbonding_period = unbonding_period;
+    }
+
+    pub fn set_slash_rates_for_test(&mut self, double_vote_slash_rate: u64, downtime_slash_rate: u64) {
+        self.size = std::cmp::max(self.size, SLASHING_PARAM_SIZE);
+        self.double_vote_slash_rate = double_vote_slash_rate;
+        self.downtime_slash_rate = downtime_slash_rate;
+    }
+
+    pub fn set_liveness_params_for_test(&mut self, liveness_window: u64, liveness_threshold: u64) {
+        self.size = std::cmp::max(self.size, LIVENESS_PARAM_SIZE);
+        self.liveness_window = liveness_window;
+        self.liveness_threshold = liveness_threshold;
+    }
+
+    pub fn set_governance_params_for_test(&mut self, proposal_deposit: u64, voting_period: u64) {
+        self.size = std::cmp::max(self.size, GOVERNANCE_PARAM_SIZE);
+        self.proposal_deposit = proposal_deposit;
+        self.voting_period = voting_period;
+    }
+
+    pub fn set_max_script_cost_for_test(&mut self, max_script_cost: u64) {
+        self.size = std::cmp::max(self.size, VM_PARAM_SIZE);
+        self.max_script_cost = max_script_cost;
+    }
 }
 
 #[cfg(test)]
@@ -635,6 +845,14 @@ mod tests {
         assert_eq!(deserialized.min_deposit, 0);
         assert_eq!(deserialized.max_candidate_metadata_size, 0);
         assert_eq!(deserialized.era, 0);
+        assert_eq!(deserialized.unbonding_period, 0);
+        assert_eq!(deserialized.double_vote_slash_rate, 0);
+        assert_eq!(deserialized.downtime_slash_rate, 0);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
 
         assert_eq!(params, deserialized.into());
     }
@@ -705,6 +923,14 @@ mod tests {
         assert_eq!(deserialized.min_deposit, 0);
         assert_eq!(deserialized.max_candidate_metadata_size, 0);
         assert_eq!(deserialized.era, 0);
+        assert_eq!(deserialized.unbonding_period, 0);
+        assert_eq!(deserialized.double_vote_slash_rate, 0);
+        assert_eq!(deserialized.downtime_slash_rate, 0);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
 
         assert_eq!(
             Params {
@@ -797,6 +1023,14 @@ mod tests {
         assert_eq!(deserialized.min_deposit, 32);
         assert_eq!(deserialized.max_candidate_metadata_size, 33);
         assert_eq!(deserialized.era, 0);
+        assert_eq!(deserialized.unbonding_period, 0);
+        assert_eq!(deserialized.double_vote_slash_rate, 0);
+        assert_eq!(deserialized.downtime_slash_rate, 0);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
 
         assert_eq!(params, deserialized.into());
     }
@@ -875,7 +1109,319 @@ mod tests {
         assert_eq!(deserialized.min_deposit, 32);
         assert_eq!(deserialized.max_candidate_metadata_size, 33);
         assert_eq!(deserialized.era, 34);
+        assert_eq!(deserialized.unbonding_period, 0);
+        assert_eq!(deserialized.double_vote_slash_rate, 0);
+        assert_eq!(deserialized.downtime_slash_rate, 0);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
+
+        assert_eq!(params, deserialized.into());
+    }
+
+    #[test]
+    #[allow(clippy::cognitive_complexity)]
+    fn params_from_json_with_unbonding_period() {
+        let s = r#"{
+            "maxExtraDataSize": "0x20",
+            "maxAssetSchemeMetadataSize": "0x0400",
+            "maxTransferMetadataSize": "0x0100",
+            "maxTextContentSize": "0x0200",
+            "networkID" : "tc",
+            "minPayCost" : 10,
+            "minSetRegularKeyCost" : 11,
+            "minCreateShardCost" : 12,
+            "minSetShardOwnersCost" : 13,
+            "minSetShardUsersCost" : 14,
+            "minWrapCccCost" : 15,
+            "minCustomCost" : 16,
+            "minStoreCost" : 17,
+            "minRemoveCost" : 18,
+            "minMintAssetCost" : 19,
+            "minTransferAssetCost" : 20,
+            "minChangeAssetSchemeCost" : 21,
+            "minComposeAssetCost" : 22,
+            "minDecomposeAssetCost" : 23,
+            "minUnwrapCccCost" : 24,
+            "minIncreaseAssetSupplyCost": 25,
+            "maxBodySize" : 4194304,
+            "snapshotPeriod": 16384,
+            "termSeconds": 3600,
+            "nominationExpiration": 26,
+            "custodyPeriod": 27,
+            "releasePeriod": 28,
+            "maxNumOfValidators": 29,
+            "minNumOfValidators": 30,
+            "delegationThreshold": 31,
+            "minDeposit": 32,
+            "maxCandidateMetadataSize": 33,
+            "era": 34,
+            "unbondingPeriod": 35
+        }"#;
+        let params = serde_json::from_str::<Params>(s).unwrap();
+        let deserialized = CommonParams::from(params.clone());
+        assert_eq!(deserialized.size, UNBONDING_PARAM_SIZE);
+        assert_eq!(deserialized.term_seconds, 3600);
+        assert_eq!(deserialized.max_candidate_metadata_size, 33);
+        assert_eq!(deserialized.era, 34);
+        assert_eq!(deserialized.unbonding_period, 35);
+        assert_eq!(deserialized.double_vote_slash_rate, 0);
+        assert_eq!(deserialized.downtime_slash_rate, 0);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
 
         assert_eq!(params, deserialized.into());
+        rlp_encode_and_decode_test!(deserialized);
+    }
+    #[test]
+    fn params_from_json_with_slash_rates() {
+        let s = r#"{
+            "maxExtraDataSize": "0x20",
+            "maxAssetSchemeMetadataSize": "0x0400",
+            "maxTransferMetadataSize": "0x0100",
+            "maxTextContentSize": "0x0200",
+            "networkID" : "tc",
+            "minPayCost" : 10,
+            "minSetRegularKeyCost" : 11,
+            "minCreateShardCost" : 12,
+            "minSetShardOwnersCost" : 13,
+            "minSetShardUsersCost" : 14,
+            "minWrapCccCost" : 15,
+            "minCustomCost" : 16,
+            "minStoreCost" : 17,
+            "minRemoveCost" : 18,
+            "minMintAssetCost" : 19,
+            "minTransferAssetCost" : 20,
+            "minChangeAssetSchemeCost" : 21,
+            "minComposeAssetCost" : 22,
+            "minDecomposeAssetCost" : 23,
+            "minUnwrapCccCost" : 24,
+            "minIncreaseAssetSupplyCost": 25,
+            "maxBodySize" : 4194304,
+            "snapshotPeriod": 16384,
+            "termSeconds": 3600,
+            "nominationExpiration": 26,
+            "custodyPeriod": 27,
+            "releasePeriod": 28,
+            "maxNumOfValidators": 29,
+            "minNumOfValidators": 30,
+            "delegationThreshold": 31,
+            "minDeposit": 32,
+            "maxCandidateMetadataSize": 33,
+            "era": 34,
+            "unbondingPeriod": 35,
+            "doubleVoteSlashRate": 500,
+            "downtimeSlashRate": 10
+        }"#;
+        let params = serde_json::from_str::<Params>(s).unwrap();
+        let deserialized = CommonParams::from(params.clone());
+        assert_eq!(deserialized.size, SLASHING_PARAM_SIZE);
+        assert_eq!(deserialized.unbonding_period, 35);
+        assert_eq!(deserialized.double_vote_slash_rate, 500);
+        assert_eq!(deserialized.downtime_slash_rate, 10);
+        assert_eq!(deserialized.liveness_window, 0);
+        assert_eq!(deserialized.liveness_threshold, 0);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
+
+        assert_eq!(params, deserialized.into());
+        rlp_encode_and_decode_test!(deserialized);
+    }
+
+    #[test]
+    fn params_from_json_with_liveness_params() {
+        let s = r#"{
+            "maxExtraDataSize": "0x20",
+            "maxAssetSchemeMetadataSize": "0x0400",
+            "maxTransferMetadataSize": "0x0100",
+            "maxTextContentSize": "0x0200",
+            "networkID" : "tc",
+            "minPayCost" : 10,
+            "minSetRegularKeyCost" : 11,
+            "minCreateShardCost" : 12,
+            "minSetShardOwnersCost" : 13,
+            "minSetShardUsersCost" : 14,
+            "minWrapCccCost" : 15,
+            "minCustomCost" : 16,
+            "minStoreCost" : 17,
+            "minRemoveCost" : 18,
+            "minMintAssetCost" : 19,
+            "minTransferAssetCost" : 20,
+            "minChangeAssetSchemeCost" : 21,
+            "minComposeAssetCost" : 22,
+            "minDecomposeAssetCost" : 23,
+            "minUnwrapCccCost" : 24,
+            "minIncreaseAssetSupplyCost": 25,
+            "maxBodySize" : 4194304,
+            "snapshotPeriod": 16384,
+            "termSeconds": 3600,
+            "nominationExpiration": 26,
+            "custodyPeriod": 27,
+            "releasePeriod": 28,
+            "maxNumOfValidators": 29,
+            "minNumOfValidators": 30,
+            "delegationThreshold": 31,
+            "minDeposit": 32,
+            "maxCandidateMetadataSize": 33,
+            "era": 34,
+            "unbondingPeriod": 35,
+            "doubleVoteSlashRate": 500,
+            "downtimeSlashRate": 10,
+            "livenessWindow": 100,
+            "livenessThreshold": 5000
+        }"#;
+        let params = serde_json::from_str::<Params>(s).unwrap();
+        let deserialized = CommonParams::from(params.clone());
+        assert_eq!(deserialized.size, LIVENESS_PARAM_SIZE);
+        assert_eq!(deserialized.downtime_slash_rate, 10);
+        assert_eq!(deserialized.liveness_window, 100);
+        assert_eq!(deserialized.liveness_threshold, 5000);
+        assert_eq!(deserialized.proposal_deposit, 0);
+        assert_eq!(deserialized.voting_period, 0);
+        assert_eq!(deserialized.max_script_cost, 0);
+
+        assert_eq!(params, deserialized.into());
+        rlp_encode_and_decode_test!(deserialized);
+    }
+
+    #[test]
+    fn params_from_json_with_governance_params() {
+        let s = r#"{
+            "maxExtraDataSize": "0x20",
+            "maxAssetSchemeMetadataSize": "0x0400",
+            "maxTransferMetadataSize": "0x0100",
+            "maxTextContentSize": "0x0200",
+            "networkID" : "tc",
+            "minPayCost" : 10,
+            "minSetRegularKeyCost" : 11,
+            "minCreateShardCost" : 12,
+            "minSetShardOwnersCost" : 13,
+            "minSetShardUsersCost" : 14,
+            "minWrapCccCost" : 15,
+            "minCustomCost" : 16,
+            "minStoreCost" : 17,
+            "minRemoveCost" : 18,
+            "minMintAssetCost" : 19,
+            "minTransferAssetCost" : 20,
+            "minChangeAssetSchemeCost" : 21,
+            "minComposeAssetCost" : 22,
+            "minDecomposeAssetCost" : 23,
+            "minUnwrapCccCost" : 24,
+            "minIncreaseAssetSupplyCost": 25,
+            "maxBodySize" : 4194304,
+            "snapshotPeriod": 16384,
+            "termSeconds": 3600,
+            "nominationExpiration": 26,
+            "custodyPeriod": 27,
+            "releasePeriod": 28,
+            "maxNumOfValidators": 29,
+            "minNumOfValidators": 30,
+            "delegationThreshold": 31,
+            "minDeposit": 32,
+            "maxCandidateMetadataSize": 33,
+            "era": 34,
+            "unbondingPeriod": 35,
+            "doubleVoteSlashRate": 500,
+            "downtimeSlashRate": 10,
+            "livenessWindow": 100,
+            "livenessThreshold": 5000,
+            "proposalDeposit": 1000,
+            "votingPeriod": 2
+        }"#;
+        let params = serde_json::from_str::<Params>(s).unwrap();
+        let deserialized = CommonParams::from(params.clone());
+        assert_eq!(deserialized.size, GOVERNANCE_PARAM_SIZE);
+        assert_eq!(deserialized.liveness_threshold, 5000);
+        assert_eq!(deserialized.proposal_deposit, 1000);
+        assert_eq!(deserialized.voting_period, 2);
+        assert_eq!(deserialized.max_script_cost, 0);
+
+        assert_eq!(params, deserialized.into());
+        rlp_encode_and_decode_test!(deserialized);
+    }
+
+    #[test]
+    fn params_from_json_with_vm_params() {
+        let s = r#"{
+            "maxExtraDataSize": "0x20",
+            "maxAssetSchemeMetadataSize": "0x0400",
+            "maxTransferMetadataSize": "0x0100",
+            "maxTextContentSize": "0x0200",
+            "networkID" : "tc",
+            "minPayCost" : 10,
+            "minSetRegularKeyCost" : 11,
+            "minCreateShardCost" : 12,
+            "minSetShardOwnersCost" : 13,
+            "minSetShardUsersCost" : 14,
+            "minWrapCccCost" : 15,
+            "minCustomCost" : 16,
+            "minStoreCost" : 17,
+            "minRemoveCost" : 18,
+            "minMintAssetCost" : 19,
+            "minTransferAssetCost" : 20,
+            "minChangeAssetSchemeCost" : 21,
+            "minComposeAssetCost" : 22,
+            "minDecomposeAssetCost" : 23,
+            "minUnwrapCccCost" : 24,
+            "minIncreaseAssetSupplyCost": 25,
+            "maxBodySize" : 4194304,
+            "snapshotPeriod": 16384,
+            "termSeconds": 3600,
+            "nominationExpiration": 26,
+            "custodyPeriod": 27,
+            "releasePeriod": 28,
+            "maxNumOfValidators": 29,
+            "minNumOfValidators": 30,
+            "delegationThreshold": 31,
+            "minDeposit": 32,
+            "maxCandidateMetadataSize": 33,
+            "era": 34,
+            "unbondingPeriod": 35,
+            "doubleVoteSlashRate": 500,
+            "downtimeSlashRate": 10,
+            "livenessWindow": 100,
+            "livenessThreshold": 5000,
+            "proposalDeposit": 1000,
+            "votingPeriod": 2,
+            "maxScriptCost": 20000
+        }"#;
+        let params = serde_json::from_str::<Params>(s).unwrap();
+        let deserialized = CommonParams::from(params.clone());
+        assert_eq!(deserialized.size, VM_PARAM_SIZE);
+        assert_eq!(deserialized.liveness_threshold, 5000);
+        assert_eq!(deserialized.proposal_deposit, 1000);
+        assert_eq!(deserialized.voting_period, 2);
+        assert_eq!(deserialized.max_script_cost, 20000);
+
+        assert_eq!(params, deserialized.into());
+        rlp_encode_and_decode_test!(deserialized);
+    }
+
+    #[test]
+    fn liveness_threshold_should_be_set_with_the_window() {
+        let mut params = CommonParams::default_for_test();
+        params.set_liveness_params_for_test(100, 0);
+        assert!(params.verify().is_err());
+        params.set_liveness_params_for_test(100, MAX_LIVENESS_THRESHOLD + 1);
+        assert!(params.verify().is_err());
+        params.set_liveness_params_for_test(100, 5000);
+        assert_eq!(Ok(()), params.verify());
+        params.set_liveness_params_for_test(0, 0);
+        assert_eq!(Ok(()), params.verify());
+    }
+
+    #[test]
+    fn slash_rate_cannot_exceed_the_maximum() {
+        let mut params = CommonParams::default_for_test();
+        params.set_slash_rates_for_test(MAX_SLASH_RATE + 1, 0);
+        assert!(params.verify().is_err());
+        params.set_slash_rates_for_test(MAX_SLASH_RATE, MAX_SLASH_RATE);
+        assert_eq!(Ok(()), params.verify());
     }
 }
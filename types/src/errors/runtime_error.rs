@@ -105,6 +105,18 @@ pub enum Error {
         idx: usize,
         parent_height: u64,
     },
+    /// The input asset is frozen by the registrar
+    AssetFrozen {
+        shard_id: ShardId,
+        tracker: Tracker,
+        index: usize,
+    },
+    /// The assets locked by the lock script hash are frozen by the registrar
+    ScriptHashFrozen(H160),
+    /// An empty list of the allowed script hashes allows every script hash
+    CannotEmptyAllowedScriptHashes,
+    /// No one could unfreeze the frozen assets without the registrar
+    CannotRemoveRegistrarOfFrozenAssets,
 }
 
 #[derive(Clone, Copy)]
@@ -144,6 +156,10 @@ enum ErrorID {
     SignatureOfInvalid = 32,
     InsufficientStakes = 33,
     InvalidValidatorIndex = 34,
+    AssetFrozen = 35,
+    ScriptHashFrozen = 36,
+    CannotEmptyAllowedScriptHashes = 37,
+    CannotRemoveRegistrarOfFrozenAssets = 38,
 }
 
 impl Encodable for ErrorID {
@@ -159,32 +175,36 @@ impl Decodable for ErrorID {
             1u8 => Ok(ErrorID::AssetNotFound),
             2 => Ok(ErrorID::AssetSchemeDuplicated),
             3 => Ok(ErrorID::AssetSchemeNotFound),
-            4 => Ok(ErrorID::InvalidSeqOfAssetScheme),
-            6 => Ok(ErrorID::AssetSupplyOverflow),
-            7 => Ok(ErrorID::CannotBurnRegulatedAsset),
-            8 => Ok(ErrorID::FailedToHandleCustomAction),
-            9 => Ok(ErrorID::FailedToUnlock),
-            10 => Ok(ErrorID::InsufficientBalance),
-            11 => Ok(ErrorID::InsufficientPermission),
-            15 => Ok(ErrorID::InvalidAssetQuantity),
-            16 => Ok(ErrorID::UnexpectedAssetType),
-            17 => Ok(ErrorID::InvalidScript),
-            18 => Ok(ErrorID::InvalidSeq),
-            19 => Ok(ErrorID::InvalidShardID),
-            20 => Ok(ErrorID::InvalidTransferDestination),
-            21 => Ok(ErrorID::NewOwnersMustContainSender),
-            22 => Ok(ErrorID::NotApproved),
-            23 => Ok(ErrorID::RegularKeyAlreadyInUse),
-            24 => Ok(ErrorID::RegularKeyAlreadyInUseAsPlatform),
-            25 => Ok(ErrorID::ScriptHashMismatch),
-            27 => Ok(ErrorID::ScriptNotAllowed),
-            28 => Ok(ErrorID::TextNotExist),
-            29 => Ok(ErrorID::TextVerificationFail),
-            30 => Ok(ErrorID::CannotUseMasterKey),
-            31 => Ok(ErrorID::NonActiveAccount),
+            4 => Ok(ErrorID::CannotBurnRegulatedAsset),
+            6 => Ok(ErrorID::FailedToUnlock),
+            7 => Ok(ErrorID::InvalidSeqOfAssetScheme),
+            8 => Ok(ErrorID::InsufficientBalance),
+            9 => Ok(ErrorID::InsufficientPermission),
+            10 => Ok(ErrorID::InvalidAssetQuantity),
+            11 => Ok(ErrorID::UnexpectedAssetType),
+            15 => Ok(ErrorID::InvalidShardID),
+            16 => Ok(ErrorID::InvalidTransferDestination),
+            17 => Ok(ErrorID::NewOwnersMustContainSender),
+            18 => Ok(ErrorID::NotApproved),
+            19 => Ok(ErrorID::RegularKeyAlreadyInUse),
+            20 => Ok(ErrorID::RegularKeyAlreadyInUseAsPlatform),
+            21 => Ok(ErrorID::ScriptHashMismatch),
+            22 => Ok(ErrorID::ScriptNotAllowed),
+            23 => Ok(ErrorID::TextNotExist),
+            24 => Ok(ErrorID::TextVerificationFail),
+            25 => Ok(ErrorID::CannotUseMasterKey),
+            27 => Ok(ErrorID::InvalidScript),
+            28 => Ok(ErrorID::InvalidSeq),
+            29 => Ok(ErrorID::AssetSupplyOverflow),
+            30 => Ok(ErrorID::NonActiveAccount),
+            31 => Ok(ErrorID::FailedToHandleCustomAction),
             32 => Ok(ErrorID::SignatureOfInvalid),
             33 => Ok(ErrorID::InsufficientStakes),
             34 => Ok(ErrorID::InvalidValidatorIndex),
+            35 => Ok(ErrorID::AssetFrozen),
+            36 => Ok(ErrorID::ScriptHashFrozen),
+            37 => Ok(ErrorID::CannotEmptyAllowedScriptHashes),
+            38 => Ok(ErrorID::CannotRemoveRegistrarOfFrozenAssets),
             _ => Err(DecoderError::Custom("Unexpected ActionTag Value")),
         }
     }
@@ -225,6 +245,10 @@ impl TaggedRlp for RlpHelper {
             ErrorID::SignatureOfInvalid => 2,
             ErrorID::InsufficientStakes => 3,
             ErrorID::InvalidValidatorIndex => 3,
+            ErrorID::AssetFrozen => 4,
+            ErrorID::ScriptHashFrozen => 2,
+            ErrorID::CannotEmptyAllowedScriptHashes => 1,
+            ErrorID::CannotRemoveRegistrarOfFrozenAssets => 1,
         })
     }
 }
@@ -328,6 +352,18 @@ impl Encodable for Error {
                 idx,
                 parent_height,
             } => RlpHelper::new_tagged_list(s, ErrorID::InvalidValidatorIndex).append(idx).append(parent_height),
+            Error::AssetFrozen {
+                shard_id,
+                tracker,
+                index,
+            } => RlpHelper::new_tagged_list(s, ErrorID::AssetFrozen).append(shard_id).append(tracker).append(index),
+            Error::ScriptHashFrozen(hash) => RlpHelper::new_tagged_list(s, ErrorID::ScriptHashFrozen).append(hash),
+            Error::CannotEmptyAllowedScriptHashes => {
+                RlpHelper::new_tagged_list(s, ErrorID::CannotEmptyAllowedScriptHashes)
+            }
+            Error::CannotRemoveRegistrarOfFrozenAssets => {
+                RlpHelper::new_tagged_list(s, ErrorID::CannotRemoveRegistrarOfFrozenAssets)
+            }
         };
     }
 }
@@ -407,6 +443,14 @@ impl Decodable for Error {
                 idx: rlp.val_at(1)?,
                 parent_height: rlp.val_at(2)?,
             },
+            ErrorID::AssetFrozen => Error::AssetFrozen {
+                shard_id: rlp.val_at(1)?,
+                tracker: rlp.val_at(2)?,
+                index: rlp.val_at(3)?,
+            },
+            ErrorID::ScriptHashFrozen => Error::ScriptHashFrozen(rlp.val_at(1)?),
+            ErrorID::CannotEmptyAllowedScriptHashes => Error::CannotEmptyAllowedScriptHashes,
+            ErrorID::CannotRemoveRegistrarOfFrozenAssets => Error::CannotRemoveRegistrarOfFrozenAssets,
         };
         RlpHelper::check_size(rlp, tag)?;
         Ok(error)
@@ -486,6 +530,10 @@ impl Display for Error {
             Error::InvalidValidatorIndex {
                 idx, parent_height,
             } =>  write!(f, "The validator index {} is invalid at the parent hash {}", idx, parent_height),
+            Error::AssetFrozen { shard_id, tracker, index } => write!(f, "Asset is frozen: {}:{}:{}", shard_id, tracker, index),
+            Error::ScriptHashFrozen(hash) => write!(f, "The assets locked by {} are frozen", hash),
+            Error::CannotEmptyAllowedScriptHashes => write!(f, "Cannot remove every allowed script hash"),
+            Error::CannotRemoveRegistrarOfFrozenAssets => write!(f, "Cannot remove the registrar while the assets are frozen"),
         }
     }
 }
@@ -495,6 +543,7 @@ pub enum UnlockFailureReason {
     ScriptShouldBeBurnt,
     ScriptShouldNotBeBurnt,
     ScriptError,
+    ScriptOutOfGas,
 }
 
 #[derive(Clone, Copy)]
@@ -503,6 +552,7 @@ enum ScriptFailureReasonID {
     ShouldBeBurnt = 1u8,
     ShouldNotBeBurnt = 2u8,
     Error = 3u8,
+    OutOfGas = 4u8,
 }
 
 impl Encodable for ScriptFailureReasonID {
@@ -518,6 +568,7 @@ impl Decodable for ScriptFailureReasonID {
             1u8 => Ok(ScriptFailureReasonID::ShouldBeBurnt),
             2 => Ok(ScriptFailureReasonID::ShouldNotBeBurnt),
             3 => Ok(ScriptFailureReasonID::Error),
+            4 => Ok(ScriptFailureReasonID::OutOfGas),
             _ => Err(DecoderError::Custom("Unexpected ScriptFailureReasonID Value")),
         }
     }
@@ -529,6 +580,7 @@ impl Encodable for UnlockFailureReason {
             UnlockFailureReason::ScriptShouldBeBurnt => (ScriptFailureReasonID::ShouldBeBurnt).rlp_append(s),
             UnlockFailureReason::ScriptShouldNotBeBurnt => (ScriptFailureReasonID::ShouldNotBeBurnt).rlp_append(s),
             UnlockFailureReason::ScriptError => (ScriptFailureReasonID::Error).rlp_append(s),
+            UnlockFailureReason::ScriptOutOfGas => (ScriptFailureReasonID::OutOfGas).rlp_append(s),
         };
     }
 }
@@ -539,6 +591,7 @@ impl Decodable for UnlockFailureReason {
             ScriptFailureReasonID::ShouldBeBurnt => UnlockFailureReason::ScriptShouldBeBurnt,
             ScriptFailureReasonID::ShouldNotBeBurnt => UnlockFailureReason::ScriptShouldNotBeBurnt,
             ScriptFailureReasonID::Error => UnlockFailureReason::ScriptError,
+            ScriptFailureReasonID::OutOfGas => UnlockFailureReason::ScriptOutOfGas,
         })
     }
 }
@@ -549,6 +602,60 @@ impl Display for UnlockFailureReason {
             UnlockFailureReason::ScriptShouldBeBurnt => write!(f, "Script should be burnt"),
             UnlockFailureReason::ScriptShouldNotBeBurnt => write!(f, "Script should not be burnt"),
             UnlockFailureReason::ScriptError => write!(f, "Script error"),
+            UnlockFailureReason::ScriptOutOfGas => write!(f, "Script exceeded the maximum cost"),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use primitives::H256;
+    use rlp::rlp_encode_and_decode_test;
+
+    use super::*;
+
+    #[test]
+    fn rlp_encode_and_decode_errors_with_reused_tags() {
+        rlp_encode_and_decode_test!(Error::CannotBurnRegulatedAsset);
+        rlp_encode_and_decode_test!(Error::InvalidSeqOfAssetScheme {
+            asset_type: H160::random(),
+            shard_id: 3,
+            expected: 1,
+            actual: 2,
+        });
+        rlp_encode_and_decode_test!(Error::InsufficientBalance {
+            address: Address::random(),
+            balance: 10,
+            cost: 20,
+        });
+        rlp_encode_and_decode_test!(Error::FailedToHandleCustomAction("invalid".to_string()));
+        rlp_encode_and_decode_test!(Error::InvalidSeq(Mismatch {
+            expected: 1,
+            found: 2,
+        }));
+    }
+
+    #[test]
+    fn rlp_encode_and_decode_frozen_errors() {
+        rlp_encode_and_decode_test!(Error::AssetFrozen {
+            shard_id: 3,
+            tracker: H256::random().into(),
+            index: 1,
+        });
+        rlp_encode_and_decode_test!(Error::ScriptHashFrozen(H160::random()));
+        rlp_encode_and_decode_test!(Error::CannotEmptyAllowedScriptHashes);
+        rlp_encode_and_decode_test!(Error::CannotRemoveRegistrarOfFrozenAssets);
+    }
+
+    #[test]
+    fn rlp_encode_and_decode_unlock_failure_reasons() {
+        rlp_encode_and_decode_test!(UnlockFailureReason::ScriptError);
+        rlp_encode_and_decode_test!(UnlockFailureReason::ScriptOutOfGas);
+        rlp_encode_and_decode_test!(Error::FailedToUnlock {
+            shard_id: 3,
+            tracker: H256::random().into(),
+            index: 1,
+            reason: UnlockFailureReason::ScriptOutOfGas,
+        });
+    }
+}
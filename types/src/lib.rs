@@ -38,7 +38,7 @@ pub type BlockNumber = u64;
 pub type ShardId = u16;
 
 pub use block_hash::BlockHash;
-pub use common_params::CommonParams;
+pub use common_params::{CommonParams, MAX_LIVENESS_THRESHOLD, MAX_SLASH_RATE};
 pub use header::Header;
 pub use tracker::Tracker;
 pub use tx_hash::TxHash;
@@ -15,7 +15,7 @@
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
 use crate::errors::SyntaxError;
-use crate::transaction::{AssetMintOutput, AssetTransferInput, AssetTransferOutput, ShardTransaction};
+use crate::transaction::{AssetMintOutput, AssetOutPoint, AssetTransferInput, AssetTransferOutput, ShardTransaction};
 use crate::{CommonParams, ShardId, Tracker, TxHash};
 use ccrypto::Blake;
 use ckey::{recover, Address, NetworkId, Public, Signature};
@@ -41,6 +41,10 @@ const CHANGE_ASSET_SCHEME: u8 = 0x15;
 //const DECOMPOSE_ASSET: u8 = 0x17;
 const INCREASE_ASSET_SUPPLY: u8 = 0x18;
 const SHARD_STORE: u8 = 0x19;
+const SET_ASSET_FROZEN: u8 = 0x1A;
+const SET_SCRIPT_HASH_FROZEN: u8 = 0x1B;
+const UPDATE_ALLOWED_SCRIPT_HASHES: u8 = 0x1C;
+const CLAWBACK_ASSET: u8 = 0x1D;
 
 const CUSTOM: u8 = 0xFF;
 
@@ -133,6 +137,41 @@ pub enum Action {
         shard_id: ShardId,
         content: String,
     },
+    SetAssetFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        asset_tracker: Tracker,
+        index: usize,
+        frozen: bool,
+        approvals: Vec<Signature>,
+    },
+    SetScriptHashFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        lock_script_hash: H160,
+        frozen: bool,
+        approvals: Vec<Signature>,
+    },
+    UpdateAllowedScriptHashes {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        added: Vec<H160>,
+        removed: Vec<H160>,
+        approvals: Vec<Signature>,
+    },
+    ClawbackAsset {
+        network_id: NetworkId,
+        prev_out: AssetOutPoint,
+        lock_script_hash: H160,
+        parameters: Vec<Bytes>,
+        approvals: Vec<Signature>,
+    },
 }
 
 impl Action {
@@ -157,6 +196,18 @@ impl Action {
             }
             | Action::UnwrapCCC {
                 ..
+            }
+            | Action::SetAssetFrozen {
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                ..
+            }
+            | Action::ClawbackAsset {
+                ..
             } => self.clone().into(),
             _ => None,
         }
@@ -220,6 +271,33 @@ impl Action {
                     return Err(SyntaxError::CannotChangeWcccAssetScheme)
                 }
             }
+            Action::SetAssetFrozen {
+                asset_type,
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                asset_type,
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                asset_type,
+                ..
+            } => {
+                if asset_type.is_zero() {
+                    return Err(SyntaxError::CannotChangeWcccAssetScheme)
+                }
+            }
+            Action::ClawbackAsset {
+                prev_out,
+                ..
+            } => {
+                if prev_out.quantity == 0 {
+                    return Err(SyntaxError::ZeroQuantity)
+                }
+                if prev_out.asset_type.is_zero() {
+                    return Err(SyntaxError::CannotChangeWcccAssetScheme)
+                }
+            }
             Action::UnwrapCCC {
                 burn,
                 ..
@@ -343,6 +421,22 @@ impl Action {
             | Action::IncreaseAssetSupply {
                 approvals,
                 ..
+            }
+            | Action::SetAssetFrozen {
+                approvals,
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                approvals,
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                approvals,
+                ..
+            }
+            | Action::ClawbackAsset {
+                approvals,
+                ..
             } => Some(approvals),
             _ => None,
         }
@@ -369,6 +463,22 @@ impl Action {
             | Action::UnwrapCCC {
                 network_id,
                 ..
+            }
+            | Action::SetAssetFrozen {
+                network_id,
+                ..
+            }
+            | Action::SetScriptHashFrozen {
+                network_id,
+                ..
+            }
+            | Action::UpdateAllowedScriptHashes {
+                network_id,
+                ..
+            }
+            | Action::ClawbackAsset {
+                network_id,
+                ..
             } => Some(*network_id),
             _ => None,
         }
@@ -460,6 +570,68 @@ impl From<Action> for Option<ShardTransaction> {
                 shard_id,
                 content,
             }),
+            Action::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+                ..
+            } => Some(ShardTransaction::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+            }),
+            Action::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+                ..
+            } => Some(ShardTransaction::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+            }),
+            Action::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+                ..
+            } => Some(ShardTransaction::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+            }),
+            Action::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+                ..
+            } => Some(ShardTransaction::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+            }),
             _ => None,
         }
     }
@@ -658,6 +830,80 @@ impl Encodable for Action {
                 s.append(shard_id);
                 s.append(content);
             }
+            Action::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+                approvals,
+            } => {
+                s.begin_list(9)
+                    .append(&SET_ASSET_FROZEN)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append(asset_tracker)
+                    .append(index)
+                    .append(frozen)
+                    .append_list(approvals);
+            }
+            Action::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+                approvals,
+            } => {
+                s.begin_list(8)
+                    .append(&SET_SCRIPT_HASH_FROZEN)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append(lock_script_hash)
+                    .append(frozen)
+                    .append_list(approvals);
+            }
+            Action::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+                approvals,
+            } => {
+                s.begin_list(8)
+                    .append(&UPDATE_ALLOWED_SCRIPT_HASHES)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append_list(added)
+                    .append_list(removed)
+                    .append_list(approvals);
+            }
+            Action::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+                approvals,
+            } => {
+                s.begin_list(6)
+                    .append(&CLAWBACK_ASSET)
+                    .append(network_id)
+                    .append(prev_out)
+                    .append(lock_script_hash)
+                    .append(parameters)
+                    .append_list(approvals);
+            }
         }
     }
 }
@@ -894,6 +1140,77 @@ impl Decodable for Action {
                     content: rlp.val_at(3)?,
                 })
             }
+            SET_ASSET_FROZEN => {
+                let item_count = rlp.item_count()?;
+                if item_count != 9 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 9,
+                    })
+                }
+                Ok(Action::SetAssetFrozen {
+                    network_id: rlp.val_at(1)?,
+                    shard_id: rlp.val_at(2)?,
+                    asset_type: rlp.val_at(3)?,
+                    seq: rlp.val_at(4)?,
+                    asset_tracker: rlp.val_at(5)?,
+                    index: rlp.val_at(6)?,
+                    frozen: rlp.val_at(7)?,
+                    approvals: rlp.list_at(8)?,
+                })
+            }
+            SET_SCRIPT_HASH_FROZEN => {
+                let item_count = rlp.item_count()?;
+                if item_count != 8 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 8,
+                    })
+                }
+                Ok(Action::SetScriptHashFrozen {
+                    network_id: rlp.val_at(1)?,
+                    shard_id: rlp.val_at(2)?,
+                    asset_type: rlp.val_at(3)?,
+                    seq: rlp.val_at(4)?,
+                    lock_script_hash: rlp.val_at(5)?,
+                    frozen: rlp.val_at(6)?,
+                    approvals: rlp.list_at(7)?,
+                })
+            }
+            UPDATE_ALLOWED_SCRIPT_HASHES => {
+                let item_count = rlp.item_count()?;
+                if item_count != 8 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 8,
+                    })
+                }
+                Ok(Action::UpdateAllowedScriptHashes {
+                    network_id: rlp.val_at(1)?,
+                    shard_id: rlp.val_at(2)?,
+                    asset_type: rlp.val_at(3)?,
+                    seq: rlp.val_at(4)?,
+                    added: rlp.list_at(5)?,
+                    removed: rlp.list_at(6)?,
+                    approvals: rlp.list_at(7)?,
+                })
+            }
+            CLAWBACK_ASSET => {
+                let item_count = rlp.item_count()?;
+                if item_count != 6 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 6,
+                    })
+                }
+                Ok(Action::ClawbackAsset {
+                    network_id: rlp.val_at(1)?,
+                    prev_out: rlp.val_at(2)?,
+                    lock_script_hash: rlp.val_at(3)?,
+                    parameters: rlp.val_at(4)?,
+                    approvals: rlp.list_at(5)?,
+                })
+            }
             _ => Err(DecoderError::Custom("Unexpected action prefix")),
         }
     }
@@ -947,7 +1264,6 @@ mod tests {
     use rlp::rlp_encode_and_decode_test;
 
     use super::*;
-    use crate::transaction::AssetOutPoint;
 
     #[test]
     fn encode_and_decode_mint_asset() {
@@ -1095,6 +1411,63 @@ mod tests {
         });
     }
 
+    #[test]
+    fn encode_and_decode_set_asset_frozen() {
+        rlp_encode_and_decode_test!(Action::SetAssetFrozen {
+            network_id: "tc".into(),
+            shard_id: 1,
+            asset_type: H160::random(),
+            seq: 3,
+            asset_tracker: H256::random().into(),
+            index: 2,
+            frozen: true,
+            approvals: vec![Signature::random()],
+        });
+    }
+
+    #[test]
+    fn encode_and_decode_set_script_hash_frozen() {
+        rlp_encode_and_decode_test!(Action::SetScriptHashFrozen {
+            network_id: "tc".into(),
+            shard_id: 1,
+            asset_type: H160::random(),
+            seq: 0,
+            lock_script_hash: H160::random(),
+            frozen: false,
+            approvals: vec![],
+        });
+    }
+
+    #[test]
+    fn encode_and_decode_update_allowed_script_hashes() {
+        rlp_encode_and_decode_test!(Action::UpdateAllowedScriptHashes {
+            network_id: "tc".into(),
+            shard_id: 1,
+            asset_type: H160::random(),
+            seq: 1,
+            added: vec![H160::random(), H160::random()],
+            removed: vec![H160::random()],
+            approvals: vec![],
+        });
+    }
+
+    #[test]
+    fn encode_and_decode_clawback_asset() {
+        rlp_encode_and_decode_test!(Action::ClawbackAsset {
+            network_id: "tc".into(),
+            prev_out: AssetOutPoint {
+                tracker: H256::random().into(),
+                index: 0,
+                asset_type: H160::random(),
+                shard_id: 1,
+                quantity: 100,
+            },
+            lock_script_hash: H160::random(),
+            parameters: vec![vec![1, 2, 3]],
+            approvals: vec![Signature::random()],
+        });
+    }
+
     #[test]
     fn verify_unwrap_ccc_transaction_should_fail() {
         let tx_zero_quantity = Action::UnwrapCCC {
@@ -14,10 +14,10 @@
 // You should have received a copy of the GNU Affero General Public License
 // along with this program.  If not, see <https://www.gnu.org/licenses/>.
 
-use super::{AssetMintOutput, AssetTransferInput, AssetTransferOutput, HashingError, PartialHashing};
+use super::{AssetMintOutput, AssetOutPoint, AssetTransferInput, AssetTransferOutput, HashingError, PartialHashing};
 use crate::util::tag::Tag;
 use crate::{ShardId, Tracker, TxHash};
-use ccrypto::{blake128, blake256, blake256_with_key};
+use ccrypto::{blake128, blake256, blake256_with_key, Blake};
 use ckey::{Address, NetworkId};
 use primitives::{Bytes, H160, H256};
 use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
@@ -73,6 +73,37 @@ pub enum ShardTransaction {
         shard_id: ShardId,
         content: String,
     },
+    SetAssetFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        asset_tracker: Tracker,
+        index: usize,
+        frozen: bool,
+    },
+    SetScriptHashFrozen {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        lock_script_hash: H160,
+        frozen: bool,
+    },
+    UpdateAllowedScriptHashes {
+        network_id: NetworkId,
+        shard_id: ShardId,
+        asset_type: H160,
+        seq: usize,
+        added: Vec<H160>,
+        removed: Vec<H160>,
+    },
+    ClawbackAsset {
+        network_id: NetworkId,
+        prev_out: AssetOutPoint,
+        lock_script_hash: H160,
+        parameters: Vec<Bytes>,
+    },
 }
 
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -123,6 +154,22 @@ impl ShardTransaction {
             | ShardTransaction::ShardStore {
                 network_id,
                 ..
+            }
+            | ShardTransaction::SetAssetFrozen {
+                network_id,
+                ..
+            }
+            | ShardTransaction::SetScriptHashFrozen {
+                network_id,
+                ..
+            }
+            | ShardTransaction::UpdateAllowedScriptHashes {
+                network_id,
+                ..
+            }
+            | ShardTransaction::ClawbackAsset {
+                network_id,
+                ..
             } => *network_id,
         }
     }
@@ -167,6 +214,105 @@ impl ShardTransaction {
                 shard_id,
                 ..
             } => vec![*shard_id],
+            ShardTransaction::SetAssetFrozen {
+                shard_id,
+                ..
+            }
+            | ShardTransaction::SetScriptHashFrozen {
+                shard_id,
+                ..
+            }
+            | ShardTransaction::UpdateAllowedScriptHashes {
+                shard_id,
+                ..
+            } => vec![*shard_id],
+            ShardTransaction::ClawbackAsset {
+                prev_out,
+                ..
+            } => vec![prev_out.shard_id],
+        }
+    }
+
+    /// Returns the asset outputs that this transaction creates when it succeeds.
+    pub fn created_assets(&self) -> Vec<AssetOutPoint> {
+        let tracker = self.tracker();
+        match self {
+            ShardTransaction::MintAsset {
+                shard_id,
+                output,
+                ..
+            } => vec![AssetOutPoint {
+                tracker,
+                index: 0,
+                asset_type: Blake::blake(*tracker),
+                shard_id: *shard_id,
+                quantity: output.supply,
+            }],
+            ShardTransaction::TransferAsset {
+                outputs,
+                ..
+            } => outputs
+                .iter()
+                .enumerate()
+                .map(|(index, output)| AssetOutPoint {
+                    tracker,
+                    index,
+                    asset_type: output.asset_type,
+                    shard_id: output.shard_id,
+                    quantity: output.quantity,
+                })
+                .collect(),
+            ShardTransaction::IncreaseAssetSupply {
+                shard_id,
+                asset_type,
+                output,
+                ..
+            } => vec![AssetOutPoint {
+                tracker,
+                index: 0,
+                asset_type: *asset_type,
+                shard_id: *shard_id,
+                quantity: output.supply,
+            }],
+            ShardTransaction::WrapCCC {
+                shard_id,
+                output,
+                ..
+            } => vec![AssetOutPoint {
+                tracker,
+                index: 0,
+                asset_type: H160::zero(),
+                shard_id: *shard_id,
+                quantity: output.quantity,
+            }],
+            ShardTransaction::ClawbackAsset {
+                prev_out,
+                ..
+            } => vec![AssetOutPoint {
+                tracker,
+                index: 0,
+                asset_type: prev_out.asset_type,
+                shard_id: prev_out.shard_id,
+                quantity: prev_out.quantity,
+            }],
+            ShardTransaction::ChangeAssetScheme {
+                ..
+            }
+            | ShardTransaction::UnwrapCCC {
+                ..
+            }
+            | ShardTransaction::ShardStore {
+                ..
+            }
+            | ShardTransaction::SetAssetFrozen {
+                ..
+            }
+            | ShardTransaction::SetScriptHashFrozen {
+                ..
+            }
+            | ShardTransaction::UpdateAllowedScriptHashes {
+                ..
+            } => vec![],
         }
     }
 
@@ -194,6 +340,18 @@ impl ShardTransaction {
             ShardTransaction::ShardStore {
                 ..
             } => index == 0,
+            ShardTransaction::SetAssetFrozen {
+                ..
+            }
+            | ShardTransaction::SetScriptHashFrozen {
+                ..
+            }
+            | ShardTransaction::UpdateAllowedScriptHashes {
+                ..
+            } => false,
+            ShardTransaction::ClawbackAsset {
+                ..
+            } => index == 0,
         }
     }
 
@@ -228,6 +386,19 @@ impl ShardTransaction {
                 shard_id,
                 ..
             } => &id == shard_id,
+            ShardTransaction::SetAssetFrozen {
+                ..
+            }
+            | ShardTransaction::SetScriptHashFrozen {
+                ..
+            }
+            | ShardTransaction::UpdateAllowedScriptHashes {
+                ..
+            } => unreachable!("The registrar actions don't have a valid index"),
+            ShardTransaction::ClawbackAsset {
+                prev_out,
+                ..
+            } => id == prev_out.shard_id,
         }
     }
 }
@@ -359,6 +530,10 @@ enum AssetID {
     // DECOMPOSE_ID = 0x17,
     IncreaseSupply = 0x18,
     ShardStore = 0x19,
+    SetAssetFrozen = 0x1A,
+    SetScriptHashFrozen = 0x1B,
+    UpdateAllowedScriptHashes = 0x1C,
+    ClawbackAsset = 0x1D,
 }
 
 impl Encodable for AssetID {
@@ -377,6 +552,10 @@ impl Decodable for AssetID {
             0x15 => Ok(AssetID::SchemeChange),
             0x18 => Ok(AssetID::IncreaseSupply),
             0x19 => Ok(AssetID::ShardStore),
+            0x1A => Ok(AssetID::SetAssetFrozen),
+            0x1B => Ok(AssetID::SetScriptHashFrozen),
+            0x1C => Ok(AssetID::UpdateAllowedScriptHashes),
+            0x1D => Ok(AssetID::ClawbackAsset),
             _ => Err(DecoderError::Custom("Unexpected AssetID Value")),
         }
     }
@@ -493,6 +672,73 @@ impl Decodable for ShardTransaction {
                     content: d.val_at(3)?,
                 })
             }
+            AssetID::SetAssetFrozen => {
+                let item_count = d.item_count()?;
+                if item_count != 8 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 8,
+                    })
+                }
+                Ok(ShardTransaction::SetAssetFrozen {
+                    network_id: d.val_at(1)?,
+                    shard_id: d.val_at(2)?,
+                    asset_type: d.val_at(3)?,
+                    seq: d.val_at(4)?,
+                    asset_tracker: d.val_at(5)?,
+                    index: d.val_at(6)?,
+                    frozen: d.val_at(7)?,
+                })
+            }
+            AssetID::SetScriptHashFrozen => {
+                let item_count = d.item_count()?;
+                if item_count != 7 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 7,
+                    })
+                }
+                Ok(ShardTransaction::SetScriptHashFrozen {
+                    network_id: d.val_at(1)?,
+                    shard_id: d.val_at(2)?,
+                    asset_type: d.val_at(3)?,
+                    seq: d.val_at(4)?,
+                    lock_script_hash: d.val_at(5)?,
+                    frozen: d.val_at(6)?,
+                })
+            }
+            AssetID::UpdateAllowedScriptHashes => {
+                let item_count = d.item_count()?;
+                if item_count != 7 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 7,
+                    })
+                }
+                Ok(ShardTransaction::UpdateAllowedScriptHashes {
+                    network_id: d.val_at(1)?,
+                    shard_id: d.val_at(2)?,
+                    asset_type: d.val_at(3)?,
+                    seq: d.val_at(4)?,
+                    added: d.list_at(5)?,
+                    removed: d.list_at(6)?,
+                })
+            }
+            AssetID::ClawbackAsset => {
+                let item_count = d.item_count()?;
+                if item_count != 5 {
+                    return Err(DecoderError::RlpIncorrectListLen {
+                        got: item_count,
+                        expected: 5,
+                    })
+                }
+                Ok(ShardTransaction::ClawbackAsset {
+                    network_id: d.val_at(1)?,
+                    prev_out: d.val_at(2)?,
+                    lock_script_hash: d.val_at(3)?,
+                    parameters: d.val_at(4)?,
+                })
+            }
         }
     }
 }
@@ -604,6 +850,72 @@ impl Encodable for ShardTransaction {
             } => {
                 s.begin_list(4).append(&AssetID::ShardStore).append(network_id).append(shard_id).append(content);
             }
+            ShardTransaction::SetAssetFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                asset_tracker,
+                index,
+                frozen,
+            } => {
+                s.begin_list(8)
+                    .append(&AssetID::SetAssetFrozen)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append(asset_tracker)
+                    .append(index)
+                    .append(frozen);
+            }
+            ShardTransaction::SetScriptHashFrozen {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                lock_script_hash,
+                frozen,
+            } => {
+                s.begin_list(7)
+                    .append(&AssetID::SetScriptHashFrozen)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append(lock_script_hash)
+                    .append(frozen);
+            }
+            ShardTransaction::UpdateAllowedScriptHashes {
+                network_id,
+                shard_id,
+                asset_type,
+                seq,
+                added,
+                removed,
+            } => {
+                s.begin_list(7)
+                    .append(&AssetID::UpdateAllowedScriptHashes)
+                    .append(network_id)
+                    .append(shard_id)
+                    .append(asset_type)
+                    .append(seq)
+                    .append_list(added)
+                    .append_list(removed);
+            }
+            ShardTransaction::ClawbackAsset {
+                network_id,
+                prev_out,
+                lock_script_hash,
+                parameters,
+            } => {
+                s.begin_list(5)
+                    .append(&AssetID::ClawbackAsset)
+                    .append(network_id)
+                    .append(prev_out)
+                    .append(lock_script_hash)
+                    .append(parameters);
+            }
         };
     }
 }
@@ -1028,6 +1340,62 @@ mod tests {
         sum.iter().all(|(_, sum)| *sum == 0)
     }
 
+    #[test]
+    fn encode_and_decode_set_asset_frozen() {
+        let tx = ShardTransaction::SetAssetFrozen {
+            network_id: Default::default(),
+            shard_id: 0,
+            asset_type: H160::random(),
+            seq: 1,
+            asset_tracker: H256::random().into(),
+            index: 3,
+            frozen: true,
+        };
+        rlp_encode_and_decode_test!(tx);
+    }
+
+    #[test]
+    fn encode_and_decode_clawback_asset() {
+        let tx = ShardTransaction::ClawbackAsset {
+            network_id: Default::default(),
+            prev_out: AssetOutPoint {
+                tracker: H256::random().into(),
+                index: 0,
+                asset_type: H160::random(),
+                shard_id: 0,
+                quantity: 10,
+            },
+            lock_script_hash: H160::random(),
+            parameters: vec![vec![1]],
+        };
+        rlp_encode_and_decode_test!(tx);
+    }
+
+    #[test]
+    fn clawback_creates_an_asset_of_the_same_quantity() {
+        let prev_out = AssetOutPoint {
+            tracker: H256::random().into(),
+            index: 2,
+            asset_type: H160::random(),
+            shard_id: 3,
+            quantity: 10,
+        };
+        let tx = ShardTransaction::ClawbackAsset {
+            network_id: Default::default(),
+            prev_out: prev_out.clone(),
+            lock_script_hash: H160::random(),
+            parameters: vec![],
+        };
+        assert_eq!(tx.created_assets(), vec![AssetOutPoint {
+            tracker: tx.tracker(),
+            index: 0,
+            asset_type: prev_out.asset_type,
+            shard_id: 3,
+            quantity: 10,
+        }]);
+        assert_eq!(tx.related_shards(), vec![3]);
+    }
+
     #[test]
     fn encode_and_decode_shard_store_text() {
         let tx = ShardTransaction::ShardStore {
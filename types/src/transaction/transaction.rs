@@ -51,7 +51,11 @@ impl Transaction {
     }
 
     pub fn tracker(&self) -> Option<Tracker> {
-        let shard_tx = match self.action.clone() {
+        self.shard_transaction().map(|t| t.tracker())
+    }
+
+    pub fn shard_transaction(&self) -> Option<ShardTransaction> {
+        match self.action.clone() {
             Action::WrapCCC {
                 shard_id,
                 lock_script_hash,
@@ -69,9 +73,9 @@ impl Transaction {
                 },
             }),
             other_actions => other_actions.into(),
-        };
-        shard_tx.map(|t| t.tracker())
+        }
     }
+
     pub fn is_master_key_allowed(&self) -> bool {
         match self.action {
             Action::SetRegularKey {
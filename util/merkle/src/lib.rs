@@ -29,6 +29,7 @@ use std::fmt;
 
 mod nibbleslice;
 pub mod node;
+pub mod proof;
 mod skewed;
 #[allow(dead_code)]
 pub mod snapshot;
@@ -37,6 +38,7 @@ pub mod triedbmut;
 pub mod triehash;
 
 pub use crate::node::Node;
+pub use crate::proof::{verify_proof, ProofResult};
 pub use crate::skewed::skewed_merkle_root;
 use crate::triedb::TrieDB;
 use crate::triedbmut::TrieDBMut;
@@ -82,6 +84,10 @@ pub trait Trie {
     /// What is the value of the given key in this trie?
     fn get(&self, key: &[u8]) -> Result<Option<DBValue>>;
 
+    /// Get the encoded nodes on the path from the root to the given key, starting from the root.
+    /// The nodes prove either the value of the key or its absence.
+    fn get_proof(&self, key: &[u8]) -> Result<Vec<DBValue>>;
+
     /// Does all the nodes in this trie exist in the underlying database?
     fn is_complete(&self) -> bool;
 }
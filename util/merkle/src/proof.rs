@@ -0,0 +1,223 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::nibbleslice::NibbleSlice;
+use crate::node::Node;
+use ccrypto::blake256;
+use cdb::DBValue;
+use primitives::H256;
+use rlp::{Prototype, Rlp};
+
+/// The outcome of checking a proof made by `Trie::get_proof`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ProofResult {
+    /// The key exists in the trie and has the given value.
+    Included(DBValue),
+    /// The key doesn't exist in the trie.
+    Excluded,
+    /// The proof doesn't match the root or is malformed.
+    Invalid,
+}
+
+/// Check whether `proof` proves the value of `key` in the trie whose root is `root`.
+///
+/// Unlike `Node::decoded`, it never panics on malformed nodes, so it can be used on proofs from untrusted peers.
+pub fn verify_proof(root: &H256, key: &[u8], proof: &[DBValue]) -> ProofResult {
+    let path = blake256(key);
+    let mut path = NibbleSlice::new(&path);
+    let mut expected_hash = *root;
+    let mut nodes = proof.iter();
+
+    loop {
+        let node_rlp = match nodes.next() {
+            Some(node_rlp) => node_rlp,
+            None => return ProofResult::Invalid,
+        };
+        if blake256(node_rlp) != expected_hash {
+            return ProofResult::Invalid
+        }
+
+        let result = match decode_node(node_rlp) {
+            None => ProofResult::Invalid,
+            Some(None) => ProofResult::Excluded,
+            Some(Some(Node::Leaf(partial, value))) => {
+                if partial == path {
+                    ProofResult::Included(value.to_vec())
+                } else {
+                    ProofResult::Excluded
+                }
+            }
+            Some(Some(Node::Branch(partial, children))) => {
+                if !path.starts_with(&partial) {
+                    ProofResult::Excluded
+                } else if path.len() == partial.len() {
+                    ProofResult::Invalid
+                } else {
+                    match children[path.mid(partial.len()).at(0) as usize] {
+                        Some(child_hash) => {
+                            expected_hash = child_hash;
+                            path = path.mid(partial.len() + 1);
+                            continue
+                        }
+                        None => ProofResult::Excluded,
+                    }
+                }
+            }
+        };
+
+        // A valid proof ends with the node that decides the result.
+        return match nodes.next() {
+            None => result,
+            Some(_) => ProofResult::Invalid,
+        }
+    }
+}
+
+/// Decode a node like `Node::decoded`, but return `None` instead of panicking if it's malformed.
+fn decode_node(node_rlp: &[u8]) -> Option<Option<Node<'_>>> {
+    let r = Rlp::new(node_rlp);
+    match r.prototype().ok()? {
+        Prototype::Data(0) => Some(None),
+        Prototype::List(2) => {
+            let partial = r.at(0).ok()?.data().ok()?;
+            if partial.is_empty() {
+                return None
+            }
+            let value = r.at(1).ok()?.data().ok()?;
+            Some(Some(Node::Leaf(NibbleSlice::from_encoded(partial), value)))
+        }
+        Prototype::List(17) => {
+            let partial = r.at(0).ok()?.data().ok()?;
+            if partial.is_empty() {
+                return None
+            }
+            let mut children = [None; 16];
+            for (i, child) in children.iter_mut().enumerate() {
+                let item = r.at(i + 1).ok()?;
+                if !item.is_empty() {
+                    *child = Some(item.as_val::<H256>().ok()?);
+                }
+            }
+            Some(Some(Node::Branch(NibbleSlice::from_encoded(partial), children.into())))
+        }
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{Trie, TrieFactory, TrieMut};
+    use ccrypto::BLAKE_NULL_RLP;
+    use cdb::MemoryDB;
+    use standardmap::{Alphabet, StandardMap, ValueMode};
+    use std::collections::HashMap;
+
+    fn populate(memdb: &mut MemoryDB, root: &mut H256, pairs: &[(Vec<u8>, Vec<u8>)]) {
+        let mut t = TrieFactory::create(memdb, root);
+        for (key, value) in pairs {
+            t.insert(key, value).unwrap();
+        }
+    }
+
+    fn random_pairs() -> Vec<(Vec<u8>, Vec<u8>)> {
+        let pairs = StandardMap {
+            alphabet: Alphabet::Custom(b"@QWERTYUIOPASDFGHJKLZXCVBNM[/]^_".to_vec()),
+            min_key: 5,
+            journal_key: 0,
+            value_mode: ValueMode::Index,
+            count: 100,
+        }
+        .make_with(&mut H256::new());
+        // Remove duplicated keys
+        let unique: HashMap<_, _> = pairs.into_iter().collect();
+        unique.into_iter().collect()
+    }
+
+    #[test]
+    fn proof_of_existing_keys() {
+        let pairs = random_pairs();
+        let mut memdb = MemoryDB::new();
+        let mut root = H256::new();
+        populate(&mut memdb, &mut root, &pairs);
+
+        let t = TrieFactory::readonly(&memdb, &root).unwrap();
+        for (key, value) in &pairs {
+            let proof = t.get_proof(key).unwrap();
+            assert_eq!(verify_proof(&root, key, &proof), ProofResult::Included(value.clone()));
+        }
+    }
+
+    #[test]
+    fn proof_of_absent_key() {
+        let pairs = random_pairs();
+        let mut memdb = MemoryDB::new();
+        let mut root = H256::new();
+        populate(&mut memdb, &mut root, &pairs);
+
+        let t = TrieFactory::readonly(&memdb, &root).unwrap();
+        let proof = t.get_proof(b"absent key").unwrap();
+        assert_eq!(verify_proof(&root, b"absent key", &proof), ProofResult::Excluded);
+    }
+
+    #[test]
+    fn proof_of_empty_trie() {
+        assert_eq!(verify_proof(&BLAKE_NULL_RLP, b"A", &[vec![0x80]]), ProofResult::Excluded);
+        assert_eq!(verify_proof(&BLAKE_NULL_RLP, b"A", &[]), ProofResult::Invalid);
+    }
+
+    #[test]
+    fn proof_against_another_root_is_invalid() {
+        let mut memdb = MemoryDB::new();
+        let mut root = H256::new();
+        populate(&mut memdb, &mut root, &[(b"A".to_vec(), b"ABC".to_vec()), (b"B".to_vec(), b"ABCBA".to_vec())]);
+
+        let t = TrieFactory::readonly(&memdb, &root).unwrap();
+        let proof = t.get_proof(b"A").unwrap();
+        assert_eq!(verify_proof(&H256::zero(), b"A", &proof), ProofResult::Invalid);
+    }
+
+    #[test]
+    fn truncated_or_extended_proof_is_invalid() {
+        let pairs = random_pairs();
+        let mut memdb = MemoryDB::new();
+        let mut root = H256::new();
+        populate(&mut memdb, &mut root, &pairs);
+
+        let t = TrieFactory::readonly(&memdb, &root).unwrap();
+        let (key, _) = &pairs[0];
+        let mut proof = t.get_proof(key).unwrap();
+        assert!(proof.len() > 1);
+
+        let last = proof.pop().unwrap();
+        assert_eq!(verify_proof(&root, key, &proof), ProofResult::Invalid);
+
+        proof.push(last.clone());
+        proof.push(last);
+        assert_eq!(verify_proof(&root, key, &proof), ProofResult::Invalid);
+    }
+
+    #[test]
+    fn malformed_node_is_invalid() {
+        let node = vec![0xc2, 0x80, 0x80];
+        let root = blake256(&node);
+        assert_eq!(verify_proof(&root, b"A", &[node]), ProofResult::Invalid);
+
+        let node = vec![0x01];
+        let root = blake256(&node);
+        assert_eq!(verify_proof(&root, b"A", &[node]), ProofResult::Invalid);
+    }
+}
@@ -28,29 +28,30 @@ use ccrypto::BLAKE_NULL_RLP;
 use cdb::HashDB;
 use primitives::H256;
 use std::cmp::Ordering;
+use std::collections::HashMap;
 
 const CHUNK_HEIGHT: usize = 3;
 const CHUNK_MAX_NODES: usize = 256; // 16 ^ (CHUNK_HEIGHT-1)
 
 /// Example:
 /// use codechain_merkle::snapshot::Restore;
-/// let mut rm = Restore::new(db, root);
+/// let mut rm = Restore::new(root);
 /// while let Some(root) = rm.next_to_feed() {
 ///     let raw_chunk = request(block_hash, root)?;
 ///     let chunk = raw_chunk.recover(root)?;
-///     rm.feed(chunk);
+///     rm.feed(db, chunk);
 /// }
-pub struct Restore<'a> {
-    db: &'a mut dyn HashDB,
-    pending: Option<ChunkPathPrefix>,
+///
+/// The chunks returned by `next_to_feed` stay pending until they are fed, so they can be requested in parallel.
+pub struct Restore {
+    pending: HashMap<H256, ChunkPathPrefix>,
     unresolved: OrderedHeap<DepthFirst<ChunkPathPrefix>>,
 }
 
-impl<'a> Restore<'a> {
-    pub fn new(db: &'a mut dyn HashDB, merkle_root: H256) -> Self {
+impl Restore {
+    pub fn new(merkle_root: H256) -> Self {
         let mut result = Restore {
-            db,
-            pending: None,
+            pending: HashMap::new(),
             unresolved: OrderedHeap::new(),
         };
         if merkle_root != BLAKE_NULL_RLP {
@@ -59,33 +60,63 @@ impl<'a> Restore<'a> {
         result
     }
 
-    pub fn feed(&mut self, chunk: RecoveredChunk) {
-        let pending_path = self.pending.take().expect("feed() should be called after next()");
-        assert_eq!(pending_path.chunk_root, chunk.root, "Unexpected chunk");
+    pub fn feed(&mut self, db: &mut dyn HashDB, chunk: RecoveredChunk) {
+        let pending_path = self.pending.remove(&chunk.root).expect("feed() should be called after next_to_feed()");
 
         // Pour nodes into the DB
         for (_, value) in chunk.nodes {
-            self.db.insert(&value);
+            db.insert(&value);
         }
 
         // Extend search paths
         for unresolved in chunk.unresolved_chunks {
             self.unresolved.push(pending_path.with_unresolved_chunk(&unresolved).into());
         }
+    }
 
-        self.pending = None;
+    /// Feeds the chunk whose nodes are already in the DB. It's used to resume the restoration.
+    pub fn feed_restored(&mut self, db: &dyn HashDB, chunk_root: H256) {
+        let pending_path =
+            self.pending.remove(&chunk_root).expect("feed_restored() should be called after next_to_feed()");
+        for unresolved in Chunk::from_chunk_root(db, chunk_root).unresolved_chunks() {
+            self.unresolved.push(pending_path.with_unresolved_chunk(&unresolved).into());
+        }
     }
 
+    /// Returns the root of the next chunk to be fed, and marks it as pending.
     pub fn next_to_feed(&mut self) -> Option<H256> {
-        if let Some(path) = self.unresolved.pop() {
-            assert!(self.pending.is_none(), "Previous feed() was failed");
+        while let Some(path) = self.unresolved.pop() {
             let chunk_root = path.chunk_root;
-            self.pending = Some(path.0);
-
-            Some(chunk_root)
-        } else {
-            None
+            // The same subtrie may appear in several paths. It's enough to feed it once.
+            if self.pending.contains_key(&chunk_root) {
+                continue
+            }
+            self.pending.insert(chunk_root, path.0);
+            return Some(chunk_root)
         }
+        None
+    }
+
+    pub fn is_pending(&self, chunk_root: &H256) -> bool {
+        self.pending.contains_key(chunk_root)
+    }
+
+    /// Returns the roots of the chunks which are returned by `next_to_feed` but not fed yet.
+    pub fn pending_chunks(&self) -> impl Iterator<Item = &H256> {
+        self.pending.keys()
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.pending.is_empty() && self.unresolved.is_empty()
+    }
+}
+
+impl std::fmt::Debug for Restore {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Restore")
+            .field("pending", &self.pending.keys().collect::<Vec<_>>())
+            .field("unresolved", &self.unresolved.len())
+            .finish()
     }
 }
 
@@ -254,8 +285,8 @@ impl<T> std::ops::Deref for DepthFirst<T> {
 mod tests {
     use super::*;
 
-    use std::collections::HashMap;
-    use std::iter::FromIterator;
+    use std::collections::{HashMap, HashSet};
+    use std::iter::{self, FromIterator};
 
     use cdb::MemoryDB;
     use primitives::{Bytes, H256};
@@ -264,7 +295,7 @@ mod tests {
     use super::chunk::RawChunk;
     use crate::{Trie, TrieDB, TrieDBMut, TrieMut};
 
-    fn random_insert_and_restore_with_count(count: usize) {
+    fn snapshot_chunks(count: usize) -> (H256, HashMap<Bytes, Bytes>, HashMap<H256, RawChunk>) {
         let standard_map = StandardMap {
             alphabet: Alphabet::Custom(b"@QWERTYUIOPASDFGHJKLZXCVBNM[/]^_".to_vec()),
             min_key: 5,
@@ -287,19 +318,29 @@ mod tests {
 
             Snapshot::from_hashdb(&db, root).map(|chunk| (chunk.root, chunk.into_raw_chunk())).collect()
         };
+        (root, unique_map, chunks)
+    }
+
+    fn assert_restored(db: &MemoryDB, root: &H256, unique_map: &HashMap<Bytes, Bytes>) {
+        let trie = TrieDB::try_new(db, root).unwrap();
+        for (key, value) in unique_map {
+            assert_eq!(trie.get(key).unwrap().as_ref(), Some(value));
+        }
+    }
+
+    fn random_insert_and_restore_with_count(count: usize) {
+        let (root, unique_map, chunks) = snapshot_chunks(count);
         dbg!(chunks.len());
 
         let mut db = MemoryDB::new();
-        let mut recover = Restore::new(&mut db, root);
+        let mut recover = Restore::new(root);
         while let Some(chunk_root) = recover.next_to_feed() {
             let recovered = chunks[&chunk_root].recover(chunk_root).unwrap();
-            recover.feed(recovered);
+            recover.feed(&mut db, recovered);
         }
+        assert!(recover.is_complete());
 
-        let trie = TrieDB::try_new(&db, &root).unwrap();
-        for (key, value) in &unique_map {
-            assert_eq!(trie.get(key).unwrap().as_ref(), Some(value));
-        }
+        assert_restored(&db, &root, &unique_map);
     }
 
     #[test]
@@ -332,4 +373,57 @@ mod tests {
     fn random_insert_and_restore_100000() {
         random_insert_and_restore_with_count(100_000);
     }
+
+    #[test]
+    fn restore_pending_chunks_in_any_order() {
+        let (root, unique_map, chunks) = snapshot_chunks(10_000);
+
+        let mut db = MemoryDB::new();
+        let mut recover = Restore::new(root);
+        loop {
+            let chunk_roots: Vec<_> = iter::from_fn(|| recover.next_to_feed()).take(8).collect();
+            if chunk_roots.is_empty() {
+                break
+            }
+            assert_eq!(recover.pending_chunks().count(), chunk_roots.len());
+            for chunk_root in chunk_roots.into_iter().rev() {
+                let recovered = chunks[&chunk_root].recover(chunk_root).unwrap();
+                recover.feed(&mut db, recovered);
+            }
+        }
+        assert!(recover.is_complete());
+
+        assert_restored(&db, &root, &unique_map);
+    }
+
+    #[test]
+    fn resume_restore_with_restored_chunks() {
+        let (root, unique_map, chunks) = snapshot_chunks(10_000);
+
+        let mut db = MemoryDB::new();
+        let mut restored = HashSet::new();
+        let mut recover = Restore::new(root);
+        for _ in 0..chunks.len() / 2 {
+            let chunk_root = recover.next_to_feed().unwrap();
+            let recovered = chunks[&chunk_root].recover(chunk_root).unwrap();
+            recover.feed(&mut db, recovered);
+            restored.insert(chunk_root);
+        }
+
+        let mut fetched = 0;
+        let mut recover = Restore::new(root);
+        while let Some(chunk_root) = recover.next_to_feed() {
+            if restored.contains(&chunk_root) {
+                recover.feed_restored(&db, chunk_root);
+            } else {
+                let recovered = chunks[&chunk_root].recover(chunk_root).unwrap();
+                recover.feed(&mut db, recovered);
+                fetched += 1;
+            }
+        }
+        assert!(recover.is_complete());
+        assert_eq!(fetched, chunks.len() - restored.len());
+
+        assert_restored(&db, &root, &unique_map);
+    }
 }
@@ -41,6 +41,14 @@ impl<T: Ord> OrderedHeap<T> {
     pub fn pop(&mut self) -> Option<T> {
         self.heap.pop().map(|x| x.value)
     }
+
+    pub fn len(&self) -> usize {
+        self.heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.heap.is_empty()
+    }
 }
 
 #[derive(Debug, Clone)]
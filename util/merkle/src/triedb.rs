@@ -18,7 +18,7 @@ use crate::nibbleslice::NibbleSlice;
 use crate::node::Node as RlpNode;
 use crate::{Trie, TrieError};
 use ccrypto::{blake256, BLAKE_NULL_RLP};
-use cdb::HashDB;
+use cdb::{DBValue, HashDB};
 use primitives::H256;
 /// A `Trie` implementation using a generic `HashDB` backing database.
 ///
@@ -101,6 +101,29 @@ impl<'db> TrieDB<'db> {
         }
     }
 
+    /// Collect the nodes on the path to `path` into `proof`
+    fn get_proof_aux(
+        &self,
+        path: &NibbleSlice<'_>,
+        cur_node_hash: Option<H256>,
+        proof: &mut Vec<DBValue>,
+    ) -> crate::Result<()> {
+        if let Some(hash) = cur_node_hash {
+            let node_rlp = self.db.get(&hash).ok_or_else(|| TrieError::IncompleteDatabase(hash))?;
+            let child = match RlpNode::decoded(&node_rlp) {
+                Some(RlpNode::Branch(partial, children)) if path.starts_with(&partial) => {
+                    Some((path.mid(partial.len() + 1), children[path.mid(partial.len()).at(0) as usize]))
+                }
+                _ => None,
+            };
+            proof.push(node_rlp);
+            if let Some((path, child_hash)) = child {
+                return self.get_proof_aux(&path, child_hash, proof)
+            }
+        }
+        Ok(())
+    }
+
     /// Check if every leaf of the trie starting from `hash` exists
     fn is_complete_aux(&self, hash: &H256) -> bool {
         if let Some(node_rlp) = self.db.get(hash) {
@@ -129,6 +152,15 @@ impl<'db> Trie for TrieDB<'db> {
         self.get_aux(&NibbleSlice::new(&path), Some(root), &|bytes| bytes.to_vec())
     }
 
+    fn get_proof(&self, key: &[u8]) -> crate::Result<Vec<DBValue>> {
+        let path = blake256(key);
+        let root = *self.root;
+        let mut proof = Vec::new();
+
+        self.get_proof_aux(&NibbleSlice::new(&path), Some(root), &mut proof)?;
+        Ok(proof)
+    }
+
     fn is_complete(&self) -> bool {
         *self.root == BLAKE_NULL_RLP || self.is_complete_aux(self.root)
     }
@@ -416,6 +416,12 @@ impl<'a> Trie for TrieDBMut<'a> {
         t.get(key)
     }
 
+    fn get_proof(&self, key: &[u8]) -> crate::Result<Vec<DBValue>> {
+        let t = TrieDB::try_new(self.db, self.root)?;
+
+        t.get_proof(key)
+    }
+
     fn is_complete(&self) -> bool {
         TrieDB::try_new(self.db, self.root).map(|t| t.is_complete()).unwrap_or(false)
     }
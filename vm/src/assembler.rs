@@ -0,0 +1,588 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+//! The textual format of the scripts.
+//!
+//! A script is a sequence of instructions separated by whitespaces, and a comment starts with `;`.
+//! The immediate value follows the instruction: `PUSH 1`, `PUSHB 0x0102`, `COPY 0`, `CHKTIMELOCK blockAge`.
+//! A label is a name followed by `:`, and `JMP`, `JNZ` and `JZ` can jump forward to a label.
+
+use ccrypto::Blake;
+use ckey::hex::{FromHex, ToHex};
+use primitives::H160;
+use std::collections::HashMap;
+use std::fmt;
+
+use crate::decoder::{decode_at_era, DecoderError};
+use crate::executor::TimelockType;
+use crate::instruction::Instruction;
+use crate::opcode;
+
+#[derive(Debug, PartialEq)]
+pub enum AssemblerError {
+    UnknownInstruction {
+        line: usize,
+        name: String,
+    },
+    MissingImmediateValue {
+        line: usize,
+        name: String,
+    },
+    InvalidImmediateValue {
+        line: usize,
+        value: String,
+    },
+    UnknownLabel {
+        line: usize,
+        label: String,
+    },
+    DuplicatedLabel {
+        line: usize,
+        label: String,
+    },
+    /// The jumps can only go forward, and skip at most 255 instructions.
+    UnreachableLabel {
+        line: usize,
+        label: String,
+    },
+}
+
+impl fmt::Display for AssemblerError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssemblerError::UnknownInstruction {
+                line,
+                name,
+            } => write!(f, "line {}: Unknown instruction {}", line, name),
+            AssemblerError::MissingImmediateValue {
+                line,
+                name,
+            } => write!(f, "line {}: {} needs an immediate value", line, name),
+            AssemblerError::InvalidImmediateValue {
+                line,
+                value,
+            } => write!(f, "line {}: Invalid immediate value {}", line, value),
+            AssemblerError::UnknownLabel {
+                line,
+                label,
+            } => write!(f, "line {}: Unknown label {}", line, label),
+            AssemblerError::DuplicatedLabel {
+                line,
+                label,
+            } => write!(f, "line {}: Label {} is already defined", line, label),
+            AssemblerError::UnreachableLabel {
+                line,
+                label,
+            } => write!(f, "line {}: Cannot jump to {}", line, label),
+        }
+    }
+}
+
+enum Jump {
+    Jmp,
+    Jnz,
+    Jz,
+}
+
+enum Assembled {
+    Instruction(Instruction),
+    Jump {
+        jump: Jump,
+        label: String,
+        line: usize,
+    },
+}
+
+/// Assembles the source into the bytes of a script.
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssemblerError> {
+    let mut tokens = source.lines().enumerate().flat_map(|(index, line)| {
+        let code = line.split(';').next().unwrap_or_default();
+        code.split_whitespace().map(move |token| (index + 1, token))
+    });
+
+    let mut assembled = Vec::new();
+    let mut labels = HashMap::new();
+    while let Some((line, token)) = tokens.next() {
+        if token.ends_with(':') {
+            let label = token.trim_end_matches(':').to_string();
+            if labels.insert(label.clone(), assembled.len()).is_some() {
+                return Err(AssemblerError::DuplicatedLabel {
+                    line,
+                    label,
+                })
+            }
+            continue
+        }
+
+        let name = token.to_uppercase();
+        let mut immediate = || {
+            tokens.next().map(|(_, value)| value).ok_or_else(|| AssemblerError::MissingImmediateValue {
+                line,
+                name: name.clone(),
+            })
+        };
+        let instruction = match name.as_str() {
+            "NOP" => Instruction::Nop,
+            "BURN" => Instruction::Burn,
+            "SUCCESS" => Instruction::Success,
+            "FAIL" => Instruction::Fail,
+            "NOT" => Instruction::Not,
+            "EQ" => Instruction::Eq,
+            "VERIFY" => Instruction::Verify,
+            "EQVERIFY" => Instruction::EqVerify,
+            "JMP" | "JNZ" | "JZ" => {
+                let value = immediate()?;
+                let jump = match name.as_str() {
+                    "JMP" => Jump::Jmp,
+                    "JNZ" => Jump::Jnz,
+                    _ => Jump::Jz,
+                };
+                match parse_u8(value) {
+                    Some(offset) => jump_instruction(&jump, offset),
+                    None => {
+                        assembled.push(Assembled::Jump {
+                            jump,
+                            label: value.to_string(),
+                            line,
+                        });
+                        continue
+                    }
+                }
+            }
+            "IF" => Instruction::If,
+            "ELSE" => Instruction::Else,
+            "ENDIF" => Instruction::EndIf,
+            "PUSH" => Instruction::Push(parse_immediate(immediate()?, line)?),
+            "POP" => Instruction::Pop,
+            "PUSHB" => {
+                let value = immediate()?;
+                let blob =
+                    value.trim_start_matches("0x").from_hex().ok().filter(|blob| blob.len() <= 0xff).ok_or_else(
+                        || AssemblerError::InvalidImmediateValue {
+                            line,
+                            value: value.to_string(),
+                        },
+                    )?;
+                Instruction::PushB(blob)
+            }
+            "DUP" => Instruction::Dup,
+            "SWAP" => Instruction::Swap,
+            "COPY" => Instruction::Copy(parse_immediate(immediate()?, line)?),
+            "DROP" => Instruction::Drop(parse_immediate(immediate()?, line)?),
+            "ADD" => Instruction::Add,
+            "SUB" => Instruction::Sub,
+            "LT" => Instruction::Lt,
+            "GT" => Instruction::Gt,
+            "CAT" => Instruction::Cat,
+            "SPLIT" => Instruction::Split,
+            "SIZE" => Instruction::Size,
+            "CHKSIG" => Instruction::ChkSig,
+            "CHKMULTISIG" => Instruction::ChkMultiSig,
+            "BLAKE256" => Instruction::Blake256,
+            "SHA256" => Instruction::Sha256,
+            "RIPEMD160" => Instruction::Ripemd160,
+            "KECCAK256" => Instruction::Keccak256,
+            "BLAKE160" => Instruction::Blake160,
+            "CHKTIMELOCK" => {
+                let value = immediate()?;
+                let timelock_type = match value.to_lowercase().as_str() {
+                    "block" | "1" => TimelockType::Block,
+                    "blockage" | "2" => TimelockType::BlockAge,
+                    "time" | "3" => TimelockType::Time,
+                    "timeage" | "4" => TimelockType::TimeAge,
+                    _ => {
+                        return Err(AssemblerError::InvalidImmediateValue {
+                            line,
+                            value: value.to_string(),
+                        })
+                    }
+                };
+                Instruction::ChkTimelock(timelock_type)
+            }
+            _ => {
+                return Err(AssemblerError::UnknownInstruction {
+                    line,
+                    name: token.to_string(),
+                })
+            }
+        };
+        assembled.push(Assembled::Instruction(instruction));
+    }
+
+    let mut bytes = Vec::new();
+    for (index, assembled) in assembled.into_iter().enumerate() {
+        let instruction = match assembled {
+            Assembled::Instruction(instruction) => instruction,
+            Assembled::Jump {
+                jump,
+                label,
+                line,
+            } => {
+                let target = *labels.get(&label).ok_or_else(|| AssemblerError::UnknownLabel {
+                    line,
+                    label: label.clone(),
+                })?;
+                // The jump skips the instructions between itself and the target.
+                let offset = target.checked_sub(index + 1).filter(|offset| *offset <= 0xff).ok_or_else(|| {
+                    AssemblerError::UnreachableLabel {
+                        line,
+                        label,
+                    }
+                })?;
+                jump_instruction(&jump, offset as u8)
+            }
+        };
+        encode(&instruction, &mut bytes);
+    }
+    Ok(bytes)
+}
+
+/// Returns the hash with which an asset is locked by the script.
+pub fn lock_script_hash(script: &[u8]) -> H160 {
+    Blake::blake(script)
+}
+
+/// Disassembles the script into the format `assemble` accepts.
+/// The targets of the jumps are labeled.
+pub fn disassemble(bytes: &[u8], era: u64) -> Result<String, DecoderError> {
+    let instructions = decode_at_era(bytes, era)?;
+    let mut labels: Vec<usize> = instructions
+        .iter()
+        .enumerate()
+        .filter_map(|(index, instruction)| match instruction {
+            Instruction::Jmp(offset) | Instruction::Jnz(offset) | Instruction::Jz(offset) => {
+                Some(index + 1 + *offset as usize)
+            }
+            _ => None,
+        })
+        .filter(|target| *target <= instructions.len())
+        .collect();
+    labels.sort();
+    labels.dedup();
+    let label_of = |target: usize| labels.binary_search(&target).ok().map(|index| format!("L{}", index));
+
+    let mut lines = Vec::with_capacity(instructions.len());
+    for (index, instruction) in instructions.iter().enumerate() {
+        if let Some(label) = label_of(index) {
+            lines.push(format!("{}:", label));
+        }
+        let line = match instruction {
+            Instruction::Jmp(offset) | Instruction::Jnz(offset) | Instruction::Jz(offset) => {
+                match label_of(index + 1 + *offset as usize) {
+                    Some(label) => format!("    {} {}", mnemonic(instruction), label),
+                    None => format!("    {}", instruction),
+                }
+            }
+            _ => format!("    {}", instruction),
+        };
+        lines.push(line);
+    }
+    if let Some(label) = label_of(instructions.len()) {
+        lines.push(format!("{}:", label));
+    }
+    Ok(lines.join("\n"))
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Instruction::Jmp(value)
+            | Instruction::Jnz(value)
+            | Instruction::Jz(value)
+            | Instruction::Push(value)
+            | Instruction::Copy(value)
+            | Instruction::Drop(value) => write!(f, "{} {}", mnemonic(self), value),
+            Instruction::PushB(blob) => write!(f, "{} 0x{}", mnemonic(self), blob.to_hex()),
+            Instruction::ChkTimelock(timelock_type) => {
+                let timelock_type = match timelock_type {
+                    TimelockType::Block => "block",
+                    TimelockType::BlockAge => "blockAge",
+                    TimelockType::Time => "time",
+                    TimelockType::TimeAge => "timeAge",
+                };
+                write!(f, "{} {}", mnemonic(self), timelock_type)
+            }
+            _ => write!(f, "{}", mnemonic(self)),
+        }
+    }
+}
+
+fn mnemonic(instruction: &Instruction) -> &'static str {
+    match instruction {
+        Instruction::Nop => "NOP",
+        Instruction::Burn => "BURN",
+        Instruction::Success => "SUCCESS",
+        Instruction::Fail => "FAIL",
+        Instruction::Not => "NOT",
+        Instruction::Eq => "EQ",
+        Instruction::Verify => "VERIFY",
+        Instruction::EqVerify => "EQVERIFY",
+        Instruction::Jmp(_) => "JMP",
+        Instruction::Jnz(_) => "JNZ",
+        Instruction::Jz(_) => "JZ",
+        Instruction::If => "IF",
+        Instruction::Else => "ELSE",
+        Instruction::EndIf => "ENDIF",
+        Instruction::Push(_) => "PUSH",
+        Instruction::Pop => "POP",
+        Instruction::PushB(_) => "PUSHB",
+        Instruction::Dup => "DUP",
+        Instruction::Swap => "SWAP",
+        Instruction::Copy(_) => "COPY",
+        Instruction::Drop(_) => "DROP",
+        Instruction::Add => "ADD",
+        Instruction::Sub => "SUB",
+        Instruction::Lt => "LT",
+        Instruction::Gt => "GT",
+        Instruction::Cat => "CAT",
+        Instruction::Split => "SPLIT",
+        Instruction::Size => "SIZE",
+        Instruction::ChkSig => "CHKSIG",
+        Instruction::ChkMultiSig => "CHKMULTISIG",
+        Instruction::Blake256 => "BLAKE256",
+        Instruction::Sha256 => "SHA256",
+        Instruction::Ripemd160 => "RIPEMD160",
+        Instruction::Keccak256 => "KECCAK256",
+        Instruction::Blake160 => "BLAKE160",
+        Instruction::ChkTimelock(_) => "CHKTIMELOCK",
+    }
+}
+
+fn jump_instruction(jump: &Jump, offset: u8) -> Instruction {
+    match jump {
+        Jump::Jmp => Instruction::Jmp(offset),
+        Jump::Jnz => Instruction::Jnz(offset),
+        Jump::Jz => Instruction::Jz(offset),
+    }
+}
+
+fn parse_u8(value: &str) -> Option<u8> {
+    if value.starts_with("0x") {
+        u8::from_str_radix(&value[2..], 16).ok()
+    } else {
+        value.parse().ok()
+    }
+}
+
+fn parse_immediate(value: &str, line: usize) -> Result<u8, AssemblerError> {
+    parse_u8(value).ok_or_else(|| AssemblerError::InvalidImmediateValue {
+        line,
+        value: value.to_string(),
+    })
+}
+
+fn encode(instruction: &Instruction, bytes: &mut Vec<u8>) {
+    match instruction {
+        Instruction::Nop => bytes.push(opcode::NOP),
+        Instruction::Burn => bytes.push(opcode::BURN),
+        Instruction::Success => bytes.push(opcode::SUCCESS),
+        Instruction::Fail => bytes.push(opcode::FAIL),
+        Instruction::Not => bytes.push(opcode::NOT),
+        Instruction::Eq => bytes.push(opcode::EQ),
+        Instruction::Verify => bytes.push(opcode::VERIFY),
+        Instruction::EqVerify => bytes.push(opcode::EQVERIFY),
+        Instruction::Jmp(offset) => bytes.extend_from_slice(&[opcode::JMP, *offset]),
+        Instruction::Jnz(offset) => bytes.extend_from_slice(&[opcode::JNZ, *offset]),
+        Instruction::Jz(offset) => bytes.extend_from_slice(&[opcode::JZ, *offset]),
+        Instruction::If => bytes.push(opcode::IF),
+        Instruction::Else => bytes.push(opcode::ELSE),
+        Instruction::EndIf => bytes.push(opcode::ENDIF),
+        Instruction::Push(value) => bytes.extend_from_slice(&[opcode::PUSH, *value]),
+        Instruction::Pop => bytes.push(opcode::POP),
+        Instruction::PushB(blob) => {
+            bytes.extend_from_slice(&[opcode::PUSHB, blob.len() as u8]);
+            bytes.extend_from_slice(blob);
+        }
+        Instruction::Dup => bytes.push(opcode::DUP),
+        Instruction::Swap => bytes.push(opcode::SWAP),
+        Instruction::Copy(index) => bytes.extend_from_slice(&[opcode::COPY, *index]),
+        Instruction::Drop(index) => bytes.extend_from_slice(&[opcode::DROP, *index]),
+        Instruction::Add => bytes.push(opcode::ADD),
+        Instruction::Sub => bytes.push(opcode::SUB),
+        Instruction::Lt => bytes.push(opcode::LT),
+        Instruction::Gt => bytes.push(opcode::GT),
+        Instruction::Cat => bytes.push(opcode::CAT),
+        Instruction::Split => bytes.push(opcode::SPLIT),
+        Instruction::Size => bytes.push(opcode::SIZE),
+        Instruction::ChkSig => bytes.push(opcode::CHKSIG),
+        Instruction::ChkMultiSig => bytes.push(opcode::CHKMULTISIG),
+        Instruction::Blake256 => bytes.push(opcode::BLAKE256),
+        Instruction::Sha256 => bytes.push(opcode::SHA256),
+        Instruction::Ripemd160 => bytes.push(opcode::RIPEMD160),
+        Instruction::Keccak256 => bytes.push(opcode::KECCAK256),
+        Instruction::Blake160 => bytes.push(opcode::BLAKE160),
+        Instruction::ChkTimelock(timelock_type) => {
+            bytes.extend_from_slice(&[opcode::CHKTIMELOCK, timelock_type.clone() as u8])
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::decoder::EXTENSION_ERA;
+
+    #[test]
+    fn assemble_pay_to_public_key_hash() {
+        let source = "
+            ; The unlock script pushes the signature, the tag and the public key.
+            COPY 0x01
+            BLAKE160
+            PUSHB 0x0102030405060708090a0b0c0d0e0f1011121314
+            EQ
+            JZ fail
+            CHKSIG
+            JMP end
+        fail:
+            FAIL
+        end:
+        ";
+        let mut expected = vec![opcode::COPY, 1, opcode::BLAKE160, opcode::PUSHB, 20];
+        expected.extend(1..=20);
+        expected.extend_from_slice(&[opcode::EQ, opcode::JZ, 2, opcode::CHKSIG, opcode::JMP, 1, opcode::FAIL]);
+        assert_eq!(Ok(expected), assemble(source));
+    }
+
+    #[test]
+    fn instructions_are_case_insensitive() {
+        assert_eq!(assemble("push 1 Dup eq"), assemble("PUSH 1\nDUP\nEQ"));
+    }
+
+    #[test]
+    fn chk_timelock() {
+        assert_eq!(Ok(vec![opcode::CHKTIMELOCK, 2]), assemble("CHKTIMELOCK blockAge"));
+        assert_eq!(Ok(vec![opcode::CHKTIMELOCK, 4]), assemble("CHKTIMELOCK 4"));
+        assert_eq!(
+            Err(AssemblerError::InvalidImmediateValue {
+                line: 1,
+                value: "5".to_string()
+            }),
+            assemble("CHKTIMELOCK 5")
+        );
+    }
+
+    #[test]
+    fn errors_are_located() {
+        assert_eq!(
+            Err(AssemblerError::UnknownInstruction {
+                line: 2,
+                name: "PUSHX".to_string()
+            }),
+            assemble("NOP\nPUSHX 1")
+        );
+        assert_eq!(
+            Err(AssemblerError::MissingImmediateValue {
+                line: 1,
+                name: "PUSH".to_string()
+            }),
+            assemble("PUSH")
+        );
+        assert_eq!(
+            Err(AssemblerError::InvalidImmediateValue {
+                line: 1,
+                value: "256".to_string()
+            }),
+            assemble("PUSH 256")
+        );
+        assert_eq!(
+            Err(AssemblerError::InvalidImmediateValue {
+                line: 1,
+                value: "0x123".to_string()
+            }),
+            assemble("PUSHB 0x123")
+        );
+    }
+
+    #[test]
+    fn labels_must_be_defined_once() {
+        assert_eq!(
+            Err(AssemblerError::UnknownLabel {
+                line: 1,
+                label: "end".to_string()
+            }),
+            assemble("JMP end")
+        );
+        assert_eq!(
+            Err(AssemblerError::DuplicatedLabel {
+                line: 3,
+                label: "end".to_string()
+            }),
+            assemble("end:\nNOP\nend:")
+        );
+    }
+
+    #[test]
+    fn jumps_only_go_forward() {
+        assert_eq!(
+            Err(AssemblerError::UnreachableLabel {
+                line: 2,
+                label: "start".to_string()
+            }),
+            assemble("start: NOP\nJMP start")
+        );
+        let far = format!("JMP end {} end:", "NOP ".repeat(256));
+        assert_eq!(
+            Err(AssemblerError::UnreachableLabel {
+                line: 1,
+                label: "end".to_string()
+            }),
+            assemble(&far)
+        );
+    }
+
+    #[test]
+    fn disassemble_labels_the_jump_targets() {
+        let bytes = vec![opcode::PUSH, 1, opcode::JZ, 1, opcode::FAIL, opcode::JMP, 0, opcode::SUCCESS];
+        assert_eq!(
+            Ok("    PUSH 1\n    JZ L0\n    FAIL\nL0:\n    JMP L1\nL1:\n    SUCCESS".to_string()),
+            disassemble(&bytes, 0)
+        );
+    }
+
+    #[test]
+    fn disassemble_keeps_the_jumps_out_of_the_script() {
+        assert_eq!(Ok("    JMP 3".to_string()), disassemble(&[opcode::JMP, 3], 0));
+    }
+
+    #[test]
+    fn disassemble_respects_the_era() {
+        assert_eq!(Err(DecoderError::InvalidOpCode(opcode::ADD)), disassemble(&[opcode::ADD], 0));
+        assert_eq!(Ok("    ADD".to_string()), disassemble(&[opcode::ADD], EXTENSION_ERA));
+    }
+
+    #[test]
+    fn assemble_and_disassemble() {
+        let source = "
+            PUSH 2 PUSHB 0xcafe SIZE EQVERIFY
+            IF COPY 0 DROP 1 ELSE CHKTIMELOCK timeAge ENDIF
+            JNZ end
+            BLAKE256 SHA256 RIPEMD160 KECCAK256 BLAKE160
+            ADD SUB LT GT CAT SPLIT
+            CHKSIG CHKMULTISIG VERIFY SWAP DUP POP NOT BURN
+        end:
+            SUCCESS
+        ";
+        let bytes = assemble(source).unwrap();
+        let disassembled = disassemble(&bytes, EXTENSION_ERA).unwrap();
+        assert_eq!(Ok(bytes), assemble(&disassembled));
+    }
+
+    #[test]
+    fn lock_script_hash_is_blake160() {
+        let bytes = assemble("PUSH 1").unwrap();
+        assert_eq!(H160::blake(&bytes), lock_script_hash(&bytes));
+    }
+}
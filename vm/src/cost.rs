@@ -0,0 +1,75 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+use crate::instruction::Instruction;
+
+/// The cost of the instructions that only touch the stack.
+pub const STEP_COST: u64 = 1;
+/// The cost of a hash instruction is `HASH_COST` and `HASH_WORD_COST` for every 32 bytes of the input.
+pub const HASH_COST: u64 = 20;
+pub const HASH_WORD_COST: u64 = 4;
+/// The cost of reading the age of the asset from the chain.
+pub const TIMELOCK_COST: u64 = 20;
+/// The cost of hashing the transaction and verifying a signature.
+pub const SIGNATURE_COST: u64 = 1_000;
+
+/// Returns the cost of the instruction that does not depend on the operands.
+pub fn instruction_cost(instruction: &Instruction) -> u64 {
+    match instruction {
+        Instruction::ChkSig => SIGNATURE_COST,
+        // The public keys are charged by `multi_sig_cost`.
+        Instruction::ChkMultiSig => STEP_COST,
+        Instruction::Blake256
+        | Instruction::Sha256
+        | Instruction::Ripemd160
+        | Instruction::Keccak256
+        | Instruction::Blake160 => HASH_COST,
+        Instruction::ChkTimelock(_) => TIMELOCK_COST,
+        _ => STEP_COST,
+    }
+}
+
+/// Returns the additional cost of hashing `len` bytes.
+pub fn hash_cost(len: usize) -> u64 {
+    ((len as u64 + 31) / 32) * HASH_WORD_COST
+}
+
+/// Returns the additional cost of CHKMULTISIG with `n` public keys.
+/// The signatures can be verified against every public key in the worst case.
+pub fn multi_sig_cost(n: usize) -> u64 {
+    n as u64 * SIGNATURE_COST
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hash_cost_is_charged_per_word() {
+        assert_eq!(0, hash_cost(0));
+        assert_eq!(HASH_WORD_COST, hash_cost(1));
+        assert_eq!(HASH_WORD_COST, hash_cost(32));
+        assert_eq!(2 * HASH_WORD_COST, hash_cost(33));
+    }
+
+    #[test]
+    fn signature_checks_are_the_most_expensive() {
+        let cheap = [Instruction::Nop, Instruction::PushB(vec![0; 64]), Instruction::Blake256, Instruction::Copy(1)];
+        for instruction in cheap.iter() {
+            assert!(instruction_cost(instruction) < instruction_cost(&Instruction::ChkSig));
+        }
+    }
+}
@@ -24,7 +24,16 @@ pub enum DecoderError {
     InvalidImmediateValue(u8),
 }
 
+/// The era of `CommonParams` from which the arithmetic, byte manipulation and conditional instructions are available.
+pub const EXTENSION_ERA: u64 = 1;
+
 pub fn decode(bytes: &[u8]) -> Result<Vec<Instruction>, DecoderError> {
+    decode_at_era(bytes, 0)
+}
+
+/// Decodes the script with the instructions available at the era of `CommonParams`.
+pub fn decode_at_era(bytes: &[u8], era: u64) -> Result<Vec<Instruction>, DecoderError> {
+    let extended = era >= EXTENSION_ERA;
     let mut iter = bytes.iter();
     let mut result = Vec::new();
     while let Some(b) = iter.next() {
@@ -90,13 +99,34 @@ pub fn decode(bytes: &[u8]) -> Result<Vec<Instruction>, DecoderError> {
                 }
                 result.push(Instruction::ChkTimelock(timelock_type));
             }
-            invalid_opcode => return Err(DecoderError::InvalidOpCode(invalid_opcode)),
+            opcode => match decode_extension(opcode) {
+                Some(instruction) if extended => result.push(instruction),
+                _ => return Err(DecoderError::InvalidOpCode(opcode)),
+            },
         }
     }
 
     Ok(result)
 }
 
+fn decode_extension(opcode: u8) -> Option<Instruction> {
+    Some(match opcode {
+        opcode::VERIFY => Instruction::Verify,
+        opcode::EQVERIFY => Instruction::EqVerify,
+        opcode::IF => Instruction::If,
+        opcode::ELSE => Instruction::Else,
+        opcode::ENDIF => Instruction::EndIf,
+        opcode::ADD => Instruction::Add,
+        opcode::SUB => Instruction::Sub,
+        opcode::LT => Instruction::Lt,
+        opcode::GT => Instruction::Gt,
+        opcode::CAT => Instruction::Cat,
+        opcode::SPLIT => Instruction::Split,
+        opcode::SIZE => Instruction::Size,
+        _ => return None,
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +184,44 @@ mod tests {
     test_no_argument_opcode!(KECCAK256, Keccak256);
     test_no_argument_opcode!(BLAKE160, Blake160);
 
+    macro_rules! test_extension_opcode {
+        ($opcode:ident, $instruction:ident) => {
+            #[test]
+            #[allow(non_snake_case)]
+            fn $instruction() {
+                assert_eq!(decode(&[opcode::$opcode]), Err(DecoderError::InvalidOpCode(opcode::$opcode)));
+                assert_eq!(
+                    decode_at_era(&[opcode::$opcode], EXTENSION_ERA - 1),
+                    Err(DecoderError::InvalidOpCode(opcode::$opcode))
+                );
+                assert_eq!(decode_at_era(&[opcode::$opcode], EXTENSION_ERA), Ok(vec![Instruction::$instruction]));
+                assert_eq!(
+                    decode_at_era(&[opcode::$opcode, opcode::$opcode], EXTENSION_ERA + 1),
+                    Ok(vec![Instruction::$instruction, Instruction::$instruction])
+                );
+            }
+        };
+    }
+
+    test_extension_opcode!(VERIFY, Verify);
+    test_extension_opcode!(EQVERIFY, EqVerify);
+    test_extension_opcode!(IF, If);
+    test_extension_opcode!(ELSE, Else);
+    test_extension_opcode!(ENDIF, EndIf);
+    test_extension_opcode!(ADD, Add);
+    test_extension_opcode!(SUB, Sub);
+    test_extension_opcode!(LT, Lt);
+    test_extension_opcode!(GT, Gt);
+    test_extension_opcode!(CAT, Cat);
+    test_extension_opcode!(SPLIT, Split);
+    test_extension_opcode!(SIZE, Size);
+
+    #[test]
+    fn base_instructions_are_decoded_at_every_era() {
+        let script = [opcode::PUSH, 1, opcode::DUP, opcode::EQ];
+        assert_eq!(decode(&script), decode_at_era(&script, EXTENSION_ERA));
+    }
+
     #[test]
     #[allow(non_snake_case)]
     fn PushB() {
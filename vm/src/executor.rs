@@ -20,6 +20,7 @@ use ctypes::util::tag::Tag;
 use ctypes::{BlockNumber, Tracker};
 use primitives::{H160, H256};
 
+use crate::cost::{hash_cost, instruction_cost, multi_sig_cost, STEP_COST};
 use crate::instruction::{has_expensive_opcodes, is_valid_unlock_script, Instruction};
 
 const DEFAULT_MAX_MEMORY: usize = 1024;
@@ -35,12 +36,29 @@ pub enum TimelockType {
 
 pub struct Config {
     pub max_memory: usize,
+    /// The cost a script can consume. The cost is not limited if it is None.
+    pub max_cost: Option<u64>,
+}
+
+impl Config {
+    /// Zero means the cost is not limited as in `CommonParams`.
+    pub fn with_max_cost(max_cost: u64) -> Self {
+        Self {
+            max_cost: if max_cost == 0 {
+                None
+            } else {
+                Some(max_cost)
+            },
+            ..Default::default()
+        }
+    }
 }
 
 impl Default for Config {
     fn default() -> Self {
         Self {
             max_memory: DEFAULT_MAX_MEMORY,
+            max_cost: None,
         }
     }
 }
@@ -61,6 +79,9 @@ pub enum RuntimeError {
     InvalidFilter,
     InvalidSigCount,
     InvalidTimelockType,
+    OutOfGas,
+    Overflow,
+    UnbalancedConditional,
 }
 
 impl From<HashingError> for RuntimeError {
@@ -71,6 +92,24 @@ impl From<HashingError> for RuntimeError {
     }
 }
 
+/// The state of the machine before an instruction is run.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Step {
+    /// The index of the instruction in the unlock script, the parameters and the lock script, which are run in order.
+    pub pc: usize,
+    pub instruction: Instruction,
+    /// The items from the bottom to the top.
+    pub stack: Vec<Vec<u8>>,
+    /// The memory used by the stack before the instruction.
+    pub memory: usize,
+    /// The cost consumed before the instruction.
+    pub cost: u64,
+    /// The partial hashing tag that CHKSIG or CHKMULTISIG pops.
+    pub tag: Option<Vec<u8>>,
+    /// False if the instruction is skipped by IF or ELSE.
+    pub executed: bool,
+}
+
 #[derive(Clone)]
 struct Item(Vec<u8>);
 
@@ -146,6 +185,10 @@ impl Stack {
         self.stack.len()
     }
 
+    fn items(&self) -> Vec<Vec<u8>> {
+        self.stack.iter().map(|item| item.0.clone()).collect()
+    }
+
     fn get(&self, index: usize) -> Result<Item, RuntimeError> {
         self.stack.get(index).cloned().ok_or(RuntimeError::IndexOutOfBound)
     }
@@ -161,6 +204,34 @@ impl Stack {
     }
 }
 
+struct Meter {
+    consumed: u64,
+    max_cost: Option<u64>,
+}
+
+impl Meter {
+    fn new(max_cost: Option<u64>) -> Self {
+        Self {
+            consumed: 0,
+            max_cost,
+        }
+    }
+
+    fn charge(&mut self, cost: u64) -> Result<(), RuntimeError> {
+        let consumed = self.consumed.saturating_add(cost);
+        match self.max_cost {
+            Some(max_cost) if consumed > max_cost => {
+                self.consumed = max_cost;
+                Err(RuntimeError::OutOfGas)
+            }
+            _ => {
+                self.consumed = consumed;
+                Ok(())
+            }
+        }
+    }
+}
+
 pub fn execute<C>(
     unlock: &[Instruction],
     params: &[Vec<u8>],
@@ -173,6 +244,95 @@ pub fn execute<C>(
     parent_block_number: BlockNumber,
     parent_block_timestamp: u64,
 ) -> Result<ScriptResult, RuntimeError>
+where
+    C: ChainTimeInfo, {
+    execute_with_cost(unlock, params, lock, tx, config, cur, burn, client, parent_block_number, parent_block_timestamp)
+        .0
+}
+
+/// Executes the scripts and returns the cost consumed with the result.
+/// The cost is consumed even if the execution fails.
+pub fn execute_with_cost<C>(
+    unlock: &[Instruction],
+    params: &[Vec<u8>],
+    lock: &[Instruction],
+    tx: &dyn PartialHashing,
+    config: Config,
+    cur: &AssetTransferInput,
+    burn: bool,
+    client: &C,
+    parent_block_number: BlockNumber,
+    parent_block_timestamp: u64,
+) -> (Result<ScriptResult, RuntimeError>, u64)
+where
+    C: ChainTimeInfo, {
+    let mut meter = Meter::new(config.max_cost);
+    let result = run(
+        unlock,
+        params,
+        lock,
+        tx,
+        config,
+        &mut meter,
+        None,
+        cur,
+        burn,
+        client,
+        parent_block_number,
+        parent_block_timestamp,
+    );
+    (result, meter.consumed)
+}
+
+/// Executes the scripts recording the state of the machine before each instruction.
+pub fn execute_with_trace<C>(
+    unlock: &[Instruction],
+    params: &[Vec<u8>],
+    lock: &[Instruction],
+    tx: &dyn PartialHashing,
+    config: Config,
+    cur: &AssetTransferInput,
+    burn: bool,
+    client: &C,
+    parent_block_number: BlockNumber,
+    parent_block_timestamp: u64,
+) -> (Result<ScriptResult, RuntimeError>, u64, Vec<Step>)
+where
+    C: ChainTimeInfo, {
+    let mut meter = Meter::new(config.max_cost);
+    let mut trace = Vec::new();
+    let result = run(
+        unlock,
+        params,
+        lock,
+        tx,
+        config,
+        &mut meter,
+        Some(&mut trace),
+        cur,
+        burn,
+        client,
+        parent_block_number,
+        parent_block_timestamp,
+    );
+    (result, meter.consumed, trace)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run<C>(
+    unlock: &[Instruction],
+    params: &[Vec<u8>],
+    lock: &[Instruction],
+    tx: &dyn PartialHashing,
+    config: Config,
+    meter: &mut Meter,
+    mut trace: Option<&mut Vec<Step>>,
+    cur: &AssetTransferInput,
+    burn: bool,
+    client: &C,
+    parent_block_number: BlockNumber,
+    parent_block_timestamp: u64,
+) -> Result<ScriptResult, RuntimeError>
 where
     C: ChainTimeInfo, {
     // FIXME: don't merge scripts
@@ -189,8 +349,37 @@ where
     let script = [unlock, &param_scripts, lock].concat();
 
     let mut stack = Stack::new(config);
+    // The conditions of the enclosing IF blocks. The instructions are skipped unless all of them are true.
+    let mut conditions: Vec<bool> = Vec::new();
     let mut pc = 0;
     while pc < script.len() {
+        let executed = !conditions.contains(&false);
+        if let Some(trace) = trace.as_mut() {
+            trace.push(Step {
+                pc,
+                instruction: script[pc].clone(),
+                stack: stack.items(),
+                memory: stack.memory_usage,
+                cost: meter.consumed,
+                tag: None,
+                executed,
+            });
+        }
+        if !executed {
+            meter.charge(STEP_COST)?;
+            match &script[pc] {
+                Instruction::If => conditions.push(false),
+                Instruction::Else => flip_condition(&mut conditions)?,
+                Instruction::EndIf => {
+                    conditions.pop();
+                }
+                _ => {}
+            }
+            pc += 1;
+            continue
+        }
+
+        meter.charge(instruction_cost(&script[pc]))?;
         match &script[pc] {
             Instruction::Nop => {}
             Instruction::Burn => return Ok(ScriptResult::Burnt),
@@ -205,6 +394,18 @@ where
                 let second = stack.pop()?;
                 stack.push(Item::from(first.as_ref() == second.as_ref()))?;
             }
+            Instruction::Verify => {
+                if !bool::from(stack.pop()?) {
+                    return Ok(ScriptResult::Fail)
+                }
+            }
+            Instruction::EqVerify => {
+                let first = stack.pop()?;
+                let second = stack.pop()?;
+                if first.as_ref() != second.as_ref() {
+                    return Ok(ScriptResult::Fail)
+                }
+            }
             Instruction::Jmp(val) => {
                 pc += *val as usize;
             }
@@ -219,6 +420,14 @@ where
                     pc += *val as usize;
                 }
             }
+            Instruction::If => {
+                let condition: bool = stack.pop()?.into();
+                conditions.push(condition);
+            }
+            Instruction::Else => flip_condition(&mut conditions)?,
+            Instruction::EndIf => {
+                conditions.pop().ok_or(RuntimeError::UnbalancedConditional)?;
+            }
             Instruction::Push(val) => stack.push(Item(vec![*val]))?,
             Instruction::Pop => {
                 stack.pop()?;
@@ -245,10 +454,53 @@ where
             Instruction::Drop(index) => {
                 stack.remove(*index as usize)?;
             }
+            Instruction::Add => {
+                let first = read_u64(stack.pop()?)?;
+                let second = read_u64(stack.pop()?)?;
+                stack.push(write_u64(second.checked_add(first).ok_or(RuntimeError::Overflow)?))?;
+            }
+            Instruction::Sub => {
+                let first = read_u64(stack.pop()?)?;
+                let second = read_u64(stack.pop()?)?;
+                stack.push(write_u64(second.checked_sub(first).ok_or(RuntimeError::Overflow)?))?;
+            }
+            Instruction::Lt => {
+                let first = read_u64(stack.pop()?)?;
+                let second = read_u64(stack.pop()?)?;
+                stack.push(Item::from(second < first))?;
+            }
+            Instruction::Gt => {
+                let first = read_u64(stack.pop()?)?;
+                let second = read_u64(stack.pop()?)?;
+                stack.push(Item::from(second > first))?;
+            }
+            Instruction::Cat => {
+                let first = stack.pop()?;
+                let mut second = stack.pop()?;
+                second.0.extend_from_slice(first.as_ref());
+                stack.push(second)?;
+            }
+            Instruction::Split => {
+                let index = read_u64(stack.pop()?)?;
+                let mut value = stack.pop()?;
+                if index > value.len() as u64 {
+                    return Err(RuntimeError::IndexOutOfBound)
+                }
+                let right = value.0.split_off(index as usize);
+                stack.push(value)?;
+                stack.push(Item(right))?;
+            }
+            Instruction::Size => {
+                let top = stack.pop()?;
+                let size = top.len() as u64;
+                stack.push(top)?;
+                stack.push(write_u64(size))?;
+            }
             Instruction::ChkSig => {
                 let pubkey = Public::from_slice(stack.pop()?.assert_len(64)?.as_ref());
-                let tag = Tag::try_new(stack.pop()?.as_ref().to_vec())?;
-                let tx_hash = tx.hash_partially(tag, cur, burn)?;
+                let tag = stack.pop()?.as_ref().to_vec();
+                record_tag(&mut trace, &tag);
+                let tx_hash = tx.hash_partially(Tag::try_new(tag)?, cur, burn)?;
                 let signature = Signature::from(stack.pop()?.assert_len(SIGNATURE_LENGTH)?.as_ref());
                 let result = match verify(&pubkey, &signature, &tx_hash) {
                     Ok(true) => 1,
@@ -259,6 +511,7 @@ where
             Instruction::ChkMultiSig => {
                 // Get n pubkey. If there are more than six pubkeys, return error.
                 let n = stack.pop()?.assert_len(1)?.as_ref()[0] as usize;
+                meter.charge(multi_sig_cost(n))?;
 
                 let mut pubkey: Vec<Public> = Vec::with_capacity(n);
                 for _ in 0..n {
@@ -276,8 +529,9 @@ where
                     signatures.push(Signature::from(stack.pop()?.assert_len(SIGNATURE_LENGTH)?.as_ref()));
                 }
 
-                let tag = Tag::try_new(stack.pop()?.as_ref().to_vec())?;
-                let tx_hash = tx.hash_partially(tag, cur, burn)?;
+                let tag = stack.pop()?.as_ref().to_vec();
+                record_tag(&mut trace, &tag);
+                let tx_hash = tx.hash_partially(Tag::try_new(tag)?, cur, burn)?;
 
                 let result = if check_multi_sig(&tx_hash, pubkey, signatures) {
                     1
@@ -288,22 +542,27 @@ where
             }
             Instruction::Blake256 => {
                 let value = stack.pop()?;
+                meter.charge(hash_cost(value.len()))?;
                 stack.push(Item(blake256(value).to_vec()))?;
             }
             Instruction::Sha256 => {
                 let value = stack.pop()?;
+                meter.charge(hash_cost(value.len()))?;
                 stack.push(Item(sha256(value).to_vec()))?;
             }
             Instruction::Ripemd160 => {
                 let value = stack.pop()?;
+                meter.charge(hash_cost(value.len()))?;
                 stack.push(Item(ripemd160(value).to_vec()))?;
             }
             Instruction::Keccak256 => {
                 let value = stack.pop()?;
+                meter.charge(hash_cost(value.len()))?;
                 stack.push(Item(keccak256(value).to_vec()))?;
             }
             Instruction::Blake160 => {
                 let value = stack.pop()?;
+                meter.charge(hash_cost(value.len()))?;
                 stack.push(Item(H160::blake(value).to_vec()))?;
             }
             Instruction::ChkTimelock(timelock_type) => {
@@ -336,6 +595,10 @@ where
         pc += 1;
     }
 
+    if !conditions.is_empty() {
+        return Err(RuntimeError::UnbalancedConditional)
+    }
+
     let result = stack.pop()?;
     if result.into() && stack.len() == 0 {
         Ok(ScriptResult::Unlocked)
@@ -353,6 +616,18 @@ fn read_u64(value_item: Item) -> Result<u64, RuntimeError> {
     Ok(u64::from_be_bytes(value_bytes))
 }
 
+fn write_u64(value: u64) -> Item {
+    let bytes = value.to_be_bytes();
+    let leading_zeros = bytes.iter().take_while(|byte| **byte == 0).count();
+    Item(bytes[leading_zeros..].to_vec())
+}
+
+fn flip_condition(conditions: &mut Vec<bool>) -> Result<(), RuntimeError> {
+    let condition = conditions.last_mut().ok_or(RuntimeError::UnbalancedConditional)?;
+    *condition = !*condition;
+    Ok(())
+}
+
 #[inline]
 fn check_multi_sig(tx_hash: &H256, mut pubkey: Vec<Public>, mut signatures: Vec<Signature>) -> bool {
     while let Some(sig) = signatures.pop() {
@@ -369,6 +644,12 @@ fn check_multi_sig(tx_hash: &H256, mut pubkey: Vec<Public>, mut signatures: Vec<
     true
 }
 
+fn record_tag(trace: &mut Option<&mut Vec<Step>>, tag: &[u8]) {
+    if let Some(step) = trace.as_mut().and_then(|trace| trace.last_mut()) {
+        step.tag = Some(tag.to_vec());
+    }
+}
+
 pub trait ChainTimeInfo {
     /// Get the block height of the transaction.
     fn transaction_block_age(&self, tracker: &Tracker, parent_block_number: BlockNumber) -> Option<u64>;
@@ -450,6 +731,21 @@ mod tests {
     fn read_1_0_0_0_0_0_1_0() {
         assert_eq!(Ok(0x0100_0000_0000_0100), read_u64(Item(vec![1, 0, 0, 0, 0, 0, 1, 0])));
     }
+
+    #[test]
+    fn write_truncates_leading_zeros() {
+        assert_eq!(Vec::<u8>::new(), write_u64(0).as_ref());
+        assert_eq!(vec![1], write_u64(1).as_ref());
+        assert_eq!(vec![1, 0], write_u64(0x0100).as_ref());
+        assert_eq!(vec![0xff; 8], write_u64(u64::max_value()).as_ref());
+    }
+
+    #[test]
+    fn write_and_read() {
+        for value in [0, 1, 0xff, 0x0100, 0x0102_0304, u64::max_value()].iter() {
+            assert_eq!(Ok(*value), read_u64(write_u64(*value)));
+        }
+    }
 }
 
 #[cfg(test)]
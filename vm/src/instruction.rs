@@ -23,9 +23,14 @@ pub enum Instruction {
     Fail,
     Not,
     Eq,
+    Verify,
+    EqVerify,
     Jmp(u8),
     Jnz(u8),
     Jz(u8),
+    If,
+    Else,
+    EndIf,
     Push(u8),
     Pop,
     PushB(Vec<u8>),
@@ -33,6 +38,13 @@ pub enum Instruction {
     Swap,
     Copy(u8),
     Drop(u8),
+    Add,
+    Sub,
+    Lt,
+    Gt,
+    Cat,
+    Split,
+    Size,
     ChkSig,
     ChkMultiSig,
     Blake256,
@@ -21,11 +21,17 @@ extern crate codechain_types as ctypes;
 #[cfg(test)]
 extern crate secp256k1;
 
+mod assembler;
+mod cost;
 mod decoder;
 mod executor;
 mod instruction;
 mod opcode;
 
-pub use crate::decoder::{decode, DecoderError};
-pub use crate::executor::{execute, ChainTimeInfo, Config as VMConfig, RuntimeError, ScriptResult, TimelockType};
+pub use crate::assembler::{assemble, disassemble, lock_script_hash, AssemblerError};
+pub use crate::decoder::{decode, decode_at_era, DecoderError, EXTENSION_ERA};
+pub use crate::executor::{
+    execute, execute_with_cost, execute_with_trace, ChainTimeInfo, Config as VMConfig, RuntimeError, ScriptResult,
+    Step, TimelockType,
+};
 pub use crate::instruction::Instruction;
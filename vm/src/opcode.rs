@@ -20,9 +20,14 @@ pub const SUCCESS: u8 = 0x02;
 pub const FAIL: u8 = 0x03;
 pub const NOT: u8 = 0x10;
 pub const EQ: u8 = 0x11;
+pub const VERIFY: u8 = 0x12;
+pub const EQVERIFY: u8 = 0x13;
 pub const JMP: u8 = 0x20;
 pub const JNZ: u8 = 0x21;
 pub const JZ: u8 = 0x22;
+pub const IF: u8 = 0x23;
+pub const ELSE: u8 = 0x24;
+pub const ENDIF: u8 = 0x25;
 pub const PUSH: u8 = 0x30;
 pub const POP: u8 = 0x31;
 pub const PUSHB: u8 = 0x32;
@@ -30,6 +35,13 @@ pub const DUP: u8 = 0x33;
 pub const SWAP: u8 = 0x34;
 pub const COPY: u8 = 0x35;
 pub const DROP: u8 = 0x36;
+pub const ADD: u8 = 0x40;
+pub const SUB: u8 = 0x41;
+pub const LT: u8 = 0x42;
+pub const GT: u8 = 0x43;
+pub const CAT: u8 = 0x50;
+pub const SPLIT: u8 = 0x51;
+pub const SIZE: u8 = 0x52;
 pub const CHKSIG: u8 = 0x80;
 pub const CHKMULTISIG: u8 = 0x81;
 pub const BLAKE256: u8 = 0x90;
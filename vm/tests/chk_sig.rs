@@ -29,7 +29,7 @@ use ckey::{sign, KeyPair, NetworkId, Private};
 use common::TestClient;
 use ctypes::transaction::{AssetOutPoint, AssetTransferInput, AssetTransferOutput, ShardTransaction};
 use cvm::Instruction;
-use cvm::{execute, ScriptResult, VMConfig};
+use cvm::{execute, execute_with_trace, ScriptResult, VMConfig};
 use primitives::H160;
 use rlp::Encodable;
 use secp256k1::key::{MINUS_ONE_KEY, ONE_KEY};
@@ -540,3 +540,46 @@ fn distinguish_sign_single_output_with_sign_all() {
         Ok(ScriptResult::Fail)
     );
 }
+
+#[test]
+fn trace_records_the_tag_of_chk_sig() {
+    let client = TestClient::default();
+    let transaction = ShardTransaction::TransferAsset {
+        network_id: NetworkId::default(),
+        burns: Vec::new(),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+    };
+    let input = AssetTransferInput {
+        prev_out: AssetOutPoint {
+            tracker: Default::default(),
+            index: 0,
+            asset_type: H160::default(),
+            shard_id: 0,
+            quantity: 0,
+        },
+        timelock: None,
+        lock_script: Vec::new(),
+        unlock_script: Vec::new(),
+    };
+    let keypair = KeyPair::from_private(Private::from(ONE_KEY)).unwrap();
+    let pubkey = <&[u8]>::from(keypair.public()).to_vec();
+    let unlock_script = vec![Instruction::PushB(vec![0; 65]), Instruction::PushB(vec![0b11 as u8])];
+    let lock_script = vec![Instruction::PushB(pubkey), Instruction::ChkSig];
+
+    let (result, _, trace) = execute_with_trace(
+        &unlock_script,
+        &[],
+        &lock_script,
+        &transaction,
+        VMConfig::default(),
+        &input,
+        false,
+        &client,
+        0,
+        0,
+    );
+    assert_eq!(result, Ok(ScriptResult::Fail));
+    let tags: Vec<_> = trace.into_iter().map(|step| step.tag).collect();
+    assert_eq!(tags, vec![None, None, None, Some(vec![0b11])]);
+}
@@ -26,7 +26,7 @@ use ccrypto::{BLAKE_EMPTY, BLAKE_NULL_RLP};
 use ckey::NetworkId;
 use common::TestClient;
 use ctypes::transaction::{AssetOutPoint, AssetTransferInput, ShardTransaction};
-use cvm::{execute, RuntimeError, ScriptResult, VMConfig};
+use cvm::{execute, execute_with_cost, execute_with_trace, RuntimeError, ScriptResult, Step, VMConfig};
 use cvm::{Instruction, TimelockType};
 use primitives::{H160, H256};
 
@@ -170,6 +170,7 @@ fn out_of_memory() {
     };
     let config = VMConfig {
         max_memory: 2,
+        max_cost: None,
     };
     assert_eq!(
         execute(
@@ -902,3 +903,132 @@ fn copy_stack_underflow() {
         Err(RuntimeError::StackUnderflow)
     );
 }
+
+#[test]
+fn cost_is_reported() {
+    let client = TestClient::default();
+    let lock_script =
+        vec![Instruction::PushB(vec![0; 33]), Instruction::Blake256, Instruction::Pop, Instruction::Push(1)];
+    assert_eq!(
+        execute_with_cost(
+            &[],
+            &[],
+            &lock_script,
+            &dummy_tx(),
+            VMConfig::default(),
+            &dummy_input(),
+            false,
+            &client,
+            0,
+            0
+        ),
+        (Ok(ScriptResult::Unlocked), 31)
+    );
+}
+
+#[test]
+fn out_of_gas() {
+    let client = TestClient::default();
+    let config = VMConfig {
+        max_memory: 1024,
+        max_cost: Some(30),
+    };
+    let lock_script =
+        vec![Instruction::PushB(vec![0; 33]), Instruction::Blake256, Instruction::Pop, Instruction::Push(1)];
+    assert_eq!(
+        execute_with_cost(&[], &[], &lock_script, &dummy_tx(), config, &dummy_input(), false, &client, 0, 0),
+        (Err(RuntimeError::OutOfGas), 30)
+    );
+}
+
+#[test]
+fn chk_multi_sig_is_charged_by_the_number_of_public_keys() {
+    let client = TestClient::default();
+    let config = VMConfig {
+        max_memory: 1024,
+        max_cost: Some(5_000),
+    };
+    assert_eq!(
+        execute(
+            &[],
+            &[],
+            &[Instruction::Push(6), Instruction::ChkMultiSig],
+            &dummy_tx(),
+            config,
+            &dummy_input(),
+            false,
+            &client,
+            0,
+            0
+        ),
+        Err(RuntimeError::OutOfGas)
+    );
+}
+
+#[test]
+fn trace_records_the_stack_before_each_instruction() {
+    let client = TestClient::default();
+    let unlock_script = vec![Instruction::Push(1)];
+    let lock_script = vec![Instruction::Pop, Instruction::Push(1), Instruction::Eq];
+    let (result, cost, trace) = execute_with_trace(
+        &unlock_script,
+        &[vec![2]],
+        &lock_script,
+        &dummy_tx(),
+        VMConfig::default(),
+        &dummy_input(),
+        false,
+        &client,
+        0,
+        0,
+    );
+    assert_eq!(result, Ok(ScriptResult::Unlocked));
+    assert_eq!(cost, 5);
+    assert_eq!(trace, vec![
+        Step {
+            pc: 0,
+            instruction: Instruction::Push(1),
+            stack: vec![],
+            memory: 0,
+            cost: 0,
+            tag: None,
+            executed: true,
+        },
+        Step {
+            pc: 1,
+            instruction: Instruction::PushB(vec![2]),
+            stack: vec![vec![1]],
+            memory: 1,
+            cost: 1,
+            tag: None,
+            executed: true,
+        },
+        Step {
+            pc: 2,
+            instruction: Instruction::Pop,
+            stack: vec![vec![1], vec![2]],
+            memory: 2,
+            cost: 2,
+            tag: None,
+            executed: true,
+        },
+        Step {
+            pc: 3,
+            instruction: Instruction::Push(1),
+            stack: vec![vec![1]],
+            memory: 1,
+            cost: 3,
+            tag: None,
+            executed: true,
+        },
+        Step {
+            pc: 4,
+            instruction: Instruction::Eq,
+            stack: vec![vec![1], vec![1]],
+            memory: 2,
+            cost: 4,
+            tag: None,
+            executed: true,
+        },
+    ]);
+}
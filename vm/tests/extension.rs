@@ -0,0 +1,380 @@
+// Copyright 2020 Kodebox, Inc.
+// This file is part of CodeChain.
+//
+// This program is free software: you can redistribute it and/or modify
+// it under the terms of the GNU Affero General Public License as
+// published by the Free Software Foundation, either version 3 of the
+// License, or (at your option) any later version.
+//
+// This program is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU Affero General Public License for more details.
+//
+// You should have received a copy of the GNU Affero General Public License
+// along with this program.  If not, see <https://www.gnu.org/licenses/>.
+
+extern crate codechain_crypto as ccrypto;
+extern crate codechain_key as ckey;
+extern crate codechain_types as ctypes;
+extern crate codechain_vm as cvm;
+extern crate primitives;
+
+mod common;
+
+use ccrypto::blake256;
+use ckey::NetworkId;
+use common::TestClient;
+use ctypes::transaction::{AssetOutPoint, AssetTransferInput, ShardTransaction};
+use cvm::{execute, execute_with_cost, RuntimeError, ScriptResult, VMConfig};
+use cvm::{Instruction, TimelockType};
+use primitives::H160;
+
+fn dummy_tx() -> ShardTransaction {
+    ShardTransaction::TransferAsset {
+        network_id: NetworkId::default(),
+        burns: Vec::new(),
+        inputs: Vec::new(),
+        outputs: Vec::new(),
+    }
+}
+
+fn dummy_input() -> AssetTransferInput {
+    AssetTransferInput {
+        prev_out: AssetOutPoint {
+            tracker: Default::default(),
+            index: 0,
+            asset_type: H160::default(),
+            shard_id: 0,
+            quantity: 0,
+        },
+        timelock: None,
+        lock_script: Vec::new(),
+        unlock_script: Vec::new(),
+    }
+}
+
+fn run(lock: &[Instruction]) -> Result<ScriptResult, RuntimeError> {
+    execute(&[], &[], lock, &dummy_tx(), VMConfig::default(), &dummy_input(), false, &TestClient::default(), 0, 0)
+}
+
+#[test]
+fn add() {
+    assert_eq!(
+        run(&[Instruction::Push(2), Instruction::Push(3), Instruction::Add, Instruction::Push(5), Instruction::Eq]),
+        Ok(ScriptResult::Unlocked)
+    );
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![0x01, 0xff]),
+            Instruction::Push(1),
+            Instruction::Add,
+            Instruction::PushB(vec![0x02, 0x00]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn add_overflow() {
+    assert_eq!(
+        run(&[Instruction::PushB(vec![0xff; 8]), Instruction::Push(1), Instruction::Add]),
+        Err(RuntimeError::Overflow)
+    );
+}
+
+#[test]
+fn add_type_mismatch() {
+    assert_eq!(
+        run(&[Instruction::PushB(vec![1; 9]), Instruction::Push(1), Instruction::Add]),
+        Err(RuntimeError::TypeMismatch)
+    );
+}
+
+#[test]
+fn add_underflow() {
+    assert_eq!(run(&[Instruction::Push(1), Instruction::Add]), Err(RuntimeError::StackUnderflow));
+}
+
+#[test]
+fn sub() {
+    assert_eq!(
+        run(&[Instruction::Push(5), Instruction::Push(3), Instruction::Sub, Instruction::Push(2), Instruction::Eq]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn sub_to_zero_truncates_leading_zeros() {
+    assert_eq!(
+        run(&[
+            Instruction::Push(3),
+            Instruction::Push(3),
+            Instruction::Sub,
+            Instruction::PushB(vec![]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn sub_overflow() {
+    assert_eq!(run(&[Instruction::Push(3), Instruction::Push(5), Instruction::Sub]), Err(RuntimeError::Overflow));
+}
+
+#[test]
+fn lt() {
+    assert_eq!(run(&[Instruction::Push(2), Instruction::Push(3), Instruction::Lt]), Ok(ScriptResult::Unlocked));
+    assert_eq!(run(&[Instruction::Push(3), Instruction::Push(3), Instruction::Lt]), Ok(ScriptResult::Fail));
+    assert_eq!(run(&[Instruction::Push(4), Instruction::Push(3), Instruction::Lt]), Ok(ScriptResult::Fail));
+}
+
+#[test]
+fn gt() {
+    assert_eq!(run(&[Instruction::Push(2), Instruction::Push(3), Instruction::Gt]), Ok(ScriptResult::Fail));
+    assert_eq!(run(&[Instruction::Push(3), Instruction::Push(3), Instruction::Gt]), Ok(ScriptResult::Fail));
+    assert_eq!(run(&[Instruction::Push(4), Instruction::Push(3), Instruction::Gt]), Ok(ScriptResult::Unlocked));
+}
+
+#[test]
+fn comparison_ignores_leading_zeros() {
+    assert_eq!(
+        run(&[Instruction::PushB(vec![0, 0, 2]), Instruction::Push(3), Instruction::Lt]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn cat() {
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![1, 2]),
+            Instruction::PushB(vec![3]),
+            Instruction::Cat,
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn split() {
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Push(1),
+            Instruction::Split,
+            Instruction::PushB(vec![2, 3]),
+            Instruction::EqVerify,
+            Instruction::PushB(vec![1]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn split_at_the_end() {
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Push(3),
+            Instruction::Split,
+            Instruction::PushB(vec![]),
+            Instruction::EqVerify,
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn split_out_of_bound() {
+    assert_eq!(
+        run(&[Instruction::PushB(vec![1, 2, 3]), Instruction::Push(4), Instruction::Split]),
+        Err(RuntimeError::IndexOutOfBound)
+    );
+}
+
+#[test]
+fn size_keeps_the_item() {
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Size,
+            Instruction::Push(3),
+            Instruction::EqVerify,
+            Instruction::PushB(vec![1, 2, 3]),
+            Instruction::Eq
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn size_of_empty_item() {
+    assert_eq!(
+        run(&[
+            Instruction::PushB(vec![]),
+            Instruction::Size,
+            Instruction::PushB(vec![]),
+            Instruction::EqVerify,
+            Instruction::Pop,
+            Instruction::Push(1)
+        ]),
+        Ok(ScriptResult::Unlocked)
+    );
+}
+
+#[test]
+fn verify() {
+    assert_eq!(run(&[Instruction::Push(1), Instruction::Verify, Instruction::Push(1)]), Ok(ScriptResult::Unlocked));
+    assert_eq!(run(&[Instruction::Push(0), Instruction::Verify, Instruction::Push(1)]), Ok(ScriptResult::Fail));
+    assert_eq!(run(&[Instruction::Verify]), Err(RuntimeError::StackUnderflow));
+}
+
+#[test]
+fn eq_verify() {
+    assert_eq!(
+        run(&[Instruction::Push(1), Instruction::Push(1), Instruction::EqVerify, Instruction::Push(1)]),
+        Ok(ScriptResult::Unlocked)
+    );
+    assert_eq!(
+        run(&[Instruction::Push(1), Instruction::Push(2), Instruction::EqVerify, Instruction::Push(1)]),
+        Ok(ScriptResult::Fail)
+    );
+}
+
+#[test]
+fn if_else() {
+    let branches = |condition| {
+        vec![
+            Instruction::Push(condition),
+            Instruction::If,
+            Instruction::Push(1),
+            Instruction::Else,
+            Instruction::Push(0),
+            Instruction::EndIf,
+        ]
+    };
+    assert_eq!(run(&branches(1)), Ok(ScriptResult::Unlocked));
+    assert_eq!(run(&branches(0)), Ok(ScriptResult::Fail));
+}
+
+#[test]
+fn if_without_else() {
+    let script = |condition| vec![Instruction::Push(condition), Instruction::If, Instruction::Fail, Instruction::EndIf];
+    assert_eq!(run(&[&script(0)[..], &[Instruction::Push(1)]].concat()), Ok(ScriptResult::Unlocked));
+    assert_eq!(run(&[&script(1)[..], &[Instruction::Push(1)]].concat()), Ok(ScriptResult::Fail));
+}
+
+#[test]
+fn nested_if() {
+    let script = |outer, inner| {
+        vec![
+            Instruction::Push(inner),
+            Instruction::Push(outer),
+            Instruction::If,
+            Instruction::If,
+            Instruction::Push(1),
+            Instruction::Else,
+            Instruction::Push(2),
+            Instruction::EndIf,
+            Instruction::Else,
+            Instruction::If,
+            Instruction::Push(3),
+            Instruction::Else,
+            Instruction::Push(4),
+            Instruction::EndIf,
+            Instruction::EndIf,
+        ]
+    };
+    for (outer, inner, expected) in [(1, 1, 1), (1, 0, 2), (0, 1, 3), (0, 0, 4)].iter() {
+        assert_eq!(
+            run(&[&script(*outer, *inner)[..], &[Instruction::Push(*expected), Instruction::Eq]].concat()),
+            Ok(ScriptResult::Unlocked),
+            "outer: {}, inner: {}",
+            outer,
+            inner
+        );
+    }
+}
+
+#[test]
+fn skipped_branch_is_charged_by_steps() {
+    let (result, cost) = execute_with_cost(
+        &[],
+        &[],
+        &[Instruction::Push(0), Instruction::If, Instruction::ChkSig, Instruction::EndIf, Instruction::Push(1)],
+        &dummy_tx(),
+        VMConfig::default(),
+        &dummy_input(),
+        false,
+        &TestClient::default(),
+        0,
+        0,
+    );
+    assert_eq!(result, Ok(ScriptResult::Unlocked));
+    assert_eq!(cost, 5);
+}
+
+#[test]
+fn unbalanced_conditionals() {
+    assert_eq!(
+        run(&[Instruction::Push(1), Instruction::If, Instruction::Push(1)]),
+        Err(RuntimeError::UnbalancedConditional)
+    );
+    assert_eq!(
+        run(&[Instruction::Push(0), Instruction::If, Instruction::Push(1)]),
+        Err(RuntimeError::UnbalancedConditional)
+    );
+    assert_eq!(run(&[Instruction::Push(1), Instruction::Else]), Err(RuntimeError::UnbalancedConditional));
+    assert_eq!(run(&[Instruction::Push(1), Instruction::EndIf]), Err(RuntimeError::UnbalancedConditional));
+}
+
+#[test]
+fn success_in_if_block() {
+    assert_eq!(run(&[Instruction::Push(1), Instruction::If, Instruction::Success]), Ok(ScriptResult::Unlocked));
+}
+
+#[test]
+fn hash_time_locked_contract() {
+    let preimage = vec![0xca, 0xfe];
+    let lock_script = vec![
+        Instruction::If,
+        Instruction::Blake256,
+        Instruction::PushB(blake256(&preimage).to_vec()),
+        Instruction::Eq,
+        Instruction::Else,
+        Instruction::Push(10),
+        Instruction::ChkTimelock(TimelockType::Block),
+        Instruction::EndIf,
+    ];
+    let run_htlc = |unlock_script: &[Instruction], parent_block_number| {
+        execute(
+            unlock_script,
+            &[],
+            &lock_script,
+            &dummy_tx(),
+            VMConfig::default(),
+            &dummy_input(),
+            false,
+            &TestClient::default(),
+            parent_block_number,
+            0,
+        )
+    };
+
+    let claim = [Instruction::PushB(preimage.clone()), Instruction::Push(1)];
+    assert_eq!(run_htlc(&claim, 0), Ok(ScriptResult::Unlocked));
+    let wrong_claim = [Instruction::PushB(vec![0xbe, 0xef]), Instruction::Push(1)];
+    assert_eq!(run_htlc(&wrong_claim, 0), Ok(ScriptResult::Fail));
+
+    let refund = [Instruction::Push(0)];
+    assert_eq!(run_htlc(&refund, 9), Ok(ScriptResult::Fail));
+    assert_eq!(run_htlc(&refund, 10), Ok(ScriptResult::Unlocked));
+}